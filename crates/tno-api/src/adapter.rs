@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use tno_core::SupervisorApi;
-use tno_model::{CreateSpec, TaskId, TaskInfo, TaskStatus};
+use tno_core::{CoreError, SupervisorApi, TaskWatch};
+use tno_model::{
+    BackoffStrategy, CreateSpec, DashboardSnapshot, HealthStatus, RestartStrategy, RunnerErrorInfo,
+    Submission, TaskFilter, TaskId, TaskInfo, TaskPage, TaskQuery, TaskStatus,
+};
 
+use crate::bulk::BulkSubmitReport;
 use crate::error::ApiError;
 use crate::handler::ApiHandler;
+use crate::readiness::{Readiness, ReadinessMode, StatusReport, SubsystemStatus};
 
 /// Adapter that bridges `SupervisorApi` to `ApiHandler`.
 ///
@@ -23,10 +28,32 @@ impl SupervisorApiAdapter {
 
 #[async_trait]
 impl ApiHandler for SupervisorApiAdapter {
-    async fn submit_task(&self, spec: CreateSpec) -> Result<TaskId, ApiError> {
+    async fn submit_task(&self, spec: CreateSpec) -> Result<Submission, ApiError> {
         self.supervisor.submit(&spec).await.map_err(ApiError::from)
     }
 
+    async fn submit_many(&self, specs: Vec<CreateSpec>) -> Result<BulkSubmitReport, ApiError> {
+        let results = self
+            .supervisor
+            .submit_many(&specs)
+            .await
+            .into_iter()
+            .map(|r| r.map_err(ApiError::from))
+            .collect();
+        Ok(BulkSubmitReport::from_results(results))
+    }
+
+    async fn validate_task(&self, spec: CreateSpec) -> Result<String, ApiError> {
+        match self.supervisor.validate(&spec).await {
+            Ok(runner) => Ok(runner.to_string()),
+            // The spec itself is what failed: no runner takes it, or the chosen runner rejects it.
+            Err(e @ (CoreError::NoRunner(_) | CoreError::Runner(_))) => {
+                Err(ApiError::InvalidRequest(e.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
     async fn get_task_status(&self, id: &TaskId) -> Result<Option<TaskInfo>, ApiError> {
         Ok(self.supervisor.get_task(id))
     }
@@ -35,18 +62,107 @@ impl ApiHandler for SupervisorApiAdapter {
         Ok(self.supervisor.list_all_tasks())
     }
 
+    async fn list_tasks_page(&self, offset: usize, limit: usize) -> Result<TaskPage, ApiError> {
+        Ok(self.supervisor.list_tasks_page(offset, limit))
+    }
+
     async fn list_tasks_by_slot(&self, slot: &str) -> Result<Vec<TaskInfo>, ApiError> {
         Ok(self.supervisor.list_tasks_by_slot(slot))
     }
 
+    async fn list_tasks_filtered(&self, filter: &TaskFilter) -> Result<Vec<TaskInfo>, ApiError> {
+        Ok(self.supervisor.list_tasks_filtered(filter))
+    }
+
+    async fn query_tasks(&self, query: TaskQuery) -> Result<Vec<TaskInfo>, ApiError> {
+        Ok(self.supervisor.query_tasks(query))
+    }
+
+    async fn list_tasks_by_batch(&self, batch_id: &str) -> Result<Vec<TaskInfo>, ApiError> {
+        Ok(self.supervisor.list_tasks_by_batch(batch_id))
+    }
+
     async fn list_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<TaskInfo>, ApiError> {
         Ok(self.supervisor.list_tasks_by_status(status))
     }
 
+    async fn dashboard(&self, recent: usize) -> Result<DashboardSnapshot, ApiError> {
+        Ok(self.supervisor.dashboard(recent))
+    }
+
     async fn cancel_task(&self, id: &TaskId) -> Result<(), ApiError> {
         self.supervisor
             .cancel_task(id)
             .await
             .map_err(ApiError::from)
     }
+
+    async fn cancel_batch(&self, batch_id: &str) -> Result<Vec<TaskId>, ApiError> {
+        self.supervisor
+            .cancel_batch(batch_id)
+            .await
+            .map_err(ApiError::from)
+    }
+
+    async fn update_policy(
+        &self,
+        id: &TaskId,
+        restart: RestartStrategy,
+        backoff: BackoffStrategy,
+    ) -> Result<(), ApiError> {
+        self.supervisor
+            .update_policy(id, restart, backoff)
+            .await
+            .map_err(ApiError::from)
+    }
+
+    async fn watch_task(&self, id: &TaskId) -> Result<TaskWatch, ApiError> {
+        self.supervisor.watch_task(id).map_err(ApiError::from)
+    }
+
+    async fn list_runner_errors(&self) -> Result<Vec<RunnerErrorInfo>, ApiError> {
+        Ok(self.supervisor.recent_runner_errors())
+    }
+
+    async fn readiness(&self, mode: ReadinessMode) -> Result<Readiness, ApiError> {
+        // The adapter only exists once the supervisor reported readiness, but it may have died or started shutting down since.
+        let mut failing = Vec::new();
+        if let Some(e) = self.supervisor.last_error() {
+            failing.push(format!("supervisor: run loop exited: {e}"));
+        } else if self.supervisor.is_shutting_down() {
+            failing.push("supervisor: shutting down".into());
+        }
+        if mode == ReadinessMode::SupervisorAndRunners {
+            failing.extend(
+                self.supervisor
+                    .check_runners_health()
+                    .into_iter()
+                    .filter_map(|(name, res)| res.err().map(|e| format!("{name}: {e}"))),
+            );
+        }
+        Ok(Readiness::from_failures(failing))
+    }
+
+    async fn health(&self) -> Result<HealthStatus, ApiError> {
+        Ok(self.supervisor.health())
+    }
+
+    async fn status(&self) -> Result<StatusReport, ApiError> {
+        let supervisor = match self.supervisor.last_error() {
+            Some(e) => Err(format!("run loop exited: {e}")),
+            None => Ok(()),
+        };
+        let mut subsystems = vec![SubsystemStatus::from_result("supervisor", supervisor)];
+        subsystems.extend(
+            self.supervisor
+                .check_runners_health()
+                .into_iter()
+                .map(|(name, res)| SubsystemStatus::from_result(format!("runner:{name}"), res)),
+        );
+        subsystems.push(SubsystemStatus::from_result(
+            "metrics",
+            self.supervisor.check_metrics_health(),
+        ));
+        Ok(StatusReport::from_subsystems(subsystems))
+    }
 }
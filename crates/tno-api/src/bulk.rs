@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use tno_model::{AdmissionOutcome, Submission, TaskId};
+
+use crate::error::ApiError;
+
+/// Outcome of one spec in a bulk submission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkSubmitItem {
+    /// Position of the spec in the submitted batch.
+    pub index: usize,
+    /// Assigned task id (set on success).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<TaskId>,
+    /// Admission decision for the task (set on success).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admission: Option<AdmissionOutcome>,
+    /// Submission error (set on failure).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Summary of a bulk submission.
+///
+/// A failing spec does not abort the batch; every spec gets its own entry in `results`, in submission order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkSubmitReport {
+    /// Number of specs that were submitted.
+    pub succeeded: usize,
+    /// Number of specs that were rejected.
+    pub failed: usize,
+    /// Per-spec outcomes.
+    pub results: Vec<BulkSubmitItem>,
+}
+
+impl BulkSubmitReport {
+    /// Build a report from per-spec results (index `i` belongs to spec `i`).
+    pub fn from_results(results: Vec<Result<Submission, ApiError>>) -> Self {
+        let mut report = Self::default();
+        for (index, res) in results.into_iter().enumerate() {
+            let item = match res {
+                Ok(submission) => {
+                    report.succeeded += 1;
+                    BulkSubmitItem {
+                        index,
+                        task_id: Some(submission.task_id),
+                        admission: Some(submission.admission),
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    BulkSubmitItem {
+                        index,
+                        task_id: None,
+                        admission: None,
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            report.results.push(item);
+        }
+        report
+    }
+}
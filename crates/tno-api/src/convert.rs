@@ -1,8 +1,10 @@
 use tno_model::{
-    AdmissionStrategy, BackoffStrategy, CreateSpec, Flag, JitterStrategy, RestartStrategy,
-    RunnerLabels, TaskEnv, TaskInfo, TaskKind, TaskStatus,
+    AdmissionOutcome, AdmissionStrategy, BackoffStrategy, CreateSpec, Flag, JitterStrategy,
+    LabelLimits, RestartStrategy, RunnerErrorInfo, RunnerLabels, SortDirection, TaskEnv,
+    TaskFilter, TaskInfo, TaskKind, TaskQuery, TaskSortKey, TaskStatus,
 };
 
+use crate::bulk::BulkSubmitReport;
 use crate::error::ApiError;
 use crate::proto;
 
@@ -24,10 +26,85 @@ impl From<TaskStatus> for proto::TaskStatus {
     }
 }
 
+/// Convert a raw proto `TaskStatus` value into the domain status.
+pub(crate) fn task_status_from_proto(raw: i32) -> Result<TaskStatus, ApiError> {
+    let status = proto::TaskStatus::try_from(raw)
+        .map_err(|_| ApiError::InvalidRequest("invalid status".into()))?;
+    match status {
+        proto::TaskStatus::Pending => Ok(TaskStatus::Pending),
+        proto::TaskStatus::Running => Ok(TaskStatus::Running),
+        proto::TaskStatus::Succeeded => Ok(TaskStatus::Succeeded),
+        proto::TaskStatus::Failed => Ok(TaskStatus::Failed),
+        proto::TaskStatus::Timeout => Ok(TaskStatus::Timeout),
+        proto::TaskStatus::Canceled => Ok(TaskStatus::Canceled),
+        proto::TaskStatus::Exhausted => Ok(TaskStatus::Exhausted),
+        proto::TaskStatus::Unspecified => Err(ApiError::InvalidRequest(
+            "status cannot be unspecified".into(),
+        )),
+    }
+}
+
+impl From<AdmissionOutcome> for proto::AdmissionOutcome {
+    fn from(outcome: AdmissionOutcome) -> Self {
+        match outcome {
+            AdmissionOutcome::Admitted => proto::AdmissionOutcome::Admitted,
+            AdmissionOutcome::Dropped => proto::AdmissionOutcome::Dropped,
+            AdmissionOutcome::Replaced => proto::AdmissionOutcome::Replaced,
+            AdmissionOutcome::Queued => proto::AdmissionOutcome::Queued,
+        }
+    }
+}
+
+// ============================================================================
+// BulkSubmitReport conversions
+// ============================================================================
+
+impl From<BulkSubmitReport> for proto::SubmitTasksResponse {
+    fn from(report: BulkSubmitReport) -> Self {
+        proto::SubmitTasksResponse {
+            succeeded: report.succeeded as u32,
+            failed: report.failed as u32,
+            results: report
+                .results
+                .into_iter()
+                .map(|item| proto::SubmitResult {
+                    index: item.index as u32,
+                    task_id: item.task_id.map(|id| id.to_string()),
+                    admission: item
+                        .admission
+                        .map_or(proto::AdmissionOutcome::Unspecified, Into::into)
+                        as i32,
+                    error: item.error,
+                })
+                .collect(),
+        }
+    }
+}
+
 // ============================================================================
 // TaskInfo conversions
 // ============================================================================
 
+impl From<RunnerErrorInfo> for proto::RunnerError {
+    fn from(info: RunnerErrorInfo) -> Self {
+        use std::time::UNIX_EPOCH;
+
+        let at = info
+            .at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        proto::RunnerError {
+            runner: info.runner,
+            kind: info.kind,
+            message: info.message,
+            task_id: info.task_id.map(|id| id.to_string()),
+            at,
+        }
+    }
+}
+
 impl From<TaskInfo> for proto::TaskInfo {
     fn from(info: TaskInfo) -> Self {
         use std::time::UNIX_EPOCH;
@@ -35,23 +112,33 @@ impl From<TaskInfo> for proto::TaskInfo {
         let created_at = info
             .created_at
             .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as i64;
+            .unwrap_or_default();
 
         let updated_at = info
             .updated_at
             .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as i64;
+            .unwrap_or_default();
 
         proto::TaskInfo {
             id: info.id.to_string(),
             slot: info.slot,
             status: proto::TaskStatus::from(info.status) as i32,
             attempt: info.attempt,
-            created_at,
-            updated_at,
+            created_at: created_at.as_secs() as i64,
+            updated_at: updated_at.as_secs() as i64,
+            created_at_ms: created_at.as_millis() as i64,
+            updated_at_ms: updated_at.as_millis() as i64,
             error: info.error,
+            exit_code: info.exit_code,
+            signal: info.signal,
+            kind_summary: info.kind_summary,
+            runner_name: info.runner_name,
+            cgroup: info.cgroup,
+            stdout: info.stdout,
+            stderr: info.stderr,
+            limit_cause: info.limit_cause,
+            batch_id: info.batch_id,
+            labels: info.labels.0.into_iter().collect(),
         }
     }
 }
@@ -76,6 +163,7 @@ impl TryFrom<proto::CreateSpec> for CreateSpec {
             proto::RestartStrategy::try_from(spec.restart)
                 .map_err(|_| ApiError::InvalidRequest("invalid restart strategy".into()))?,
             spec.restart_interval_ms,
+            spec.restart_max_attempts,
         )?;
 
         let backoff = spec
@@ -92,11 +180,83 @@ impl TryFrom<proto::CreateSpec> for CreateSpec {
                 proto::AdmissionStrategy::try_from(spec.admission)
                     .map_err(|_| ApiError::InvalidRequest("invalid admission strategy".into()))?,
             )?,
-            labels: convert_labels(spec.labels),
+            labels: convert_labels(spec.labels)?,
+            runner_selector: convert_labels(spec.runner_selector)?,
+            cancel_is_success: spec.cancel_is_success,
+            idempotency_key: validate_idempotency_key(spec.idempotency_key)?,
+            batch_id: validate_batch_id(spec.batch_id)?,
+            schedule: None,
         })
     }
 }
 
+/// Spec submitted in a request; with `pinned_images` container images must be pinned by digest.
+pub(crate) fn convert_spec(
+    spec: proto::CreateSpec,
+    pinned_images: bool,
+) -> Result<CreateSpec, ApiError> {
+    let spec = CreateSpec::try_from(spec)?;
+    if pinned_images {
+        spec.kind
+            .validate_image(true)
+            .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+    }
+    Ok(spec)
+}
+
+/// Restart and backoff policy requested by an `UpdatePolicy` call.
+pub(crate) fn convert_policy_update(
+    req: proto::UpdatePolicyRequest,
+) -> Result<(RestartStrategy, BackoffStrategy), ApiError> {
+    let restart = convert_restart_strategy(
+        proto::RestartStrategy::try_from(req.restart)
+            .map_err(|_| ApiError::InvalidRequest("invalid restart strategy".into()))?,
+        req.restart_interval_ms,
+        req.restart_max_attempts,
+    )?;
+    let backoff = req
+        .backoff
+        .ok_or_else(|| ApiError::InvalidRequest("missing backoff strategy".into()))?;
+    Ok((restart, convert_backoff_strategy(backoff)?))
+}
+
+/// Task query requested by a `ListTasks` call.
+pub(crate) fn convert_task_query(req: proto::ListTasksRequest) -> Result<TaskQuery, ApiError> {
+    if req.slot.as_deref().is_some_and(|s| s.trim().is_empty()) {
+        return Err(ApiError::InvalidRequest("slot cannot be empty".into()));
+    }
+    if req.batch_id.as_deref().is_some_and(|b| b.trim().is_empty()) {
+        return Err(ApiError::InvalidRequest("batch_id cannot be empty".into()));
+    }
+
+    let mut filter = TaskFilter {
+        slot: req.slot,
+        batch_id: req.batch_id,
+        labels: convert_labels(req.labels)?,
+        ..TaskFilter::default()
+    };
+    for raw in req.statuses {
+        filter = filter.with_status(task_status_from_proto(raw)?);
+    }
+
+    let sort_by = match proto::TaskSortKey::try_from(req.sort_by)
+        .map_err(|_| ApiError::InvalidRequest("invalid sort key".into()))?
+    {
+        proto::TaskSortKey::Unspecified | proto::TaskSortKey::CreatedAt => TaskSortKey::CreatedAt,
+        proto::TaskSortKey::UpdatedAt => TaskSortKey::UpdatedAt,
+        proto::TaskSortKey::Attempt => TaskSortKey::Attempt,
+    };
+    let direction = match proto::SortDirection::try_from(req.direction)
+        .map_err(|_| ApiError::InvalidRequest("invalid sort direction".into()))?
+    {
+        proto::SortDirection::Unspecified | proto::SortDirection::Ascending => {
+            SortDirection::Ascending
+        }
+        proto::SortDirection::Descending => SortDirection::Descending,
+    };
+    Ok(TaskQuery::new(filter).sorted_by(sort_by, direction))
+}
+
 fn convert_task_kind(kind: proto::task_kind::Kind) -> Result<TaskKind, ApiError> {
     match kind {
         proto::task_kind::Kind::Subprocess(sub) => {
@@ -109,7 +269,8 @@ fn convert_task_kind(kind: proto::task_kind::Kind) -> Result<TaskKind, ApiError>
             Ok(TaskKind::Subprocess {
                 command: sub.command,
                 args: sub.args,
-                env: convert_env(sub.env),
+                env: convert_env(sub.env)?,
+                env_file: sub.env_file.map(std::path::PathBuf::from),
                 cwd: sub.cwd.map(std::path::PathBuf::from),
                 fail_on_non_zero: Flag::from(sub.fail_on_non_zero),
             })
@@ -122,7 +283,7 @@ fn convert_task_kind(kind: proto::task_kind::Kind) -> Result<TaskKind, ApiError>
             Ok(TaskKind::Wasm {
                 module: std::path::PathBuf::from(wasm.module),
                 args: wasm.args,
-                env: convert_env(wasm.env),
+                env: convert_env(wasm.env)?,
             })
         }
         proto::task_kind::Kind::Container(cont) => {
@@ -132,19 +293,36 @@ fn convert_task_kind(kind: proto::task_kind::Kind) -> Result<TaskKind, ApiError>
 
             Ok(TaskKind::Container {
                 image: cont.image,
-                command: if cont.command.is_empty() {
-                    None
-                } else {
-                    Some(cont.command)
-                },
+                command: cont.command.map(|c| c.argv),
                 args: cont.args,
-                env: convert_env(cont.env),
+                env: convert_env(cont.env)?,
+            })
+        }
+        proto::task_kind::Kind::Builtin(builtin) => {
+            if builtin.name.trim().is_empty() {
+                return Err(ApiError::InvalidRequest("builtin name is empty".into()));
+            }
+
+            Ok(TaskKind::Builtin {
+                name: builtin.name,
+                args: convert_key_values(builtin.args),
             })
         }
     }
 }
 
-fn convert_env(kvs: Vec<proto::KeyValue>) -> TaskEnv {
+/// Convert a proto env list, rejecting keys that cannot be passed to a process (see [`TaskEnv::validate`]).
+fn convert_env(kvs: Vec<proto::KeyValue>) -> Result<TaskEnv, ApiError> {
+    let env = convert_key_values(kvs);
+    env.validate()
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+    Ok(env)
+}
+
+/// Convert a proto key-value list, keeping its order and duplicate keys.
+///
+/// Later entries override earlier ones (see [`TaskEnv::get`]), so the order must survive conversion.
+fn convert_key_values(kvs: Vec<proto::KeyValue>) -> TaskEnv {
     let mut env = TaskEnv::new();
     for kv in kvs {
         env.push(kv.key, kv.value);
@@ -155,11 +333,23 @@ fn convert_env(kvs: Vec<proto::KeyValue>) -> TaskEnv {
 fn convert_restart_strategy(
     strategy: proto::RestartStrategy,
     interval_ms: Option<u64>,
+    max_attempts: Option<u32>,
 ) -> Result<RestartStrategy, ApiError> {
+    if max_attempts == Some(0) {
+        return Err(ApiError::InvalidRequest(
+            "restart_max_attempts must be positive".into(),
+        ));
+    }
     match strategy {
+        proto::RestartStrategy::Never if max_attempts.is_some() => Err(ApiError::InvalidRequest(
+            "restart_max_attempts is not applicable to RESTART_STRATEGY_NEVER".into(),
+        )),
         proto::RestartStrategy::Never => Ok(RestartStrategy::Never),
-        proto::RestartStrategy::OnFailure => Ok(RestartStrategy::OnFailure),
-        proto::RestartStrategy::Always => Ok(RestartStrategy::Always { interval_ms }),
+        proto::RestartStrategy::OnFailure => Ok(RestartStrategy::OnFailure { max_attempts }),
+        proto::RestartStrategy::Always => Ok(RestartStrategy::Always {
+            interval_ms,
+            max_attempts,
+        }),
         proto::RestartStrategy::Unspecified => Err(ApiError::InvalidRequest(
             "restart strategy not specified".into(),
         )),
@@ -198,12 +388,16 @@ fn convert_backoff_strategy(backoff: proto::BackoffStrategy) -> Result<BackoffSt
         ));
     }
 
-    Ok(BackoffStrategy {
+    let backoff = BackoffStrategy {
         jitter,
         first_ms: backoff.first_ms,
         max_ms: backoff.max_ms,
         factor: backoff.factor,
-    })
+    };
+    backoff
+        .validate()
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+    Ok(backoff)
 }
 
 fn convert_admission_strategy(
@@ -219,12 +413,22 @@ fn convert_admission_strategy(
     }
 }
 
-fn convert_labels(map: std::collections::HashMap<String, String>) -> RunnerLabels {
+/// Convert proto labels; keys are unique and stored sorted, so the map's iteration order does not matter.
+fn convert_labels(
+    map: std::collections::HashMap<String, String>,
+) -> Result<RunnerLabels, ApiError> {
+    let limits = LabelLimits::default();
+    limits
+        .check_count(map.len())
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
     let mut labels = RunnerLabels::new();
     for (k, v) in map {
-        labels.insert(k, v);
+        labels
+            .insert_with_limits(k, v, &limits)
+            .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
     }
-    labels
+    Ok(labels)
 }
 
 fn validate_slot(slot: String) -> Result<String, ApiError> {
@@ -240,3 +444,161 @@ fn validate_timeout(timeout_ms: u64) -> Result<u64, ApiError> {
     }
     Ok(timeout_ms)
 }
+
+fn validate_idempotency_key(key: Option<String>) -> Result<Option<String>, ApiError> {
+    if key.as_deref().is_some_and(|k| k.trim().is_empty()) {
+        return Err(ApiError::InvalidRequest(
+            "idempotency_key cannot be empty".into(),
+        ));
+    }
+    Ok(key)
+}
+
+fn validate_batch_id(batch_id: Option<String>) -> Result<Option<String>, ApiError> {
+    if batch_id.as_deref().is_some_and(|b| b.trim().is_empty()) {
+        return Err(ApiError::InvalidRequest("batch_id cannot be empty".into()));
+    }
+    Ok(batch_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kv(key: &str, value: &str) -> proto::KeyValue {
+        proto::KeyValue {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    fn subprocess_spec(env: Vec<proto::KeyValue>) -> proto::CreateSpec {
+        proto::CreateSpec {
+            slot: "env-slot".into(),
+            kind: Some(proto::TaskKind {
+                kind: Some(proto::task_kind::Kind::Subprocess(proto::SubprocessTask {
+                    command: "env".into(),
+                    env,
+                    ..Default::default()
+                })),
+            }),
+            timeout_ms: 1_000,
+            restart: proto::RestartStrategy::Never as i32,
+            backoff: Some(proto::BackoffStrategy {
+                jitter: proto::JitterStrategy::None as i32,
+                first_ms: 100,
+                max_ms: 1_000,
+                factor: 2.0,
+            }),
+            admission: proto::AdmissionStrategy::DropIfRunning as i32,
+            ..Default::default()
+        }
+    }
+
+    fn container_spec(command: Option<proto::ContainerCommand>) -> proto::CreateSpec {
+        let mut spec = subprocess_spec(Vec::new());
+        spec.kind = Some(proto::TaskKind {
+            kind: Some(proto::task_kind::Kind::Container(proto::ContainerTask {
+                image: "redis:7".into(),
+                command,
+                ..Default::default()
+            })),
+        });
+        spec
+    }
+
+    fn container_command(spec: proto::CreateSpec) -> Option<Vec<String>> {
+        match CreateSpec::try_from(spec).unwrap().kind {
+            TaskKind::Container { command, .. } => command,
+            other => panic!("expected container kind, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn task_info_timestamps_keep_milliseconds() {
+        use std::time::{Duration, UNIX_EPOCH};
+        use tno_model::TaskId;
+
+        let info = TaskInfo {
+            id: TaskId::from("task-1"),
+            slot: "slot".into(),
+            status: TaskStatus::Succeeded,
+            attempt: 1,
+            created_at: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+            updated_at: UNIX_EPOCH + Duration::from_millis(1_700_000_000_987),
+            error: None,
+            exit_code: Some(0),
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
+        };
+
+        let proto = proto::TaskInfo::from(info);
+        assert_eq!(proto.created_at, 1_700_000_000);
+        assert_eq!(proto.updated_at, 1_700_000_000);
+        assert_eq!(proto.created_at_ms, 1_700_000_000_123);
+        assert_eq!(proto.updated_at_ms - proto.created_at_ms, 864);
+    }
+
+    #[test]
+    fn container_empty_command_is_distinct_from_absent() {
+        assert_eq!(container_command(container_spec(None)), None);
+        assert_eq!(
+            container_command(container_spec(Some(proto::ContainerCommand::default()))),
+            Some(Vec::new())
+        );
+        assert_eq!(
+            container_command(container_spec(Some(proto::ContainerCommand {
+                argv: vec!["redis-server".into()],
+            }))),
+            Some(vec!["redis-server".to_string()])
+        );
+    }
+
+    #[test]
+    fn unpinned_image_is_rejected_only_when_required() {
+        assert!(convert_spec(container_spec(None), false).is_ok());
+        let err = convert_spec(container_spec(None), true).unwrap_err();
+        assert!(matches!(err, ApiError::InvalidRequest(ref msg) if msg.contains("not pinned")));
+
+        let mut spec = container_spec(None);
+        if let Some(proto::task_kind::Kind::Container(container)) =
+            spec.kind.as_mut().and_then(|k| k.kind.as_mut())
+        {
+            container.image = format!("redis@sha256:{}", "ab".repeat(32));
+        }
+        assert!(convert_spec(spec, true).is_ok());
+        assert!(convert_spec(subprocess_spec(Vec::new()), true).is_ok());
+    }
+
+    #[test]
+    fn env_duplicates_keep_order_and_last_wins() {
+        let spec = subprocess_spec(vec![kv("FOO", "one"), kv("BAR", "x"), kv("FOO", "two")]);
+
+        let spec = CreateSpec::try_from(spec).unwrap();
+        let TaskKind::Subprocess { env, .. } = spec.kind else {
+            panic!("expected subprocess kind");
+        };
+
+        let pairs: Vec<_> = env.iter().map(|kv| (kv.key(), kv.value())).collect();
+        assert_eq!(pairs, [("FOO", "one"), ("BAR", "x"), ("FOO", "two")]);
+        assert_eq!(env.get("FOO"), Some("two"));
+    }
+
+    #[test]
+    fn invalid_env_keys_are_rejected() {
+        for key in ["FOO=BAR", "FOO\0BAR"] {
+            let err = CreateSpec::try_from(subprocess_spec(vec![kv(key, "x")])).unwrap_err();
+            assert!(
+                matches!(err, ApiError::InvalidRequest(_)),
+                "{key:?}: {err:?}"
+            );
+        }
+    }
+}
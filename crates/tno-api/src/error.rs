@@ -21,6 +21,21 @@ impl From<ApiError> for tonic::Status {
         match err {
             ApiError::InvalidRequest(msg) => tonic::Status::invalid_argument(msg),
             ApiError::TaskNotFound(msg) => tonic::Status::not_found(msg),
+            ApiError::Core(
+                e @ (tno_core::CoreError::TaskNotFound(_) | tno_core::CoreError::BatchNotFound(_)),
+            ) => tonic::Status::not_found(e.to_string()),
+            ApiError::Core(e @ tno_core::CoreError::AtCapacity(_)) => {
+                tonic::Status::resource_exhausted(e.to_string())
+            }
+            ApiError::Core(
+                e @ (tno_core::CoreError::SlotBusy(_) | tno_core::CoreError::DuplicateTaskId(_)),
+            ) => tonic::Status::already_exists(e.to_string()),
+            ApiError::Core(e @ tno_core::CoreError::PolicyUpdate { .. }) => {
+                tonic::Status::failed_precondition(e.to_string())
+            }
+            ApiError::Core(e @ tno_core::CoreError::Model(_)) => {
+                tonic::Status::invalid_argument(e.to_string())
+            }
             ApiError::Internal(msg) => tonic::Status::internal(format!("internal error: {}", msg)),
             ApiError::Core(e) => tonic::Status::internal(format!("core error: {}", e)),
         }
@@ -35,6 +50,21 @@ impl axum::response::IntoResponse for ApiError {
         let (status, message) = match self {
             ApiError::InvalidRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::TaskNotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::Core(
+                e @ (tno_core::CoreError::TaskNotFound(_) | tno_core::CoreError::BatchNotFound(_)),
+            ) => (StatusCode::NOT_FOUND, e.to_string()),
+            ApiError::Core(e @ tno_core::CoreError::AtCapacity(_)) => {
+                (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+            }
+            ApiError::Core(
+                e @ (tno_core::CoreError::SlotBusy(_) | tno_core::CoreError::DuplicateTaskId(_)),
+            ) => (StatusCode::CONFLICT, e.to_string()),
+            ApiError::Core(e @ tno_core::CoreError::PolicyUpdate { .. }) => {
+                (StatusCode::CONFLICT, e.to_string())
+            }
+            ApiError::Core(e @ tno_core::CoreError::Model(_)) => {
+                (StatusCode::BAD_REQUEST, e.to_string())
+            }
             ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             ApiError::Core(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
         };
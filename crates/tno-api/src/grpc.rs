@@ -1,16 +1,31 @@
 use std::sync::Arc;
 
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
+use tracing::debug;
 
+use crate::convert::{
+    convert_policy_update, convert_spec, convert_task_query, task_status_from_proto,
+};
 use crate::error::ApiError;
 use crate::handler::ApiHandler;
+use crate::page::page_size;
 use crate::proto::{self, tno_api_server::TnoApi};
+use crate::readiness::ReadinessMode;
+
+/// Updates buffered per `WatchTask` stream before the watch waits for the client.
+///
+/// A client that stays behind skips intermediate updates (see [`tno_core::TaskWatch`]).
+const WATCH_STREAM_BUFFER: usize = 16;
 
 /// gRPC service implementation.
 ///
 /// This struct wraps an `ApiHandler` and implements the generated `TnoApi` trait.
 pub struct TnoApiService<H> {
     handler: Arc<H>,
+    readiness: ReadinessMode,
+    pinned_images: bool,
 }
 
 impl<H> TnoApiService<H>
@@ -19,7 +34,25 @@ where
 {
     /// Create a new gRPC service with the given handler.
     pub fn new(handler: Arc<H>) -> Self {
-        Self { handler }
+        Self {
+            handler,
+            readiness: ReadinessMode::default(),
+            pinned_images: false,
+        }
+    }
+
+    /// Set what `GetReadiness` takes into account (default: [`ReadinessMode::SupervisorOnly`]).
+    pub fn with_readiness(mut self, mode: ReadinessMode) -> Self {
+        self.readiness = mode;
+        self
+    }
+
+    /// Reject container tasks whose image is not pinned by digest (default: `false`).
+    ///
+    /// See [`tno_model::TaskKind::validate_image`].
+    pub fn with_pinned_images(mut self, required: bool) -> Self {
+        self.pinned_images = required;
+        self
     }
 }
 
@@ -28,6 +61,8 @@ impl<H> TnoApi for TnoApiService<H>
 where
     H: ApiHandler,
 {
+    type WatchTaskStream = ReceiverStream<Result<proto::WatchTaskResponse, Status>>;
+
     async fn submit_task(
         &self,
         request: Request<proto::SubmitTaskRequest>,
@@ -38,15 +73,61 @@ where
             .spec
             .ok_or_else(|| Status::invalid_argument("missing spec"))?;
 
-        let spec = tno_model::CreateSpec::try_from(spec).map_err(|e: ApiError| Status::from(e))?;
+        let spec = convert_spec(spec, self.pinned_images).map_err(|e: ApiError| Status::from(e))?;
 
-        let task_id = self.handler.submit_task(spec).await.map_err(Status::from)?;
+        let submission = self.handler.submit_task(spec).await.map_err(Status::from)?;
 
         Ok(Response::new(proto::SubmitTaskResponse {
-            task_id: task_id.to_string(),
+            task_id: submission.task_id.to_string(),
+            admission: proto::AdmissionOutcome::from(submission.admission) as i32,
         }))
     }
 
+    async fn submit_tasks(
+        &self,
+        request: Request<proto::SubmitTasksRequest>,
+    ) -> Result<Response<proto::SubmitTasksResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.specs.is_empty() {
+            return Err(Status::invalid_argument("specs cannot be empty"));
+        }
+
+        let mut specs = Vec::with_capacity(req.specs.len());
+        for (i, spec) in req.specs.into_iter().enumerate() {
+            let spec = convert_spec(spec, self.pinned_images)
+                .map_err(|e| Status::invalid_argument(format!("specs[{i}]: {e}")))?;
+            specs.push(spec);
+        }
+
+        let report = self
+            .handler
+            .submit_many(specs)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(proto::SubmitTasksResponse::from(report)))
+    }
+
+    async fn validate_task(
+        &self,
+        request: Request<proto::ValidateTaskRequest>,
+    ) -> Result<Response<proto::ValidateTaskResponse>, Status> {
+        let spec = request
+            .into_inner()
+            .spec
+            .ok_or_else(|| Status::invalid_argument("missing spec"))?;
+        let spec = convert_spec(spec, self.pinned_images).map_err(Status::from)?;
+
+        let runner = self
+            .handler
+            .validate_task(spec)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(proto::ValidateTaskResponse { runner }))
+    }
+
     async fn get_task_status(
         &self,
         request: Request<proto::GetTaskStatusRequest>,
@@ -68,13 +149,24 @@ where
 
     async fn list_all_tasks(
         &self,
-        _request: Request<proto::ListAllTasksRequest>,
+        request: Request<proto::ListAllTasksRequest>,
     ) -> Result<Response<proto::ListAllTasksResponse>, Status> {
-        let tasks = self.handler.list_all_tasks().await.map_err(Status::from)?;
+        let req = request.into_inner();
 
-        let tasks = tasks.into_iter().map(proto::TaskInfo::from).collect();
+        let limit = page_size(req.limit.map(|n| n as usize)).map_err(Status::from)?;
+        let page = self
+            .handler
+            .list_tasks_page(req.offset as usize, limit)
+            .await
+            .map_err(Status::from)?;
 
-        Ok(Response::new(proto::ListAllTasksResponse { tasks }))
+        Ok(Response::new(proto::ListAllTasksResponse {
+            tasks: page.tasks.into_iter().map(proto::TaskInfo::from).collect(),
+            total: u32::try_from(page.total).unwrap_or(u32::MAX),
+            next_offset: page
+                .next_offset
+                .map(|n| u32::try_from(n).unwrap_or(u32::MAX)),
+        }))
     }
 
     async fn list_tasks_by_slot(
@@ -98,29 +190,34 @@ where
         Ok(Response::new(proto::ListTasksBySlotResponse { tasks }))
     }
 
+    async fn list_tasks_by_batch(
+        &self,
+        request: Request<proto::ListTasksByBatchRequest>,
+    ) -> Result<Response<proto::ListTasksByBatchResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.batch_id.trim().is_empty() {
+            return Err(Status::invalid_argument("batch_id cannot be empty"));
+        }
+
+        let tasks = self
+            .handler
+            .list_tasks_by_batch(&req.batch_id)
+            .await
+            .map_err(Status::from)?;
+
+        let tasks = tasks.into_iter().map(proto::TaskInfo::from).collect();
+
+        Ok(Response::new(proto::ListTasksByBatchResponse { tasks }))
+    }
+
     async fn list_tasks_by_status(
         &self,
         request: Request<proto::ListTasksByStatusRequest>,
     ) -> Result<Response<proto::ListTasksByStatusResponse>, Status> {
         let req = request.into_inner();
 
-        let status = proto::TaskStatus::try_from(req.status)
-            .map_err(|_| Status::invalid_argument("invalid status"))?;
-
-        if status == proto::TaskStatus::Unspecified {
-            return Err(Status::invalid_argument("status cannot be unspecified"));
-        }
-
-        let domain_status = match status {
-            proto::TaskStatus::Pending => tno_model::TaskStatus::Pending,
-            proto::TaskStatus::Running => tno_model::TaskStatus::Running,
-            proto::TaskStatus::Succeeded => tno_model::TaskStatus::Succeeded,
-            proto::TaskStatus::Failed => tno_model::TaskStatus::Failed,
-            proto::TaskStatus::Timeout => tno_model::TaskStatus::Timeout,
-            proto::TaskStatus::Canceled => tno_model::TaskStatus::Canceled,
-            proto::TaskStatus::Exhausted => tno_model::TaskStatus::Exhausted,
-            proto::TaskStatus::Unspecified => unreachable!(),
-        };
+        let domain_status = task_status_from_proto(req.status).map_err(Status::from)?;
 
         let tasks = self
             .handler
@@ -133,6 +230,23 @@ where
         Ok(Response::new(proto::ListTasksByStatusResponse { tasks }))
     }
 
+    async fn list_tasks(
+        &self,
+        request: Request<proto::ListTasksRequest>,
+    ) -> Result<Response<proto::ListTasksResponse>, Status> {
+        let query = convert_task_query(request.into_inner()).map_err(Status::from)?;
+
+        let tasks = self
+            .handler
+            .query_tasks(query)
+            .await
+            .map_err(Status::from)?;
+
+        let tasks = tasks.into_iter().map(proto::TaskInfo::from).collect();
+
+        Ok(Response::new(proto::ListTasksResponse { tasks }))
+    }
+
     async fn cancel_task(
         &self,
         request: Request<proto::CancelTaskRequest>,
@@ -152,4 +266,122 @@ where
 
         Ok(Response::new(proto::CancelTaskResponse {}))
     }
+
+    async fn cancel_batch(
+        &self,
+        request: Request<proto::CancelBatchRequest>,
+    ) -> Result<Response<proto::CancelBatchResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.batch_id.trim().is_empty() {
+            return Err(Status::invalid_argument("batch_id cannot be empty"));
+        }
+
+        let cancelled = self
+            .handler
+            .cancel_batch(&req.batch_id)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(proto::CancelBatchResponse {
+            task_ids: cancelled.into_iter().map(|id| id.to_string()).collect(),
+        }))
+    }
+
+    async fn update_policy(
+        &self,
+        request: Request<proto::UpdatePolicyRequest>,
+    ) -> Result<Response<proto::UpdatePolicyResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.task_id.trim().is_empty() {
+            return Err(Status::invalid_argument("task_id cannot be empty"));
+        }
+
+        let task_id = tno_model::TaskId::from(req.task_id.clone());
+        let (restart, backoff) = convert_policy_update(req).map_err(Status::from)?;
+
+        self.handler
+            .update_policy(&task_id, restart, backoff)
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(proto::UpdatePolicyResponse {}))
+    }
+
+    async fn watch_task(
+        &self,
+        request: Request<proto::WatchTaskRequest>,
+    ) -> Result<Response<Self::WatchTaskStream>, Status> {
+        let req = request.into_inner();
+
+        if req.task_id.trim().is_empty() {
+            return Err(Status::invalid_argument("task_id cannot be empty"));
+        }
+
+        let task_id = tno_model::TaskId::from(req.task_id);
+
+        let mut watch = self
+            .handler
+            .watch_task(&task_id)
+            .await
+            .map_err(Status::from)?;
+
+        let (tx, rx) = mpsc::channel(WATCH_STREAM_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                // Drop the watch as soon as the client disconnects, even while the task is idle.
+                let info = tokio::select! {
+                    _ = tx.closed() => break,
+                    info = watch.next() => info,
+                };
+                let Some(info) = info else {
+                    break;
+                };
+                let update = proto::WatchTaskResponse {
+                    info: Some(proto::TaskInfo::from(info)),
+                };
+                if tx.send(Ok(update)).await.is_err() {
+                    break;
+                }
+            }
+            debug!(task_id = %watch.task_id(), "task watch stream closed");
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn get_readiness(
+        &self,
+        _request: Request<proto::GetReadinessRequest>,
+    ) -> Result<Response<proto::GetReadinessResponse>, Status> {
+        let readiness = self
+            .handler
+            .readiness(self.readiness)
+            .await
+            .map_err(Status::from)?;
+
+        if !readiness.ready {
+            return Err(Status::unavailable(format!(
+                "not ready: {}",
+                readiness.failing.join("; ")
+            )));
+        }
+        Ok(Response::new(proto::GetReadinessResponse { ready: true }))
+    }
+
+    async fn list_runner_errors(
+        &self,
+        _request: Request<proto::ListRunnerErrorsRequest>,
+    ) -> Result<Response<proto::ListRunnerErrorsResponse>, Status> {
+        let errors = self
+            .handler
+            .list_runner_errors()
+            .await
+            .map_err(Status::from)?;
+
+        let errors = errors.into_iter().map(proto::RunnerError::from).collect();
+
+        Ok(Response::new(proto::ListRunnerErrorsResponse { errors }))
+    }
 }
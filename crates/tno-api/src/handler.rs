@@ -1,7 +1,13 @@
 use async_trait::async_trait;
-use tno_model::{CreateSpec, TaskId, TaskInfo, TaskStatus};
+use tno_core::{CoreError, TaskWatch, UNKNOWN_RUNNER};
+use tno_model::{
+    BackoffStrategy, CreateSpec, DashboardSnapshot, HealthStatus, RestartStrategy, RunnerErrorInfo,
+    Submission, TaskFilter, TaskId, TaskInfo, TaskPage, TaskQuery, TaskStatus,
+};
 
+use crate::bulk::BulkSubmitReport;
 use crate::error::ApiError;
+use crate::readiness::{Readiness, ReadinessMode, StatusReport, SubsystemStatus};
 
 /// Task execution API handler.
 ///
@@ -11,7 +17,32 @@ use crate::error::ApiError;
 #[async_trait]
 pub trait ApiHandler: Send + Sync + 'static {
     /// Submit a new task for execution.
-    async fn submit_task(&self, spec: CreateSpec) -> Result<TaskId, ApiError>;
+    ///
+    /// The returned [`Submission`] carries the task ID and the admission decision for it.
+    async fn submit_task(&self, spec: CreateSpec) -> Result<Submission, ApiError>;
+
+    /// Submit several tasks at once.
+    ///
+    /// A failing spec does not abort the batch; failures are reported per spec.
+    /// The default implementation calls [`ApiHandler::submit_task`] for each spec in order.
+    async fn submit_many(&self, specs: Vec<CreateSpec>) -> Result<BulkSubmitReport, ApiError> {
+        let mut results = Vec::with_capacity(specs.len());
+        for spec in specs {
+            results.push(self.submit_task(spec).await);
+        }
+        Ok(BulkSubmitReport::from_results(results))
+    }
+
+    /// Check that a spec would be accepted by a runner, without submitting it.
+    ///
+    /// Returns the name of the runner that would build the task.
+    /// The default implementation does not support validation.
+    async fn validate_task(&self, spec: CreateSpec) -> Result<String, ApiError> {
+        let _ = spec;
+        Err(ApiError::Internal(
+            "task validation is not supported".into(),
+        ))
+    }
 
     /// Get current status of a task by ID.
     async fn get_task_status(&self, id: &TaskId) -> Result<Option<TaskInfo>, ApiError>;
@@ -19,15 +50,142 @@ pub trait ApiHandler: Send + Sync + 'static {
     /// List all tasks.
     async fn list_all_tasks(&self) -> Result<Vec<TaskInfo>, ApiError>;
 
+    /// List one page of all tasks, ordered by creation time (see [`TaskPage`]).
+    ///
+    /// The default implementation pages [`ApiHandler::list_all_tasks`].
+    async fn list_tasks_page(&self, offset: usize, limit: usize) -> Result<TaskPage, ApiError> {
+        let tasks = self.list_all_tasks().await?;
+        Ok(TaskPage::collect(&tasks, offset, limit))
+    }
+
     /// List tasks in a specific slot.
     async fn list_tasks_by_slot(&self, slot: &str) -> Result<Vec<TaskInfo>, ApiError>;
 
+    /// List tasks matching a combined slot and status filter.
+    ///
+    /// The default implementation filters [`ApiHandler::list_all_tasks`].
+    async fn list_tasks_filtered(&self, filter: &TaskFilter) -> Result<Vec<TaskInfo>, ApiError> {
+        let mut tasks = self.list_all_tasks().await?;
+        tasks.retain(|t| filter.matches(t));
+        Ok(tasks)
+    }
+
+    /// List tasks matching a query (status, slot, label and batch filters) in the requested order.
+    ///
+    /// The default implementation filters and sorts [`ApiHandler::list_all_tasks`].
+    async fn query_tasks(&self, query: TaskQuery) -> Result<Vec<TaskInfo>, ApiError> {
+        let mut tasks = self.list_all_tasks().await?;
+        query.apply(&mut tasks);
+        Ok(tasks)
+    }
+
+    /// List tasks submitted with the given batch ID.
+    ///
+    /// The default implementation filters [`ApiHandler::list_all_tasks`].
+    async fn list_tasks_by_batch(&self, batch_id: &str) -> Result<Vec<TaskInfo>, ApiError> {
+        let mut tasks = self.list_all_tasks().await?;
+        tasks.retain(|t| t.batch_id.as_deref() == Some(batch_id));
+        Ok(tasks)
+    }
+
     /// List tasks by status.
     async fn list_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<TaskInfo>, ApiError>;
 
+    /// Get an aggregated overview of all tasks with up to `recent` most recently finished tasks.
+    ///
+    /// The default implementation aggregates [`ApiHandler::list_all_tasks`].
+    async fn dashboard(&self, recent: usize) -> Result<DashboardSnapshot, ApiError> {
+        let tasks = self.list_all_tasks().await?;
+        Ok(DashboardSnapshot::collect(
+            tasks
+                .iter()
+                .map(|t| (t, t.runner_name.as_deref().unwrap_or(UNKNOWN_RUNNER))),
+            recent,
+        ))
+    }
+
     /// Cancel a running task.
     ///
     /// Sends cancellation signal to the task. The task must cooperate
     /// by checking its `CancellationToken`.
     async fn cancel_task(&self, id: &TaskId) -> Result<(), ApiError>;
+
+    /// Cancel all non-terminal tasks of a batch and return their IDs.
+    ///
+    /// The default implementation calls [`ApiHandler::cancel_task`] for each task listed by
+    /// [`ApiHandler::list_tasks_by_batch`], skipping tasks that finish in the meantime.
+    async fn cancel_batch(&self, batch_id: &str) -> Result<Vec<TaskId>, ApiError> {
+        let tasks = self.list_tasks_by_batch(batch_id).await?;
+        if tasks.is_empty() {
+            return Err(CoreError::BatchNotFound(batch_id.to_string()).into());
+        }
+
+        let mut cancelled = Vec::new();
+        for info in tasks.into_iter().filter(|t| !t.status.is_terminal()) {
+            match self.cancel_task(&info.id).await {
+                Ok(()) => cancelled.push(info.id),
+                Err(ApiError::TaskNotFound(_) | ApiError::Core(CoreError::TaskNotFound(_))) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Change the restart and backoff policy of a live task going forward (see [`tno_core::SupervisorApi::update_policy`]).
+    ///
+    /// The default implementation does not support policy updates.
+    async fn update_policy(
+        &self,
+        id: &TaskId,
+        restart: RestartStrategy,
+        backoff: BackoffStrategy,
+    ) -> Result<(), ApiError> {
+        let _ = (id, restart, backoff);
+        Err(ApiError::Internal("policy update is not supported".into()))
+    }
+
+    /// Watch status changes of a task until it reaches a terminal status.
+    ///
+    /// The default implementation does not support watching.
+    async fn watch_task(&self, id: &TaskId) -> Result<TaskWatch, ApiError> {
+        let _ = id;
+        Err(ApiError::Internal("task watch is not supported".into()))
+    }
+
+    /// Most recent runner-level errors (spawn or runner config failures), newest first.
+    ///
+    /// The default implementation reports none.
+    async fn list_runner_errors(&self) -> Result<Vec<RunnerErrorInfo>, ApiError> {
+        Ok(Vec::new())
+    }
+
+    /// Report readiness according to the given mode.
+    ///
+    /// The default implementation always reports ready.
+    async fn readiness(&self, mode: ReadinessMode) -> Result<Readiness, ApiError> {
+        let _ = mode;
+        Ok(Readiness::ready())
+    }
+
+    /// Report whether the supervisor is alive and how many tasks it is running or holding.
+    ///
+    /// The default implementation reports alive, with counts taken from [`ApiHandler::list_all_tasks`].
+    async fn health(&self) -> Result<HealthStatus, ApiError> {
+        let tasks = self.list_all_tasks().await?;
+        let count = |status| tasks.iter().filter(|t| t.status == status).count();
+        Ok(HealthStatus::new(
+            None,
+            count(TaskStatus::Running),
+            count(TaskStatus::Pending),
+        ))
+    }
+
+    /// Report the health of each subsystem (supervisor, runners, metrics backend).
+    ///
+    /// The default implementation reports a healthy supervisor only.
+    async fn status(&self) -> Result<StatusReport, ApiError> {
+        Ok(StatusReport::from_subsystems(vec![
+            SubsystemStatus::from_result("supervisor", Ok::<(), String>(())),
+        ]))
+    }
 }
@@ -3,17 +3,27 @@ use std::sync::Arc;
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
+    http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, patch, post},
 };
 use serde::{Deserialize, Serialize};
-use tno_model::{CreateSpec, TaskId, TaskInfo, TaskStatus};
+use tno_model::{
+    AdmissionOutcome, BackoffStrategy, CreateSpec, DEFAULT_DASHBOARD_RECENT, LabelLimits,
+    RestartStrategy, RunnerErrorInfo, SortDirection, TaskFilter, TaskId, TaskInfo, TaskKind,
+    TaskQuery, TaskSortKey, TaskStatus,
+};
+
+use crate::{error::ApiError, handler::ApiHandler, page::page_size, readiness::ReadinessMode};
 
-use crate::{error::ApiError, handler::ApiHandler};
+/// Upper bound for `?recent=` on the dashboard endpoint.
+const MAX_DASHBOARD_RECENT: usize = 1_000;
 
 /// HTTP API service builder.
 pub struct HttpApi<H> {
     handler: Arc<H>,
+    readiness: ReadinessMode,
+    pinned_images: bool,
 }
 
 impl<H> HttpApi<H>
@@ -22,21 +32,72 @@ where
 {
     /// Create new HTTP API with the given handler.
     pub fn new(handler: Arc<H>) -> Self {
-        Self { handler }
+        Self {
+            handler,
+            readiness: ReadinessMode::default(),
+            pinned_images: false,
+        }
+    }
+
+    /// Set what `/readyz` takes into account (default: [`ReadinessMode::SupervisorOnly`]).
+    pub fn with_readiness(mut self, mode: ReadinessMode) -> Self {
+        self.readiness = mode;
+        self
+    }
+
+    /// Reject container tasks whose image is not pinned by digest (default: `false`).
+    ///
+    /// See [`tno_model::TaskKind::validate_image`].
+    pub fn with_pinned_images(mut self, required: bool) -> Self {
+        self.pinned_images = required;
+        self
     }
 
     /// Build axum router with mounted endpoints.
     ///
     /// Routes:
     /// - POST /api/v1/tasks - Submit task
+    /// - POST /api/v1/tasks/batch - Submit several tasks (per-spec results)
+    /// - POST /api/v1/tasks/validate - Check that a task can be built, without submitting it
     /// - GET /api/v1/tasks/:id - Get task status
     /// - GET /api/v1/tasks - List all tasks (or filter by query params)
+    /// - POST /api/v1/batches/:id/cancel - Cancel all tasks of a batch
+    /// - GET /api/v1/dashboard - Aggregated task overview
+    /// - GET /api/v1/runner-errors - Most recent runner-level errors
+    /// - GET /api/v1/status - Per-subsystem health report
+    /// - GET /readyz - Readiness probe (`503` when not ready)
+    /// - GET /healthz - Liveness probe with running/pending counts (`503` when the run loop is dead)
     pub fn router(self) -> Router {
+        let readiness = self.readiness;
+        let pinned = self.pinned_images;
+        let handler = Arc::clone(&self.handler);
+
         Router::new()
-            .route("/api/v1/tasks", post(submit_task::<H>))
+            .route(
+                "/readyz",
+                get(move || readyz::<H>(Arc::clone(&handler), readiness)),
+            )
+            .route("/healthz", get(healthz::<H>))
+            .route(
+                "/api/v1/tasks",
+                post(move |state, body| submit_task::<H>(state, body, pinned)),
+            )
+            .route(
+                "/api/v1/tasks/batch",
+                post(move |state, body| submit_tasks::<H>(state, body, pinned)),
+            )
+            .route(
+                "/api/v1/tasks/validate",
+                post(move |state, body| validate_task::<H>(state, body, pinned)),
+            )
             .route("/api/v1/tasks", get(list_tasks::<H>))
             .route("/api/v1/tasks/{id}", get(get_task_status::<H>))
             .route("/api/v1/tasks/{id}/cancel", post(cancel_task::<H>)) // НОВОЕ
+            .route("/api/v1/tasks/{id}/policy", patch(update_policy::<H>))
+            .route("/api/v1/batches/{id}/cancel", post(cancel_batch::<H>))
+            .route("/api/v1/dashboard", get(dashboard::<H>))
+            .route("/api/v1/runner-errors", get(list_runner_errors::<H>))
+            .route("/api/v1/status", get(status::<H>))
             .with_state(self.handler)
     }
 }
@@ -53,6 +114,17 @@ struct SubmitTaskRequest {
 #[derive(Debug, Serialize, Deserialize)]
 struct SubmitTaskResponse {
     task_id: String,
+    admission: AdmissionOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ValidateTaskResponse {
+    runner: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SubmitTasksRequest {
+    specs: Vec<CreateSpec>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,11 +139,51 @@ struct ListTasksQuery {
     slot: Option<String>,
     /// Filter by task status
     status: Option<String>,
+    /// Filter by batch ID
+    batch: Option<String>,
+    /// Filter by labels (`key=value`, comma-separated)
+    label: Option<String>,
+    /// Sort key
+    sort: Option<String>,
+    /// Sort direction
+    order: Option<String>,
+    /// Page size when listing all tasks
+    limit: Option<usize>,
+    /// Page start when listing all tasks
+    offset: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ListTasksResponse {
     tasks: Vec<TaskInfo>,
+    /// Number of tasks across all pages (unfiltered listing only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<usize>,
+    /// Offset of the next page (unfiltered listing only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UpdatePolicyRequest {
+    restart: RestartStrategy,
+    backoff: BackoffStrategy,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CancelBatchResponse {
+    cancelled: Vec<TaskId>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ListRunnerErrorsResponse {
+    errors: Vec<RunnerErrorInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DashboardQuery {
+    /// Number of recent terminal tasks to include
+    recent: Option<usize>,
 }
 
 // ============================================================================
@@ -82,19 +194,124 @@ struct ListTasksResponse {
 async fn submit_task<H>(
     State(handler): State<Arc<H>>,
     Json(req): Json<SubmitTaskRequest>,
+    pinned_images: bool,
 ) -> Result<impl IntoResponse, ApiError>
 where
     H: ApiHandler,
 {
-    let task_id = handler.submit_task(req.spec).await?;
+    validate_spec(&req.spec, pinned_images).map_err(ApiError::InvalidRequest)?;
+
+    let submission = handler.submit_task(req.spec).await?;
 
     let response = SubmitTaskResponse {
-        task_id: task_id.to_string(),
+        task_id: submission.task_id.to_string(),
+        admission: submission.admission,
     };
 
     Ok(Json(response))
 }
 
+/// POST /api/v1/tasks/validate
+///
+/// Builds the task with the runner it would be routed to, without submitting it.
+async fn validate_task<H>(
+    State(handler): State<Arc<H>>,
+    Json(req): Json<SubmitTaskRequest>,
+    pinned_images: bool,
+) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    validate_spec(&req.spec, pinned_images).map_err(ApiError::InvalidRequest)?;
+
+    let runner = handler.validate_task(req.spec).await?;
+    Ok(Json(ValidateTaskResponse { runner }))
+}
+
+/// POST /api/v1/tasks/batch
+///
+/// Malformed specs reject the whole request; submission failures are reported per spec.
+async fn submit_tasks<H>(
+    State(handler): State<Arc<H>>,
+    Json(req): Json<SubmitTasksRequest>,
+    pinned_images: bool,
+) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    if req.specs.is_empty() {
+        return Err(ApiError::InvalidRequest("specs cannot be empty".into()));
+    }
+    for (i, spec) in req.specs.iter().enumerate() {
+        validate_spec(spec, pinned_images)
+            .map_err(|e| ApiError::InvalidRequest(format!("specs[{i}]: {e}")))?;
+    }
+
+    let report = handler.submit_many(req.specs).await?;
+    Ok(Json(report))
+}
+
+/// Reject specs that can never be submitted through the API, before they reach the router.
+///
+/// With `pinned_images` container images must also be pinned by digest.
+fn validate_spec(spec: &CreateSpec, pinned_images: bool) -> Result<(), String> {
+    if matches!(spec.kind, TaskKind::None) {
+        return Err("task kind 'none' is not submittable via the API".into());
+    }
+    if spec.restart.max_attempts() == Some(0) {
+        return Err("restart maxAttempts must be positive".into());
+    }
+    if let Some(env) = spec.kind.env() {
+        env.validate().map_err(|e| e.to_string())?;
+    }
+    if pinned_images {
+        spec.kind.validate_image(true).map_err(|e| e.to_string())?;
+    }
+    spec.labels
+        .validate(&LabelLimits::default())
+        .map_err(|e| e.to_string())?;
+    spec.runner_selector
+        .validate(&LabelLimits::default())
+        .map_err(|e| format!("runner selector: {e}"))
+}
+
+/// GET /readyz
+async fn readyz<H>(handler: Arc<H>, mode: ReadinessMode) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    let readiness = handler.readiness(mode).await?;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((status, Json(readiness)))
+}
+
+/// GET /healthz
+async fn healthz<H>(State(handler): State<Arc<H>>) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    let health = handler.health().await?;
+    let status = if health.alive {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((status, Json(health)))
+}
+
+/// GET /api/v1/status
+async fn status<H>(State(handler): State<Arc<H>>) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    let report = handler.status().await?;
+    Ok((StatusCode::OK, Json(report)))
+}
+
 /// GET /api/v1/tasks/:id
 async fn get_task_status<H>(
     State(handler): State<Arc<H>>,
@@ -113,10 +330,15 @@ where
 
 /// GET /api/v1/tasks
 ///
-/// Query params:
+/// Query params (filters can be combined; a task must match all of them):
 /// - ?slot=name - filter by slot
-/// - ?status=running - filter by status
-/// - no params - list all tasks
+/// - ?status=running,pending - filter by status (any of the listed ones)
+/// - ?batch=id - filter by batch
+/// - ?label=team=infra,tier=gold - filter by labels (all of the listed ones)
+/// - ?sort=created_at|updated_at|attempt (default: created_at) and ?order=asc|desc (default: asc)
+/// - no filters and no sorting - list all tasks, one page at a time:
+///   ?limit=N (default: 100, max: 1000) and ?offset=N (default: 0);
+///   the response carries `total` and `next_offset` (absent on the last page)
 async fn list_tasks<H>(
     State(handler): State<Arc<H>>,
     Query(query): Query<ListTasksQuery>,
@@ -124,34 +346,130 @@ async fn list_tasks<H>(
 where
     H: ApiHandler,
 {
-    let tasks = match (query.slot, query.status) {
-        // Filter by slot
-        (Some(slot), None) => {
-            if slot.trim().is_empty() {
-                return Err(ApiError::InvalidRequest("slot cannot be empty".into()));
-            }
-            handler.list_tasks_by_slot(&slot).await?
-        }
-        // Filter by status
-        (None, Some(status_str)) => {
-            let status = parse_status(&status_str)?;
-            handler.list_tasks_by_status(status).await?
-        }
-        // Both filters - not supported
-        (Some(_), Some(_)) => {
-            return Err(ApiError::InvalidRequest(
-                "cannot filter by both slot and status simultaneously".into(),
-            ));
-        }
-        // No filters - list all
-        (None, None) => handler.list_all_tasks().await?,
+    let filtered = query.slot.is_some()
+        || query.status.is_some()
+        || query.batch.is_some()
+        || query.label.is_some();
+    let sorted = query.sort.is_some() || query.order.is_some();
+    let paged = query.limit.is_some() || query.offset.is_some();
+    if paged && (filtered || sorted) {
+        return Err(ApiError::InvalidRequest(
+            "limit and offset are only supported without filters or sorting".into(),
+        ));
+    }
+
+    if query.slot.as_deref().is_some_and(|s| s.trim().is_empty()) {
+        return Err(ApiError::InvalidRequest("slot cannot be empty".into()));
+    }
+    if query.batch.as_deref().is_some_and(|b| b.trim().is_empty()) {
+        return Err(ApiError::InvalidRequest("batch cannot be empty".into()));
+    }
+
+    // No filters - list one page of all tasks
+    if !filtered && !sorted {
+        let limit = page_size(query.limit)?;
+        let page = handler
+            .list_tasks_page(query.offset.unwrap_or(0), limit)
+            .await?;
+        return Ok(Json(ListTasksResponse {
+            tasks: page.tasks,
+            total: Some(page.total),
+            next_offset: page.next_offset,
+        }));
+    }
+
+    let mut filter = TaskFilter {
+        slot: query.slot,
+        statuses: query
+            .status
+            .as_deref()
+            .map(parse_statuses)
+            .transpose()?
+            .unwrap_or_default(),
+        batch_id: query.batch,
+        ..TaskFilter::default()
     };
+    if let Some(labels) = query.label.as_deref() {
+        for (key, value) in parse_label_selector(labels)? {
+            filter = filter.with_label(key, value);
+        }
+    }
+    let sort_by = query
+        .sort
+        .as_deref()
+        .map(parse_sort_key)
+        .transpose()?
+        .unwrap_or_default();
+    let direction = query
+        .order
+        .as_deref()
+        .map(parse_sort_direction)
+        .transpose()?
+        .unwrap_or_default();
 
-    let response = ListTasksResponse { tasks };
+    let tasks = handler
+        .query_tasks(TaskQuery::new(filter).sorted_by(sort_by, direction))
+        .await?;
+
+    let response = ListTasksResponse {
+        tasks,
+        total: None,
+        next_offset: None,
+    };
 
     Ok(Json(response))
 }
 
+/// Parse a comma-separated list of statuses (duplicates are ignored).
+fn parse_statuses(s: &str) -> Result<Vec<TaskStatus>, ApiError> {
+    let mut statuses = Vec::new();
+    for part in s.split(',') {
+        let status = parse_status(part.trim())?;
+        if !statuses.contains(&status) {
+            statuses.push(status);
+        }
+    }
+    Ok(statuses)
+}
+
+/// Parse a comma-separated list of `key=value` label selectors.
+fn parse_label_selector(s: &str) -> Result<Vec<(&str, &str)>, ApiError> {
+    s.split(',')
+        .map(|part| match part.trim().split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok((key.trim(), value.trim())),
+            _ => Err(ApiError::InvalidRequest(format!(
+                "invalid label selector: '{}' (expected key=value)",
+                part
+            ))),
+        })
+        .collect()
+}
+
+/// Parse TaskSortKey from string
+fn parse_sort_key(s: &str) -> Result<TaskSortKey, ApiError> {
+    match s.to_lowercase().as_str() {
+        "created_at" => Ok(TaskSortKey::CreatedAt),
+        "updated_at" => Ok(TaskSortKey::UpdatedAt),
+        "attempt" => Ok(TaskSortKey::Attempt),
+        _ => Err(ApiError::InvalidRequest(format!(
+            "invalid sort key: '{}' (valid: created_at, updated_at, attempt)",
+            s
+        ))),
+    }
+}
+
+/// Parse SortDirection from string
+fn parse_sort_direction(s: &str) -> Result<SortDirection, ApiError> {
+    match s.to_lowercase().as_str() {
+        "asc" => Ok(SortDirection::Ascending),
+        "desc" => Ok(SortDirection::Descending),
+        _ => Err(ApiError::InvalidRequest(format!(
+            "invalid sort order: '{}' (valid: asc, desc)",
+            s
+        ))),
+    }
+}
+
 /// Parse TaskStatus from string
 fn parse_status(s: &str) -> Result<TaskStatus, ApiError> {
     match s.to_lowercase().as_str() {
@@ -169,6 +487,37 @@ fn parse_status(s: &str) -> Result<TaskStatus, ApiError> {
     }
 }
 
+/// GET /api/v1/dashboard
+///
+/// Query params:
+/// - ?recent=N - number of recently finished tasks to include (default: 10, max: 1000)
+async fn dashboard<H>(
+    State(handler): State<Arc<H>>,
+    Query(query): Query<DashboardQuery>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    let recent = query.recent.unwrap_or(DEFAULT_DASHBOARD_RECENT);
+    if recent > MAX_DASHBOARD_RECENT {
+        return Err(ApiError::InvalidRequest(format!(
+            "recent cannot exceed {MAX_DASHBOARD_RECENT}"
+        )));
+    }
+
+    let snapshot = handler.dashboard(recent).await?;
+    Ok(Json(snapshot))
+}
+
+/// GET /api/v1/runner-errors
+async fn list_runner_errors<H>(State(handler): State<Arc<H>>) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    let errors = handler.list_runner_errors().await?;
+    Ok(Json(ListRunnerErrorsResponse { errors }))
+}
+
 /// POST /api/v1/tasks/:id/cancel
 async fn cancel_task<H>(
     State(handler): State<Arc<H>>,
@@ -186,3 +535,543 @@ where
 
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
+
+/// PATCH /api/v1/tasks/:id/policy
+///
+/// Applies from the end of the current run; see [`ApiHandler::update_policy`].
+async fn update_policy<H>(
+    State(handler): State<Arc<H>>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdatePolicyRequest>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    if id.trim().is_empty() {
+        return Err(ApiError::InvalidRequest("task_id cannot be empty".into()));
+    }
+
+    let task_id = TaskId::from(id);
+    handler
+        .update_policy(&task_id, req.restart, req.backoff)
+        .await?;
+
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/batches/:id/cancel
+///
+/// Responds with the IDs of the cancelled tasks.
+async fn cancel_batch<H>(
+    State(handler): State<Arc<H>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    H: ApiHandler,
+{
+    if id.trim().is_empty() {
+        return Err(ApiError::InvalidRequest("batch_id cannot be empty".into()));
+    }
+
+    let cancelled = handler.cancel_batch(&id).await?;
+    Ok(Json(CancelBatchResponse { cancelled }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use taskvisor::{ControllerConfig, SupervisorConfig, TaskRef};
+    use tno_core::{BuildContext, Runner, RunnerError, RunnerRouter, SupervisorApi};
+    use tower::ServiceExt;
+
+    use crate::SupervisorApiAdapter;
+
+    struct BrokenRunner;
+
+    impl Runner for BrokenRunner {
+        fn name(&self) -> &'static str {
+            "broken-runner"
+        }
+
+        fn supports(&self, _spec: &CreateSpec) -> bool {
+            false
+        }
+
+        fn build_task(
+            &self,
+            _spec: &CreateSpec,
+            _ctx: &BuildContext,
+        ) -> Result<TaskRef, RunnerError> {
+            Err(RunnerError::Internal("unreachable".into()))
+        }
+
+        fn health_check(&self) -> Result<(), RunnerError> {
+            Err(RunnerError::Internal("backend unavailable".into()))
+        }
+    }
+
+    async fn readyz_status(mode: ReadinessMode) -> StatusCode {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(BrokenRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let handler = Arc::new(SupervisorApiAdapter::new(Arc::new(api)));
+        let app = HttpApi::new(handler).with_readiness(mode).router();
+
+        let resp = app
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        resp.status()
+    }
+
+    struct NoopSubprocessRunner;
+
+    impl Runner for NoopSubprocessRunner {
+        fn name(&self) -> &'static str {
+            "noop-subprocess"
+        }
+
+        fn supports(&self, spec: &CreateSpec) -> bool {
+            matches!(spec.kind, tno_model::TaskKind::Subprocess { .. })
+        }
+
+        fn build_task(
+            &self,
+            spec: &CreateSpec,
+            _ctx: &BuildContext,
+        ) -> Result<TaskRef, RunnerError> {
+            Ok(taskvisor::TaskFn::arc(
+                self.build_run_id(&spec.slot),
+                |_ctx: tokio_util::sync::CancellationToken| async move {
+                    Ok::<(), taskvisor::TaskError>(())
+                },
+            ))
+        }
+    }
+
+    fn spec(slot: &str, kind: tno_model::TaskKind) -> serde_json::Value {
+        serde_json::to_value(CreateSpec {
+            slot: slot.into(),
+            kind,
+            timeout_ms: 1_000,
+            restart: tno_model::RestartStrategy::Never,
+            backoff: tno_model::BackoffStrategy {
+                jitter: tno_model::JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: tno_model::AdmissionStrategy::DropIfRunning,
+            labels: tno_model::RunnerLabels::default(),
+            runner_selector: tno_model::RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn batch_submit_reports_mixed_results() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(NoopSubprocessRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::new(api)))).router();
+
+        let subprocess = tno_model::TaskKind::Subprocess {
+            command: "true".into(),
+            args: Vec::new(),
+            env: Default::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Default::default(),
+        };
+        let wasm = tno_model::TaskKind::Wasm {
+            module: "/nowhere.wasm".into(),
+            args: Vec::new(),
+            env: Default::default(),
+        };
+        let body = serde_json::json!({
+            "specs": [spec("a", subprocess.clone()), spec("b", wasm), spec("c", subprocess)]
+        });
+
+        let resp = app
+            .oneshot(
+                Request::post("/api/v1/tasks/batch")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: crate::BulkSubmitReport = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(report.succeeded, 2);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.results.len(), 3);
+        assert!(report.results[1].error.is_some());
+        assert!(report.results[1].task_id.is_none());
+        assert!(report.results[2].task_id.is_some());
+        assert_eq!(
+            report.results[2].admission,
+            Some(tno_model::AdmissionOutcome::Admitted)
+        );
+        assert!(report.results[1].admission.is_none());
+    }
+
+    #[tokio::test]
+    async fn validate_reports_runner_without_submitting() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(NoopSubprocessRunner)).unwrap();
+        let api = Arc::new(
+            SupervisorApi::new(
+                SupervisorConfig::default(),
+                ControllerConfig::default(),
+                Vec::new(),
+                router,
+            )
+            .await
+            .expect("failed to create SupervisorApi"),
+        );
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::clone(&api)))).router();
+
+        let validate = |kind: tno_model::TaskKind| {
+            let body = serde_json::json!({ "spec": spec("validate", kind) });
+            app.clone().oneshot(
+                Request::post("/api/v1/tasks/validate")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+        };
+
+        let resp = validate(tno_model::TaskKind::Subprocess {
+            command: "true".into(),
+            args: Vec::new(),
+            env: Default::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Default::default(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: ValidateTaskResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body.runner, "noop-subprocess");
+
+        let resp = validate(tno_model::TaskKind::Wasm {
+            module: "/nowhere.wasm".into(),
+            args: Vec::new(),
+            env: Default::default(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        assert!(api.list_all_tasks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_unknown_task_returns_not_found() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let handler = Arc::new(SupervisorApiAdapter::new(Arc::new(api)));
+        let app = HttpApi::new(handler).router();
+
+        let resp = app
+            .oneshot(
+                Request::post("/api/v1/tasks/missing-task/cancel")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn list_all_tasks_is_paginated() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        for slot in ["page-a", "page-b", "page-c"] {
+            let task: TaskRef = taskvisor::TaskFn::arc(
+                format!("{slot}-task"),
+                |ctx: tokio_util::sync::CancellationToken| async move {
+                    ctx.cancelled().await;
+                    Ok::<(), taskvisor::TaskError>(())
+                },
+            );
+            let policy = tno_core::TaskPolicy::new(
+                slot.to_string(),
+                60_000,
+                tno_model::RestartStrategy::Never,
+                tno_model::BackoffStrategy {
+                    jitter: tno_model::JitterStrategy::None,
+                    first_ms: 0,
+                    max_ms: 0,
+                    factor: 1.0,
+                },
+                tno_model::AdmissionStrategy::DropIfRunning,
+            );
+            api.submit_with_task(task, &policy).await.unwrap();
+        }
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::new(api)))).router();
+
+        let list = |uri: &str| {
+            let app = app.clone();
+            let req = Request::get(uri).body(Body::empty()).unwrap();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                let status = resp.status();
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).ok(),
+                )
+            }
+        };
+
+        let (status, first) = list("/api/v1/tasks?limit=2").await;
+        assert_eq!(status, StatusCode::OK);
+        let first = first.unwrap();
+        assert_eq!(first["tasks"].as_array().unwrap().len(), 2);
+        assert_eq!(first["total"], 3);
+        assert_eq!(first["next_offset"], 2);
+
+        let (_, last) = list("/api/v1/tasks?limit=2&offset=2").await;
+        let last = last.unwrap();
+        assert_eq!(last["tasks"].as_array().unwrap().len(), 1);
+        assert!(last.get("next_offset").is_none());
+
+        let (status, _) = list("/api/v1/tasks?limit=100000").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = list("/api/v1/tasks?slot=page-a&limit=1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn status_filter_accepts_comma_separated_list() {
+        assert_eq!(
+            parse_statuses("running, pending,RUNNING").unwrap(),
+            [TaskStatus::Running, TaskStatus::Pending]
+        );
+        assert!(parse_statuses("running,bogus").is_err());
+    }
+
+    #[test]
+    fn label_selector_and_sort_params_are_parsed() {
+        assert_eq!(
+            parse_label_selector("team=infra, tier=gold").unwrap(),
+            [("team", "infra"), ("tier", "gold")]
+        );
+        assert!(parse_label_selector("team").is_err());
+        assert_eq!(
+            parse_sort_key("updated_at").unwrap(),
+            TaskSortKey::UpdatedAt
+        );
+        assert_eq!(
+            parse_sort_direction("DESC").unwrap(),
+            SortDirection::Descending
+        );
+        assert!(parse_sort_key("name").is_err());
+    }
+
+    #[tokio::test]
+    async fn readyz_ignores_runners_in_supervisor_only_mode() {
+        assert_eq!(
+            readyz_status(ReadinessMode::SupervisorOnly).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn readyz_fails_on_unhealthy_runner_in_strict_mode() {
+        assert_eq!(
+            readyz_status(ReadinessMode::SupervisorAndRunners).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn healthz_reports_live_supervisor() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::new(api)))).router();
+
+        let resp = app
+            .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let health: tno_model::HealthStatus = serde_json::from_slice(&body).unwrap();
+        assert_eq!(health, tno_model::HealthStatus::new(None, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn status_reports_failing_runner_while_supervisor_is_ready() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(BrokenRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::new(api)))).router();
+
+        let resp = app
+            .oneshot(Request::get("/api/v1/status").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: crate::StatusReport = serde_json::from_slice(&body).unwrap();
+
+        assert!(!report.healthy);
+        let subsystem = |name: &str| {
+            report
+                .subsystems
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing subsystem {name}"))
+        };
+        assert!(subsystem("supervisor").healthy);
+        assert!(subsystem("metrics").healthy);
+        let runner = subsystem("runner:broken-runner");
+        assert!(!runner.healthy);
+        assert!(
+            runner
+                .error
+                .as_deref()
+                .is_some_and(|e| e.contains("backend unavailable"))
+        );
+    }
+
+    #[tokio::test]
+    async fn submit_none_kind_is_rejected_with_clear_error() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::new(api)))).router();
+
+        let body = serde_json::json!({ "spec": spec("none-slot", tno_model::TaskKind::None) });
+        assert_eq!(body["spec"]["kind"], "none");
+
+        let resp = app
+            .oneshot(
+                Request::post("/api/v1/tasks")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(
+            text.contains("task kind 'none' is not submittable via the API"),
+            "{text}"
+        );
+    }
+
+    #[tokio::test]
+    async fn unpinned_image_is_rejected_when_pinning_is_required() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let app = HttpApi::new(Arc::new(SupervisorApiAdapter::new(Arc::new(api))))
+            .with_pinned_images(true)
+            .router();
+
+        let kind = tno_model::TaskKind::Container {
+            image: "nginx:latest".into(),
+            command: None,
+            args: Vec::new(),
+            env: tno_model::TaskEnv::default(),
+        };
+        let body = serde_json::json!({ "spec": spec("image-slot", kind) });
+        let resp = app
+            .oneshot(
+                Request::post("/api/v1/tasks/validate")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(text.contains("not pinned by digest"), "{text}");
+    }
+}
@@ -12,6 +12,15 @@ pub use handler::ApiHandler;
 mod adapter;
 pub use adapter::SupervisorApiAdapter;
 
+mod readiness;
+pub use readiness::{Readiness, ReadinessMode, StatusReport, SubsystemStatus};
+
+mod bulk;
+pub use bulk::{BulkSubmitItem, BulkSubmitReport};
+
+#[cfg(any(feature = "grpc", feature = "http"))]
+mod page;
+
 #[cfg(feature = "grpc")]
 mod convert;
 
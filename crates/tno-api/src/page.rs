@@ -0,0 +1,16 @@
+use tno_model::{DEFAULT_TASK_PAGE_SIZE, MAX_TASK_PAGE_SIZE};
+
+use crate::error::ApiError;
+
+/// Resolve the requested page size of a task listing.
+///
+/// Defaults to [`DEFAULT_TASK_PAGE_SIZE`]; zero and sizes above [`MAX_TASK_PAGE_SIZE`] are rejected.
+pub(crate) fn page_size(limit: Option<usize>) -> Result<usize, ApiError> {
+    match limit.unwrap_or(DEFAULT_TASK_PAGE_SIZE) {
+        0 => Err(ApiError::InvalidRequest("limit must be positive".into())),
+        n if n > MAX_TASK_PAGE_SIZE => Err(ApiError::InvalidRequest(format!(
+            "limit cannot exceed {MAX_TASK_PAGE_SIZE}"
+        ))),
+        n => Ok(n),
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// What readiness probes take into account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReadinessMode {
+    /// Ready as soon as the supervisor accepts tasks.
+    #[default]
+    SupervisorOnly,
+    /// Additionally require every registered runner to pass its health check.
+    SupervisorAndRunners,
+}
+
+/// Result of a readiness check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Readiness {
+    /// Whether the service is ready to accept tasks.
+    pub ready: bool,
+    /// Human-readable reasons for not being ready (e.g. `"runner-a: io error: ..."`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failing: Vec<String>,
+}
+
+impl Readiness {
+    /// Ready state without failures.
+    pub fn ready() -> Self {
+        Self {
+            ready: true,
+            failing: Vec::new(),
+        }
+    }
+
+    /// Build readiness from a list of failures (ready if empty).
+    pub fn from_failures(failing: Vec<String>) -> Self {
+        Self {
+            ready: failing.is_empty(),
+            failing,
+        }
+    }
+}
+
+/// Health of a single subsystem in a [`StatusReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubsystemStatus {
+    /// Subsystem name (`"supervisor"`, `"metrics"` or `"runner:<name>"`).
+    pub name: String,
+    /// Whether the subsystem is healthy.
+    pub healthy: bool,
+    /// Reason the subsystem is unhealthy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SubsystemStatus {
+    /// Build a subsystem status from a health check result.
+    pub fn from_result<E: ToString>(name: impl Into<String>, res: Result<(), E>) -> Self {
+        let error = res.err().map(|e| e.to_string());
+        Self {
+            name: name.into(),
+            healthy: error.is_none(),
+            error,
+        }
+    }
+}
+
+/// Structured health report of the service, one entry per subsystem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusReport {
+    /// Whether every subsystem is healthy.
+    pub healthy: bool,
+    /// Per-subsystem health.
+    pub subsystems: Vec<SubsystemStatus>,
+}
+
+impl StatusReport {
+    /// Build a report from subsystem statuses (healthy if all are).
+    pub fn from_subsystems(subsystems: Vec<SubsystemStatus>) -> Self {
+        Self {
+            healthy: subsystems.iter().all(|s| s.healthy),
+            subsystems,
+        }
+    }
+}
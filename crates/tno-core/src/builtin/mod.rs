@@ -0,0 +1,69 @@
+//! In-process tasks described by `TaskKind::Builtin`.
+//!
+//! Functions are registered by name in a [`BuiltinRegistry`]; the [`BuiltinRunner`] looks them up
+//! when a spec is routed to it, so builtin tasks share the submit/restart/metrics machinery of other kinds.
+mod runner;
+pub use runner::{BuiltinRunner, RUNNER_TYPE_BUILTIN};
+
+use std::{collections::HashMap, fmt, future::Future, pin::Pin, sync::Arc};
+
+use taskvisor::TaskError;
+use tno_model::TaskEnv;
+use tokio_util::sync::CancellationToken;
+
+/// Future returned by a builtin function.
+pub type BuiltinFuture = Pin<Box<dyn Future<Output = Result<(), TaskError>> + Send>>;
+
+/// Builtin function: called once per attempt with the task arguments and its cancellation token.
+pub type BuiltinFn = Arc<dyn Fn(TaskEnv, CancellationToken) -> BuiltinFuture + Send + Sync>;
+
+/// Named in-process functions runnable as `TaskKind::Builtin` tasks.
+#[derive(Clone, Default)]
+pub struct BuiltinRegistry {
+    fns: HashMap<String, BuiltinFn>,
+}
+
+impl BuiltinRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `f` under `name`, replacing a function registered earlier under the same name.
+    ///
+    /// `f` should stop promptly once the token is cancelled.
+    pub fn register<F, Fut>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        F: Fn(TaskEnv, CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), TaskError>> + Send + 'static,
+    {
+        let f: BuiltinFn = Arc::new(move |args, cancel| Box::pin(f(args, cancel)));
+        self.fns.insert(name.into(), f);
+        self
+    }
+
+    /// Function registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&BuiltinFn> {
+        self.fns.get(name)
+    }
+
+    /// Whether a function is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.fns.contains_key(name)
+    }
+
+    /// Registered names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.fns.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl fmt::Debug for BuiltinRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuiltinRegistry")
+            .field("names", &self.names())
+            .finish()
+    }
+}
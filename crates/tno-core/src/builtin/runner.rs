@@ -0,0 +1,172 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use taskvisor::{TaskError, TaskFn, TaskRef};
+use tno_model::{CreateSpec, TaskKind};
+use tokio_util::sync::CancellationToken;
+
+use super::BuiltinRegistry;
+use crate::{
+    metrics::TaskOutcome,
+    runner::{BuildContext, Runner, RunnerError},
+};
+
+/// Runner type label used in metrics for builtin tasks.
+pub const RUNNER_TYPE_BUILTIN: &str = "builtin";
+
+/// Runner that executes `TaskKind::Builtin` by calling the function registered under its name.
+pub struct BuiltinRunner {
+    name: &'static str,
+    registry: Arc<BuiltinRegistry>,
+}
+
+impl BuiltinRunner {
+    /// Create a runner named `"builtin"` over `registry`.
+    pub fn new(registry: BuiltinRegistry) -> Self {
+        Self {
+            name: RUNNER_TYPE_BUILTIN,
+            registry: Arc::new(registry),
+        }
+    }
+
+    /// Replace the runner name (e.g. to register several builtin runners with different registries).
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Functions this runner can execute.
+    pub fn registry(&self) -> &BuiltinRegistry {
+        &self.registry
+    }
+}
+
+impl Runner for BuiltinRunner {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn supports(&self, spec: &CreateSpec) -> bool {
+        matches!(spec.kind, TaskKind::Builtin { .. })
+    }
+
+    fn build_task(&self, spec: &CreateSpec, ctx: &BuildContext) -> Result<TaskRef, RunnerError> {
+        let TaskKind::Builtin { name, args } = &spec.kind else {
+            return Err(RunnerError::UnsupportedKind {
+                runner: self.name,
+                kind: spec.kind.kind().to_string(),
+            });
+        };
+        let f =
+            self.registry.get(name).cloned().ok_or_else(|| {
+                RunnerError::InvalidSpec(format!("unknown builtin function: {name}"))
+            })?;
+
+        let run_id = ctx
+            .task_id()
+            .map_or_else(|| self.build_run_id(&spec.slot), |id| id.to_string());
+        let args = args.clone();
+        let metrics = ctx.metrics().clone();
+        let cancel_is_success = spec.cancel_is_success;
+        let timeout = Duration::from_millis(spec.timeout_ms);
+
+        Ok(TaskFn::arc(run_id, move |cancel: CancellationToken| {
+            let f = Arc::clone(&f);
+            let args = args.clone();
+            let metrics = metrics.clone();
+            async move {
+                metrics.record_task_started(RUNNER_TYPE_BUILTIN);
+                let start = Instant::now();
+                let result = f(args, cancel).await;
+                let elapsed = start.elapsed();
+                let outcome = match &result {
+                    Ok(()) => TaskOutcome::Success,
+                    // The supervisor enforces the timeout by cancelling the task.
+                    Err(TaskError::Canceled) if elapsed >= timeout => TaskOutcome::Timeout,
+                    Err(TaskError::Canceled) if cancel_is_success => TaskOutcome::Success,
+                    Err(TaskError::Canceled) => TaskOutcome::Canceled,
+                    Err(TaskError::Timeout { .. }) => TaskOutcome::Timeout,
+                    Err(_) => TaskOutcome::Failure,
+                };
+                let duration_ms = elapsed.as_millis() as u64;
+                metrics.record_task_completed(RUNNER_TYPE_BUILTIN, outcome, duration_ms);
+                result
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tno_model::{AdmissionStrategy, BackoffStrategy, JitterStrategy, RestartStrategy, TaskEnv};
+
+    fn spec(name: &str, args: TaskEnv) -> CreateSpec {
+        CreateSpec {
+            slot: "housekeeping".into(),
+            kind: TaskKind::Builtin {
+                name: name.into(),
+                args,
+            },
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: Default::default(),
+            runner_selector: Default::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_registered_function_with_spec_args() {
+        let mut registry = BuiltinRegistry::new();
+        registry.register("require-days", |args: TaskEnv, _cancel| async move {
+            match args.get("DAYS") {
+                Some("7") => Ok(()),
+                other => Err(TaskError::Fail {
+                    reason: format!("unexpected DAYS: {other:?}"),
+                }),
+            }
+        });
+        let runner = BuiltinRunner::new(registry);
+        let ctx = BuildContext::default();
+
+        let mut args = TaskEnv::new();
+        args.push("DAYS", "7");
+        let task = runner
+            .build_task(&spec("require-days", args), &ctx)
+            .unwrap();
+        assert!(task.name().starts_with("builtin-housekeeping"));
+        task.spawn(CancellationToken::new()).await.unwrap();
+
+        let task = runner
+            .build_task(&spec("require-days", TaskEnv::new()), &ctx)
+            .unwrap();
+        assert!(task.spawn(CancellationToken::new()).await.is_err());
+    }
+
+    #[test]
+    fn unknown_function_is_rejected_at_build() {
+        let runner = BuiltinRunner::new(BuiltinRegistry::new());
+        let spec = spec("missing", TaskEnv::new());
+
+        assert!(runner.supports(&spec));
+        let err = runner
+            .build_task(&spec, &BuildContext::default())
+            .err()
+            .expect("unknown builtin must not build");
+        assert!(matches!(err, RunnerError::InvalidSpec(ref msg) if msg.contains("missing")));
+    }
+}
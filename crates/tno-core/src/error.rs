@@ -1,4 +1,7 @@
+use std::time::Duration;
+
 use thiserror::Error;
+use tno_model::{ModelError, TaskStatus};
 
 use crate::runner::RunnerError;
 
@@ -7,12 +10,46 @@ pub enum CoreError {
     #[error("no suitable runner for task kind: {0}")]
     NoRunner(String),
 
+    #[error("runner limit reached: at most {0} runners can be registered")]
+    TooManyRunners(usize),
+
+    #[error("all runners for task kind {0} are at capacity")]
+    AtCapacity(String),
+
+    #[error("slot is busy: {0}")]
+    SlotBusy(String),
+
+    #[error("validation of task kind {kind} timed out after {timeout:?}")]
+    ValidateTimeout { kind: String, timeout: Duration },
+
     #[error("supervisor error: {0}")]
     Supervisor(String),
 
+    #[error("task not found: {0}")]
+    TaskNotFound(String),
+
+    #[error("task id already in use: {0}")]
+    DuplicateTaskId(String),
+
+    #[error("batch not found: {0}")]
+    BatchNotFound(String),
+
+    #[error("cannot update policy of task {id}: {reason}")]
+    PolicyUpdate { id: String, reason: String },
+
+    #[error("task {id} did not reach status {status:?} within {timeout:?}")]
+    WaitTimeout {
+        id: String,
+        status: TaskStatus,
+        timeout: Duration,
+    },
+
     #[error("mapping error: {0}")]
     Mapping(String),
 
+    #[error("invalid spec: {0}")]
+    Model(#[from] ModelError),
+
     #[error("runner error: {0}")]
     Runner(#[from] RunnerError),
 }
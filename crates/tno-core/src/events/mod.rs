@@ -0,0 +1,152 @@
+//! Event fan-out for external consumers.
+//!
+//! Taskvisor delivers events only to subscribers registered at build time.
+//! [`EventBroadcaster`] is one such subscriber: it re-publishes every event into a Tokio broadcast channel
+//! so that consumers can attach at any point via [`crate::SupervisorApi::subscribe_events`].
+//!
+//! The broadcaster also keeps a bounded buffer of the most recent events.
+//! Receivers that opt into replay get that history first, then continue with live events.
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use taskvisor::{Event, Subscribe};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+
+/// Default number of recent events kept for replay.
+pub const DEFAULT_EVENT_REPLAY_CAPACITY: usize = 256;
+
+/// Capacity of the live broadcast channel (per receiver lag budget).
+const BROADCAST_CAPACITY: usize = 1024;
+
+/// Subscriber that re-publishes taskvisor events to dynamically attached receivers.
+#[derive(Clone)]
+pub(crate) struct EventBroadcaster {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    tx: broadcast::Sender<Event>,
+    replay: Mutex<VecDeque<Event>>,
+    replay_capacity: usize,
+}
+
+impl EventBroadcaster {
+    /// Create a broadcaster keeping up to `replay_capacity` recent events (`0` disables replay).
+    pub(crate) fn new(replay_capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self {
+            inner: Arc::new(Inner {
+                tx,
+                replay: Mutex::new(VecDeque::with_capacity(replay_capacity)),
+                replay_capacity,
+            }),
+        }
+    }
+
+    /// Attach a new receiver.
+    ///
+    /// With `replay = true` the receiver first yields buffered recent events (oldest first).
+    pub(crate) fn subscribe(&self, replay: bool) -> EventStream {
+        // Holding the buffer lock while subscribing guarantees no event is both replayed and received live (or missed).
+        let buf = self.inner.replay.lock().unwrap();
+        let rx = self.inner.tx.subscribe();
+        let backlog = if replay { buf.clone() } else { VecDeque::new() };
+        EventStream { backlog, rx }
+    }
+
+    fn publish(&self, event: &Event) {
+        let mut buf = self.inner.replay.lock().unwrap();
+        if self.inner.replay_capacity > 0 {
+            if buf.len() == self.inner.replay_capacity {
+                buf.pop_front();
+            }
+            buf.push_back(event.clone());
+        }
+        // No active receivers is not an error.
+        let _ = self.inner.tx.send(event.clone());
+    }
+}
+
+#[async_trait]
+impl Subscribe for EventBroadcaster {
+    async fn on_event(&self, event: &Event) {
+        self.publish(event);
+    }
+
+    fn name(&self) -> &'static str {
+        "event-broadcaster"
+    }
+
+    fn queue_capacity(&self) -> usize {
+        2048
+    }
+}
+
+/// Receiver of supervisor events created by [`crate::SupervisorApi::subscribe_events`].
+pub struct EventStream {
+    backlog: VecDeque<Event>,
+    rx: broadcast::Receiver<Event>,
+}
+
+impl EventStream {
+    /// Receive the next event.
+    ///
+    /// Replayed events (if requested) are yielded first.
+    /// If the receiver falls behind, missed live events are skipped.
+    /// Returns `None` once the supervisor is gone.
+    pub async fn recv(&mut self) -> Option<Event> {
+        if let Some(ev) = self.backlog.pop_front() {
+            return Some(ev);
+        }
+        loop {
+            match self.rx.recv().await {
+                Ok(ev) => return Some(ev),
+                Err(RecvError::Lagged(n)) => {
+                    debug!(skipped = n, "event stream lagged; skipping events");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Number of replayed events not yet consumed.
+    pub fn pending_replay(&self) -> usize {
+        self.backlog.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use taskvisor::EventKind;
+
+    #[tokio::test]
+    async fn replay_is_bounded_and_ordered() {
+        let b = EventBroadcaster::new(2);
+        for name in ["a", "b", "c"] {
+            b.publish(&Event::new(EventKind::TaskAdded).with_task(name));
+        }
+
+        let mut stream = b.subscribe(true);
+        assert_eq!(stream.pending_replay(), 2);
+        assert_eq!(stream.recv().await.unwrap().task.as_deref(), Some("b"));
+        assert_eq!(stream.recv().await.unwrap().task.as_deref(), Some("c"));
+    }
+
+    #[tokio::test]
+    async fn live_only_subscriber_skips_history() {
+        let b = EventBroadcaster::new(8);
+        b.publish(&Event::new(EventKind::TaskAdded).with_task("old"));
+
+        let mut stream = b.subscribe(false);
+        assert_eq!(stream.pending_replay(), 0);
+
+        b.publish(&Event::new(EventKind::TaskAdded).with_task("new"));
+        assert_eq!(stream.recv().await.unwrap().task.as_deref(), Some("new"));
+    }
+}
@@ -8,19 +8,33 @@ pub use map::{
 };
 
 mod router;
-pub use router::RunnerRouter;
+pub use router::{DEFAULT_VALIDATE_TIMEOUT, RunnerRouter, SelectionPolicy};
 
 mod runner;
 pub use runner::make_run_id;
 pub use runner::{BuildContext, Runner, RunnerError};
 
+mod builtin;
+pub use builtin::{BuiltinFn, BuiltinFuture, BuiltinRegistry, BuiltinRunner, RUNNER_TYPE_BUILTIN};
+
 mod policy;
 pub use policy::TaskPolicy;
 
+mod events;
+pub use events::{DEFAULT_EVENT_REPLAY_CAPACITY, EventStream};
+
 pub mod supervisor;
-pub use supervisor::SupervisorApi;
+pub use supervisor::{ShutdownReport, SupervisorApi};
 
 mod metrics;
-pub use metrics::{MetricsBackend, MetricsHandle, NoOpMetrics, TaskOutcome, noop_metrics};
+pub use metrics::{
+    MetricsBackend, MetricsHandle, MetricsSubscriber, NoOpMetrics, RunnerErrorKind, TaskOutcome,
+    TerminalReason, noop_metrics,
+};
 
 mod state;
+pub use state::{
+    CapturedOutput, DEFAULT_RETENTION_MAX_AGE, DEFAULT_RETENTION_MAX_TERMINAL,
+    DEFAULT_RUNNER_ERROR_CAPACITY, Retention, RunnerErrorLog, TaskReporter, TaskWatch,
+    UNKNOWN_RUNNER,
+};
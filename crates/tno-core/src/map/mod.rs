@@ -28,17 +28,22 @@ pub fn to_jitter_policy(s: JitterStrategy) -> JitterPolicy {
 }
 
 /// Convert a high-level restart strategy into the restart policy used by taskvisor.
+///
+/// Taskvisor has no notion of an attempt limit, so `max_attempts` is dropped here;
+/// [`crate::SupervisorApi`] enforces it by wrapping the submitted task.
 pub fn to_restart_policy(s: RestartStrategy) -> RestartPolicy {
     match s {
-        RestartStrategy::Always { interval_ms } => RestartPolicy::Always {
+        RestartStrategy::Always { interval_ms, .. } => RestartPolicy::Always {
             interval: interval_ms.map(Duration::from_millis),
         },
-        RestartStrategy::OnFailure => RestartPolicy::OnFailure,
+        RestartStrategy::OnFailure { .. } => RestartPolicy::OnFailure,
         RestartStrategy::Never => RestartPolicy::Never,
     }
 }
 
 /// Convert a high-level backoff strategy into a backoff policy used by taskvisor.
+///
+/// Taskvisor computes the delays itself; [`BackoffStrategy::next_delay`] describes what each jitter mode produces.
 pub fn to_backoff_policy(s: &BackoffStrategy) -> BackoffPolicy {
     BackoffPolicy {
         first: Duration::from_millis(s.first_ms),
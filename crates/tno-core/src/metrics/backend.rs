@@ -26,6 +26,96 @@ impl TaskOutcome {
     }
 }
 
+/// Why a task ended without succeeding, for metrics classification.
+///
+/// Recorded once per task, when it reaches its final state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TerminalReason {
+    /// The last attempt hit its timeout.
+    Timeout,
+    /// The task failed on its first and only attempt.
+    Failed,
+    /// The task was canceled via the API.
+    Canceled,
+    /// The task kept failing after restarts until its restart policy gave up.
+    Exhausted,
+    /// The task was replaced by a newer submission to the same slot (`AdmissionStrategy::Replace`).
+    Replaced,
+}
+
+impl TerminalReason {
+    /// Return label value for metrics.
+    #[inline]
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            TerminalReason::Timeout => "timeout",
+            TerminalReason::Failed => "failed",
+            TerminalReason::Canceled => "canceled",
+            TerminalReason::Exhausted => "exhausted",
+            TerminalReason::Replaced => "replaced",
+        }
+    }
+}
+
+/// Category of a runner error, for metrics classification.
+///
+/// Variants map to a fixed set of label values; use [`RunnerErrorKind::Other`] for runner-specific
+/// categories not covered here. Its value becomes a label too, so it must come from a bounded set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunnerErrorKind {
+    /// The task process could not be spawned.
+    SpawnFailed,
+    /// The runner backend configuration could not be applied to the task.
+    BackendConfigFailed,
+    /// A task cgroup could not be set up.
+    CgroupFailed,
+    /// Secret files could not be written for the task.
+    SecretFilesFailed,
+    /// The pre-spawn hook failed.
+    PreSpawnFailed,
+    /// The output of a spawned task could not be captured.
+    OutputCaptureFailed,
+    /// Waiting for a spawned task to exit failed.
+    WaitFailed,
+    /// The runner gave up waiting on the task.
+    Timeout,
+    /// The task actor died.
+    ActorDead,
+    /// The task was killed by the OOM killer under its memory limit.
+    LimitOom,
+    /// The task hit its process count limit.
+    LimitPids,
+    /// The task exceeded its file size limit.
+    LimitFileSize,
+    /// The task exceeded its CPU time limit.
+    LimitCpu,
+    /// Runner-specific category.
+    Other(&'static str),
+}
+
+impl RunnerErrorKind {
+    /// Return label value for metrics.
+    #[inline]
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            RunnerErrorKind::SpawnFailed => "spawn_failed",
+            RunnerErrorKind::BackendConfigFailed => "backend_config_failed",
+            RunnerErrorKind::CgroupFailed => "cgroup_failed",
+            RunnerErrorKind::SecretFilesFailed => "secret_files_failed",
+            RunnerErrorKind::PreSpawnFailed => "pre_spawn_failed",
+            RunnerErrorKind::OutputCaptureFailed => "output_capture_failed",
+            RunnerErrorKind::WaitFailed => "wait_failed",
+            RunnerErrorKind::Timeout => "timeout",
+            RunnerErrorKind::ActorDead => "actor_dead",
+            RunnerErrorKind::LimitOom => "limit_oom",
+            RunnerErrorKind::LimitPids => "limit_pids",
+            RunnerErrorKind::LimitFileSize => "limit_fsize",
+            RunnerErrorKind::LimitCpu => "limit_cpu",
+            RunnerErrorKind::Other(label) => label,
+        }
+    }
+}
+
 /// Backend metrics collection interface.
 ///
 /// This trait abstracts metrics collection across different backends.
@@ -38,6 +128,13 @@ pub trait MetricsBackend: Send + Sync + 'static {
     /// # Arguments
     /// - `runner_type`: Runner implementation
     fn record_task_started(&self, runner_type: &str);
+    /// Check that the backend is able to record and export metrics.
+    ///
+    /// Surfaced in the service status report; returns a human-readable reason on failure.
+    /// The default implementation always reports healthy.
+    fn health_check(&self) -> Result<(), String> {
+        Ok(())
+    }
     /// Record task completion with outcome and duration.
     ///
     /// Called when task exits (success, failure, timeout, cancel).
@@ -55,7 +152,70 @@ pub trait MetricsBackend: Send + Sync + 'static {
     /// # Arguments
     /// - `runner_type`: Runner implementation
     /// - `error_kind`: Error category
-    fn record_runner_error(&self, runner_type: &str, error_kind: &str);
+    fn record_runner_error(&self, runner_type: &str, error_kind: RunnerErrorKind);
+    /// Record that a runner created a cgroup for a task.
+    ///
+    /// Together with [`MetricsBackend::record_cgroup_removed`] this tracks the number of cgroups currently owned by runners.
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    /// - `runner_type`: Runner implementation
+    fn record_cgroup_created(&self, runner_type: &str) {
+        let _ = runner_type;
+    }
+    /// Record that a cgroup previously reported via [`MetricsBackend::record_cgroup_created`] is gone.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    /// - `runner_type`: Runner implementation
+    fn record_cgroup_removed(&self, runner_type: &str) {
+        let _ = runner_type;
+    }
+    /// Record peak memory usage of a finished task, read back from its cgroup.
+    ///
+    /// Only called when the value is available (cgroup v2 with `memory.peak`).
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    /// - `runner_type`: Runner implementation
+    /// - `bytes`: Peak memory usage in bytes
+    fn record_task_memory_peak(&self, runner_type: &str, bytes: u64) {
+        let _ = (runner_type, bytes);
+    }
+    /// Record the amount of subprocess output currently buffered but not yet logged.
+    ///
+    /// Called whenever a runner with an output byte budget acquires or releases budget.
+    /// The value is a gauge: it replaces the previous one.
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    /// - `runner_type`: Runner implementation
+    /// - `bytes`: Bytes currently in flight
+    fn record_output_bytes_in_flight(&self, runner_type: &str, bytes: u64) {
+        let _ = (runner_type, bytes);
+    }
+    /// Record why a task ended without succeeding.
+    ///
+    /// Called by the supervisor state tracking (not by runners), once per task.
+    /// `runner_type` is the name of the runner that built the task, or [`crate::UNKNOWN_RUNNER`] for tasks submitted via `submit_with_task`.
+    /// The default implementation does nothing.
+    ///
+    /// # Arguments
+    /// - `runner_type`: Runner that built the task
+    /// - `reason`: Terminal reason
+    fn record_terminal(&self, runner_type: &str, reason: TerminalReason) {
+        let _ = (runner_type, reason);
+    }
+    /// Handle recording on behalf of the runner named `runner`.
+    ///
+    /// The router hands it to the selected runner via [`crate::BuildContext`], so backends can label metrics
+    /// by runner name (several runners often share a runner type). Names come from registered runners, so the set is bounded.
+    /// The default implementation returns `None`: the shared handle is used as is.
+    fn for_runner(&self, runner: &'static str) -> Option<MetricsHandle> {
+        let _ = runner;
+        None
+    }
 }
 
 /// Shared handle to metrics backend.
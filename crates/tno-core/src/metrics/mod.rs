@@ -3,11 +3,14 @@
 //! This module provides a backend interface for collecting runtime metrics from task execution.
 //! Metrics backends (prometheus, statsd, etc) implement [`MetricsBackend`] and are injected via [`crate::BuildContext`].
 mod backend;
-pub use backend::{MetricsBackend, MetricsHandle, TaskOutcome};
+pub use backend::{MetricsBackend, MetricsHandle, RunnerErrorKind, TaskOutcome, TerminalReason};
 
 mod noop;
 pub use noop::NoOpMetrics;
 
+mod subscriber;
+pub use subscriber::MetricsSubscriber;
+
 use std::sync::Arc;
 
 /// Create a no-op metrics handle.
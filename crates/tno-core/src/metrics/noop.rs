@@ -1,4 +1,4 @@
-use crate::metrics::backend::{MetricsBackend, TaskOutcome};
+use crate::metrics::backend::{MetricsBackend, RunnerErrorKind, TaskOutcome};
 
 /// No-op metrics backend that compiles to nothing.
 #[derive(Debug, Clone, Copy, Default)]
@@ -12,7 +12,7 @@ impl MetricsBackend for NoOpMetrics {
     fn record_task_completed(&self, _: &str, _: TaskOutcome, _: u64) {}
 
     #[inline(always)]
-    fn record_runner_error(&self, _: &str, _: &str) {}
+    fn record_runner_error(&self, _: &str, _: RunnerErrorKind) {}
 }
 
 #[cfg(test)]
@@ -30,7 +30,7 @@ mod tests {
         for _ in 0..1000 {
             metrics.record_task_started("test");
             metrics.record_task_completed("test", TaskOutcome::Success, 100);
-            metrics.record_runner_error("test", "error");
+            metrics.record_runner_error("test", RunnerErrorKind::SpawnFailed);
         }
     }
 }
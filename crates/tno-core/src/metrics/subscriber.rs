@@ -0,0 +1,198 @@
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+use async_trait::async_trait;
+use taskvisor::{Event, EventKind, Subscribe};
+
+use super::{MetricsHandle, RunnerErrorKind, TaskOutcome};
+use crate::state::UNKNOWN_RUNNER;
+
+/// Subscriber that records task metrics from taskvisor events.
+///
+/// Maps `TaskStarting` to [`MetricsBackend::record_task_started`](super::MetricsBackend::record_task_started),
+/// `TaskStopped` / `TaskFailed` to `record_task_completed` (a failure preceded by `TimeoutHit` counts as a timeout)
+/// and `ActorDead` to `record_runner_error`.
+///
+/// The runner type is inferred from the task name, which starts with the runner name (see [`crate::make_run_id`]);
+/// register runners with [`MetricsSubscriber::with_runner`]. Tasks of unregistered runners are recorded
+/// as [`UNKNOWN_RUNNER`].
+///
+/// Use it for runners that do not record these metrics themselves; otherwise tasks are counted twice.
+pub struct MetricsSubscriber {
+    metrics: MetricsHandle,
+    /// `(runner name, runner type)` pairs.
+    runners: Vec<(String, String)>,
+    /// Attempts in flight, keyed by task name.
+    attempts: Mutex<HashMap<String, Attempt>>,
+}
+
+struct Attempt {
+    started: Instant,
+    timed_out: bool,
+}
+
+impl MetricsSubscriber {
+    /// Create a subscriber recording into `metrics`.
+    pub fn new(metrics: MetricsHandle) -> Self {
+        Self {
+            metrics,
+            runners: Vec::new(),
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record tasks of the runner named `name` under `runner_type`.
+    pub fn with_runner(mut self, name: impl Into<String>, runner_type: impl Into<String>) -> Self {
+        self.runners.push((name.into(), runner_type.into()));
+        self
+    }
+
+    /// Runner type of a task, matching the longest registered runner name.
+    fn runner_type(&self, task: &str) -> &str {
+        self.runners
+            .iter()
+            .filter(|(name, _)| {
+                task.strip_prefix(name.as_str())
+                    .is_some_and(|rest| rest.starts_with('-'))
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map_or(UNKNOWN_RUNNER, |(_, runner_type)| runner_type.as_str())
+    }
+
+    fn apply(&self, event: &Event) {
+        let Some(task) = event.task.as_deref() else {
+            return;
+        };
+
+        match event.kind {
+            EventKind::TaskStarting => {
+                self.attempts.lock().unwrap().insert(
+                    task.to_string(),
+                    Attempt {
+                        started: Instant::now(),
+                        timed_out: false,
+                    },
+                );
+                self.metrics.record_task_started(self.runner_type(task));
+            }
+            EventKind::TimeoutHit => {
+                if let Some(attempt) = self.attempts.lock().unwrap().get_mut(task) {
+                    attempt.timed_out = true;
+                }
+            }
+            EventKind::TaskStopped | EventKind::TaskFailed => {
+                let Some(attempt) = self.attempts.lock().unwrap().remove(task) else {
+                    return;
+                };
+                let outcome = match event.kind {
+                    EventKind::TaskStopped => TaskOutcome::Success,
+                    _ if attempt.timed_out => TaskOutcome::Timeout,
+                    _ => TaskOutcome::Failure,
+                };
+                let duration_ms = attempt.started.elapsed().as_millis() as u64;
+                self.metrics
+                    .record_task_completed(self.runner_type(task), outcome, duration_ms);
+            }
+            EventKind::ActorDead => {
+                self.attempts.lock().unwrap().remove(task);
+                self.metrics
+                    .record_runner_error(self.runner_type(task), RunnerErrorKind::ActorDead);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[async_trait]
+impl Subscribe for MetricsSubscriber {
+    async fn on_event(&self, event: &Event) {
+        self.apply(event);
+    }
+
+    fn name(&self) -> &'static str {
+        "metrics-subscriber"
+    }
+
+    fn queue_capacity(&self) -> usize {
+        2048
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::metrics::MetricsBackend;
+
+    #[derive(Default)]
+    struct Recorded {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl MetricsBackend for Recorded {
+        fn record_task_started(&self, runner_type: &str) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("started {runner_type}"));
+        }
+
+        fn record_task_completed(&self, runner_type: &str, outcome: TaskOutcome, _: u64) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("completed {runner_type} {}", outcome.as_label()));
+        }
+
+        fn record_runner_error(&self, runner_type: &str, error_kind: RunnerErrorKind) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("error {runner_type} {}", error_kind.as_label()));
+        }
+    }
+
+    fn event(kind: EventKind, task: &str) -> Event {
+        Event::new(kind).with_task(task)
+    }
+
+    #[test]
+    fn maps_events_to_metrics() {
+        let recorded = Arc::new(Recorded::default());
+        let sub = MetricsSubscriber::new(recorded.clone())
+            .with_runner("subprocess", "subprocess")
+            .with_runner("subprocess-gpu", "gpu");
+
+        sub.apply(&event(EventKind::TaskStarting, "subprocess-build-1"));
+        sub.apply(&event(EventKind::TaskStopped, "subprocess-build-1"));
+        sub.apply(&event(EventKind::TaskStarting, "subprocess-gpu-train-2"));
+        sub.apply(&event(EventKind::TimeoutHit, "subprocess-gpu-train-2"));
+        sub.apply(&event(EventKind::TaskFailed, "subprocess-gpu-train-2"));
+        sub.apply(&event(EventKind::TaskStarting, "wasm-job-3"));
+        sub.apply(&event(EventKind::TaskFailed, "wasm-job-3"));
+        sub.apply(&event(EventKind::ActorDead, "wasm-job-3"));
+
+        assert_eq!(
+            *recorded.calls.lock().unwrap(),
+            [
+                "started subprocess",
+                "completed subprocess success",
+                "started gpu",
+                "completed gpu timeout",
+                "started unknown",
+                "completed unknown failure",
+                "error unknown actor_dead",
+            ]
+        );
+    }
+
+    #[test]
+    fn completion_without_start_is_ignored() {
+        let recorded = Arc::new(Recorded::default());
+        let sub = MetricsSubscriber::new(recorded.clone());
+
+        sub.apply(&event(EventKind::TaskStopped, "subprocess-build-1"));
+        assert!(recorded.calls.lock().unwrap().is_empty());
+    }
+}
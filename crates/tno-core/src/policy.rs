@@ -1,4 +1,7 @@
-use tno_model::{AdmissionStrategy, BackoffStrategy, CreateSpec, RestartStrategy, Slot, TimeoutMs};
+use tno_model::{
+    AdmissionStrategy, BackoffStrategy, CreateSpec, ModelError, RestartStrategy, ScheduleStrategy,
+    Slot, TimeoutMs,
+};
 
 /// Runtime policy for a pre-built task.
 ///
@@ -11,18 +14,24 @@ pub struct TaskPolicy {
     pub restart: RestartStrategy,
     pub backoff: BackoffStrategy,
     pub admission: AdmissionStrategy,
+    /// Optional schedule (see [`CreateSpec::schedule`]).
+    pub schedule: Option<ScheduleStrategy>,
 }
 
 impl TaskPolicy {
     /// Build a policy from a full `CreateSpec`, dropping the `kind` information.
-    pub fn from_spec(spec: &CreateSpec) -> Self {
-        Self {
+    ///
+    /// Fails if the spec's backoff is invalid (see [`BackoffStrategy::validate`]).
+    pub fn from_spec(spec: &CreateSpec) -> Result<Self, ModelError> {
+        spec.backoff.validate()?;
+        Ok(Self {
             slot: spec.slot.clone(),
             timeout_ms: spec.timeout_ms,
             restart: spec.restart,
             backoff: spec.backoff.clone(),
             admission: spec.admission,
-        }
+            schedule: spec.schedule.clone(),
+        })
     }
 
     /// Convenience constructor.
@@ -39,6 +48,13 @@ impl TaskPolicy {
             restart,
             backoff,
             admission,
+            schedule: None,
         }
     }
+
+    /// Run the task on the given schedule instead of right away.
+    pub fn with_schedule(mut self, schedule: ScheduleStrategy) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
 }
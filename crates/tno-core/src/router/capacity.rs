@@ -0,0 +1,80 @@
+//! In-flight accounting for runners registered with a capacity limit.
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Number of tasks built by a runner that have not finished yet, with an optional upper bound.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    max: Option<usize>,
+    current: Arc<AtomicUsize>,
+}
+
+impl InFlight {
+    pub(crate) fn new(max: Option<usize>) -> Self {
+        Self {
+            max,
+            current: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns `true` if another task can be admitted.
+    pub(crate) fn has_capacity(&self) -> bool {
+        self.max
+            .is_none_or(|max| self.current.load(Ordering::Acquire) < max)
+    }
+
+    /// Number of tasks currently in flight.
+    pub(crate) fn current(&self) -> usize {
+        self.current.load(Ordering::Acquire)
+    }
+
+    /// Reserve a slot for a new task (`None` if the runner is at capacity).
+    ///
+    /// Unbounded runners are not tracked.
+    pub(crate) fn try_reserve(&self) -> Option<Option<InFlightGuard>> {
+        let Some(max) = self.max else {
+            return Some(None);
+        };
+        self.current
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Some(InFlightGuard(Arc::clone(&self.current))))
+    }
+}
+
+/// Releases an in-flight slot on drop.
+#[derive(Debug)]
+pub(crate) struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Reserved slots of in-flight tasks, keyed by task name.
+///
+/// The supervisor does not drop a task promptly once it has finished,
+/// so slots are released explicitly when the task is reported as done.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InFlightLeases(Arc<Mutex<HashMap<String, InFlightGuard>>>);
+
+impl InFlightLeases {
+    /// Hold `guard` until [`InFlightLeases::release`] is called for `task`.
+    pub(crate) fn hold(&self, task: &str, guard: InFlightGuard) {
+        self.0.lock().unwrap().insert(task.to_string(), guard);
+    }
+
+    /// Release the slot held by `task` (no-op if it holds none).
+    pub(crate) fn release(&self, task: &str) {
+        let guard = self.0.lock().unwrap().remove(task);
+        drop(guard);
+    }
+}
@@ -2,33 +2,104 @@
 //!
 //! The router checks registered runners in order and delegates task construction
 //! to the first one that reports `supports(spec) == true` and matches label constraints (if any).
-use std::sync::Arc;
+//! With [`SelectionPolicy::RoundRobin`] matching runners are used in turn instead.
+//! Runners registered with a capacity (see [`RunnerRouter::register_with_capacity`]) are skipped while they have
+//! that many tasks in flight.
+//!
+//! Runners are indexed by their `runner-tag` label, so routing a spec that requests a tag only looks at runners
+//! advertising that tag. A spec's [`CreateSpec::runner_selector`] further restricts routing to runners whose labels
+//! contain all of its pairs.
+mod capacity;
+use capacity::InFlight;
+pub(crate) use capacity::InFlightLeases;
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 use taskvisor::TaskRef;
-use tno_model::{CreateSpec, LABEL_RUNNER_TAG, RunnerLabels, TaskKind};
-use tracing::{debug, instrument, trace};
+use tno_model::{CreateSpec, LABEL_RUNNER_TAG, RunnerLabels, TaskId, TaskKind};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, instrument, trace, warn};
 
 use crate::{
     error::CoreError,
-    runner::{BuildContext, Runner},
+    metrics::{MetricsHandle, noop_metrics},
+    runner::{BuildContext, Runner, RunnerError},
+    state::TaskReporter,
 };
 
+/// Upper bound for a single runner self-test task.
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default upper bound for building a task in [`RunnerRouter::validate`].
+pub const DEFAULT_VALIDATE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Single runner entry with optional static labels used for routing.
 pub struct RunnerEntry {
     /// Concrete runner implementation.
     pub runner: Arc<dyn Runner>,
     /// Static labels attached to this runner (e.g. capacity class, backend tag).
     pub labels: RunnerLabels,
+    /// Tasks built by this runner that have not finished yet.
+    in_flight: InFlight,
+}
+
+impl RunnerEntry {
+    fn new(runner: Arc<dyn Runner>, labels: RunnerLabels, max_in_flight: Option<usize>) -> Self {
+        Self {
+            runner,
+            labels,
+            in_flight: InFlight::new(max_in_flight),
+        }
+    }
+
+    /// Whether the runner supports the spec and carries every label of its runner selector.
+    fn accepts(&self, spec: &CreateSpec) -> bool {
+        self.labels.matches(&spec.runner_selector) && self.runner.supports(spec)
+    }
+}
+
+/// How the router chooses among several runners matching the same spec.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionPolicy {
+    /// Always use the first matching runner in registration order.
+    #[default]
+    FirstMatch,
+    /// Rotate through all matching runners (e.g. several runners sharing a `runner-tag` for load spreading).
+    ///
+    /// Each group of candidates (task kind + requested tag) keeps its own counter.
+    RoundRobin,
 }
 
 /// Router that selects an appropriate [`Runner`] for a given [`CreateSpec`].
 ///
 /// Runners are checked in the order they were registered.
-/// The first runner whose [`Runner::supports`] method returns `true` and satisfies label constraints (see [`CreateSpec::runner_tag`]) is used to build the task.
+/// The first runner whose [`Runner::supports`] method returns `true` and satisfies label constraints (see [`CreateSpec::runner_tag`]) is used to build the task,
+/// unless a different [`SelectionPolicy`] is configured.
 #[derive(Default)]
 pub struct RunnerRouter {
     runners: Vec<RunnerEntry>,
     ctx: BuildContext,
+    selection: SelectionPolicy,
+    /// Last-resort runner used when no registered runner matches.
+    fallback: Option<Arc<dyn Runner>>,
+    /// Round-robin counters by candidate group.
+    rotation: RwLock<HashMap<String, AtomicUsize>>,
+    /// Slots reserved by in-flight tasks of runners with a capacity.
+    leases: InFlightLeases,
+    /// Indices into `runners` by `runner-tag` label, in registration order.
+    by_tag: HashMap<String, Vec<usize>>,
+    /// Maximum number of registered runners (`None` = unbounded).
+    max_runners: Option<usize>,
+    /// Upper bound for building a task during validation.
+    validate_timeout: Duration,
 }
 
 impl RunnerRouter {
@@ -38,9 +109,39 @@ impl RunnerRouter {
         Self {
             runners: Vec::new(),
             ctx: BuildContext::default(),
+            selection: SelectionPolicy::default(),
+            fallback: None,
+            rotation: RwLock::new(HashMap::new()),
+            leases: InFlightLeases::default(),
+            by_tag: HashMap::new(),
+            max_runners: None,
+            validate_timeout: DEFAULT_VALIDATE_TIMEOUT,
         }
     }
 
+    /// Set how to choose among several matching runners (default: [`SelectionPolicy::FirstMatch`]).
+    #[inline]
+    pub fn with_selection(mut self, selection: SelectionPolicy) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Limit the number of runners that can be registered (default: unbounded).
+    ///
+    /// Registering more runners fails with [`CoreError::TooManyRunners`]; the fallback runner does not count.
+    #[inline]
+    pub fn with_max_runners(mut self, max: usize) -> Self {
+        self.max_runners = Some(max);
+        self
+    }
+
+    /// Set the upper bound for building a task in [`RunnerRouter::validate`] (default: [`DEFAULT_VALIDATE_TIMEOUT`]).
+    #[inline]
+    pub fn with_validate_timeout(mut self, timeout: Duration) -> Self {
+        self.validate_timeout = timeout;
+        self
+    }
+
     /// Set a custom build context for all runners managed by this router.
     ///
     /// This is typically used to inject shared dependencies (config, observability, global handles, etc.) into runner instances.
@@ -50,49 +151,163 @@ impl RunnerRouter {
         self
     }
 
+    /// Attach a task reporter to the build context (used by [`crate::SupervisorApi`]).
+    pub(crate) fn set_reporter(&mut self, reporter: TaskReporter) {
+        self.ctx = std::mem::take(&mut self.ctx).with_reporter(reporter);
+    }
+
     /// Register a new runner without labels.
     ///
     /// Runners are queried in the order they are registered; the first one that reports `supports(spec) == true` (and matches labels, if any) is used.
+    /// Fails with [`CoreError::TooManyRunners`] if the router is full (see [`RunnerRouter::with_max_runners`]).
     #[inline]
-    pub fn register(&mut self, runner: Arc<dyn Runner>) {
-        self.runners.push(RunnerEntry {
-            runner,
-            labels: RunnerLabels::default(),
-        });
+    pub fn register(&mut self, runner: Arc<dyn Runner>) -> Result<(), CoreError> {
+        self.push_entry(RunnerEntry::new(runner, RunnerLabels::default(), None))
     }
 
     /// Register a new runner with static labels.
     ///
     /// These labels are used by the router to further narrow down candidates when [`CreateSpec::runner_tag`] is set.
     #[inline]
-    pub fn register_with_labels(&mut self, runner: Arc<dyn Runner>, labels: RunnerLabels) {
-        self.runners.push(RunnerEntry { runner, labels });
+    pub fn register_with_labels(
+        &mut self,
+        runner: Arc<dyn Runner>,
+        labels: RunnerLabels,
+    ) -> Result<(), CoreError> {
+        self.push_entry(RunnerEntry::new(runner, labels, None))
     }
 
-    /// Pick the first runner that claims to support the given spec and matches label selector.
+    /// Register a new runner with static labels and at most `max_in_flight` tasks in flight.
+    ///
+    /// A task counts as in flight from the moment it is built until it is released: [`crate::SupervisorApi`]
+    /// does that once the task finishes for good or is rejected at admission; tasks built directly via
+    /// [`RunnerRouter::build`] must be released with [`RunnerRouter::release`]. While the runner is at capacity,
+    /// [`RunnerRouter::pick`] skips it; if every matching runner is full, building fails with [`CoreError::AtCapacity`].
+    pub fn register_with_capacity(
+        &mut self,
+        runner: Arc<dyn Runner>,
+        labels: RunnerLabels,
+        max_in_flight: usize,
+    ) -> Result<(), CoreError> {
+        self.push_entry(RunnerEntry::new(runner, labels, Some(max_in_flight)))
+    }
+
+    fn push_entry(&mut self, entry: RunnerEntry) -> Result<(), CoreError> {
+        if let Some(max) = self.max_runners
+            && self.runners.len() >= max
+        {
+            return Err(CoreError::TooManyRunners(max));
+        }
+        if let Some(tag) = entry.labels.get(LABEL_RUNNER_TAG) {
+            self.by_tag
+                .entry(tag.to_string())
+                .or_default()
+                .push(self.runners.len());
+        }
+        self.runners.push(entry);
+        Ok(())
+    }
+
+    /// Number of in-flight tasks per registered runner, in registration order.
+    pub fn in_flight(&self) -> Vec<(&'static str, usize)> {
+        self.runners
+            .iter()
+            .map(|e| (e.runner.name(), e.in_flight.current()))
+            .collect()
+    }
+
+    /// Release the in-flight slot held by the task named `task_name` (no-op if it holds none).
+    pub fn release(&self, task_name: &str) {
+        self.leases.release(task_name);
+    }
+
+    /// Shared handle used to release in-flight slots from outside the router.
+    pub(crate) fn leases(&self) -> &InFlightLeases {
+        &self.leases
+    }
+
+    /// Set a last-resort runner for specs that no registered runner matches.
+    ///
+    /// The fallback is not part of normal routing: it is consulted only when [`RunnerRouter::pick`] finds nothing
+    /// (e.g. the spec requests a `runner-tag` nobody advertises) and it still supports the spec's kind.
+    /// Every fallback is logged at `warn` level so misrouted specs get noticed.
+    pub fn set_fallback(&mut self, runner: Arc<dyn Runner>) {
+        self.fallback = Some(runner);
+    }
+
+    /// Pick a runner that claims to support the given spec and matches label selector.
     ///
     /// Routing rules:
     /// - filter runners by `Runner::supports(spec)`;
     /// - if `spec.runner_tag()` is set, keep only runners whose `labels` contain this tag;
-    /// - pick the first matching entry.
+    /// - keep only runners whose `labels` contain every `spec.runner_selector` pair;
+    /// - skip runners that are at capacity;
+    /// - pick the first matching entry, or the next one in turn with [`SelectionPolicy::RoundRobin`].
     pub fn pick(&self, spec: &CreateSpec) -> Option<&Arc<dyn Runner>> {
-        let wanted = spec.runner_tag();
+        self.pick_entry(spec).map(|entry| &entry.runner)
+    }
 
-        self.runners
-            .iter()
-            .filter(|entry| entry.runner.supports(spec))
-            .filter(move |entry| {
-                if let Some(wanted) = wanted {
-                    match entry.labels.get(LABEL_RUNNER_TAG) {
-                        Some(actual) => actual == wanted,
-                        None => false,
-                    }
-                } else {
-                    true
+    fn pick_entry(&self, spec: &CreateSpec) -> Option<&RunnerEntry> {
+        let mut candidates = self
+            .candidates(spec)
+            .filter(|entry| entry.in_flight.has_capacity());
+
+        match self.selection {
+            SelectionPolicy::FirstMatch => candidates.next(),
+            SelectionPolicy::RoundRobin => {
+                let candidates: Vec<_> = candidates.collect();
+                if candidates.len() <= 1 {
+                    return candidates.first().copied();
                 }
-            })
-            .map(|entry| &entry.runner)
-            .next()
+                let mut group = format!(
+                    "{}/{}",
+                    spec.kind.kind(),
+                    spec.runner_tag().unwrap_or_default()
+                );
+                for (key, value) in spec.runner_selector.iter() {
+                    group.push_str(&format!("/{key}={value}"));
+                }
+                let turn = self.next_turn(group);
+                Some(candidates[turn % candidates.len()])
+            }
+        }
+    }
+
+    /// Runners that support the spec and match its runner tag and selector, regardless of capacity.
+    ///
+    /// With a requested `runner-tag` only the runners indexed under that tag are checked.
+    fn candidates<'a, 's>(
+        &'a self,
+        spec: &'s CreateSpec,
+    ) -> Box<dyn Iterator<Item = &'a RunnerEntry> + 's>
+    where
+        'a: 's,
+    {
+        match spec.runner_tag() {
+            Some(wanted) => Box::new(
+                self.by_tag
+                    .get(wanted)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|&idx| &self.runners[idx])
+                    .filter(|entry| entry.accepts(spec)),
+            ),
+            None => Box::new(self.runners.iter().filter(|entry| entry.accepts(spec))),
+        }
+    }
+
+    /// Advance the round-robin counter of a candidate group and return its previous value.
+    fn next_turn(&self, group: String) -> usize {
+        if let Some(counter) = self.rotation.read().unwrap().get(&group) {
+            return counter.fetch_add(1, Ordering::Relaxed);
+        }
+        self.rotation
+            .write()
+            .unwrap()
+            .entry(group)
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed)
     }
 
     /// Build a [`TaskRef`] for the given spec using the selected runner.
@@ -100,6 +315,25 @@ impl RunnerRouter {
     /// `TaskKind::None` is not routable and must be used with [`SupervisorApi::submit_with_task`](crate::supervisor::SupervisorApi::submit_with_task).
     #[instrument(level = "debug", skip(self, spec), fields(kind = ?spec.kind, slot = %spec.slot))]
     pub fn build(&self, spec: &CreateSpec) -> Result<TaskRef, CoreError> {
+        self.build_with_runner(spec).map(|(task, _)| task)
+    }
+
+    /// Same as [`RunnerRouter::build`], but also returns the name of the runner that built the task.
+    pub(crate) fn build_with_runner(
+        &self,
+        spec: &CreateSpec,
+    ) -> Result<(TaskRef, &'static str), CoreError> {
+        self.build_with_id(spec, None)
+    }
+
+    /// Same as [`RunnerRouter::build_with_runner`]; with `task_id` the task is named after it.
+    ///
+    /// Fails with [`RunnerError::InvalidSpec`] if the runner ignores the requested ID.
+    pub(crate) fn build_with_id(
+        &self,
+        spec: &CreateSpec,
+        task_id: Option<&TaskId>,
+    ) -> Result<(TaskRef, &'static str), CoreError> {
         trace!(spec = ?spec, "router received spec");
 
         if matches!(spec.kind, TaskKind::None) {
@@ -107,27 +341,180 @@ impl RunnerRouter {
                 "TaskKind::None requires submit_with_task()".to_string(),
             ));
         }
-        let r = self
-            .pick(spec)
-            .ok_or_else(|| CoreError::NoRunner(spec.kind.kind().to_string()))?;
+        let Some(entry) = self.pick_entry(spec) else {
+            if self.candidates(spec).next().is_some() {
+                return Err(CoreError::AtCapacity(spec.kind.kind().to_string()));
+            }
+            let fallback = self
+                .fallback
+                .as_ref()
+                .filter(|r| r.supports(spec))
+                .ok_or_else(|| CoreError::NoRunner(spec.kind.kind().to_string()))?;
+            warn!(
+                runner = fallback.name(),
+                runner_tag = spec.runner_tag().unwrap_or_default(),
+                "no registered runner matches spec; using fallback runner"
+            );
+            let task = fallback
+                .build_task(spec, &self.build_context(fallback.name(), task_id))
+                .map_err(CoreError::from)?;
+            check_task_id(&task, fallback.name(), task_id)?;
+            return Ok((task, fallback.name()));
+        };
 
-        let task = r.build_task(spec, &self.ctx).map_err(CoreError::from)?;
+        let r = &entry.runner;
+        // Another submission may have taken the last slot since `pick_entry`.
+        let guard = entry
+            .in_flight
+            .try_reserve()
+            .ok_or_else(|| CoreError::AtCapacity(spec.kind.kind().to_string()))?;
+        let task = r
+            .build_task(spec, &self.build_context(r.name(), task_id))
+            .map_err(CoreError::from)?;
+        check_task_id(&task, r.name(), task_id)?;
+        if let Some(guard) = guard {
+            self.leases.hold(task.name(), guard);
+        }
         debug!(runner = r.name(), "runner built task successfully");
-        Ok(task)
+        Ok((task, r.name()))
     }
 
-    /// Returns `true` if at least one registered runner advertises the given runner-tag.
-    pub fn contains_runner_tag(&self, tag: &str) -> bool {
+    /// Build context for the runner named `runner`, requesting `task_id` as the task name.
+    fn build_context(
+        &self,
+        runner: &'static str,
+        task_id: Option<&TaskId>,
+    ) -> Cow<'_, BuildContext> {
+        let ctx = self.ctx.for_runner(runner);
+        match task_id {
+            Some(id) => Cow::Owned(ctx.into_owned().with_task_id(id.clone())),
+            None => ctx,
+        }
+    }
+
+    /// Check that the spec can be built, without submitting or keeping the task.
+    ///
+    /// The runner is picked as in [`RunnerRouter::build`], except that capacity limits are ignored and no slot is reserved.
+    /// Building may do blocking I/O, so it runs on the blocking pool and is abandoned after the validate timeout
+    /// (see [`RunnerRouter::with_validate_timeout`]) with [`CoreError::ValidateTimeout`].
+    ///
+    /// Returns the name of the runner that would build the task.
+    #[instrument(level = "debug", skip(self, spec), fields(kind = ?spec.kind, slot = %spec.slot))]
+    pub async fn validate(&self, spec: &CreateSpec) -> Result<&'static str, CoreError> {
+        if matches!(spec.kind, TaskKind::None) {
+            return Err(CoreError::NoRunner(
+                "TaskKind::None requires submit_with_task()".to_string(),
+            ));
+        }
+        let runner = self
+            .candidates(spec)
+            .next()
+            .map(|entry| &entry.runner)
+            .or_else(|| self.fallback.as_ref().filter(|r| r.supports(spec)))
+            .cloned()
+            .ok_or_else(|| CoreError::NoRunner(spec.kind.kind().to_string()))?;
+
+        let ctx = BuildContext::new(self.ctx.env().clone(), noop_metrics());
+        let owned = spec.clone();
+        let build = tokio::task::spawn_blocking(move || {
+            runner.build_task(&owned, &ctx).map(|_| runner.name())
+        });
+        match tokio::time::timeout(self.validate_timeout, build).await {
+            Ok(Ok(res)) => res.map_err(CoreError::from),
+            Ok(Err(e)) => Err(CoreError::Supervisor(format!(
+                "validation task failed: {e}"
+            ))),
+            Err(_) => {
+                warn!(timeout = ?self.validate_timeout, "task validation timed out");
+                Err(CoreError::ValidateTimeout {
+                    kind: spec.kind.kind().to_string(),
+                    timeout: self.validate_timeout,
+                })
+            }
+        }
+    }
+
+    /// Metrics backend of the shared build context.
+    pub(crate) fn metrics(&self) -> &MetricsHandle {
+        self.ctx.metrics()
+    }
+
+    /// Run [`Runner::health_check`] for every registered runner.
+    ///
+    /// Returns `(runner name, result)` pairs in registration order.
+    pub fn check_health(&self) -> Vec<(&'static str, Result<(), RunnerError>)> {
         self.runners
             .iter()
-            .any(|e| e.labels.get(LABEL_RUNNER_TAG) == Some(tag))
+            .map(|e| (e.runner.name(), e.runner.health_check()))
+            .collect()
+    }
+
+    /// Build and run a trivial task on every registered runner (see [`Runner::self_test_spec`]).
+    ///
+    /// Intended to be called at startup, so that broken runner configuration (cgroups, capabilities, ...) surfaces before the first real submission.
+    /// Runners without a self-test spec report `Ok(())`. Self-test tasks are not recorded in metrics.
+    ///
+    /// Returns `(runner name, result)` pairs in registration order.
+    pub async fn self_test(&self) -> Vec<(&'static str, Result<(), RunnerError>)> {
+        let ctx = BuildContext::new(self.ctx.env().clone(), noop_metrics());
+        let mut results = Vec::with_capacity(self.runners.len());
+
+        for entry in &self.runners {
+            let runner = &entry.runner;
+            let Some(spec) = runner.self_test_spec() else {
+                trace!(runner = runner.name(), "runner has no self-test; skipping");
+                results.push((runner.name(), Ok(())));
+                continue;
+            };
+
+            let res = match runner.build_task(&spec, &ctx) {
+                Ok(task) => {
+                    let token = CancellationToken::new();
+                    match tokio::time::timeout(SELF_TEST_TIMEOUT, task.spawn(token.clone())).await {
+                        Ok(Ok(())) => Ok(()),
+                        Ok(Err(e)) => Err(RunnerError::SelfTestFailed(e.to_string())),
+                        Err(_) => {
+                            token.cancel();
+                            Err(RunnerError::SelfTestFailed(format!(
+                                "timed out after {SELF_TEST_TIMEOUT:?}"
+                            )))
+                        }
+                    }
+                }
+                Err(e) => Err(e),
+            };
+            match &res {
+                Ok(()) => debug!(runner = runner.name(), "runner self-test passed"),
+                Err(e) => warn!(runner = runner.name(), error = %e, "runner self-test failed"),
+            }
+            results.push((runner.name(), res));
+        }
+        results
+    }
+
+    /// Returns `true` if at least one registered runner advertises the given runner-tag.
+    pub fn contains_runner_tag(&self, tag: &str) -> bool {
+        self.by_tag.contains_key(tag)
+    }
+}
+
+/// Fail if the runner did not name the task after the requested ID.
+fn check_task_id(
+    task: &TaskRef,
+    runner: &'static str,
+    task_id: Option<&TaskId>,
+) -> Result<(), CoreError> {
+    match task_id {
+        Some(id) if task.name() != id.as_str() => Err(CoreError::Runner(RunnerError::InvalidSpec(
+            format!("runner '{runner}' does not support explicit task ids"),
+        ))),
+        _ => Ok(()),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::runner::RunnerError;
 
     use std::path::PathBuf;
     use taskvisor::{TaskError, TaskFn};
@@ -179,6 +566,11 @@ mod tests {
             backoff: mk_backoff(),
             admission: AdmissionStrategy::DropIfRunning,
             labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
         }
     }
 
@@ -204,12 +596,13 @@ mod tests {
     #[test]
     fn build_uses_registered_runner_for_subprocess() {
         let mut router = RunnerRouter::new();
-        router.register(Arc::new(SubprocessRunnerDummy));
+        router.register(Arc::new(SubprocessRunnerDummy)).unwrap();
 
         let spec = mk_spec(TaskKind::Subprocess {
             command: "echo".to_string(),
             args: vec!["hello".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::default(),
         });
@@ -225,7 +618,7 @@ mod tests {
     #[test]
     fn build_fails_when_no_runner_supports_kind() {
         let mut router = RunnerRouter::new();
-        router.register(Arc::new(SubprocessRunnerDummy));
+        router.register(Arc::new(SubprocessRunnerDummy)).unwrap();
 
         let spec = mk_spec(TaskKind::Wasm {
             module: PathBuf::from("mod.wasm"),
@@ -292,19 +685,24 @@ mod tests {
         }
 
         let mut labels_r1 = RunnerLabels::new();
-        labels_r1.insert(LABEL_RUNNER_TAG, "runner-a");
+        labels_r1.insert(LABEL_RUNNER_TAG, "runner-a").unwrap();
         let mut labels_r2 = RunnerLabels::new();
-        labels_r2.insert(LABEL_RUNNER_TAG, "runner-b");
+        labels_r2.insert(LABEL_RUNNER_TAG, "runner-b").unwrap();
 
         let mut router = RunnerRouter::new();
-        router.register_with_labels(Arc::new(R1), labels_r1);
-        router.register_with_labels(Arc::new(R2), labels_r2);
+        router
+            .register_with_labels(Arc::new(R1), labels_r1)
+            .unwrap();
+        router
+            .register_with_labels(Arc::new(R2), labels_r2)
+            .unwrap();
 
         let spec = {
             let base = mk_spec(TaskKind::Subprocess {
                 command: "echo".into(),
                 args: vec!["hi".into()],
                 env: TaskEnv::default(),
+                env_file: None,
                 cwd: None,
                 fail_on_non_zero: Flag::enabled(),
             });
@@ -314,4 +712,392 @@ mod tests {
         let picked = router.pick(&spec).expect("runner should be picked");
         assert_eq!(picked.name(), "r2");
     }
+
+    struct NamedRunner(&'static str);
+
+    impl Runner for NamedRunner {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn supports(&self, _spec: &CreateSpec) -> bool {
+            true
+        }
+
+        fn build_task(
+            &self,
+            _spec: &CreateSpec,
+            _ctx: &BuildContext,
+        ) -> Result<TaskRef, RunnerError> {
+            Ok(TaskFn::arc(self.0, |_ctx: CancellationToken| async move {
+                Ok::<(), TaskError>(())
+            }))
+        }
+    }
+
+    #[test]
+    fn tag_routing_checks_only_the_tagged_runner() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        const RUNNERS: usize = 5_000;
+
+        /// Runner that counts how often the router asks whether it supports a spec.
+        struct CountingRunner {
+            name: &'static str,
+            supports_calls: Arc<AtomicUsize>,
+        }
+
+        impl Runner for CountingRunner {
+            fn name(&self) -> &'static str {
+                self.name
+            }
+
+            fn supports(&self, _spec: &CreateSpec) -> bool {
+                self.supports_calls.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+
+            fn build_task(
+                &self,
+                _spec: &CreateSpec,
+                _ctx: &BuildContext,
+            ) -> Result<TaskRef, RunnerError> {
+                Ok(TaskFn::arc(
+                    self.name,
+                    |_ctx: CancellationToken| async move { Ok::<(), TaskError>(()) },
+                ))
+            }
+        }
+
+        let supports_calls = Arc::new(AtomicUsize::new(0));
+        let mut router = RunnerRouter::new().with_max_runners(RUNNERS);
+        let names: Vec<&'static str> = (0..RUNNERS)
+            .map(|i| &*Box::leak(format!("runner-{i}").into_boxed_str()))
+            .collect();
+        for name in &names {
+            let mut labels = RunnerLabels::new();
+            labels.insert(LABEL_RUNNER_TAG, *name).unwrap();
+            let runner = CountingRunner {
+                name,
+                supports_calls: supports_calls.clone(),
+            };
+            router
+                .register_with_labels(Arc::new(runner), labels)
+                .unwrap();
+        }
+        assert!(matches!(
+            router.register(Arc::new(NamedRunner("one-too-many"))),
+            Err(CoreError::TooManyRunners(RUNNERS))
+        ));
+
+        let base = mk_spec(TaskKind::Subprocess {
+            command: "echo".into(),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::enabled(),
+        });
+        for name in names.iter().rev() {
+            let spec = base.clone().with_runner_tag(*name);
+            assert!(router.contains_runner_tag(name));
+            assert_eq!(router.pick(&spec).unwrap().name(), *name);
+        }
+        // A linear scan per pick would ask every runner registered before the tagged one.
+        assert_eq!(supports_calls.load(Ordering::Relaxed), RUNNERS);
+        assert!(router.pick(&base.with_runner_tag("missing")).is_none());
+        assert_eq!(supports_calls.load(Ordering::Relaxed), RUNNERS);
+    }
+
+    #[test]
+    fn pick_requires_runner_labels_to_cover_selector() {
+        let labels = |pairs: &[(&str, &str)]| {
+            let mut labels = RunnerLabels::new();
+            for (k, v) in pairs {
+                labels.insert(*k, *v).unwrap();
+            }
+            labels
+        };
+        let mut router = RunnerRouter::new();
+        for (name, pairs) in [
+            ("eu-cpu", labels(&[("region", "eu"), ("class", "cpu")])),
+            (
+                "eu-gpu",
+                labels(&[
+                    ("region", "eu"),
+                    ("class", "gpu"),
+                    (LABEL_RUNNER_TAG, "batch"),
+                ]),
+            ),
+            ("us-gpu", labels(&[("region", "us"), ("class", "gpu")])),
+        ] {
+            router
+                .register_with_labels(Arc::new(NamedRunner(name)), pairs)
+                .unwrap();
+        }
+        let base = mk_spec(TaskKind::Subprocess {
+            command: "echo".into(),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::enabled(),
+        });
+        let pick = |spec: &CreateSpec| router.pick(spec).map(|r| r.name());
+
+        assert_eq!(pick(&base), Some("eu-cpu"));
+        let gpu = base.clone().with_runner_selector("class", "gpu");
+        assert_eq!(pick(&gpu), Some("eu-gpu"));
+        assert_eq!(
+            pick(&gpu.clone().with_runner_selector("region", "us")),
+            Some("us-gpu")
+        );
+        // The selector applies on top of the runner tag.
+        assert_eq!(pick(&gpu.clone().with_runner_tag("batch")), Some("eu-gpu"));
+        assert_eq!(
+            pick(
+                &gpu.with_runner_tag("batch")
+                    .with_runner_selector("region", "us")
+            ),
+            None
+        );
+        assert_eq!(pick(&base.with_runner_selector("region", "ap")), None);
+    }
+
+    #[test]
+    fn round_robin_rotates_runners_sharing_a_tag() {
+        let mut labels = RunnerLabels::new();
+        labels.insert(LABEL_RUNNER_TAG, "pool").unwrap();
+
+        let register = |mut router: RunnerRouter| {
+            router.register(Arc::new(NamedRunner("untagged"))).unwrap();
+            for name in ["pool-1", "pool-2", "pool-3"] {
+                router
+                    .register_with_labels(Arc::new(NamedRunner(name)), labels.clone())
+                    .unwrap();
+            }
+            router
+        };
+        let spec = mk_spec(TaskKind::Subprocess {
+            command: "echo".into(),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::enabled(),
+        })
+        .with_runner_tag("pool");
+
+        let router = register(RunnerRouter::new().with_selection(SelectionPolicy::RoundRobin));
+        let picked: Vec<_> = (0..6).map(|_| router.pick(&spec).unwrap().name()).collect();
+        assert_eq!(
+            picked,
+            ["pool-1", "pool-2", "pool-3", "pool-1", "pool-2", "pool-3"]
+        );
+
+        let router = register(RunnerRouter::new());
+        assert!((0..3).all(|_| router.pick(&spec).unwrap().name() == "pool-1"));
+    }
+
+    #[test]
+    fn build_uses_fallback_only_when_nothing_matches() {
+        let mut labels = RunnerLabels::new();
+        labels.insert(LABEL_RUNNER_TAG, "primary").unwrap();
+        let mut router = RunnerRouter::new();
+        router
+            .register_with_labels(Arc::new(NamedRunner("primary")), labels)
+            .unwrap();
+        router.set_fallback(Arc::new(SubprocessRunnerDummy));
+
+        let subprocess = mk_spec(TaskKind::Subprocess {
+            command: "echo".into(),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::enabled(),
+        });
+
+        let (_, runner) = router
+            .build_with_runner(&subprocess.clone().with_runner_tag("primary"))
+            .unwrap();
+        assert_eq!(runner, "primary");
+
+        let (_, runner) = router
+            .build_with_runner(&subprocess.with_runner_tag("missing"))
+            .unwrap();
+        assert_eq!(runner, "subprocess-only");
+
+        let wasm = mk_spec(TaskKind::Wasm {
+            module: PathBuf::from("mod.wasm"),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+        })
+        .with_runner_tag("missing");
+        assert!(matches!(
+            router.build_with_runner(&wasm),
+            Err(CoreError::NoRunner(kind)) if kind == "wasm"
+        ));
+    }
+
+    #[test]
+    fn runners_at_capacity_are_skipped_until_tasks_are_released() {
+        let mut router = RunnerRouter::new();
+        router
+            .register_with_capacity(Arc::new(NamedRunner("small")), RunnerLabels::new(), 1)
+            .unwrap();
+        let spec = mk_spec(TaskKind::Subprocess {
+            command: "echo".into(),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::enabled(),
+        });
+
+        let (first, runner) = router.build_with_runner(&spec).unwrap();
+        assert_eq!(runner, "small");
+        assert_eq!(router.in_flight(), vec![("small", 1)]);
+        assert!(matches!(
+            router.build_with_runner(&spec),
+            Err(CoreError::AtCapacity(kind)) if kind == "subprocess"
+        ));
+
+        router.register(Arc::new(NamedRunner("overflow"))).unwrap();
+        let (second, runner) = router.build_with_runner(&spec).unwrap();
+        assert_eq!(runner, "overflow");
+
+        router.release(first.name());
+        assert_eq!(router.in_flight(), vec![("small", 0), ("overflow", 0)]);
+        let (_third, runner) = router.build_with_runner(&spec).unwrap();
+        assert_eq!(runner, "small");
+        router.release(second.name());
+        assert_eq!(router.in_flight(), vec![("small", 1), ("overflow", 0)]);
+    }
+
+    struct SelfTestRunner {
+        name: &'static str,
+        build_ok: bool,
+        task_ok: bool,
+    }
+
+    impl Runner for SelfTestRunner {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn supports(&self, _spec: &CreateSpec) -> bool {
+            false
+        }
+
+        fn build_task(
+            &self,
+            _spec: &CreateSpec,
+            _ctx: &BuildContext,
+        ) -> Result<TaskRef, RunnerError> {
+            if !self.build_ok {
+                return Err(RunnerError::InvalidSpec("bad cgroup config".into()));
+            }
+            let task_ok = self.task_ok;
+            Ok(TaskFn::arc(
+                "self-test",
+                move |_ctx: CancellationToken| async move {
+                    if task_ok {
+                        Ok(())
+                    } else {
+                        Err(TaskError::Fatal {
+                            reason: "spawn failed: permission denied".into(),
+                        })
+                    }
+                },
+            ))
+        }
+
+        fn self_test_spec(&self) -> Option<CreateSpec> {
+            Some(mk_spec(TaskKind::None))
+        }
+    }
+
+    #[tokio::test]
+    async fn self_test_reports_misconfigured_runners() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(SubprocessRunnerDummy)).unwrap();
+        for (name, build_ok, task_ok) in [
+            ("healthy", true, true),
+            ("bad-build", false, true),
+            ("bad-spawn", true, false),
+        ] {
+            router
+                .register(Arc::new(SelfTestRunner {
+                    name,
+                    build_ok,
+                    task_ok,
+                }))
+                .unwrap();
+        }
+
+        let results = router.self_test().await;
+        let summary: Vec<_> = results.iter().map(|(n, r)| (*n, r.is_ok())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("subprocess-only", true),
+                ("healthy", true),
+                ("bad-build", false),
+                ("bad-spawn", false),
+            ]
+        );
+        assert!(matches!(
+            results[3].1,
+            Err(RunnerError::SelfTestFailed(ref msg)) if msg.contains("permission denied")
+        ));
+    }
+
+    /// Runner whose build blocks until the test lets it go.
+    struct BlockingRunner(std::sync::Mutex<std::sync::mpsc::Receiver<()>>);
+
+    impl Runner for BlockingRunner {
+        fn name(&self) -> &'static str {
+            "blocking"
+        }
+
+        fn supports(&self, spec: &CreateSpec) -> bool {
+            matches!(spec.kind, TaskKind::Subprocess { .. })
+        }
+
+        fn build_task(
+            &self,
+            _spec: &CreateSpec,
+            _ctx: &BuildContext,
+        ) -> Result<TaskRef, RunnerError> {
+            let _ = self.0.lock().unwrap().recv_timeout(Duration::from_secs(10));
+            Err(RunnerError::Internal("unblocked".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn validate_times_out_on_blocking_build() {
+        let (release, rx) = std::sync::mpsc::channel();
+        let mut router = RunnerRouter::new().with_validate_timeout(Duration::from_millis(50));
+        router
+            .register(Arc::new(BlockingRunner(std::sync::Mutex::new(rx))))
+            .unwrap();
+
+        let spec = mk_spec(TaskKind::Subprocess {
+            command: "echo".to_string(),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::default(),
+        });
+        let res = router.validate(&spec).await;
+        release.send(()).unwrap();
+
+        assert!(matches!(
+            res,
+            Err(CoreError::ValidateTimeout { ref kind, timeout })
+                if kind == "subprocess" && timeout == Duration::from_millis(50)
+        ));
+    }
 }
@@ -1,20 +1,27 @@
-use std::fmt;
+use std::{borrow::Cow, fmt};
 
-use tno_model::TaskEnv;
+use tno_model::{TaskEnv, TaskId};
 
-use crate::metrics::MetricsHandle;
+use crate::{metrics::MetricsHandle, state::TaskReporter};
 
 /// Shared build context passed to all runners.
 #[derive(Clone)]
 pub struct BuildContext {
     env: TaskEnv,
     metrics: MetricsHandle,
+    reporter: TaskReporter,
+    task_id: Option<TaskId>,
 }
 
 impl BuildContext {
     /// Create a new build context with the given params.
     pub fn new(env: TaskEnv, metrics: MetricsHandle) -> Self {
-        Self { env, metrics }
+        Self {
+            env,
+            metrics,
+            reporter: TaskReporter::default(),
+            task_id: None,
+        }
     }
 
     /// Get a reference to the shared environment.
@@ -27,6 +34,18 @@ impl BuildContext {
         &self.metrics
     }
 
+    /// Get a handle for reporting execution details (e.g. exit codes) into task state.
+    pub fn reporter(&self) -> &TaskReporter {
+        &self.reporter
+    }
+
+    /// Task ID requested by the caller (see [`crate::SupervisorApi::submit_with_id`]).
+    ///
+    /// When set, runners must use it as the task name instead of generating a run id.
+    pub fn task_id(&self) -> Option<&TaskId> {
+        self.task_id.as_ref()
+    }
+
     /// Replace the environment and return updated context.
     pub fn with_env(mut self, env: TaskEnv) -> Self {
         self.env = env;
@@ -38,6 +57,26 @@ impl BuildContext {
         self.metrics = metrics;
         self
     }
+
+    /// Context for tasks built by the runner named `runner`, with runner-scoped metrics if the backend supports them.
+    pub(crate) fn for_runner(&self, runner: &'static str) -> Cow<'_, BuildContext> {
+        match self.metrics.for_runner(runner) {
+            Some(metrics) => Cow::Owned(self.clone().with_metrics(metrics)),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Request `id` as the name of the task being built.
+    pub(crate) fn with_task_id(mut self, id: TaskId) -> Self {
+        self.task_id = Some(id);
+        self
+    }
+
+    /// Replace the task reporter (wired by [`crate::SupervisorApi`]).
+    pub(crate) fn with_reporter(mut self, reporter: TaskReporter) -> Self {
+        self.reporter = reporter;
+        self
+    }
 }
 
 impl Default for BuildContext {
@@ -45,6 +84,8 @@ impl Default for BuildContext {
         Self {
             env: TaskEnv::default(),
             metrics: crate::metrics::noop_metrics(),
+            reporter: TaskReporter::default(),
+            task_id: None,
         }
     }
 }
@@ -16,6 +16,9 @@ pub enum RunnerError {
 
     #[error("io error: {0}")]
     Io(String),
+
+    #[error("self-test failed: {0}")]
+    SelfTestFailed(String),
 }
 
 impl From<std::io::Error> for RunnerError {
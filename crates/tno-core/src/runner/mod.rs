@@ -28,6 +28,7 @@ pub trait Runner: Send + Sync {
     /// Build a concrete [`TaskRef`] for the given spec.
     ///
     /// The provided [`BuildContext`] carries shared dependencies injected at router setup time.
+    /// If [`BuildContext::task_id`] is set, the task must be named after it instead of [`Runner::build_run_id`].
     fn build_task(&self, spec: &CreateSpec, ctx: &BuildContext) -> Result<TaskRef, RunnerError>;
 
     /// Builds a default run id for a given slot.
@@ -37,4 +38,20 @@ pub trait Runner: Send + Sync {
     fn build_run_id(&self, slot: &str) -> String {
         make_run_id(self.name(), slot)
     }
+
+    /// Check whether the runner is currently able to execute tasks.
+    ///
+    /// Used by readiness probes; should be cheap and non-blocking.
+    /// The default implementation always reports healthy.
+    fn health_check(&self) -> Result<(), RunnerError> {
+        Ok(())
+    }
+
+    /// Spec of a trivial task used by [`RunnerRouter::self_test`](crate::RunnerRouter::self_test).
+    ///
+    /// The task should go through the full spawn path of the runner (security, limits, ...) and finish immediately.
+    /// The default implementation returns `None`: the runner is skipped by self-tests.
+    fn self_test_spec(&self) -> Option<CreateSpec> {
+        None
+    }
 }
@@ -1,18 +1,46 @@
 mod subscriber;
 pub use subscriber::StateSubscriber;
 
+mod reporter;
+pub use reporter::TaskReporter;
+
+mod runner_errors;
+pub use runner_errors::{DEFAULT_RUNNER_ERROR_CAPACITY, RunnerErrorLog};
+
+mod output;
+pub use output::CapturedOutput;
+
+mod watch;
+pub use watch::TaskWatch;
+
+mod retention;
+pub use retention::{DEFAULT_RETENTION_MAX_AGE, DEFAULT_RETENTION_MAX_TERMINAL, Retention};
+
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
     time::SystemTime,
 };
 
-use tno_model::{Slot, TaskId, TaskInfo, TaskStatus};
+use tno_model::{
+    CreateSpec, DashboardSnapshot, RunnerLabels, Slot, TaskCounts, TaskFilter, TaskId, TaskInfo,
+    TaskPage, TaskQuery, TaskStatus,
+};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+/// Runner label used for tasks that were not built by a registered runner (`submit_with_task`).
+pub const UNKNOWN_RUNNER: &str = "unknown";
+
+/// Capacity of the status update channel (see [`TaskState::subscribe_updates`]).
+const UPDATE_CAPACITY: usize = 256;
 
 /// In-memory task state storage.
 #[derive(Clone)]
 pub struct TaskState {
     inner: Arc<RwLock<TaskStateInner>>,
+    /// Task snapshot after every status change.
+    updates: broadcast::Sender<TaskInfo>,
 }
 
 struct TaskStateInner {
@@ -20,23 +48,94 @@ struct TaskStateInner {
     tasks: HashMap<TaskId, TaskInfo>,
     /// Index: slot -> list of task IDs in that slot.
     by_slot: HashMap<Slot, Vec<TaskId>>,
+    /// Index: batch ID -> list of task IDs in that batch.
+    by_batch: HashMap<String, Vec<TaskId>>,
+    /// Index: caller-supplied idempotency key -> task ID.
+    by_key: HashMap<String, TaskId>,
+    /// Captured output of the latest finished attempt, kept out of [`TaskInfo`] until read.
+    outputs: HashMap<TaskId, TaskOutput>,
+    /// Tasks whose actor finished for good: no further attempts will change their status.
+    finished: HashSet<TaskId>,
+    /// Tasks whose cancellation counts as success (see [`CreateSpec::cancel_is_success`]).
+    cancel_is_success: HashSet<TaskId>,
+    /// Limits on evictable tasks; `None` keeps them forever.
+    retention: Option<Retention>,
+    /// Number of tasks evicted under `retention`.
+    evicted: u64,
 }
 
-impl TaskState {
-    /// Create empty task state.
-    pub fn new() -> Self {
+/// Captured stdout/stderr of one task.
+#[derive(Default)]
+struct TaskOutput {
+    stdout: Option<CapturedOutput>,
+    stderr: Option<CapturedOutput>,
+}
+
+/// Task registration request (see [`TaskState::register`]).
+pub(crate) struct NewTask {
+    pub(crate) id: TaskId,
+    pub(crate) slot: Slot,
+    pub(crate) kind_summary: Option<String>,
+    pub(crate) runner: Option<&'static str>,
+    pub(crate) idempotency_key: Option<String>,
+    pub(crate) batch_id: Option<String>,
+    pub(crate) labels: RunnerLabels,
+    pub(crate) cancel_is_success: bool,
+    /// Caller-supplied ID that must not be used by another non-terminal task.
+    pub(crate) explicit_id: bool,
+}
+
+/// Why a task was not registered (see [`TaskState::register`]).
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RegisterError {
+    /// The idempotency key is held by this non-terminal task.
+    KeyTaken(TaskId),
+    /// A non-terminal task already uses the caller-supplied ID.
+    DuplicateId(TaskId),
+}
+
+impl NewTask {
+    /// Registration of a task built by `runner` from `spec`.
+    pub(crate) fn from_spec(id: TaskId, spec: &CreateSpec, runner: &'static str) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(TaskStateInner {
-                tasks: HashMap::new(),
-                by_slot: HashMap::new(),
-            })),
+            id,
+            slot: spec.slot.clone(),
+            kind_summary: spec.kind.summary(),
+            runner: Some(runner),
+            idempotency_key: spec.idempotency_key.clone(),
+            batch_id: spec.batch_id.clone(),
+            labels: spec.labels.clone(),
+            cancel_is_success: spec.cancel_is_success,
+            explicit_id: false,
         }
     }
 
-    /// Register a new task (called on TaskAdded event).
-    pub fn add_task(&self, id: TaskId, slot: Slot) {
-        let mut inner = self.inner.write().unwrap();
+    /// Registration of a code-defined task (no spec, no runner).
+    pub(crate) fn code_defined(id: TaskId, slot: Slot) -> Self {
+        Self {
+            id,
+            slot,
+            kind_summary: None,
+            runner: None,
+            idempotency_key: None,
+            batch_id: None,
+            labels: RunnerLabels::new(),
+            cancel_is_success: false,
+            explicit_id: false,
+        }
+    }
+}
 
+impl TaskStateInner {
+    fn insert_task(
+        &mut self,
+        id: TaskId,
+        slot: Slot,
+        kind_summary: Option<String>,
+        runner_name: Option<String>,
+        batch_id: Option<String>,
+        labels: RunnerLabels,
+    ) {
         let now = SystemTime::now();
         let info = TaskInfo {
             id: id.clone(),
@@ -46,10 +145,219 @@ impl TaskState {
             created_at: now,
             updated_at: now,
             error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary,
+            runner_name,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: batch_id.clone(),
+            labels,
+        };
+
+        // A caller-supplied ID may be reused once its previous task has finished.
+        if let Some(previous) = self.tasks.insert(id.clone(), info) {
+            self.outputs.remove(&id);
+            self.finished.remove(&id);
+            self.cancel_is_success.remove(&id);
+            self.unindex(&previous);
+        }
+        if let Some(batch) = batch_id {
+            self.by_batch.entry(batch).or_default().push(id.clone());
+        }
+        self.by_slot.entry(slot).or_default().push(id);
+    }
+
+    /// Drop a removed task from the slot and batch indexes.
+    fn unindex(&mut self, info: &TaskInfo) {
+        if let Some(ids) = self.by_slot.get_mut(&info.slot) {
+            ids.retain(|task_id| task_id != &info.id);
+        }
+        if let Some(batch) = &info.batch_id
+            && let Some(ids) = self.by_batch.get_mut(batch)
+        {
+            ids.retain(|task_id| task_id != &info.id);
+            if ids.is_empty() {
+                self.by_batch.remove(batch);
+            }
+        }
+    }
+
+    /// Drop a task and everything recorded for it.
+    fn remove(&mut self, id: &TaskId) {
+        self.by_key.retain(|_, task_id| task_id != id);
+        self.outputs.remove(id);
+        self.finished.remove(id);
+        self.cancel_is_success.remove(id);
+        if let Some(info) = self.tasks.remove(id) {
+            self.unindex(&info);
+        }
+    }
+
+    /// Whether a task may be evicted: terminal, and not going to run again.
+    ///
+    /// Periodic and restarting tasks pass through terminal statuses between runs,
+    /// so only canceled and exhausted tasks or those whose actor finished qualify.
+    fn is_evictable(&self, info: &TaskInfo) -> bool {
+        match info.status {
+            TaskStatus::Canceled | TaskStatus::Exhausted => true,
+            status => status.is_terminal() && self.finished.contains(&info.id),
+        }
+    }
+
+    /// Evict the tasks exceeding the retention limits at `now`.
+    fn evict(&mut self, now: SystemTime) {
+        let Some(retention) = self.retention else {
+            return;
+        };
+        let evictable = self
+            .tasks
+            .values()
+            .filter(|info| self.is_evictable(info))
+            .map(|info| (info.updated_at, info.id.clone()))
+            .collect();
+        for id in retention.select(evictable, now) {
+            debug!(task = %id, "evicting finished task from state");
+            self.remove(&id);
+            self.evicted += 1;
+        }
+    }
+
+    /// Copy of `info` with its captured output expanded.
+    fn read_info(&self, info: &TaskInfo) -> TaskInfo {
+        let mut info = info.clone();
+        if let Some(out) = self.outputs.get(&info.id) {
+            info.stdout = out.stdout.as_ref().map(CapturedOutput::text);
+            info.stderr = out.stderr.as_ref().map(CapturedOutput::text);
+        }
+        info
+    }
+
+    /// Tasks that may match `filter`: those of its slot or batch index if it names one, otherwise all tasks.
+    fn candidates<'a>(
+        &'a self,
+        filter: &TaskFilter,
+    ) -> Box<dyn Iterator<Item = &'a TaskInfo> + 'a> {
+        let ids = match (&filter.slot, &filter.batch_id) {
+            (Some(slot), _) => self.by_slot.get(slot),
+            (None, Some(batch)) => self.by_batch.get(batch),
+            (None, None) => return Box::new(self.tasks.values()),
         };
+        Box::new(
+            ids.into_iter()
+                .flatten()
+                .filter_map(|id| self.tasks.get(id)),
+        )
+    }
 
-        inner.tasks.insert(id.clone(), info);
-        inner.by_slot.entry(slot).or_default().push(id);
+    /// Task ID holding `key`, if that task is still known and not terminal.
+    fn active_by_key(&self, key: &str) -> Option<TaskId> {
+        let id = self.by_key.get(key)?;
+        let info = self.tasks.get(id)?;
+        (!info.status.is_terminal()).then(|| id.clone())
+    }
+
+    /// Whether a non-terminal task with the given ID exists.
+    fn is_active(&self, id: &TaskId) -> bool {
+        self.tasks
+            .get(id)
+            .is_some_and(|info| !info.status.is_terminal())
+    }
+
+    fn register(&mut self, task: NewTask) -> Result<(), RegisterError> {
+        if let Some(existing) = task
+            .idempotency_key
+            .as_deref()
+            .and_then(|key| self.active_by_key(key))
+        {
+            return Err(RegisterError::KeyTaken(existing));
+        }
+        if task.explicit_id && self.is_active(&task.id) {
+            return Err(RegisterError::DuplicateId(task.id));
+        }
+        if let Some(key) = &task.idempotency_key {
+            self.by_key.insert(key.clone(), task.id.clone());
+        }
+        let id = task.id.clone();
+        self.insert_task(
+            task.id,
+            task.slot,
+            task.kind_summary,
+            task.runner.map(str::to_string),
+            task.batch_id,
+            task.labels,
+        );
+        if task.cancel_is_success {
+            self.cancel_is_success.insert(id);
+        }
+        Ok(())
+    }
+}
+
+impl TaskState {
+    /// Create empty task state with the default [`Retention`].
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(TaskStateInner {
+                tasks: HashMap::new(),
+                by_slot: HashMap::new(),
+                by_batch: HashMap::new(),
+                by_key: HashMap::new(),
+                outputs: HashMap::new(),
+                finished: HashSet::new(),
+                cancel_is_success: HashSet::new(),
+                retention: Some(Retention::default()),
+                evicted: 0,
+            })),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+        }
+    }
+
+    /// Register a new task without runner or idempotency key.
+    ///
+    /// `kind_summary` is a short description of what the task executes (see [`tno_model::TaskKind::summary`]).
+    #[cfg(test)]
+    pub fn add_task(&self, id: TaskId, slot: Slot, kind_summary: Option<String>) {
+        let mut inner = self.inner.write().unwrap();
+        inner.insert_task(id, slot, kind_summary, None, None, RunnerLabels::new());
+    }
+
+    /// Register a new task (called on submit).
+    ///
+    /// Nothing is registered if the task carries an idempotency key already held by a non-terminal task,
+    /// or if its caller-supplied ID is used by a non-terminal task.
+    pub(crate) fn register(&self, task: NewTask) -> Result<(), RegisterError> {
+        let mut inner = self.inner.write().unwrap();
+        let result = inner.register(task);
+        inner.evict(SystemTime::now());
+        result
+    }
+
+    /// Whether a non-terminal task with the given ID exists.
+    pub fn is_active(&self, id: &TaskId) -> bool {
+        self.inner.read().unwrap().is_active(id)
+    }
+
+    /// Find the non-terminal task submitted with idempotency key `key`.
+    pub fn find_by_idempotency_key(&self, key: &str) -> Option<TaskId> {
+        self.inner.read().unwrap().active_by_key(key)
+    }
+
+    /// Whether cancelling a task counts as success (see [`CreateSpec::cancel_is_success`]).
+    pub(crate) fn cancel_is_success(&self, id: &TaskId) -> bool {
+        self.inner.read().unwrap().cancel_is_success.contains(id)
+    }
+
+    /// Name of the runner that built a task ([`UNKNOWN_RUNNER`] if not known).
+    pub fn runner(&self, id: &TaskId) -> String {
+        let inner = self.inner.read().unwrap();
+        inner
+            .tasks
+            .get(id)
+            .and_then(|info| info.runner_name.clone())
+            .unwrap_or_else(|| UNKNOWN_RUNNER.to_string())
     }
 
     /// Update task status (called on state transition events).
@@ -63,6 +371,119 @@ impl TaskState {
                 info.error = Some(err);
             }
         }
+
+        // Sent under the lock so that watchers see updates in order.
+        if self.updates.receiver_count() > 0
+            && let Some(info) = inner.tasks.get(id)
+        {
+            let _ = self.updates.send(inner.read_info(info));
+        }
+        if status.is_terminal() {
+            inner.evict(SystemTime::now());
+        }
+    }
+
+    /// Record that a task's actor finished for good, making the task evictable once terminal.
+    pub(crate) fn mark_finished(&self, id: &TaskId) {
+        let mut inner = self.inner.write().unwrap();
+        if inner.tasks.contains_key(id) {
+            inner.finished.insert(id.clone());
+            inner.evict(SystemTime::now());
+        }
+    }
+
+    /// Bound the finished tasks kept in state (`None` keeps them forever).
+    ///
+    /// Tasks over the limits are evicted right away, then whenever a task is registered or finishes.
+    pub fn set_retention(&self, retention: Option<Retention>) {
+        let mut inner = self.inner.write().unwrap();
+        inner.retention = retention;
+        inner.evict(SystemTime::now());
+    }
+
+    /// Number of tasks evicted under the retention policy so far.
+    pub fn evicted_count(&self) -> u64 {
+        self.inner.read().unwrap().evicted
+    }
+
+    /// Subscribe to task snapshots sent after every status change.
+    ///
+    /// The channel is bounded; slow receivers skip updates (see [`TaskWatch`]).
+    pub(crate) fn subscribe_updates(&self) -> broadcast::Receiver<TaskInfo> {
+        self.updates.subscribe()
+    }
+
+    /// Record the exit code of the latest finished attempt (reported by runners).
+    pub fn set_exit_code(&self, id: &TaskId, code: i32) {
+        self.set_exit(id, Some(code), None);
+    }
+
+    /// Record the signal that terminated the latest finished attempt (reported by runners).
+    ///
+    /// The exit code is set to the negative signal number.
+    pub fn set_signal(&self, id: &TaskId, signal: i32) {
+        self.set_exit(id, Some(-signal), Some(signal));
+    }
+
+    fn set_exit(&self, id: &TaskId, code: Option<i32>, signal: Option<i32>) {
+        let mut inner = self.inner.write().unwrap();
+
+        if let Some(info) = inner.tasks.get_mut(id) {
+            info.exit_code = code;
+            info.signal = signal;
+            info.updated_at = SystemTime::now();
+        }
+    }
+
+    /// Record the cgroup the task runs in (reported by runners).
+    pub fn set_cgroup(&self, id: &TaskId, cgroup: String) {
+        let mut inner = self.inner.write().unwrap();
+
+        if let Some(info) = inner.tasks.get_mut(id) {
+            info.cgroup = Some(cgroup);
+        }
+    }
+
+    /// Record which resource limit most likely ended the latest finished attempt (reported by runners).
+    ///
+    /// `None` clears the cause left by an earlier attempt.
+    pub fn set_limit_cause(&self, id: &TaskId, cause: Option<String>) {
+        let mut inner = self.inner.write().unwrap();
+
+        if let Some(info) = inner.tasks.get_mut(id) {
+            info.limit_cause = cause;
+        }
+    }
+
+    /// Record the captured output of the latest finished attempt (reported by runners).
+    ///
+    /// Output is stored as given (possibly compressed) and expanded into [`TaskInfo`] only when read.
+    pub fn set_output(
+        &self,
+        id: &TaskId,
+        stdout: Option<CapturedOutput>,
+        stderr: Option<CapturedOutput>,
+    ) {
+        let mut inner = self.inner.write().unwrap();
+
+        if inner.tasks.contains_key(id) {
+            inner
+                .outputs
+                .insert(id.clone(), TaskOutput { stdout, stderr });
+        }
+    }
+
+    /// Bytes of captured output held in memory for a task.
+    #[cfg(test)]
+    pub fn output_stored_len(&self, id: &TaskId) -> usize {
+        let inner = self.inner.read().unwrap();
+        inner.outputs.get(id).map_or(0, |out| {
+            [&out.stdout, &out.stderr]
+                .into_iter()
+                .flatten()
+                .map(CapturedOutput::stored_len)
+                .sum()
+        })
     }
 
     /// Increment attempt counter (called on TaskStarting event).
@@ -75,21 +496,16 @@ impl TaskState {
         }
     }
 
-    /// Remove task from state (called on TaskRemoved event).
+    /// Remove task from state.
+    #[cfg(test)]
     pub fn remove_task(&self, id: &TaskId) {
-        let mut inner = self.inner.write().unwrap();
-
-        if let Some(info) = inner.tasks.remove(id)
-            && let Some(ids) = inner.by_slot.get_mut(&info.slot)
-        {
-            ids.retain(|task_id| task_id != id);
-        }
+        self.inner.write().unwrap().remove(id);
     }
 
     /// Get task info by ID.
     pub fn get(&self, id: &TaskId) -> Option<TaskInfo> {
         let inner = self.inner.read().unwrap();
-        inner.tasks.get(id).cloned()
+        inner.tasks.get(id).map(|info| inner.read_info(info))
     }
 
     /// List all tasks in a specific slot.
@@ -101,7 +517,44 @@ impl TaskState {
             .get(slot)
             .map(|ids| {
                 ids.iter()
-                    .filter_map(|id| inner.tasks.get(id).cloned())
+                    .filter_map(|id| inner.tasks.get(id))
+                    .map(|info| inner.read_info(info))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Tasks of a slot that may still run, with their current status, in submission order.
+    ///
+    /// Canceled, exhausted and finished tasks are left out (see [`TaskStateInner::is_evictable`]);
+    /// a terminal status of the others may be just the outcome of an attempt between restarts.
+    pub(crate) fn unfinished_in_slot(&self, slot: &str) -> Vec<(TaskId, TaskStatus)> {
+        let inner = self.inner.read().unwrap();
+
+        inner
+            .by_slot
+            .get(slot)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| inner.tasks.get(id))
+                    .filter(|info| !inner.is_evictable(info))
+                    .map(|info| (info.id.clone(), info.status))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// List all tasks of a batch (see [`tno_model::CreateSpec::batch_id`]).
+    pub fn list_by_batch(&self, batch_id: &str) -> Vec<TaskInfo> {
+        let inner = self.inner.read().unwrap();
+
+        inner
+            .by_batch
+            .get(batch_id)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| inner.tasks.get(id))
+                    .map(|info| inner.read_info(info))
                     .collect()
             })
             .unwrap_or_default()
@@ -110,7 +563,41 @@ impl TaskState {
     /// List all tasks.
     pub fn list_all(&self) -> Vec<TaskInfo> {
         let inner = self.inner.read().unwrap();
-        inner.tasks.values().cloned().collect()
+        inner
+            .tasks
+            .values()
+            .map(|info| inner.read_info(info))
+            .collect()
+    }
+
+    /// List one page of all tasks, ordered by creation time (see [`TaskPage`]).
+    pub fn list_page(&self, offset: usize, limit: usize) -> TaskPage {
+        let inner = self.inner.read().unwrap();
+        let mut page = TaskPage::collect(inner.tasks.values(), offset, limit);
+        for info in &mut page.tasks {
+            *info = inner.read_info(info);
+        }
+        page
+    }
+
+    /// Aggregate a dashboard snapshot under a single read lock.
+    ///
+    /// Includes up to `recent` most recently finished tasks.
+    pub fn dashboard(&self, recent: usize) -> DashboardSnapshot {
+        let inner = self.inner.read().unwrap();
+        let tasks = inner.tasks.values().map(|info| {
+            let runner = info.runner_name.as_deref().unwrap_or(UNKNOWN_RUNNER);
+            (info, runner)
+        });
+        DashboardSnapshot::collect(tasks, recent)
+    }
+
+    /// Count tasks by status and slot under a single read lock.
+    ///
+    /// O(n) over tracked tasks, but nothing is cloned except slot names.
+    pub fn counts(&self) -> TaskCounts {
+        let inner = self.inner.read().unwrap();
+        TaskCounts::collect(inner.tasks.values())
     }
 
     /// List tasks matching a status filter.
@@ -120,7 +607,31 @@ impl TaskState {
             .tasks
             .values()
             .filter(|info| info.status == status)
-            .cloned()
+            .map(|info| inner.read_info(info))
+            .collect()
+    }
+
+    /// List tasks matching `filter` in a single pass (over the slot or batch index if the filter names one).
+    pub fn list_filtered(&self, filter: &TaskFilter) -> Vec<TaskInfo> {
+        let inner = self.inner.read().unwrap();
+        inner
+            .candidates(filter)
+            .filter(|info| filter.matches(info))
+            .map(|info| inner.read_info(info))
+            .collect()
+    }
+
+    /// List tasks matching `query`, in query order, in a single pass under one read lock.
+    pub fn query(&self, query: &TaskQuery) -> Vec<TaskInfo> {
+        let inner = self.inner.read().unwrap();
+        let mut tasks: Vec<&TaskInfo> = inner
+            .candidates(&query.filter)
+            .filter(|info| query.matches(info))
+            .collect();
+        tasks.sort_by(|a, b| query.compare(a, b));
+        tasks
+            .into_iter()
+            .map(|info| inner.read_info(info))
             .collect()
     }
 }
@@ -135,13 +646,15 @@ impl Default for TaskState {
 mod tests {
     use super::*;
 
+    use std::time::Duration;
+
     #[test]
     fn add_and_get_task() {
         let state = TaskState::new();
         let id = TaskId::from("task-1");
         let slot = "demo-slot".to_string();
 
-        state.add_task(id.clone(), slot.clone());
+        state.add_task(id.clone(), slot.clone(), None);
 
         let info = state.get(&id).expect("task should exist");
         assert_eq!(info.id, id);
@@ -155,7 +668,7 @@ mod tests {
         let state = TaskState::new();
         let id = TaskId::from("task-1");
 
-        state.add_task(id.clone(), "slot".to_string());
+        state.add_task(id.clone(), "slot".to_string(), None);
         state.update_status(&id, TaskStatus::Running, None);
 
         let info = state.get(&id).unwrap();
@@ -168,7 +681,7 @@ mod tests {
         let state = TaskState::new();
         let id = TaskId::from("task-1");
 
-        state.add_task(id.clone(), "slot".to_string());
+        state.add_task(id.clone(), "slot".to_string(), None);
         state.update_status(&id, TaskStatus::Failed, Some("timeout".to_string()));
 
         let info = state.get(&id).unwrap();
@@ -176,12 +689,28 @@ mod tests {
         assert_eq!(info.error.as_deref(), Some("timeout"));
     }
 
+    #[test]
+    fn set_exit_code_survives_status_update() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+
+        state.add_task(id.clone(), "slot".to_string(), None);
+        assert!(state.get(&id).unwrap().exit_code.is_none());
+
+        state.set_exit_code(&id, 3);
+        state.update_status(&id, TaskStatus::Failed, Some("exit 3".to_string()));
+
+        let info = state.get(&id).unwrap();
+        assert_eq!(info.exit_code, Some(3));
+        assert_eq!(info.status, TaskStatus::Failed);
+    }
+
     #[test]
     fn increment_attempt_updates_counter() {
         let state = TaskState::new();
         let id = TaskId::from("task-1");
 
-        state.add_task(id.clone(), "slot".to_string());
+        state.add_task(id.clone(), "slot".to_string(), None);
         state.increment_attempt(&id);
         state.increment_attempt(&id);
 
@@ -194,7 +723,7 @@ mod tests {
         let state = TaskState::new();
         let id = TaskId::from("task-1");
 
-        state.add_task(id.clone(), "slot".to_string());
+        state.add_task(id.clone(), "slot".to_string(), None);
         assert!(state.get(&id).is_some());
 
         state.remove_task(&id);
@@ -205,9 +734,9 @@ mod tests {
     fn list_by_slot_returns_correct_tasks() {
         let state = TaskState::new();
 
-        state.add_task(TaskId::from("task-1"), "slot-a".to_string());
-        state.add_task(TaskId::from("task-2"), "slot-a".to_string());
-        state.add_task(TaskId::from("task-3"), "slot-b".to_string());
+        state.add_task(TaskId::from("task-1"), "slot-a".to_string(), None);
+        state.add_task(TaskId::from("task-2"), "slot-a".to_string(), None);
+        state.add_task(TaskId::from("task-3"), "slot-b".to_string(), None);
 
         let slot_a_tasks = state.list_by_slot("slot-a");
         assert_eq!(slot_a_tasks.len(), 2);
@@ -216,14 +745,90 @@ mod tests {
         assert_eq!(slot_b_tasks.len(), 1);
     }
 
+    #[test]
+    fn list_filtered_combines_slot_and_statuses() {
+        let state = TaskState::new();
+        let cases = [
+            ("run-a", "slot-a", TaskStatus::Running),
+            ("pend-a", "slot-a", TaskStatus::Pending),
+            ("fail-a", "slot-a", TaskStatus::Failed),
+            ("run-b", "slot-b", TaskStatus::Running),
+        ];
+        for (id, slot, status) in cases {
+            state.add_task(TaskId::from(id), slot.to_string(), None);
+            state.update_status(&TaskId::from(id), status, None);
+        }
+
+        let filter = TaskFilter::default()
+            .with_slot("slot-a")
+            .with_status(TaskStatus::Running)
+            .with_status(TaskStatus::Pending);
+        let mut ids: Vec<String> = state
+            .list_filtered(&filter)
+            .into_iter()
+            .map(|t| t.id.into_inner())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["pend-a", "run-a"]);
+
+        let running = TaskFilter::default().with_status(TaskStatus::Running);
+        assert_eq!(state.list_filtered(&running).len(), 2);
+        assert!(
+            state
+                .list_filtered(&TaskFilter::default().with_slot("missing"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn query_filters_by_status_and_sorts_by_updated_at_descending() {
+        use std::time::Duration;
+        use tno_model::{SortDirection, TaskSortKey};
+
+        let state = TaskState::new();
+        for id in ["fail-1", "run-1", "fail-2", "fail-3"] {
+            let mut task = NewTask::code_defined(TaskId::from(id), "slot".to_string());
+            if id != "fail-3" {
+                task.labels.insert("team", "infra").unwrap();
+            }
+            state.register(task).unwrap();
+        }
+        // Later status changes get a newer `updated_at`.
+        for (id, status) in [
+            ("fail-2", TaskStatus::Failed),
+            ("run-1", TaskStatus::Running),
+            ("fail-3", TaskStatus::Failed),
+            ("fail-1", TaskStatus::Failed),
+        ] {
+            std::thread::sleep(Duration::from_millis(2));
+            state.update_status(&TaskId::from(id), status, None);
+        }
+
+        let ids = |query: &TaskQuery| -> Vec<String> {
+            state
+                .query(query)
+                .into_iter()
+                .map(|t| t.id.into_inner())
+                .collect()
+        };
+        let failed = TaskFilter::default().with_status(TaskStatus::Failed);
+        let newest_first = TaskQuery::new(failed.clone())
+            .sorted_by(TaskSortKey::UpdatedAt, SortDirection::Descending);
+        assert_eq!(ids(&newest_first), ["fail-1", "fail-3", "fail-2"]);
+
+        let labeled = TaskQuery::new(failed.with_label("team", "infra"))
+            .sorted_by(TaskSortKey::UpdatedAt, SortDirection::Descending);
+        assert_eq!(ids(&labeled), ["fail-1", "fail-2"]);
+    }
+
     #[test]
     fn list_by_status_filters_correctly() {
         let state = TaskState::new();
         let id1 = TaskId::from("task-1");
         let id2 = TaskId::from("task-2");
 
-        state.add_task(id1.clone(), "slot".to_string());
-        state.add_task(id2.clone(), "slot".to_string());
+        state.add_task(id1.clone(), "slot".to_string(), None);
+        state.add_task(id2.clone(), "slot".to_string(), None);
         state.update_status(&id1, TaskStatus::Running, None);
 
         let running_tasks = state.list_by_status(TaskStatus::Running);
@@ -239,11 +844,228 @@ mod tests {
     fn list_all_returns_all_tasks() {
         let state = TaskState::new();
 
-        state.add_task(TaskId::from("task-1"), "slot-a".to_string());
-        state.add_task(TaskId::from("task-2"), "slot-b".to_string());
-        state.add_task(TaskId::from("task-3"), "slot-c".to_string());
+        state.add_task(TaskId::from("task-1"), "slot-a".to_string(), None);
+        state.add_task(TaskId::from("task-2"), "slot-b".to_string(), None);
+        state.add_task(TaskId::from("task-3"), "slot-c".to_string(), None);
 
         let all_tasks = state.list_all();
         assert_eq!(all_tasks.len(), 3);
     }
+
+    #[test]
+    fn list_page_splits_tasks_in_creation_order() {
+        let state = TaskState::new();
+        for i in 0..5 {
+            state.add_task(TaskId::from(format!("task-{i}")), "slot".to_string(), None);
+        }
+
+        let first = state.list_page(0, 2);
+        assert_eq!(first.total, 5);
+        assert_eq!(first.tasks.len(), 2);
+        assert_eq!(first.next_offset, Some(2));
+
+        let mut seen: Vec<TaskId> = first.tasks.into_iter().map(|t| t.id).collect();
+        let mut offset = first.next_offset;
+        while let Some(next) = offset {
+            let page = state.list_page(next, 2);
+            seen.extend(page.tasks.into_iter().map(|t| t.id));
+            offset = page.next_offset;
+        }
+        seen.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        seen.dedup();
+        assert_eq!(seen.len(), 5);
+    }
+
+    #[test]
+    fn compressed_output_is_stored_smaller_and_read_back_verbatim() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+
+        let stdout = "tick\n".repeat(50_000);
+        state.set_output(&id, Some(CapturedOutput::gzip(stdout.clone())), None);
+
+        assert!(state.output_stored_len(&id) < stdout.len() / 10);
+        let info = state.get(&id).unwrap();
+        assert_eq!(info.stdout.as_deref(), Some(stdout.as_str()));
+        assert_eq!(info.stderr, None);
+        assert_eq!(state.list_all()[0].stdout, info.stdout);
+
+        state.remove_task(&id);
+        assert_eq!(state.output_stored_len(&id), 0);
+    }
+
+    #[test]
+    fn idempotency_key_is_released_by_terminal_status() {
+        let state = TaskState::new();
+        let new_task = |id: &str| NewTask {
+            id: TaskId::from(id),
+            slot: "keyed-slot".to_string(),
+            kind_summary: None,
+            runner: Some("runner"),
+            idempotency_key: Some("key-1".to_string()),
+            batch_id: None,
+            labels: RunnerLabels::new(),
+            cancel_is_success: false,
+            explicit_id: false,
+        };
+
+        assert_eq!(state.register(new_task("task-1")), Ok(()));
+        assert_eq!(
+            state.register(new_task("task-2")),
+            Err(RegisterError::KeyTaken(TaskId::from("task-1")))
+        );
+        assert!(state.get(&TaskId::from("task-2")).is_none());
+
+        state.update_status(&TaskId::from("task-1"), TaskStatus::Succeeded, None);
+        assert_eq!(state.find_by_idempotency_key("key-1"), None);
+        assert_eq!(state.register(new_task("task-3")), Ok(()));
+        assert_eq!(
+            state.find_by_idempotency_key("key-1"),
+            Some(TaskId::from("task-3"))
+        );
+        assert_eq!(state.runner(&TaskId::from("task-3")), "runner");
+    }
+
+    #[test]
+    fn explicit_id_is_not_registered_twice_while_active() {
+        let state = TaskState::new();
+        let id = TaskId::from("order-1");
+        let new_task = |slot: &str| NewTask {
+            explicit_id: true,
+            ..NewTask::code_defined(id.clone(), slot.to_string())
+        };
+
+        assert_eq!(state.register(new_task("slot-a")), Ok(()));
+        assert_eq!(
+            state.register(new_task("slot-b")),
+            Err(RegisterError::DuplicateId(id.clone()))
+        );
+        assert_eq!(state.get(&id).unwrap().slot, "slot-a");
+        assert!(state.list_by_slot("slot-b").is_empty());
+
+        state.update_status(&id, TaskStatus::Succeeded, None);
+        assert_eq!(state.register(new_task("slot-b")), Ok(()));
+        assert_eq!(state.get(&id).unwrap().slot, "slot-b");
+    }
+
+    #[test]
+    fn dashboard_aggregates_counts() {
+        let state = TaskState::new();
+        let cases = [
+            ("run-1", "slot-a", Some("subprocess"), TaskStatus::Running),
+            ("run-2", "slot-b", Some("subprocess"), TaskStatus::Running),
+            ("pend-1", "slot-a", Some("wasm"), TaskStatus::Pending),
+            ("fail-1", "slot-a", Some("subprocess"), TaskStatus::Failed),
+            ("ok-1", "slot-c", None, TaskStatus::Succeeded),
+            ("ok-2", "slot-c", None, TaskStatus::Succeeded),
+        ];
+        for (id, slot, runner, status) in cases {
+            let id = TaskId::from(id);
+            state
+                .register(NewTask {
+                    id: id.clone(),
+                    slot: slot.to_string(),
+                    kind_summary: None,
+                    runner,
+                    idempotency_key: None,
+                    batch_id: None,
+                    labels: RunnerLabels::new(),
+                    cancel_is_success: false,
+                    explicit_id: false,
+                })
+                .unwrap();
+            state.update_status(&id, status, None);
+        }
+
+        let counts = state.counts();
+        assert_eq!(counts.total, 6);
+        assert_eq!(counts.status(TaskStatus::Running), 2);
+        assert_eq!(counts.slot("slot-a"), 3);
+
+        let dashboard = state.dashboard(2);
+
+        assert_eq!(dashboard.total, 6);
+        assert_eq!(dashboard.by_status[&TaskStatus::Running], 2);
+        assert_eq!(dashboard.by_status[&TaskStatus::Pending], 1);
+        assert_eq!(dashboard.by_status[&TaskStatus::Succeeded], 2);
+        assert_eq!(dashboard.by_status[&TaskStatus::Failed], 1);
+        assert!(!dashboard.by_status.contains_key(&TaskStatus::Canceled));
+
+        assert_eq!(dashboard.active_by_runner["subprocess"], 2);
+        assert_eq!(dashboard.active_by_runner["wasm"], 1);
+        assert!(!dashboard.active_by_runner.contains_key(UNKNOWN_RUNNER));
+
+        assert_eq!(dashboard.slots["slot-a"].running, 1);
+        assert_eq!(dashboard.slots["slot-a"].pending, 1);
+        assert_eq!(dashboard.slots["slot-b"].running, 1);
+        assert!(!dashboard.slots.contains_key("slot-c"));
+
+        assert_eq!(dashboard.recent_terminal.len(), 2);
+        assert!(
+            dashboard
+                .recent_terminal
+                .iter()
+                .all(|t| t.status.is_terminal())
+        );
+        assert!(dashboard.recent_terminal[0].updated_at >= dashboard.recent_terminal[1].updated_at);
+    }
+
+    #[test]
+    fn retention_evicts_only_tasks_that_will_not_run_again() {
+        let state = TaskState::new();
+        let id = |n: usize| TaskId::from(format!("task-{n}"));
+        for n in 0..5 {
+            state.add_task(id(n), "slot".to_string(), None);
+        }
+        state.update_status(&id(0), TaskStatus::Running, None);
+        // Between runs of a periodic task: terminal, but its actor is still alive.
+        state.update_status(&id(1), TaskStatus::Succeeded, None);
+        state.update_status(&id(2), TaskStatus::Canceled, None);
+        state.update_status(&id(3), TaskStatus::Failed, None);
+        state.mark_finished(&id(3));
+        state.update_status(&id(4), TaskStatus::Exhausted, None);
+
+        state.set_retention(Some(Retention {
+            max_terminal: 1,
+            max_age: Duration::from_secs(3_600),
+        }));
+        assert_eq!(state.evicted_count(), 2);
+        assert!(state.get(&id(2)).is_none());
+        assert!(state.get(&id(3)).is_none());
+        assert!(state.get(&id(4)).is_some());
+
+        state.set_retention(Some(Retention {
+            max_terminal: 10,
+            max_age: Duration::ZERO,
+        }));
+        assert_eq!(state.evicted_count(), 3);
+        let mut left: Vec<_> = state.list_all().into_iter().map(|t| t.id).collect();
+        left.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(left, [id(0), id(1)]);
+    }
+
+    #[test]
+    fn default_retention_bounds_finished_tasks() {
+        let state = TaskState::new();
+        let total = DEFAULT_RETENTION_MAX_TERMINAL + 50;
+        for n in 0..total {
+            let id = TaskId::from(format!("task-{n}"));
+            state
+                .register(NewTask::code_defined(id.clone(), format!("slot-{n}")))
+                .unwrap();
+            state.update_status(&id, TaskStatus::Running, None);
+            state.update_status(&id, TaskStatus::Succeeded, None);
+            state.mark_finished(&id);
+        }
+
+        assert_eq!(state.list_all().len(), DEFAULT_RETENTION_MAX_TERMINAL);
+        assert_eq!(state.evicted_count(), 50);
+        assert!(state.get(&TaskId::from("task-0")).is_none());
+        assert!(
+            state
+                .get(&TaskId::from(format!("task-{}", total - 1)))
+                .is_some()
+        );
+    }
 }
@@ -0,0 +1,97 @@
+use std::{fmt, io::Read, io::Write};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+/// Captured task output as kept in task state.
+///
+/// Runners choose the representation: [`CapturedOutput::Plain`] keeps the text as is,
+/// [`CapturedOutput::gzip`] trades CPU for memory on verbose tasks.
+/// Either way, readers get the original text back in [`tno_model::TaskInfo`].
+#[derive(Clone, PartialEq, Eq)]
+pub enum CapturedOutput {
+    /// Uncompressed text.
+    Plain(String),
+    /// Gzip-compressed UTF-8 text.
+    Gzip(Vec<u8>),
+}
+
+impl CapturedOutput {
+    /// Compress `text` with gzip.
+    ///
+    /// Falls back to [`CapturedOutput::Plain`] if compression fails or does not make the output smaller.
+    pub fn gzip(text: String) -> Self {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        match encoder
+            .write_all(text.as_bytes())
+            .and_then(|()| encoder.finish())
+        {
+            Ok(data) if data.len() < text.len() => CapturedOutput::Gzip(data),
+            _ => CapturedOutput::Plain(text),
+        }
+    }
+
+    /// Bytes held in memory.
+    pub fn stored_len(&self) -> usize {
+        match self {
+            CapturedOutput::Plain(text) => text.len(),
+            CapturedOutput::Gzip(data) => data.len(),
+        }
+    }
+
+    /// The original text (decompressed if needed).
+    pub fn text(&self) -> String {
+        match self {
+            CapturedOutput::Plain(text) => text.clone(),
+            CapturedOutput::Gzip(data) => {
+                let mut text = String::new();
+                // Data comes from `gzip`, so decoding only fails on memory corruption.
+                if let Err(e) = GzDecoder::new(data.as_slice()).read_to_string(&mut text) {
+                    text = format!("<captured output unreadable: {e}>");
+                }
+                text
+            }
+        }
+    }
+}
+
+impl From<String> for CapturedOutput {
+    fn from(text: String) -> Self {
+        CapturedOutput::Plain(text)
+    }
+}
+
+impl From<&str> for CapturedOutput {
+    fn from(text: &str) -> Self {
+        CapturedOutput::Plain(text.to_string())
+    }
+}
+
+impl fmt::Debug for CapturedOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapturedOutput::Plain(text) => f.debug_tuple("Plain").field(&text.len()).finish(),
+            CapturedOutput::Gzip(data) => f.debug_tuple("Gzip").field(&data.len()).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_shrinks_repetitive_output_and_roundtrips() {
+        let text = "progress: 42%\n".repeat(10_000);
+        let output = CapturedOutput::gzip(text.clone());
+
+        assert!(matches!(output, CapturedOutput::Gzip(_)));
+        assert!(output.stored_len() < text.len() / 10);
+        assert_eq!(output.text(), text);
+    }
+
+    #[test]
+    fn gzip_keeps_tiny_output_plain() {
+        let output = CapturedOutput::gzip("ok".to_string());
+        assert_eq!(output, CapturedOutput::Plain("ok".to_string()));
+    }
+}
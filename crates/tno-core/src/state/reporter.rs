@@ -0,0 +1,158 @@
+use std::fmt;
+
+use tno_model::TaskId;
+
+use super::{CapturedOutput, RunnerErrorLog, TaskState};
+use crate::metrics::RunnerErrorKind;
+
+/// Handle used by runners to report execution details back into task state.
+///
+/// Runners receive it through [`crate::BuildContext::reporter`].
+/// A default reporter is detached and silently drops all reports;
+/// [`crate::SupervisorApi`] wires a reporter bound to its task state into the router's build context.
+#[derive(Clone, Default)]
+pub struct TaskReporter {
+    state: Option<TaskState>,
+    errors: Option<RunnerErrorLog>,
+}
+
+impl TaskReporter {
+    /// Create a reporter bound to the given task state.
+    pub(crate) fn new(state: TaskState) -> Self {
+        Self {
+            state: Some(state),
+            errors: None,
+        }
+    }
+
+    /// Record runner errors into the given log.
+    pub fn with_runner_errors(mut self, errors: RunnerErrorLog) -> Self {
+        self.errors = Some(errors);
+        self
+    }
+
+    /// Report the exit code of a finished attempt.
+    ///
+    /// `task` is the task name (same as the taskvisor task name / [`TaskId`]).
+    pub fn report_exit_code(&self, task: &str, code: i32) {
+        if let Some(state) = &self.state {
+            state.set_exit_code(&TaskId::from(task), code);
+        }
+    }
+
+    /// Report the signal that terminated a finished attempt.
+    ///
+    /// `task` is the task name (same as the taskvisor task name / [`TaskId`]).
+    pub fn report_signal(&self, task: &str, signal: i32) {
+        if let Some(state) = &self.state {
+            state.set_signal(&TaskId::from(task), signal);
+        }
+    }
+
+    /// Report the cgroup the task was placed in.
+    ///
+    /// `task` is the task name (same as the taskvisor task name / [`TaskId`]).
+    pub fn report_cgroup(&self, task: &str, cgroup: &str) {
+        if let Some(state) = &self.state {
+            state.set_cgroup(&TaskId::from(task), cgroup.to_string());
+        }
+    }
+
+    /// Report the resource limit that most likely ended a finished attempt (`None` if none did).
+    ///
+    /// `task` is the task name (same as the taskvisor task name / [`TaskId`]).
+    pub fn report_limit_cause(&self, task: &str, cause: Option<&str>) {
+        if let Some(state) = &self.state {
+            state.set_limit_cause(&TaskId::from(task), cause.map(str::to_string));
+        }
+    }
+
+    /// Report the captured output of a finished attempt (already bounded by the runner).
+    ///
+    /// `task` is the task name (same as the taskvisor task name / [`TaskId`]).
+    pub fn report_output(
+        &self,
+        task: &str,
+        stdout: Option<CapturedOutput>,
+        stderr: Option<CapturedOutput>,
+    ) {
+        if let Some(state) = &self.state {
+            state.set_output(&TaskId::from(task), stdout, stderr);
+        }
+    }
+
+    /// Report a runner-level error (spawn or runner config failure) with its message.
+    ///
+    /// `kind` is the error category also passed to [`crate::MetricsBackend::record_runner_error`], stored by its label;
+    /// `task` is the task name the runner was working on, if any.
+    pub fn report_runner_error(
+        &self,
+        runner: &str,
+        kind: RunnerErrorKind,
+        task: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        if let Some(errors) = &self.errors {
+            errors.push(runner, kind.as_label(), task, message.into());
+        }
+    }
+}
+
+impl fmt::Debug for TaskReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskReporter")
+            .field("attached", &self.state.is_some())
+            .field("runner_errors", &self.errors.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attached_reporter_updates_state() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+
+        TaskReporter::new(state.clone()).report_exit_code("task-1", 2);
+        assert_eq!(state.get(&id).unwrap().exit_code, Some(2));
+    }
+
+    #[test]
+    fn signal_is_reported_as_negative_exit_code() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+
+        let reporter = TaskReporter::new(state.clone());
+        reporter.report_exit_code("task-1", 1);
+        reporter.report_signal("task-1", 9);
+
+        let info = state.get(&id).unwrap();
+        assert_eq!(info.exit_code, Some(-9));
+        assert_eq!(info.signal, Some(9));
+    }
+
+    #[test]
+    fn output_is_replaced_per_attempt() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+
+        let reporter = TaskReporter::new(state.clone());
+        reporter.report_output("task-1", Some("first\n".into()), Some("oops\n".into()));
+        reporter.report_output("task-1", Some("second\n".into()), None);
+
+        let info = state.get(&id).unwrap();
+        assert_eq!(info.stdout.as_deref(), Some("second\n"));
+        assert_eq!(info.stderr, None);
+    }
+
+    #[test]
+    fn detached_reporter_is_noop() {
+        TaskReporter::default().report_exit_code("task-1", 1);
+    }
+}
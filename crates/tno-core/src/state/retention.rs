@@ -0,0 +1,87 @@
+use std::time::{Duration, SystemTime};
+
+use tno_model::TaskId;
+
+/// Default maximum number of finished tasks kept in task state.
+pub const DEFAULT_RETENTION_MAX_TERMINAL: usize = 1_000;
+
+/// Default maximum time a finished task is kept in task state.
+pub const DEFAULT_RETENTION_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Limits on the finished tasks kept in task state (see [`crate::SupervisorApi::set_retention`]).
+///
+/// A task is evictable once its status is terminal and it will not run again: its actor finished
+/// for good, or it was canceled or exhausted. Tasks between restarts are never evicted.
+///
+/// Task state starts with [`Retention::default`], so a long-running agent does not grow without bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    /// Maximum number of evictable tasks kept; the least recently updated are evicted first.
+    pub max_terminal: usize,
+    /// Maximum time an evictable task is kept after its last update.
+    pub max_age: Duration,
+}
+
+impl Default for Retention {
+    /// Keep up to [`DEFAULT_RETENTION_MAX_TERMINAL`] finished tasks for up to [`DEFAULT_RETENTION_MAX_AGE`].
+    fn default() -> Self {
+        Self {
+            max_terminal: DEFAULT_RETENTION_MAX_TERMINAL,
+            max_age: DEFAULT_RETENTION_MAX_AGE,
+        }
+    }
+}
+
+impl Retention {
+    /// Tasks to evict at `now`, given the `(last update, id)` of every evictable task.
+    pub(crate) fn select(
+        &self,
+        mut evictable: Vec<(SystemTime, TaskId)>,
+        now: SystemTime,
+    ) -> Vec<TaskId> {
+        evictable.sort_unstable_by(|(a_at, a), (b_at, b)| {
+            a_at.cmp(b_at).then_with(|| a.as_str().cmp(b.as_str()))
+        });
+        let excess = evictable.len().saturating_sub(self.max_terminal);
+        let cutoff = now.checked_sub(self.max_age);
+        evictable
+            .into_iter()
+            .enumerate()
+            .take_while(|(i, (updated_at, _))| {
+                *i < excess || cutoff.is_some_and(|cutoff| *updated_at < cutoff)
+            })
+            .map(|(_, (_, id))| id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_oldest_over_count_and_expired_by_age() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let at = |secs_ago: u64, id: &str| (now - Duration::from_secs(secs_ago), TaskId::from(id));
+        let tasks = vec![at(10, "c"), at(500, "a"), at(30, "b"), at(5, "d")];
+        let ids = |v: Vec<TaskId>| v.into_iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        let by_count = Retention {
+            max_terminal: 2,
+            max_age: Duration::from_secs(3_600),
+        };
+        assert_eq!(ids(by_count.select(tasks.clone(), now)), ["a", "b"]);
+
+        let by_age = Retention {
+            max_terminal: 10,
+            max_age: Duration::from_secs(20),
+        };
+        assert_eq!(ids(by_age.select(tasks.clone(), now)), ["a", "b"]);
+
+        let loose = Retention {
+            max_terminal: 10,
+            max_age: Duration::from_secs(3_600),
+        };
+        assert!(loose.select(tasks, now).is_empty());
+    }
+}
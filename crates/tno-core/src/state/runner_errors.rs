@@ -0,0 +1,75 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use tno_model::{RunnerErrorInfo, TaskId};
+
+/// Default number of recent runner errors kept by [`RunnerErrorLog`].
+pub const DEFAULT_RUNNER_ERROR_CAPACITY: usize = 128;
+
+/// Bounded log of the most recent runner-level errors.
+///
+/// Runners push into it through [`crate::TaskReporter::report_runner_error`];
+/// once full, the oldest entry is dropped.
+#[derive(Clone, Debug)]
+pub struct RunnerErrorLog {
+    inner: Arc<Mutex<VecDeque<RunnerErrorInfo>>>,
+    capacity: usize,
+}
+
+impl RunnerErrorLog {
+    /// Create a log keeping up to `capacity` errors (`0` disables recording).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Append an error, evicting the oldest one if the log is full.
+    pub fn push(&self, runner: &str, kind: &str, task: Option<&str>, message: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut buf = self.inner.lock().unwrap();
+        if buf.len() == self.capacity {
+            buf.pop_front();
+        }
+        buf.push_back(RunnerErrorInfo {
+            runner: runner.to_string(),
+            kind: kind.to_string(),
+            message,
+            task_id: task.map(TaskId::from),
+            at: SystemTime::now(),
+        });
+    }
+
+    /// Recorded errors, newest first.
+    pub fn recent(&self) -> Vec<RunnerErrorInfo> {
+        self.inner.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+impl Default for RunnerErrorLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_RUNNER_ERROR_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_newest_errors_up_to_capacity() {
+        let log = RunnerErrorLog::new(2);
+        for kind in ["a", "b", "c"] {
+            log.push("runner", kind, None, String::new());
+        }
+
+        let kinds: Vec<_> = log.recent().into_iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, ["c", "b"]);
+    }
+}
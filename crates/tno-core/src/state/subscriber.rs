@@ -3,17 +3,71 @@ use taskvisor::{Event, EventKind, Subscribe};
 use tracing::trace;
 
 use super::TaskState;
+use crate::{
+    metrics::{MetricsHandle, TerminalReason, noop_metrics},
+    router::InFlightLeases,
+    supervisor::{POLICY_RESCHEDULED_REASON, RESTART_LIMIT_REASON, status_after_update},
+};
 use tno_model::{TaskId, TaskStatus};
 
 /// Subscriber that updates TaskState from taskvisor events.
+///
+/// Also records [`TerminalReason`]s into the metrics backend when a task ends without succeeding.
 pub struct StateSubscriber {
     state: TaskState,
+    metrics: MetricsHandle,
+    leases: Option<InFlightLeases>,
 }
 
 impl StateSubscriber {
     /// Create a new state subscriber.
     pub fn new(state: TaskState) -> Self {
-        Self { state }
+        Self {
+            state,
+            metrics: noop_metrics(),
+            leases: None,
+        }
+    }
+
+    /// Record terminal reasons into the given metrics backend.
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Release runner capacity held by tasks once they finish for good.
+    pub(crate) fn with_leases(mut self, leases: InFlightLeases) -> Self {
+        self.leases = Some(leases);
+        self
+    }
+
+    /// Mark a task whose actor finished for good and release its runner capacity.
+    fn finish(&self, task_id: &TaskId) {
+        self.state.mark_finished(task_id);
+        self.release(task_id);
+    }
+
+    fn release(&self, task_id: &TaskId) {
+        if let Some(leases) = &self.leases {
+            leases.release(task_id.as_str());
+        }
+    }
+
+    fn record_terminal(&self, task_id: &TaskId, reason: TerminalReason) {
+        self.metrics
+            .record_terminal(&self.state.runner(task_id), reason);
+    }
+
+    /// Terminal reason of a task whose actor gave up, derived from the outcome of its last attempt.
+    ///
+    /// Returns `None` if the last attempt succeeded.
+    fn exhausted_reason(&self, task_id: &TaskId, attempt: Option<u32>) -> Option<TerminalReason> {
+        match self.state.get(task_id)?.status {
+            TaskStatus::Timeout => Some(TerminalReason::Timeout),
+            TaskStatus::Succeeded => None,
+            _ if attempt.unwrap_or(1) > 1 => Some(TerminalReason::Exhausted),
+            _ => Some(TerminalReason::Failed),
+        }
     }
 
     /// Extract TaskId from event.
@@ -51,8 +105,17 @@ impl Subscribe for StateSubscriber {
                     .map(|s| s.to_string())
                     .unwrap_or_else(|| "unknown".to_string());
                 trace!(task = %task_id, reason = %reason, "task failed");
-                self.state
-                    .update_status(&task_id, TaskStatus::Failed, Some(reason));
+                // Ended by a restart policy update: record the outcome of the run instead.
+                if let Some((status, error)) = status_after_update(&reason) {
+                    self.state.update_status(&task_id, status, error);
+                    return;
+                }
+                // `TimeoutHit` precedes the `TaskFailed` of the same attempt; keep the more specific status.
+                let status = match self.state.get(&task_id).map(|info| info.status) {
+                    Some(TaskStatus::Timeout) => TaskStatus::Timeout,
+                    _ => TaskStatus::Failed,
+                };
+                self.state.update_status(&task_id, status, Some(reason));
             }
             EventKind::TimeoutHit => {
                 trace!(task = %task_id, "task timeout");
@@ -69,12 +132,57 @@ impl Subscribe for StateSubscriber {
                     .map(|s| s.to_string())
                     .unwrap_or_else(|| "exhausted".to_string());
                 trace!(task = %task_id, "task exhausted");
-                self.state
-                    .update_status(&task_id, TaskStatus::Exhausted, Some(reason));
+                if let Some(terminal) = self.exhausted_reason(&task_id, event.attempt) {
+                    self.record_terminal(&task_id, terminal);
+                }
+                // A last attempt that timed out stays `Timeout`, so it is not mistaken for a generic failure.
+                if self.state.get(&task_id).map(|info| info.status) != Some(TaskStatus::Timeout) {
+                    self.state
+                        .update_status(&task_id, TaskStatus::Exhausted, Some(reason));
+                }
+                self.finish(&task_id);
+            }
+            EventKind::ActorDead
+                if event
+                    .reason
+                    .as_deref()
+                    .is_some_and(|r| r.contains(POLICY_RESCHEDULED_REASON)) =>
+            {
+                // The task lives on in the actor resubmitted under the updated policy.
+                trace!(task = %task_id, "task actor replaced after policy update");
+            }
+            EventKind::ActorDead => {
+                trace!(task = %task_id, "task actor dead");
+                if let Some(terminal) = self.exhausted_reason(&task_id, event.attempt) {
+                    self.record_terminal(&task_id, terminal);
+                }
+                if let Some(reason) = event
+                    .reason
+                    .as_deref()
+                    .filter(|r| r.contains(RESTART_LIMIT_REASON))
+                {
+                    self.state.update_status(
+                        &task_id,
+                        TaskStatus::Exhausted,
+                        Some(reason.to_string()),
+                    );
+                }
+                self.finish(&task_id);
+            }
+            EventKind::ControllerSlotTransition
+                if event
+                    .reason
+                    .as_deref()
+                    .is_some_and(|r| r.contains("(replace)")) =>
+            {
+                // The controller keys slots by task name: the event names the task being replaced.
+                trace!(task = %task_id, "task replaced");
+                self.record_terminal(&task_id, TerminalReason::Replaced);
             }
             EventKind::TaskRemoved => {
-                trace!(task = %task_id, "task removed from state");
-                self.state.remove_task(&task_id);
+                // The task stays queryable in its final status until retention evicts it.
+                trace!(task = %task_id, "task removed from supervisor");
+                self.release(&task_id);
             }
             _ => {}
         }
@@ -88,3 +196,51 @@ impl Subscribe for StateSubscriber {
         2048
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn restart_limit_marks_task_exhausted() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+        let subscriber = StateSubscriber::new(state.clone());
+
+        let reason = format!("fatal: {RESTART_LIMIT_REASON} after 3 failed attempts");
+        subscriber
+            .on_event(
+                &Event::new(EventKind::ActorDead)
+                    .with_task("task-1")
+                    .with_attempt(3)
+                    .with_reason(reason),
+            )
+            .await;
+
+        let info = state.get(&id).unwrap();
+        assert_eq!(info.status, TaskStatus::Exhausted);
+        assert!(info.error.unwrap().contains(RESTART_LIMIT_REASON));
+    }
+
+    #[tokio::test]
+    async fn timed_out_task_stays_timeout_once_exhausted() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+        let subscriber = StateSubscriber::new(state.clone());
+
+        for event in [
+            Event::new(EventKind::TaskStarting),
+            Event::new(EventKind::TimeoutHit),
+            Event::new(EventKind::TaskFailed).with_reason("timed out after 200ms"),
+            Event::new(EventKind::ActorExhausted).with_attempt(1),
+        ] {
+            subscriber.on_event(&event.with_task("task-1")).await;
+        }
+
+        let info = state.get(&id).unwrap();
+        assert_eq!(info.status, TaskStatus::Timeout);
+        assert_eq!(info.error.as_deref(), Some("timed out after 200ms"));
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use tno_model::{TaskId, TaskInfo};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+
+use super::TaskState;
+
+/// Status updates of one task, obtained from [`crate::SupervisorApi::watch_task`].
+///
+/// Yields the current task info first, then a snapshot after every status change.
+/// Ends after a terminal status.
+///
+/// Updates are buffered in a bounded channel shared by all watchers. A watcher that falls behind
+/// skips the missed updates and continues from the current task state.
+pub struct TaskWatch {
+    id: TaskId,
+    state: TaskState,
+    rx: broadcast::Receiver<TaskInfo>,
+    /// Snapshot to yield before waiting for updates.
+    pending: Option<TaskInfo>,
+    done: bool,
+}
+
+impl fmt::Debug for TaskWatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskWatch")
+            .field("id", &self.id)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TaskWatch {
+    /// Start watching `id`; `None` if the task is unknown.
+    pub(crate) fn new(state: TaskState, id: TaskId) -> Option<Self> {
+        // Subscribe before taking the snapshot so that no transition is missed in between.
+        let rx = state.subscribe_updates();
+        let current = state.get(&id)?;
+        Some(Self {
+            id,
+            state,
+            rx,
+            pending: Some(current),
+            done: false,
+        })
+    }
+
+    /// ID of the watched task.
+    pub fn task_id(&self) -> &TaskId {
+        &self.id
+    }
+
+    /// Wait for the next update; `None` once the watch has ended.
+    pub async fn next(&mut self) -> Option<TaskInfo> {
+        if self.done {
+            return None;
+        }
+        let info = match self.pending.take() {
+            Some(info) => info,
+            None => self.recv().await?,
+        };
+        self.done = info.status.is_terminal();
+        Some(info)
+    }
+
+    async fn recv(&mut self) -> Option<TaskInfo> {
+        loop {
+            match self.rx.recv().await {
+                Ok(info) if info.id == self.id => return Some(info),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(task_id = %self.id, skipped, "task watch lagged, resyncing from state");
+                    return self.state.get(&self.id);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tno_model::TaskStatus;
+
+    use crate::state::UPDATE_CAPACITY;
+
+    #[tokio::test]
+    async fn yields_current_state_then_updates_until_terminal() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        let other = TaskId::from("task-2");
+        state.add_task(id.clone(), "slot".to_string(), None);
+        state.add_task(other.clone(), "slot".to_string(), None);
+
+        let mut watch = TaskWatch::new(state.clone(), id.clone()).unwrap();
+        state.update_status(&other, TaskStatus::Running, None);
+        state.update_status(&id, TaskStatus::Running, None);
+        state.update_status(&id, TaskStatus::Succeeded, None);
+        state.update_status(&id, TaskStatus::Running, None);
+
+        let statuses = [
+            watch.next().await.unwrap().status,
+            watch.next().await.unwrap().status,
+            watch.next().await.unwrap().status,
+        ];
+        assert_eq!(
+            statuses,
+            [
+                TaskStatus::Pending,
+                TaskStatus::Running,
+                TaskStatus::Succeeded
+            ]
+        );
+        assert!(watch.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn lagging_watch_resyncs_from_state() {
+        let state = TaskState::new();
+        let id = TaskId::from("task-1");
+        state.add_task(id.clone(), "slot".to_string(), None);
+
+        let mut watch = TaskWatch::new(state.clone(), id.clone()).unwrap();
+        assert_eq!(watch.next().await.unwrap().status, TaskStatus::Pending);
+
+        for _ in 0..UPDATE_CAPACITY + 1 {
+            state.update_status(&id, TaskStatus::Running, None);
+        }
+        state.update_status(&id, TaskStatus::Failed, Some("boom".to_string()));
+
+        let info = watch.next().await.unwrap();
+        assert_eq!(info.status, TaskStatus::Failed);
+        assert!(watch.next().await.is_none());
+    }
+
+    #[test]
+    fn unknown_task_cannot_be_watched() {
+        assert!(TaskWatch::new(TaskState::new(), TaskId::from("missing")).is_none());
+    }
+}
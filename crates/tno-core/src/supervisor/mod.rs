@@ -4,20 +4,54 @@
 //! - owns a [`Supervisor`] instance and runs its event loop in the background;
 //! - uses [`RunnerRouter`] to build concrete tasks from [`CreateSpec`];
 //! - maps model-level specs / policies into controller specs and submits them.
-use std::{sync::Arc, time::Duration};
+mod policy_update;
+pub(crate) use policy_update::{POLICY_RESCHEDULED_REASON, status_after_update};
+use policy_update::{PolicyControlled, PolicyUpdates, Resubmit};
+
+mod restart_limit;
+pub(crate) use restart_limit::RESTART_LIMIT_REASON;
+use restart_limit::RestartLimited;
+
+mod schedule;
+use schedule::Scheduled;
+
+mod shutdown;
+pub use shutdown::ShutdownReport;
+
+mod slot;
+use slot::QueuedTasks;
+
+use std::{
+    sync::{
+        Arc, OnceLock, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 use taskvisor::{
-    ControllerConfig, ControllerSpec, Subscribe, Supervisor, SupervisorConfig, TaskRef, TaskSpec,
+    ControllerConfig, ControllerSpec, RestartPolicy, Subscribe, Supervisor, SupervisorConfig,
+    TaskRef, TaskSpec,
 };
-use tno_model::{CreateSpec, TaskId, TaskInfo, TaskStatus};
-use tracing::{debug, info, instrument};
+use tno_model::{
+    AdmissionOutcome, AdmissionStrategy, CreateSpec, DashboardSnapshot, HealthStatus,
+    RunnerErrorInfo, Submission, TaskCounts, TaskFilter, TaskId, TaskInfo, TaskPage, TaskQuery,
+    TaskStatus,
+};
+use tracing::{debug, error, info, instrument, warn};
 
 use crate::{
     error::CoreError,
+    events::{DEFAULT_EVENT_REPLAY_CAPACITY, EventBroadcaster, EventStream},
     map::{to_admission_policy, to_backoff_policy, to_restart_policy},
+    metrics::TerminalReason,
     policy::TaskPolicy,
     router::RunnerRouter,
-    state::{StateSubscriber, TaskState},
+    runner::RunnerError,
+    state::{
+        NewTask, RegisterError, Retention, RunnerErrorLog, StateSubscriber, TaskReporter,
+        TaskState, TaskWatch,
+    },
 };
 
 /// Thin wrapper around taskvisor [`Supervisor`] with a runner router.
@@ -30,6 +64,14 @@ pub struct SupervisorApi {
     sup: Arc<Supervisor>,
     router: RunnerRouter,
     state: TaskState,
+    events: EventBroadcaster,
+    runner_errors: RunnerErrorLog,
+    admission_override: RwLock<Option<AdmissionStrategy>>,
+    reject_duplicate_in_slot: AtomicBool,
+    policy_updates: PolicyUpdates,
+    queued: QueuedTasks,
+    run_loop_error: Arc<OnceLock<String>>,
+    shutting_down: AtomicBool,
 }
 
 impl SupervisorApi {
@@ -41,14 +83,46 @@ impl SupervisorApi {
     ///
     /// The supervisor run loop is spawned on the current Tokio runtime.
     /// This method waits until the supervisor reports readiness before returning.
+    ///
+    /// Keeps the last [`DEFAULT_EVENT_REPLAY_CAPACITY`] events for replay (see [`SupervisorApi::subscribe_events`]).
     pub async fn new(
         sup_cfg: SupervisorConfig,
         ctrl_cfg: ControllerConfig,
-        mut subscribers: Vec<Arc<dyn Subscribe>>,
+        subscribers: Vec<Arc<dyn Subscribe>>,
         router: RunnerRouter,
+    ) -> Result<Self, CoreError> {
+        Self::new_with_event_replay(
+            sup_cfg,
+            ctrl_cfg,
+            subscribers,
+            router,
+            DEFAULT_EVENT_REPLAY_CAPACITY,
+        )
+        .await
+    }
+
+    /// Same as [`SupervisorApi::new`], but with an explicit event replay buffer size.
+    ///
+    /// `replay_capacity` is the number of most recent events kept for late subscribers (`0` disables replay).
+    pub async fn new_with_event_replay(
+        sup_cfg: SupervisorConfig,
+        ctrl_cfg: ControllerConfig,
+        mut subscribers: Vec<Arc<dyn Subscribe>>,
+        mut router: RunnerRouter,
+        replay_capacity: usize,
     ) -> Result<Self, CoreError> {
         let state = TaskState::new();
-        subscribers.push(Arc::new(StateSubscriber::new(state.clone())));
+        let runner_errors = RunnerErrorLog::default();
+        router.set_reporter(
+            TaskReporter::new(state.clone()).with_runner_errors(runner_errors.clone()),
+        );
+        let events = EventBroadcaster::new(replay_capacity);
+        subscribers.push(Arc::new(
+            StateSubscriber::new(state.clone())
+                .with_metrics(router.metrics().clone())
+                .with_leases(router.leases().clone()),
+        ));
+        subscribers.push(Arc::new(events.clone()));
 
         let sup = Supervisor::builder(sup_cfg)
             .with_subscribers(subscribers)
@@ -56,15 +130,61 @@ impl SupervisorApi {
             .build();
 
         let runner = Arc::clone(&sup);
+        let run_loop_error = Arc::new(OnceLock::new());
+        let run_loop_failed = Arc::clone(&run_loop_error);
         tokio::spawn(async move {
             if let Err(e) = runner.run(Vec::new()).await {
-                panic!("supervisor run loop exited with error: {}", e)
+                error!(error = %e, "supervisor run loop exited with error");
+                let _ = run_loop_failed.set(e.to_string());
             }
         });
 
         sup.wait_ready().await;
         info!("supervisor is ready to accept tasks");
-        Ok(Self { sup, router, state })
+        Ok(Self {
+            sup,
+            router,
+            state,
+            events,
+            runner_errors,
+            admission_override: RwLock::new(None),
+            reject_duplicate_in_slot: AtomicBool::new(false),
+            policy_updates: PolicyUpdates::default(),
+            queued: QueuedTasks::default(),
+            run_loop_error,
+            shutting_down: AtomicBool::new(false),
+        })
+    }
+
+    /// Force an admission strategy for all subsequent submissions, ignoring the one in the spec/policy.
+    ///
+    /// Intended as an operator lever during incidents (e.g. `DropIfRunning` to shed load).
+    /// `None` restores per-spec admission. Already admitted tasks are not affected.
+    pub fn set_admission_override(&self, admission: Option<AdmissionStrategy>) {
+        match admission {
+            Some(a) => warn!(admission = ?a, "admission override enabled"),
+            None => info!("admission override cleared"),
+        }
+        *self.admission_override.write().unwrap() = admission;
+    }
+
+    /// Get the currently active admission override (if any).
+    pub fn admission_override(&self) -> Option<AdmissionStrategy> {
+        *self.admission_override.read().unwrap()
+    }
+
+    /// Fail submissions that admission would drop with [`CoreError::SlotBusy`] instead of reporting them as dropped.
+    ///
+    /// Off by default: a `DropIfRunning` submission to a busy slot returns [`AdmissionOutcome::Dropped`].
+    /// Enable it for workflows that treat a duplicate submission as an error.
+    pub fn set_reject_duplicate_in_slot(&self, reject: bool) {
+        self.reject_duplicate_in_slot
+            .store(reject, Ordering::Relaxed);
+    }
+
+    /// Whether dropped submissions fail with [`CoreError::SlotBusy`].
+    pub fn rejects_duplicate_in_slot(&self) -> bool {
+        self.reject_duplicate_in_slot.load(Ordering::Relaxed)
     }
 
     /// Get task information by ID.
@@ -82,11 +202,145 @@ impl SupervisorApi {
         self.state.list_all()
     }
 
+    /// List one page of all tasks, ordered by creation time (see [`TaskPage`]).
+    pub fn list_tasks_page(&self, offset: usize, limit: usize) -> TaskPage {
+        self.state.list_page(offset, limit)
+    }
+
+    /// List tasks matching a combined filter (e.g. running or pending tasks in one slot).
+    pub fn list_tasks_filtered(&self, filter: &TaskFilter) -> Vec<TaskInfo> {
+        self.state.list_filtered(filter)
+    }
+
+    /// List tasks matching a query (status, slot, label and batch filters) in the requested order.
+    pub fn query_tasks(&self, query: TaskQuery) -> Vec<TaskInfo> {
+        self.state.query(&query)
+    }
+
+    /// List all tasks submitted with the given batch ID (see [`CreateSpec::batch_id`]).
+    pub fn list_tasks_by_batch(&self, batch_id: &str) -> Vec<TaskInfo> {
+        self.state.list_by_batch(batch_id)
+    }
+
     /// List tasks by status.
     pub fn list_tasks_by_status(&self, status: TaskStatus) -> Vec<TaskInfo> {
         self.state.list_by_status(status)
     }
 
+    /// Get a consistent overview of all tasks: counts by status and runner, slot occupancy
+    /// and up to `recent` most recently finished tasks (see [`tno_model::DEFAULT_DASHBOARD_RECENT`]).
+    pub fn dashboard(&self, recent: usize) -> DashboardSnapshot {
+        self.state.dashboard(recent)
+    }
+
+    /// Count tracked tasks by status and slot.
+    ///
+    /// Cheaper than tallying [`SupervisorApi::list_all_tasks`]: a consistent snapshot taken
+    /// under one lock, O(n) over tracked tasks, without cloning them.
+    pub fn counts(&self) -> TaskCounts {
+        self.state.counts()
+    }
+
+    /// Bound the memory used by finished tasks.
+    ///
+    /// Finished tasks stay queryable in their final status until evicted;
+    /// the default is [`Retention::default`].
+    /// Keeps at most `max_terminal` finished tasks, none older than `max_age` since its last update;
+    /// the least recently updated are evicted first. Only tasks that will not run again are evicted
+    /// (canceled, exhausted, or whose actor is done): running tasks and periodic or restarting tasks
+    /// between runs are kept. Evicted tasks are no longer returned by `get_task` or listings.
+    pub fn set_retention(&self, max_terminal: usize, max_age: Duration) {
+        self.state.set_retention(Some(Retention {
+            max_terminal,
+            max_age,
+        }));
+    }
+
+    /// Number of finished tasks evicted under [`SupervisorApi::set_retention`].
+    pub fn evicted_tasks(&self) -> u64 {
+        self.state.evicted_count()
+    }
+
+    /// Most recent runner-level errors (spawn or runner config failures), newest first.
+    ///
+    /// Keeps up to [`crate::DEFAULT_RUNNER_ERROR_CAPACITY`] entries.
+    pub fn recent_runner_errors(&self) -> Vec<RunnerErrorInfo> {
+        self.runner_errors.recent()
+    }
+
+    /// Subscribe to supervisor events.
+    ///
+    /// With `replay = true` the stream first yields the most recent buffered events (oldest first), then live events.
+    /// With `replay = false` only events published after this call are delivered.
+    pub fn subscribe_events(&self, replay: bool) -> EventStream {
+        self.events.subscribe(replay)
+    }
+
+    /// Whether the supervisor run loop exited with an error.
+    ///
+    /// A dead supervisor no longer runs submitted tasks. The run loop returning normally
+    /// (e.g. with no tasks left) does not count: background listeners and the controller keep working.
+    pub fn is_dead(&self) -> bool {
+        self.run_loop_error.get().is_some()
+    }
+
+    /// Error the supervisor run loop exited with, if it did.
+    ///
+    /// Once set, submissions fail with [`CoreError::Supervisor`].
+    pub fn last_error(&self) -> Option<&str> {
+        self.run_loop_error.get().map(String::as_str)
+    }
+
+    /// Whether [`SupervisorApi::shutdown`] has been called.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::Acquire)
+    }
+
+    /// Why new submissions are refused, if they are: the run loop died or a shutdown started.
+    fn refusal(&self) -> Option<String> {
+        if self.is_shutting_down() {
+            return Some("supervisor is shutting down".into());
+        }
+        self.last_error()
+            .map(|e| format!("supervisor run loop exited: {e}"))
+    }
+
+    /// Whether the supervisor accepts and runs new work, for liveness/readiness probes.
+    ///
+    /// Turns `false` once the run loop dies or a shutdown starts.
+    pub fn is_ready(&self) -> bool {
+        self.refusal().is_none()
+    }
+
+    /// Run loop liveness together with the number of running and pending tasks.
+    pub fn health(&self) -> HealthStatus {
+        let counts = self.state.counts();
+        HealthStatus::new(
+            self.last_error().map(str::to_string),
+            counts.status(TaskStatus::Running),
+            counts.status(TaskStatus::Pending),
+        )
+    }
+
+    /// Run the health check of the metrics backend (see [`crate::MetricsBackend::health_check`]).
+    pub fn check_metrics_health(&self) -> Result<(), String> {
+        self.router.metrics().health_check()
+    }
+
+    /// Run health checks of all registered runners (see [`crate::Runner::health_check`]).
+    ///
+    /// Returns `(runner name, result)` pairs in registration order.
+    pub fn check_runners_health(&self) -> Vec<(&'static str, Result<(), RunnerError>)> {
+        self.router.check_health()
+    }
+
+    /// Check that a spec can be built by one of the runners, without submitting it.
+    ///
+    /// See [`RunnerRouter::validate`]; fails with [`CoreError::ValidateTimeout`] if building takes too long.
+    pub async fn validate(&self, spec: &CreateSpec) -> Result<&'static str, CoreError> {
+        self.router.validate(spec).await
+    }
+
     /// Get a clone of the underlying supervisor handle.
     pub fn supervisor(&self) -> Arc<Supervisor> {
         Arc::clone(&self.sup)
@@ -95,21 +349,96 @@ impl SupervisorApi {
     /// Build and submit a task described by [`CreateSpec`].
     ///
     /// Steps:
-    /// 1. Ask the [`RunnerRouter`] to pick a runner and build a [`TaskRef`].
-    /// 2. Convert [`CreateSpec`] into [`TaskPolicy`] (dropping the [`tno_model::TaskKind`] information).
-    /// 3. Delegate to [`SupervisorApi::submit_with_task`].
+    /// 1. If the spec carries an idempotency key held by a non-terminal task, return that task's ID.
+    /// 2. Ask the [`RunnerRouter`] to pick a runner and build a [`TaskRef`].
+    /// 3. Convert [`CreateSpec`] into [`TaskPolicy`] (dropping the [`tno_model::TaskKind`] information).
+    /// 4. Register the task in state and submit it to the controller.
+    ///
+    /// The returned [`Submission`] tells whether the task was admitted, queued, replaced the running one or was dropped.
+    /// Admission considers every task of the slot: a replacing submission returns once the tasks it replaces
+    /// are cancelled, and a queued task stays [`TaskStatus::Pending`] until the tasks ahead of it have finished.
+    /// Fails with [`CoreError::Supervisor`] once the run loop has exited (see [`SupervisorApi::last_error`])
+    /// or a shutdown has started.
     ///
     /// This is the primary entrypoint for tasks that are fully described by the public [`tno_model::TaskKind`] model.
     #[instrument(level = "debug", skip(self, spec), fields(slot = %spec.slot, kind = ?spec.kind))]
-    pub async fn submit(&self, spec: &CreateSpec) -> Result<TaskId, CoreError> {
-        let task = self.router.build(spec)?;
-        let task_id = TaskId::from(task.name());
+    pub async fn submit(&self, spec: &CreateSpec) -> Result<Submission, CoreError> {
+        self.submit_spec(spec, None).await
+    }
+
+    /// Same as [`SupervisorApi::submit`], but the task gets the caller-supplied `id`
+    /// instead of a generated run id (e.g. to correlate it with an external system).
+    ///
+    /// The runner building the task must support explicit IDs (see [`BuildContext::task_id`](crate::BuildContext::task_id)).
+    /// A finished task with the same ID is replaced in state.
+    ///
+    /// # Errors
+    /// - `Err(CoreError::Model)` if `id` is not a valid task ID (see [`TaskId::validate`])
+    /// - `Err(CoreError::DuplicateTaskId)` if a non-terminal task already uses `id`
+    /// - any error of [`SupervisorApi::submit`]
+    #[instrument(level = "debug", skip(self, spec), fields(task_id = %id, slot = %spec.slot, kind = ?spec.kind))]
+    pub async fn submit_with_id(
+        &self,
+        id: TaskId,
+        spec: &CreateSpec,
+    ) -> Result<Submission, CoreError> {
+        id.validate()?;
+        self.submit_spec(spec, Some(id)).await
+    }
+
+    async fn submit_spec(
+        &self,
+        spec: &CreateSpec,
+        id: Option<TaskId>,
+    ) -> Result<Submission, CoreError> {
+        let prepared = self.prepare_spec(spec, id)?;
+        self.submit_prepared(prepared).await
+    }
+
+    /// Check a spec and build its task, or find the task already holding its idempotency key.
+    fn prepare_spec(&self, spec: &CreateSpec, id: Option<TaskId>) -> Result<Prepared, CoreError> {
+        if let Some(reason) = self.refusal() {
+            return Err(CoreError::Supervisor(reason));
+        }
+        if let Some(key) = spec.idempotency_key.as_deref()
+            && let Some(existing) = self.state.find_by_idempotency_key(key)
+        {
+            debug!(task_id = %existing, "idempotency key matched an existing task");
+            return Ok(Prepared::Existing(existing));
+        }
+        // Fails fast before building the task; `register` checks again under the state lock.
+        if let Some(id) = &id
+            && self.state.is_active(id)
+        {
+            return Err(CoreError::DuplicateTaskId(id.to_string()));
+        }
+
+        let policy = TaskPolicy::from_spec(spec)?;
+        let (task, runner) = self.router.build_with_id(spec, id.as_ref())?;
 
-        self.state.add_task(task_id.clone(), spec.slot.clone());
-        let policy = TaskPolicy::from_spec(spec);
+        let new_task = NewTask {
+            explicit_id: id.is_some(),
+            ..NewTask::from_spec(TaskId::from(task.name()), spec, runner)
+        };
+        Ok(Prepared::Built {
+            task,
+            policy: Box::new(policy),
+            new_task,
+        })
+    }
 
-        self.submit_with_task(task, &policy).await?;
-        Ok(task_id)
+    async fn submit_prepared(&self, prepared: Prepared) -> Result<Submission, CoreError> {
+        match prepared {
+            Prepared::Existing(task_id) => Ok(Submission {
+                task_id,
+                admission: AdmissionOutcome::Admitted,
+            }),
+            Prepared::Built {
+                task,
+                policy,
+                new_task,
+            } => self.submit_inner(task, &policy, new_task).await,
+        }
     }
 
     /// Submit a pre-built task together with its runtime policy.
@@ -123,40 +452,188 @@ impl SupervisorApi {
         &self,
         task: TaskRef,
         policy: &TaskPolicy,
-    ) -> Result<TaskId, CoreError> {
-        let task_id = TaskId::from(task.name());
-        self.state.add_task(task_id.clone(), policy.slot.clone());
+    ) -> Result<Submission, CoreError> {
+        if let Some(reason) = self.refusal() {
+            return Err(CoreError::Supervisor(reason));
+        }
+        policy.backoff.validate()?;
+        let new_task = NewTask::code_defined(TaskId::from(task.name()), policy.slot.clone());
+        self.submit_inner(task, policy, new_task).await
+    }
+
+    /// Register the task in state and submit it to the controller.
+    ///
+    /// Returns the ID of the existing task instead if `idempotency_key` is already taken.
+    /// A task that admission would drop is neither registered nor submitted;
+    /// it fails with [`CoreError::SlotBusy`] if duplicates are rejected (see [`SupervisorApi::set_reject_duplicate_in_slot`]).
+    /// A replacing task is submitted once the tasks it replaces are cancelled;
+    /// a queued task stays pending until the tasks ahead of it in the slot have finished.
+    async fn submit_inner(
+        &self,
+        task: TaskRef,
+        policy: &TaskPolicy,
+        new_task: NewTask,
+    ) -> Result<Submission, CoreError> {
+        let task_id = new_task.id.clone();
+        let admission = resolve_admission(self.admission_override(), policy.admission);
+        let occupants = self.slot_occupants(&policy.slot).await;
+        let outcome = AdmissionOutcome::decide(admission, !occupants.is_empty());
+        if outcome == AdmissionOutcome::Dropped {
+            debug!(task_id = %task_id, "slot is busy; dropping submission");
+            self.router.release(task_id.as_str());
+            if self.rejects_duplicate_in_slot() {
+                return Err(CoreError::SlotBusy(policy.slot.clone()));
+            }
+            return Ok(Submission {
+                task_id,
+                admission: outcome,
+            });
+        }
+
+        match self.state.register(new_task) {
+            Ok(()) => {}
+            Err(RegisterError::KeyTaken(existing)) => {
+                debug!(task_id = %existing, "idempotency key matched an existing task");
+                self.router.release(task_id.as_str());
+                return Ok(Submission {
+                    task_id: existing,
+                    admission: AdmissionOutcome::Admitted,
+                });
+            }
+            Err(RegisterError::DuplicateId(id)) => {
+                self.router.release(task_id.as_str());
+                return Err(CoreError::DuplicateTaskId(id.to_string()));
+            }
+        }
+
+        // A task with the same name shares the controller slot, which applies admission to it by itself.
+        let others: Vec<TaskId> = occupants.into_iter().filter(|id| id != &task_id).collect();
+        let spec = self.controller_spec(&task_id, task, policy, admission);
+        match outcome {
+            AdmissionOutcome::Replaced => self.replace_occupants(&others).await,
+            AdmissionOutcome::Queued if !others.is_empty() => {
+                debug!(task_id = %task_id, ahead = others.len(), "slot is busy; queueing submission");
+                self.enqueue(task_id.clone(), policy.slot.clone(), spec, others);
+                return Ok(Submission {
+                    task_id,
+                    admission: outcome,
+                });
+            }
+            _ => {}
+        }
+        self.submit_to_controller(&task_id, spec).await?;
+        Ok(Submission {
+            task_id,
+            admission: outcome,
+        })
+    }
 
+    /// Build the controller spec of a task under its runtime policy.
+    fn controller_spec(
+        &self,
+        task_id: &TaskId,
+        task: TaskRef,
+        policy: &TaskPolicy,
+        admission: AdmissionStrategy,
+    ) -> ControllerSpec {
+        let timeout = Some(Duration::from_millis(policy.timeout_ms));
+        let (task, restart, timeout) = match &policy.schedule {
+            // The wrapper waits for each fire time and applies the timeout to the run itself.
+            Some(schedule) => (
+                Scheduled::wrap(task, schedule.clone(), timeout),
+                RestartPolicy::Always { interval: None },
+                None,
+            ),
+            None => (
+                PolicyControlled::wrap(
+                    task,
+                    policy.clone(),
+                    self.policy_updates.register(task_id),
+                    Resubmit {
+                        sup: Arc::downgrade(&self.sup),
+                        state: self.state.clone(),
+                    },
+                ),
+                to_restart_policy(policy.restart),
+                timeout,
+            ),
+        };
         let task_spec = TaskSpec::new(
-            task,
-            to_restart_policy(policy.restart),
+            RestartLimited::wrap(task, policy.restart),
+            restart,
             to_backoff_policy(&policy.backoff),
-            Some(Duration::from_millis(policy.timeout_ms)),
+            timeout,
         );
-        let controller_spec = ControllerSpec {
-            admission: to_admission_policy(policy.admission),
+        ControllerSpec {
+            admission: to_admission_policy(admission),
             task_spec,
-        };
+        }
+    }
 
+    /// Submit an already registered task to the controller.
+    ///
+    /// If the controller rejects it, the task is marked as [`TaskStatus::Failed`] so that it
+    /// does not linger as pending (and releases its idempotency key).
+    async fn submit_to_controller(
+        &self,
+        task_id: &TaskId,
+        controller_spec: ControllerSpec,
+    ) -> Result<(), CoreError> {
         debug!("submitting pre-built task via controller");
-        self.sup
-            .submit(controller_spec)
-            .await
-            .map_err(|e| CoreError::Supervisor(e.to_string()))?;
-        Ok(task_id)
+        if let Err(e) = self.sup.submit(controller_spec).await {
+            let reason = e.to_string();
+            self.state
+                .update_status(task_id, TaskStatus::Failed, Some(reason.clone()));
+            self.router.release(task_id.as_str());
+            return Err(CoreError::Supervisor(reason));
+        }
+        Ok(())
+    }
+
+    /// Build and submit several tasks described by [`CreateSpec`]s.
+    ///
+    /// All tasks are built via the [`RunnerRouter`] first; a spec that cannot be built fails on its own
+    /// without aborting the batch. Built tasks are then submitted in order, each exactly as by [`SupervisorApi::submit`]:
+    /// admission sees the tasks submitted before it in the batch, and a key repeated within the batch
+    /// resolves to its first task.
+    ///
+    /// The result at index `i` belongs to `specs[i]`.
+    #[instrument(level = "debug", skip(self, specs), fields(count = specs.len()))]
+    pub async fn submit_many(&self, specs: &[CreateSpec]) -> Vec<Result<Submission, CoreError>> {
+        let prepared: Vec<_> = specs
+            .iter()
+            .map(|spec| {
+                self.prepare_spec(spec, None).inspect_err(|e| {
+                    debug!(slot = %spec.slot, error = %e, "skipping spec in bulk submit");
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(specs.len());
+        for prepared in prepared {
+            results.push(match prepared {
+                Ok(prepared) => self.submit_prepared(prepared).await,
+                Err(e) => Err(e),
+            });
+        }
+        results
     }
 
     /// Cancel a running task by ID.
     ///
     /// This sends cancellation signal to the task and waits for confirmation
     /// with the configured grace period (from SupervisorConfig).
+    /// On success the task is marked as [`TaskStatus::Canceled`] in state,
+    /// or as [`TaskStatus::Succeeded`] if its spec sets [`CreateSpec::cancel_is_success`].
+    /// A task queued behind others in its slot is dropped before it starts.
     ///
     /// The task must be cooperative and respect the `CancellationToken`
     /// passed during execution.
     ///
     /// Returns:
     /// - `Ok(())` if task was found and successfully cancelled
-    /// - `Err(CoreError::Supervisor)` if task not found or cancellation timed out
+    /// - `Err(CoreError::TaskNotFound)` if the task is unknown or no longer running
+    /// - `Err(CoreError::Supervisor)` if cancellation failed or timed out
     ///
     /// # Example
     /// ```rust,ignore
@@ -167,32 +644,159 @@ impl SupervisorApi {
         debug!("cancelling task: {}", id);
 
         if self.state.get(id).is_none() {
-            return Err(CoreError::Supervisor(format!("task not found: {}", id)));
+            return Err(CoreError::TaskNotFound(id.to_string()));
         }
 
-        let was_cancelled = self
-            .sup
-            .cancel(id.as_str())
-            .await
-            .map_err(|e| CoreError::Supervisor(format!("cancel failed: {}", e)))?;
+        if self.queued.cancel(id) {
+            // Queued tasks were never submitted; they just must not start.
+            self.router.release(id.as_str());
+        } else {
+            let was_cancelled = self
+                .sup
+                .cancel(id.as_str())
+                .await
+                .map_err(|e| CoreError::Supervisor(format!("cancel failed: {}", e)))?;
 
-        if !was_cancelled {
-            return Err(CoreError::Supervisor(format!(
-                "task not found in registry: {}",
-                id
-            )));
+            if !was_cancelled {
+                return Err(CoreError::TaskNotFound(id.to_string()));
+            }
         }
 
+        if self.state.cancel_is_success(id) {
+            // Nothing will run again: let retention evict it like any canceled task.
+            self.state.update_status(id, TaskStatus::Succeeded, None);
+            self.state.mark_finished(id);
+        } else {
+            self.router
+                .metrics()
+                .record_terminal(&self.state.runner(id), TerminalReason::Canceled);
+            self.state.update_status(id, TaskStatus::Canceled, None);
+        }
         debug!("task cancelled successfully: {}", id);
         Ok(())
     }
+
+    /// Cancel all non-terminal tasks of a batch (see [`CreateSpec::batch_id`]).
+    ///
+    /// Returns the IDs of the cancelled tasks. Tasks that finish while the batch is being cancelled are skipped.
+    ///
+    /// # Errors
+    /// - `Err(CoreError::BatchNotFound)` if no known task belongs to the batch
+    /// - the first error of [`SupervisorApi::cancel_task`] other than a finished task
+    #[instrument(level = "debug", skip(self))]
+    pub async fn cancel_batch(&self, batch_id: &str) -> Result<Vec<TaskId>, CoreError> {
+        let tasks = self.state.list_by_batch(batch_id);
+        if tasks.is_empty() {
+            return Err(CoreError::BatchNotFound(batch_id.to_string()));
+        }
+
+        let mut cancelled = Vec::new();
+        for info in tasks.into_iter().filter(|t| !t.status.is_terminal()) {
+            match self.cancel_task(&info.id).await {
+                Ok(()) => cancelled.push(info.id),
+                Err(CoreError::TaskNotFound(_)) => {
+                    debug!(task_id = %info.id, "batch task finished before it was cancelled");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Wait until a task reaches `status`.
+    ///
+    /// Returns the task info on the first observed transition to `status`,
+    /// or once the task ends in a different terminal status (the target is then unreachable;
+    /// check [`TaskInfo::status`] to tell the two apart).
+    ///
+    /// Waiting is driven by the event stream. State is also re-checked every 50 ms,
+    /// since it may be updated slightly after the matching event is delivered.
+    ///
+    /// # Errors
+    /// - `Err(CoreError::TaskNotFound)` if the task is unknown (or removed while waiting)
+    /// - `Err(CoreError::WaitTimeout)` if neither happens within `timeout`
+    /// - `Err(CoreError::Supervisor)` if the event stream closes
+    #[instrument(level = "debug", skip(self), fields(task_id = %id))]
+    pub async fn wait_for_status(
+        &self,
+        id: &TaskId,
+        status: TaskStatus,
+        timeout: Duration,
+    ) -> Result<TaskInfo, CoreError> {
+        // Subscribe before the first check so that no transition is missed in between.
+        let mut events = self.subscribe_events(false);
+        let wait = async {
+            loop {
+                let info = self
+                    .state
+                    .get(id)
+                    .ok_or_else(|| CoreError::TaskNotFound(id.to_string()))?;
+                if info.status == status || info.status.is_terminal() {
+                    return Ok(info);
+                }
+                if let Ok(None) = tokio::time::timeout(STATUS_RECHECK_INTERVAL, events.recv()).await
+                {
+                    return Err(CoreError::Supervisor("event stream closed".into()));
+                }
+            }
+        };
+
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| CoreError::WaitTimeout {
+                id: id.to_string(),
+                status,
+                timeout,
+            })?
+    }
+
+    /// Watch status changes of a task.
+    ///
+    /// The returned [`TaskWatch`] yields the current task info, then a snapshot after every status
+    /// change, and ends after a terminal status. Drop it to stop watching.
+    ///
+    /// # Errors
+    /// - `Err(CoreError::TaskNotFound)` if the task is unknown
+    pub fn watch_task(&self, id: &TaskId) -> Result<TaskWatch, CoreError> {
+        TaskWatch::new(self.state.clone(), id.clone())
+            .ok_or_else(|| CoreError::TaskNotFound(id.to_string()))
+    }
+}
+
+/// A spec ready to be submitted.
+enum Prepared {
+    /// The task already holding the spec's idempotency key.
+    Existing(TaskId),
+    /// A freshly built task, not registered yet.
+    Built {
+        task: TaskRef,
+        policy: Box<TaskPolicy>,
+        new_task: NewTask,
+    },
+}
+
+/// How often [`SupervisorApi::wait_for_status`] re-checks task state without a new event.
+const STATUS_RECHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Pick the admission strategy to use: the operator override wins over the requested one.
+fn resolve_admission(
+    forced: Option<AdmissionStrategy>,
+    requested: AdmissionStrategy,
+) -> AdmissionStrategy {
+    match forced {
+        Some(forced) => {
+            debug!(?requested, ?forced, "admission overridden");
+            forced
+        }
+        None => requested,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use taskvisor::{TaskError, TaskFn};
+    use taskvisor::{EventKind, TaskError, TaskFn};
     use tno_model::{
         AdmissionStrategy, BackoffStrategy, JitterStrategy, RestartStrategy, RunnerLabels, TaskKind,
     };
@@ -234,7 +838,8 @@ mod tests {
 
         let res = api.submit_with_task(task, &policy).await;
         match res {
-            Ok(task_id) => {
+            Ok(submission) => {
+                let task_id = submission.task_id;
                 assert!(!task_id.as_str().is_empty());
                 assert!(task_id.as_str().contains("test-task"));
             }
@@ -243,34 +848,1300 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn submit_rejects_taskkind_none() {
-        let router = RunnerRouter::new();
+    async fn subscribe_events_replays_recent_events() {
+        let api = SupervisorApi::new_with_event_replay(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+            64,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let policy = TaskPolicy::new(
+            "replay-slot".to_string(),
+            1_000,
+            RestartStrategy::Never,
+            mk_backoff(),
+            AdmissionStrategy::DropIfRunning,
+        );
+        let task: TaskRef = TaskFn::arc("replay-task", |_ctx: CancellationToken| async move {
+            Ok::<(), TaskError>(())
+        });
+        // Subscribe before submitting so that the terminal event cannot be published before we listen.
+        let mut live = api.subscribe_events(false);
+        let task_id = api.submit_with_task(task, &policy).await.unwrap().task_id;
+
+        // Wait for the terminal event so that the whole run is in the replay buffer.
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let ev = live.recv().await.expect("event stream closed");
+                if ev.kind == EventKind::TaskStopped && ev.task.as_deref() == Some(task_id.as_str())
+                {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("task did not finish in time");
+
+        let mut stream = api.subscribe_events(true);
+        assert!(stream.pending_replay() > 0);
+
+        let mut kinds = Vec::new();
+        while stream.pending_replay() > 0 {
+            let ev = stream.recv().await.unwrap();
+            if ev.task.as_deref() == Some(task_id.as_str()) {
+                kinds.push(ev.kind);
+            }
+        }
+        assert!(kinds.contains(&EventKind::TaskStarting));
+        assert!(kinds.contains(&EventKind::TaskStopped));
+
+        let fresh = api.subscribe_events(false);
+        assert_eq!(fresh.pending_replay(), 0);
+    }
+
+    #[tokio::test]
+    async fn submit_reports_dropped_for_busy_slot() {
         let api = SupervisorApi::new(
             SupervisorConfig::default(),
             ControllerConfig::default(),
             Vec::new(),
-            router,
+            RunnerRouter::new(),
         )
         .await
         .expect("failed to create SupervisorApi");
 
-        let spec = CreateSpec {
-            slot: "test-slot-none".to_string(),
-            kind: TaskKind::None,
-            timeout_ms: 1_000,
-            restart: RestartStrategy::Never,
-            backoff: mk_backoff(),
-            admission: AdmissionStrategy::DropIfRunning,
-            labels: RunnerLabels::default(),
+        let blocking = || -> TaskRef {
+            TaskFn::arc("busy-slot", |ctx: CancellationToken| async move {
+                ctx.cancelled().await;
+                Ok::<(), TaskError>(())
+            })
+        };
+        let policy = |admission| {
+            TaskPolicy::new(
+                "busy-slot".to_string(),
+                60_000,
+                RestartStrategy::Never,
+                mk_backoff(),
+                admission,
+            )
         };
-        let res = api.submit(&spec).await;
 
-        match res {
-            Err(CoreError::NoRunner(msg)) => {
-                assert!(msg.contains("TaskKind::None"));
+        let first = api
+            .submit_with_task(blocking(), &policy(AdmissionStrategy::DropIfRunning))
+            .await
+            .unwrap();
+        assert_eq!(first.admission, AdmissionOutcome::Admitted);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !api.supervisor().is_alive("busy-slot").await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
             }
-            Ok(_) => panic!("expected error for TaskKind::None, got Ok(TaskId)"),
-            Err(e) => panic!("expected CoreError::NoRunner, got {e:?}"),
-        }
+        })
+        .await
+        .expect("first task did not start");
+
+        let second = api
+            .submit_with_task(blocking(), &policy(AdmissionStrategy::DropIfRunning))
+            .await
+            .unwrap();
+        assert_eq!(second.admission, AdmissionOutcome::Dropped);
+        assert_eq!(second.task_id, first.task_id);
+
+        let queued = api
+            .submit_with_task(blocking(), &policy(AdmissionStrategy::Queue))
+            .await
+            .unwrap();
+        assert_eq!(queued.admission, AdmissionOutcome::Queued);
+    }
+
+    #[tokio::test]
+    async fn busy_slot_is_rejected_when_duplicates_are_rejected() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        api.set_reject_duplicate_in_slot(true);
+
+        let blocking = || -> TaskRef {
+            TaskFn::arc("dup-slot", |ctx: CancellationToken| async move {
+                ctx.cancelled().await;
+                Ok::<(), TaskError>(())
+            })
+        };
+        let policy = TaskPolicy::new(
+            "dup-slot".to_string(),
+            60_000,
+            RestartStrategy::Never,
+            mk_backoff(),
+            AdmissionStrategy::DropIfRunning,
+        );
+
+        let first = api.submit_with_task(blocking(), &policy).await.unwrap();
+        assert_eq!(first.admission, AdmissionOutcome::Admitted);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !api.supervisor().is_alive("dup-slot").await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("first task did not start");
+
+        let second = api.submit_with_task(blocking(), &policy).await;
+        assert!(
+            matches!(&second, Err(CoreError::SlotBusy(slot)) if slot == "dup-slot"),
+            "{second:?}"
+        );
+
+        api.set_reject_duplicate_in_slot(false);
+        let third = api.submit_with_task(blocking(), &policy).await.unwrap();
+        assert_eq!(third.admission, AdmissionOutcome::Dropped);
+    }
+
+    #[tokio::test]
+    async fn admission_override_replaces_spec_admission() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let mut events = api.subscribe_events(false);
+
+        // The controller keys slots by task name.
+        let blocking = || -> TaskRef {
+            TaskFn::arc("override-slot", |ctx: CancellationToken| async move {
+                ctx.cancelled().await;
+                Ok::<(), TaskError>(())
+            })
+        };
+        let policy = TaskPolicy::new(
+            "override-slot".to_string(),
+            60_000,
+            RestartStrategy::Never,
+            mk_backoff(),
+            AdmissionStrategy::Queue,
+        );
+
+        api.set_admission_override(Some(AdmissionStrategy::DropIfRunning));
+        assert_eq!(
+            api.admission_override(),
+            Some(AdmissionStrategy::DropIfRunning)
+        );
+        api.submit_with_task(blocking(), &policy).await.unwrap();
+        // Slot is busy: with the override this `Queue` submission must be dropped.
+        api.submit_with_task(blocking(), &policy).await.unwrap();
+
+        api.set_admission_override(None);
+        api.submit_with_task(blocking(), &policy).await.unwrap();
+
+        let reasons = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut reasons = Vec::new();
+            while reasons.len() < 2 {
+                let ev = events.recv().await.expect("event stream closed");
+                if ev.kind == EventKind::ControllerSubmitted {
+                    reasons.push(ev.reason.as_deref().unwrap_or_default().to_string());
+                }
+            }
+            reasons
+        })
+        .await
+        .expect("submissions were not processed in time");
+
+        assert!(
+            reasons[0].contains("admission=DropIfRunning"),
+            "{reasons:?}"
+        );
+        // Only the submission made after clearing the override was queued.
+        assert!(
+            reasons[1].contains("admission=Queue depth=1"),
+            "{reasons:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_status_returns_once_task_is_running() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let task: TaskRef = TaskFn::arc("long-task", |ctx: CancellationToken| async move {
+            ctx.cancelled().await;
+            Ok::<(), TaskError>(())
+        });
+        let policy = TaskPolicy::new(
+            "wait-slot".to_string(),
+            60_000,
+            RestartStrategy::Never,
+            mk_backoff(),
+            AdmissionStrategy::DropIfRunning,
+        );
+        let task_id = api.submit_with_task(task, &policy).await.unwrap().task_id;
+
+        let info = api
+            .wait_for_status(&task_id, TaskStatus::Running, Duration::from_secs(5))
+            .await
+            .expect("task did not start in time");
+        assert_eq!(info.status, TaskStatus::Running);
+
+        // Still running: `Succeeded` is not reached within the timeout.
+        let err = api
+            .wait_for_status(&task_id, TaskStatus::Succeeded, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                CoreError::WaitTimeout {
+                    status: TaskStatus::Succeeded,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+
+        api.cancel_task(&task_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_for_unknown_task_is_not_found() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let err = api
+            .wait_for_status(
+                &TaskId::from("missing-task"),
+                TaskStatus::Running,
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CoreError::TaskNotFound(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn watch_task_streams_status_changes() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let task: TaskRef = TaskFn::arc("watched-task", |ctx: CancellationToken| async move {
+            ctx.cancelled().await;
+            Ok::<(), TaskError>(())
+        });
+        let policy = TaskPolicy::new(
+            "watch-slot".to_string(),
+            60_000,
+            RestartStrategy::Never,
+            mk_backoff(),
+            AdmissionStrategy::DropIfRunning,
+        );
+        let task_id = api.submit_with_task(task, &policy).await.unwrap().task_id;
+        let mut watch = api.watch_task(&task_id).unwrap();
+
+        async fn next(watch: &mut TaskWatch) -> Option<TaskInfo> {
+            tokio::time::timeout(Duration::from_secs(5), watch.next())
+                .await
+                .expect("no task update in time")
+        }
+        while next(&mut watch).await.unwrap().status != TaskStatus::Running {}
+
+        // The watch ends after the terminal update caused by the cancellation.
+        api.cancel_task(&task_id).await.unwrap();
+        while !next(&mut watch).await.unwrap().status.is_terminal() {}
+        assert!(next(&mut watch).await.is_none());
+
+        let err = api.watch_task(&TaskId::from("missing-task")).unwrap_err();
+        assert!(matches!(err, CoreError::TaskNotFound(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn cancel_unknown_task_is_not_found() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let err = api
+            .cancel_task(&TaskId::from("missing-task"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CoreError::TaskNotFound(ref id) if id == "missing-task"));
+    }
+
+    #[derive(Default)]
+    struct TerminalRecorder(std::sync::Mutex<Vec<(String, TerminalReason)>>);
+
+    impl crate::MetricsBackend for TerminalRecorder {
+        fn record_task_started(&self, _: &str) {}
+        fn record_task_completed(&self, _: &str, _: crate::TaskOutcome, _: u64) {}
+        fn record_runner_error(&self, _: &str, _: crate::RunnerErrorKind) {}
+        fn record_terminal(&self, runner_type: &str, reason: TerminalReason) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((runner_type.to_string(), reason));
+        }
+    }
+
+    struct HangingRunner;
+
+    impl crate::Runner for HangingRunner {
+        fn name(&self) -> &'static str {
+            "hanging-runner"
+        }
+
+        fn supports(&self, _spec: &CreateSpec) -> bool {
+            true
+        }
+
+        fn build_task(
+            &self,
+            _spec: &CreateSpec,
+            _ctx: &crate::BuildContext,
+        ) -> Result<TaskRef, RunnerError> {
+            Ok(TaskFn::arc(
+                crate::make_run_id("hanging", "timeout-slot"),
+                |ctx: CancellationToken| async move {
+                    ctx.cancelled().await;
+                    Err(TaskError::Canceled)
+                },
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn timeout_is_recorded_as_terminal_reason() {
+        let recorder = Arc::new(TerminalRecorder::default());
+        let mut router = RunnerRouter::new().with_context(crate::BuildContext::new(
+            Default::default(),
+            recorder.clone(),
+        ));
+        router.register(Arc::new(HangingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "timeout-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".into(),
+                args: vec!["60".into()],
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 50,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        api.submit(&spec).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while recorder.0.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("terminal reason was not recorded");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![("hanging-runner".to_string(), TerminalReason::Timeout)]
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_task_stops_after_max_attempts() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        let mut events = api.subscribe_events(false);
+
+        let task: TaskRef = TaskFn::arc("always-failing", |_ctx: CancellationToken| async move {
+            Err::<(), TaskError>(TaskError::Fail {
+                reason: "boom".into(),
+            })
+        });
+        let policy = TaskPolicy::new(
+            "max-attempts-slot".to_string(),
+            1_000,
+            RestartStrategy::on_failure().with_max_attempts(3),
+            BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 10,
+                max_ms: 10,
+                factor: 1.0,
+            },
+            AdmissionStrategy::DropIfRunning,
+        );
+        let task_id = api.submit_with_task(task, &policy).await.unwrap().task_id;
+
+        let (starts, last) = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut starts = 0;
+            loop {
+                let ev = events.recv().await.expect("event stream closed");
+                if ev.task.as_deref() != Some(task_id.as_str()) {
+                    continue;
+                }
+                match ev.kind {
+                    EventKind::TaskStarting => starts += 1,
+                    EventKind::ActorDead | EventKind::ActorExhausted => return (starts, ev),
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("task kept restarting");
+
+        assert_eq!(starts, 3);
+        assert_eq!(last.kind, EventKind::ActorDead);
+        assert!(last.reason.unwrap().contains(RESTART_LIMIT_REASON));
+    }
+
+    struct NoopSubprocessRunner;
+
+    impl crate::Runner for NoopSubprocessRunner {
+        fn name(&self) -> &'static str {
+            "noop-subprocess"
+        }
+
+        fn supports(&self, spec: &CreateSpec) -> bool {
+            matches!(spec.kind, TaskKind::Subprocess { .. })
+        }
+
+        fn build_task(
+            &self,
+            spec: &CreateSpec,
+            ctx: &crate::BuildContext,
+        ) -> Result<TaskRef, crate::RunnerError> {
+            let run_id = ctx
+                .task_id()
+                .map_or_else(|| self.build_run_id(&spec.slot), TaskId::to_string);
+            Ok(TaskFn::arc(run_id, |_ctx: CancellationToken| async move {
+                Ok::<(), TaskError>(())
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_records_kind_summary_and_runner() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(NoopSubprocessRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "summary-slot".to_string(),
+            kind: TaskKind::Subprocess {
+                command: "ls".to_string(),
+                args: vec!["-la".to_string()],
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task_id = api.submit(&spec).await.expect("submit failed").task_id;
+
+        let info = api.get_task(&task_id).expect("task must be registered");
+        assert_eq!(info.kind_summary.as_deref(), Some("subprocess: ls"));
+        assert_eq!(info.runner_name.as_deref(), Some("noop-subprocess"));
+        assert_eq!(api.list_tasks_by_slot("summary-slot").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn submit_with_id_uses_the_given_id() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(NoopSubprocessRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "explicit-slot".to_string(),
+            kind: TaskKind::Subprocess {
+                command: "ls".to_string(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+
+        let id = TaskId::from("order-42");
+        let submission = api.submit_with_id(id.clone(), &spec).await.unwrap();
+        assert_eq!(submission.task_id, id);
+        let info = api
+            .get_task(&id)
+            .expect("task must be registered by its id");
+        assert_eq!(info.runner_name.as_deref(), Some("noop-subprocess"));
+
+        let err = api
+            .submit_with_id(TaskId::from("not valid"), &spec)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CoreError::Model(_)), "{err:?}");
+
+        // A running task holds its id until it finishes.
+        let busy: TaskRef = TaskFn::arc("busy-id", |ctx: CancellationToken| async move {
+            ctx.cancelled().await;
+            Ok::<(), TaskError>(())
+        });
+        let policy = TaskPolicy::new(
+            "busy-slot".to_string(),
+            60_000,
+            RestartStrategy::Never,
+            mk_backoff(),
+            AdmissionStrategy::DropIfRunning,
+        );
+        let busy_id = api.submit_with_task(busy, &policy).await.unwrap().task_id;
+        api.wait_for_status(&busy_id, TaskStatus::Running, Duration::from_secs(5))
+            .await
+            .unwrap();
+        let err = api
+            .submit_with_id(busy_id.clone(), &spec)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CoreError::DuplicateTaskId(_)), "{err:?}");
+
+        api.cancel_task(&busy_id).await.unwrap();
+    }
+
+    /// Runner whose tasks run until cancelled.
+    struct WaitingRunner;
+
+    impl crate::Runner for WaitingRunner {
+        fn name(&self) -> &'static str {
+            "waiting"
+        }
+
+        fn supports(&self, spec: &CreateSpec) -> bool {
+            matches!(spec.kind, TaskKind::Subprocess { .. })
+        }
+
+        fn build_task(
+            &self,
+            spec: &CreateSpec,
+            _ctx: &crate::BuildContext,
+        ) -> Result<TaskRef, crate::RunnerError> {
+            Ok(TaskFn::arc(
+                self.build_run_id(&spec.slot),
+                |ctx: CancellationToken| async move {
+                    ctx.cancelled().await;
+                    Ok::<(), TaskError>(())
+                },
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_batch_cancels_every_task_of_the_batch() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(WaitingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = |slot: &str, batch_id: Option<&str>| CreateSpec {
+            slot: slot.to_string(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".to_string(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: batch_id.map(str::to_string),
+            schedule: None,
+        };
+
+        let mut batch = Vec::new();
+        for slot in ["batch-a", "batch-b", "batch-c"] {
+            let id = api.submit(&spec(slot, Some("wf-1"))).await.unwrap().task_id;
+            api.wait_for_status(&id, TaskStatus::Running, Duration::from_secs(5))
+                .await
+                .unwrap();
+            batch.push(id);
+        }
+        let outsider = api.submit(&spec("batch-d", None)).await.unwrap().task_id;
+
+        let mut listed: Vec<TaskId> = api
+            .list_tasks_by_batch("wf-1")
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        listed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        batch.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(listed, batch);
+
+        let mut cancelled = api.cancel_batch("wf-1").await.unwrap();
+        cancelled.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(cancelled, batch);
+        assert!(
+            api.list_tasks_by_batch("wf-1")
+                .iter()
+                .all(|t| t.status.is_terminal())
+        );
+        assert!(!api.get_task(&outsider).unwrap().status.is_terminal());
+
+        let err = api.cancel_batch("missing-batch").await.unwrap_err();
+        assert!(matches!(err, CoreError::BatchNotFound(_)), "{err:?}");
+
+        api.cancel_task(&outsider).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancel_is_success_marks_cancelled_task_succeeded() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(WaitingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = |slot: &str, cancel_is_success: bool| CreateSpec {
+            slot: slot.to_string(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".to_string(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+
+        for (slot, cancel_is_success, expected) in [
+            ("cancel-ok", true, TaskStatus::Succeeded),
+            ("cancel-plain", false, TaskStatus::Canceled),
+        ] {
+            let id = api
+                .submit(&spec(slot, cancel_is_success))
+                .await
+                .unwrap()
+                .task_id;
+            api.wait_for_status(&id, TaskStatus::Running, Duration::from_secs(5))
+                .await
+                .unwrap();
+
+            api.cancel_task(&id).await.unwrap();
+            assert_eq!(api.get_task(&id).unwrap().status, expected, "{slot}");
+        }
+    }
+
+    #[tokio::test]
+    async fn admission_applies_to_every_task_of_the_slot() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(WaitingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        // Every submission gets its own run id, so the controller never sees them in one slot.
+        let spec = |admission| CreateSpec {
+            slot: "shared-slot".to_string(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".to_string(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let wait = Duration::from_secs(5);
+
+        let first = api.submit(&spec(AdmissionStrategy::Queue)).await.unwrap();
+        assert_eq!(first.admission, AdmissionOutcome::Admitted);
+        api.wait_for_status(&first.task_id, TaskStatus::Running, wait)
+            .await
+            .unwrap();
+
+        let dropped = api
+            .submit(&spec(AdmissionStrategy::DropIfRunning))
+            .await
+            .unwrap();
+        assert_eq!(dropped.admission, AdmissionOutcome::Dropped);
+        assert!(api.get_task(&dropped.task_id).is_none());
+
+        let queued = api.submit(&spec(AdmissionStrategy::Queue)).await.unwrap();
+        assert_eq!(queued.admission, AdmissionOutcome::Queued);
+        let last = api.submit(&spec(AdmissionStrategy::Queue)).await.unwrap();
+        assert_eq!(last.admission, AdmissionOutcome::Queued);
+        assert!(!api.supervisor().is_alive(queued.task_id.as_str()).await);
+        assert_eq!(
+            api.get_task(&queued.task_id).unwrap().status,
+            TaskStatus::Pending
+        );
+
+        // Queued tasks start one at a time, in submission order.
+        api.cancel_task(&first.task_id).await.unwrap();
+        api.wait_for_status(&queued.task_id, TaskStatus::Running, wait)
+            .await
+            .unwrap();
+        assert_eq!(
+            api.get_task(&last.task_id).unwrap().status,
+            TaskStatus::Pending
+        );
+
+        let replacing = api.submit(&spec(AdmissionStrategy::Replace)).await.unwrap();
+        assert_eq!(replacing.admission, AdmissionOutcome::Replaced);
+        for replaced in [&queued.task_id, &last.task_id] {
+            let info = api.get_task(replaced).unwrap();
+            assert_eq!(info.status, TaskStatus::Canceled);
+            assert!(info.error.unwrap().contains("replaced"));
+        }
+        api.wait_for_status(&replacing.task_id, TaskStatus::Running, wait)
+            .await
+            .unwrap();
+        assert!(!api.supervisor().is_alive(last.task_id.as_str()).await);
+
+        api.cancel_task(&replacing.task_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn duplicate_spec_in_busy_slot_is_rejected() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(WaitingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        api.set_reject_duplicate_in_slot(true);
+
+        let spec = CreateSpec {
+            slot: "dup-spec-slot".to_string(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".to_string(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+
+        // The identical spec is built under a new run id, but the slot is still busy.
+        let first = api.submit(&spec).await.unwrap();
+        let second = api.submit(&spec).await;
+        assert!(
+            matches!(&second, Err(CoreError::SlotBusy(slot)) if slot == "dup-spec-slot"),
+            "{second:?}"
+        );
+        assert_eq!(api.list_tasks_by_slot("dup-spec-slot").len(), 1);
+
+        api.wait_for_status(&first.task_id, TaskStatus::Running, Duration::from_secs(5))
+            .await
+            .unwrap();
+        api.cancel_task(&first.task_id).await.unwrap();
+        let third = api.submit(&spec).await.unwrap();
+        assert_eq!(third.admission, AdmissionOutcome::Admitted);
+        api.wait_for_status(&third.task_id, TaskStatus::Running, Duration::from_secs(5))
+            .await
+            .unwrap();
+        api.cancel_task(&third.task_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn submit_rejects_taskkind_none() {
+        let router = RunnerRouter::new();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "test-slot-none".to_string(),
+            kind: TaskKind::None,
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let res = api.submit(&spec).await;
+
+        match res {
+            Err(CoreError::NoRunner(msg)) => {
+                assert!(msg.contains("TaskKind::None"));
+            }
+            Ok(_) => panic!("expected error for TaskKind::None, got Ok(TaskId)"),
+            Err(e) => panic!("expected CoreError::NoRunner, got {e:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_many_reports_per_spec_results() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(NoopSubprocessRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let mk_spec = |slot: &str, kind: TaskKind| CreateSpec {
+            slot: slot.to_string(),
+            kind,
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::Queue,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let subprocess = || TaskKind::Subprocess {
+            command: "true".to_string(),
+            args: Vec::new(),
+            env: Default::default(),
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Default::default(),
+        };
+        let specs = vec![
+            mk_spec("bulk-a", subprocess()),
+            mk_spec("bulk-none", TaskKind::None),
+            mk_spec("bulk-b", subprocess()),
+        ];
+
+        let results = api.submit_many(&specs).await;
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Err(CoreError::NoRunner(_))));
+        for (idx, slot) in [(0, "bulk-a"), (2, "bulk-b")] {
+            let submission = results[idx].as_ref().expect("valid spec must be submitted");
+            assert_eq!(submission.admission, AdmissionOutcome::Admitted);
+            let info = api
+                .get_task(&submission.task_id)
+                .expect("task must be registered");
+            assert_eq!(info.slot, slot);
+            assert_eq!(info.kind_summary.as_deref(), Some("subprocess: true"));
+        }
+        assert!(api.list_tasks_by_slot("bulk-none").is_empty());
+    }
+
+    #[tokio::test]
+    async fn submit_many_applies_admission_within_the_batch() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(WaitingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = |admission| CreateSpec {
+            slot: "bulk-slot".to_string(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".to_string(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+
+        let results = api
+            .submit_many(&[
+                spec(AdmissionStrategy::DropIfRunning),
+                spec(AdmissionStrategy::DropIfRunning),
+                spec(AdmissionStrategy::Queue),
+            ])
+            .await;
+        let outcomes: Vec<_> = results
+            .iter()
+            .map(|r| r.as_ref().expect("spec must be submitted").admission)
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                AdmissionOutcome::Admitted,
+                AdmissionOutcome::Dropped,
+                AdmissionOutcome::Queued
+            ]
+        );
+        assert_eq!(api.list_tasks_by_slot("bulk-slot").len(), 2);
+
+        api.set_reject_duplicate_in_slot(true);
+        let results = api
+            .submit_many(&[spec(AdmissionStrategy::DropIfRunning)])
+            .await;
+        assert!(
+            matches!(results.as_slice(), [Err(CoreError::SlotBusy(slot))] if slot == "bulk-slot"),
+            "{results:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn health_reports_live_run_loop_and_active_tasks() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(HangingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        assert!(api.is_ready());
+        assert_eq!(api.health(), HealthStatus::new(None, 0, 0));
+
+        let spec = CreateSpec {
+            slot: "health-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".into(),
+                args: vec!["60".into()],
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::Queue,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let id = api.submit(&spec).await.unwrap().task_id;
+        api.wait_for_status(&id, TaskStatus::Running, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let health = api.health();
+        assert!(health.alive);
+        assert_eq!((health.running, health.pending), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn submissions_fail_once_run_loop_is_dead() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(HangingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+        assert_eq!(api.last_error(), None);
+
+        api.run_loop_error.set("controller crashed".into()).unwrap();
+        assert!(!api.is_ready());
+        assert_eq!(api.last_error(), Some("controller crashed"));
+        assert!(!api.health().alive);
+
+        let spec = CreateSpec {
+            slot: "dead-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "true".into(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::Queue,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let err = api.submit(&spec).await.unwrap_err();
+        assert!(
+            matches!(err, CoreError::Supervisor(ref msg) if msg.contains("controller crashed")),
+            "{err:?}"
+        );
+        assert!(matches!(
+            api.submit_many(&[spec]).await.as_slice(),
+            [Err(CoreError::Supervisor(_))]
+        ));
+        assert!(api.list_all_tasks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn submit_with_same_idempotency_key_returns_existing_task() {
+        let mut router = RunnerRouter::new();
+        router.register(Arc::new(HangingRunner)).unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "idempotent-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".into(),
+                args: vec!["60".into()],
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 60_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::Queue,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: Some("retry-1".into()),
+            batch_id: None,
+            schedule: None,
+        };
+
+        let first = api.submit(&spec).await.unwrap().task_id;
+        let second = api.submit(&spec).await.unwrap().task_id;
+        assert_eq!(first, second);
+
+        let batch = api.submit_many(&[spec.clone(), spec.clone()]).await;
+        assert_eq!(batch[0].as_ref().unwrap().task_id, first);
+        assert_eq!(batch[1].as_ref().unwrap().task_id, first);
+        assert_eq!(api.list_tasks_by_slot("idempotent-slot").len(), 1);
+
+        let other = api
+            .submit(&CreateSpec {
+                idempotency_key: Some("retry-2".into()),
+                batch_id: None,
+                schedule: None,
+                ..spec.clone()
+            })
+            .await
+            .unwrap()
+            .task_id;
+        assert_ne!(other, first);
+    }
+
+    #[tokio::test]
+    async fn finished_task_releases_runner_capacity() {
+        let mut router = RunnerRouter::new();
+        router
+            .register_with_capacity(Arc::new(NoopSubprocessRunner), RunnerLabels::new(), 1)
+            .unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "capacity-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "true".into(),
+                args: Vec::new(),
+                env: Default::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Default::default(),
+            },
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: mk_backoff(),
+            admission: AdmissionStrategy::Queue,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        api.submit(&spec).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while api.router.in_flight() != vec![("noop-subprocess", 0)] {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("capacity was not released after the task finished");
+        api.submit(&spec)
+            .await
+            .expect("runner must accept work again");
     }
 }
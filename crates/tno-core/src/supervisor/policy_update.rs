@@ -0,0 +1,452 @@
+//! Restart policy updates of live tasks.
+//!
+//! Taskvisor fixes restart and backoff policies at submission. Tasks without a schedule are therefore
+//! wrapped in [`PolicyControlled`], which applies a pending update once the current attempt ends:
+//! instead of being restarted under the old policy, the task either stops or is resubmitted under
+//! the new one.
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use taskvisor::{ControllerSpec, Supervisor, Task, TaskError, TaskRef, TaskSpec};
+use tno_model::{AdmissionStrategy, BackoffStrategy, RestartStrategy, TaskId, TaskStatus};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, instrument, warn};
+
+use super::{RestartLimited, SupervisorApi};
+use crate::{
+    error::CoreError,
+    map::{to_admission_policy, to_backoff_policy, to_restart_policy},
+    policy::TaskPolicy,
+    state::TaskState,
+};
+
+/// Marker included in the fatal error of a task that was resubmitted under an updated policy.
+///
+/// Taskvisor reports the old actor as `ActorDead`; the state subscriber keeps the task alive instead.
+pub(crate) const POLICY_RESCHEDULED_REASON: &str = "restart policy updated; rescheduled";
+
+/// Marker included in the fatal error of a task that the updated policy does not restart.
+const POLICY_STOPPED_REASON: &str = "restart policy updated; not restarted";
+
+/// Same as [`POLICY_STOPPED_REASON`], for a task whose last attempt failed.
+const POLICY_STOPPED_AFTER_FAILURE_REASON: &str =
+    "restart policy updated; not restarted after failure";
+
+/// Status (and error) to record for a task ended with one of the policy update markers.
+///
+/// Returns `None` if `reason` carries no marker.
+pub(crate) fn status_after_update(reason: &str) -> Option<(TaskStatus, Option<String>)> {
+    if reason.contains(POLICY_RESCHEDULED_REASON) {
+        Some((TaskStatus::Pending, None))
+    } else if reason.contains(POLICY_STOPPED_AFTER_FAILURE_REASON) {
+        Some((TaskStatus::Failed, Some(reason.to_string())))
+    } else if reason.contains(POLICY_STOPPED_REASON) {
+        Some((TaskStatus::Succeeded, None))
+    } else {
+        None
+    }
+}
+
+/// Restart and backoff requested by [`SupervisorApi::update_policy`].
+#[derive(Clone, Debug)]
+struct PolicyUpdate {
+    restart: RestartStrategy,
+    backoff: BackoffStrategy,
+}
+
+/// Pending policy update of a task, shared by all of its submissions.
+#[derive(Default)]
+pub(crate) struct PolicyHandle {
+    pending: Mutex<Option<PolicyUpdate>>,
+}
+
+impl PolicyHandle {
+    fn peek(&self) -> Option<PolicyUpdate> {
+        self.pending.lock().unwrap().clone()
+    }
+
+    fn take(&self) -> Option<PolicyUpdate> {
+        self.pending.lock().unwrap().take()
+    }
+}
+
+/// Policy handles of submitted tasks, keyed by task ID.
+#[derive(Clone, Default)]
+pub(crate) struct PolicyUpdates {
+    inner: Arc<Mutex<HashMap<TaskId, Arc<PolicyHandle>>>>,
+}
+
+impl PolicyUpdates {
+    /// Create a fresh handle for a submitted task, replacing the one of a previous task with the same ID.
+    pub(crate) fn register(&self, id: &TaskId) -> Arc<PolicyHandle> {
+        let handle = Arc::new(PolicyHandle::default());
+        self.inner
+            .lock()
+            .unwrap()
+            .insert(id.clone(), Arc::clone(&handle));
+        handle
+    }
+
+    fn get(&self, id: &TaskId) -> Option<Arc<PolicyHandle>> {
+        self.inner.lock().unwrap().get(id).cloned()
+    }
+}
+
+/// Whether `restart` starts another attempt after one that succeeded (`ok`) or failed.
+fn restarts_after(restart: RestartStrategy, ok: bool) -> bool {
+    match restart {
+        RestartStrategy::Never => false,
+        RestartStrategy::OnFailure { .. } => !ok,
+        RestartStrategy::Always { .. } => true,
+    }
+}
+
+/// Delay before the first attempt under `update`, following an attempt that succeeded (`ok`) or failed.
+fn restart_delay(update: &PolicyUpdate, ok: bool) -> Duration {
+    match update.restart {
+        _ if !ok => Duration::from_millis(update.backoff.first_ms),
+        RestartStrategy::Always {
+            interval_ms: Some(ms),
+            ..
+        } => Duration::from_millis(ms),
+        _ => Duration::ZERO,
+    }
+}
+
+/// Fatal error ending a task that the updated policy does not restart after `last`.
+fn stopped_error(last: &Result<(), String>) -> TaskError {
+    let reason = match last {
+        Ok(()) => POLICY_STOPPED_REASON.to_string(),
+        Err(e) => format!("{POLICY_STOPPED_AFTER_FAILURE_REASON}: {e}"),
+    };
+    TaskError::Fatal { reason }
+}
+
+/// What a resubmission needs from the supervisor.
+#[derive(Clone)]
+pub(crate) struct Resubmit {
+    pub(crate) sup: Weak<Supervisor>,
+    pub(crate) state: TaskState,
+}
+
+/// Task wrapper that applies [`SupervisorApi::update_policy`] between attempts.
+///
+/// An attempt counts as failed until it finishes, so attempts cut short by a timeout count as failures.
+pub(crate) struct PolicyControlled {
+    controlled: Arc<Controlled>,
+    /// Outcome of the previous attempt (`None` before the first one).
+    last: Arc<Mutex<Option<Result<(), String>>>>,
+    /// Delay before the first attempt of a resubmission.
+    start_delay: Mutex<Option<Duration>>,
+}
+
+/// Everything needed to resubmit a task under a new policy.
+struct Controlled {
+    inner: TaskRef,
+    policy: TaskPolicy,
+    handle: Arc<PolicyHandle>,
+    resubmit: Resubmit,
+    /// Per-attempt timeout enforced here rather than by taskvisor (resubmissions only),
+    /// so that the start delay does not count against it.
+    timeout: Option<Duration>,
+}
+
+impl PolicyControlled {
+    pub(crate) fn wrap(
+        inner: TaskRef,
+        policy: TaskPolicy,
+        handle: Arc<PolicyHandle>,
+        resubmit: Resubmit,
+    ) -> TaskRef {
+        Arc::new(Self::new(
+            Controlled {
+                inner,
+                policy,
+                handle,
+                resubmit,
+                timeout: None,
+            },
+            None,
+        ))
+    }
+
+    fn new(controlled: Controlled, start_delay: Option<Duration>) -> Self {
+        Self {
+            controlled: Arc::new(controlled),
+            last: Arc::new(Mutex::new(None)),
+            start_delay: Mutex::new(start_delay),
+        }
+    }
+}
+
+impl Controlled {
+    /// Controller spec that runs the task under `update` after `delay`.
+    ///
+    /// The old actor is still alive while the spec is submitted, so it is queued behind it.
+    fn controller_spec(&self, update: &PolicyUpdate, delay: Duration) -> ControllerSpec {
+        let policy = TaskPolicy {
+            restart: update.restart,
+            backoff: update.backoff.clone(),
+            ..self.policy.clone()
+        };
+        let task = PolicyControlled::new(
+            Controlled {
+                inner: Arc::clone(&self.inner),
+                policy: policy.clone(),
+                handle: Arc::clone(&self.handle),
+                resubmit: self.resubmit.clone(),
+                timeout: Some(Duration::from_millis(policy.timeout_ms)).filter(|t| !t.is_zero()),
+            },
+            Some(delay),
+        );
+
+        let task_spec = TaskSpec::new(
+            RestartLimited::wrap(Arc::new(task), policy.restart),
+            to_restart_policy(policy.restart),
+            to_backoff_policy(&policy.backoff),
+            None,
+        );
+        ControllerSpec {
+            admission: to_admission_policy(AdmissionStrategy::Queue),
+            task_spec,
+        }
+    }
+
+    /// Resubmit the task under `update` once the current actor is gone.
+    fn reschedule(&self, update: &PolicyUpdate, ok: bool) {
+        let Some(sup) = self.resubmit.sup.upgrade() else {
+            return;
+        };
+        let spec = self.controller_spec(update, restart_delay(update, ok));
+        let state = self.resubmit.state.clone();
+        let task_id = TaskId::from(self.inner.name());
+        debug!(task_id = %task_id, ?update, "resubmitting task under updated policy");
+        tokio::spawn(async move {
+            if let Err(e) = sup.submit(spec).await {
+                warn!(task_id = %task_id, error = %e, "resubmission under updated policy failed");
+                state.update_status(&task_id, TaskStatus::Failed, Some(e.to_string()));
+            }
+        });
+    }
+
+    /// Run one attempt, waiting for `delay` first.
+    async fn run(&self, ctx: CancellationToken, delay: Option<Duration>) -> Result<(), TaskError> {
+        if let Some(delay) = delay {
+            tokio::select! {
+                _ = ctx.cancelled() => return Err(TaskError::Canceled),
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        let Some(timeout) = self.timeout else {
+            return self.inner.spawn(ctx).await;
+        };
+        let child = ctx.child_token();
+        match tokio::time::timeout(timeout, self.inner.spawn(child.clone())).await {
+            Ok(res) => res,
+            Err(_) => {
+                child.cancel();
+                Err(TaskError::Timeout { timeout })
+            }
+        }
+    }
+}
+
+impl Task for PolicyControlled {
+    fn name(&self) -> &str {
+        self.controlled.inner.name()
+    }
+
+    fn spawn(
+        &self,
+        ctx: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TaskError>> + Send + 'static>> {
+        // An update received between attempts applies to the restart that is about to happen.
+        let previous = self.last.lock().unwrap().clone();
+        if let Some(update) = self.controlled.handle.peek()
+            && let Some(previous) = previous
+            && !restarts_after(update.restart, previous.is_ok())
+        {
+            self.controlled.handle.take();
+            return Box::pin(async move { Err(stopped_error(&previous)) });
+        }
+        *self.last.lock().unwrap() = Some(Err("attempt did not finish".to_string()));
+
+        let controlled = Arc::clone(&self.controlled);
+        let last = Arc::clone(&self.last);
+        let delay = self.start_delay.lock().unwrap().take();
+        Box::pin(async move {
+            let result = controlled.run(ctx, delay).await;
+            if let Err(e) = &result
+                && (matches!(e, TaskError::Canceled) || e.is_fatal())
+            {
+                return result;
+            }
+            let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+            *last.lock().unwrap() = Some(outcome.clone());
+
+            let Some(update) = controlled.handle.take() else {
+                return result;
+            };
+            if !restarts_after(update.restart, outcome.is_ok()) {
+                return Err(stopped_error(&outcome));
+            }
+            controlled.reschedule(&update, outcome.is_ok());
+            Err(TaskError::Fatal {
+                reason: POLICY_RESCHEDULED_REASON.to_string(),
+            })
+        })
+    }
+}
+
+impl SupervisorApi {
+    /// Change the restart and backoff policy of a live task going forward.
+    ///
+    /// The current attempt is never interrupted. Once it ends, the restart decision follows the new policy:
+    /// - if the new policy does not restart after that outcome, the task ends with it;
+    /// - otherwise the task is resubmitted under the new policy and its next attempt starts after
+    ///   the new restart interval (after a success) or the first backoff delay (after a failure).
+    ///
+    /// If the task is waiting between attempts (backoff or restart interval), that wait is not changed.
+    /// When it ends, the task either stops (if the new policy would not have restarted it) or runs
+    /// its next attempt, after which the rule above applies.
+    ///
+    /// A later update replaces one that has not been applied yet. Attempt limits of the new policy
+    /// count from the resubmission.
+    ///
+    /// # Errors
+    /// - `Err(CoreError::TaskNotFound)` if the task is unknown
+    /// - `Err(CoreError::PolicyUpdate)` if the task has finished or runs on a schedule
+    /// - `Err(CoreError::Model)` if `backoff` is invalid
+    #[instrument(level = "debug", skip(self, backoff), fields(task_id = %id))]
+    pub async fn update_policy(
+        &self,
+        id: &TaskId,
+        restart: RestartStrategy,
+        backoff: BackoffStrategy,
+    ) -> Result<(), CoreError> {
+        backoff.validate()?;
+        let info = self
+            .state
+            .get(id)
+            .ok_or_else(|| CoreError::TaskNotFound(id.to_string()))?;
+        let Some(handle) = self.policy_updates.get(id) else {
+            return Err(CoreError::PolicyUpdate {
+                id: id.to_string(),
+                reason: "task runs on a schedule".to_string(),
+            });
+        };
+        // A periodic task between runs reports the status of its last run, but its actor is alive.
+        if info.status.is_terminal() && !self.sup.is_alive(id.as_str()).await {
+            return Err(CoreError::PolicyUpdate {
+                id: id.to_string(),
+                reason: "task has finished".to_string(),
+            });
+        }
+
+        *handle.pending.lock().unwrap() = Some(PolicyUpdate { restart, backoff });
+        debug!(?restart, "restart policy update pending");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use taskvisor::{ControllerConfig, SupervisorConfig, TaskFn};
+    use tno_model::JitterStrategy;
+
+    use crate::router::RunnerRouter;
+
+    fn backoff() -> BackoffStrategy {
+        BackoffStrategy {
+            jitter: JitterStrategy::None,
+            first_ms: 10,
+            max_ms: 10,
+            factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn markers_map_to_final_status() {
+        let rescheduled = format!("fatal: {POLICY_RESCHEDULED_REASON}");
+        let stopped = format!("fatal: {POLICY_STOPPED_REASON}");
+        let failed = stopped_error(&Err("exit code 1".into())).to_string();
+
+        assert_eq!(
+            status_after_update(&rescheduled),
+            Some((TaskStatus::Pending, None))
+        );
+        assert_eq!(
+            status_after_update(&stopped),
+            Some((TaskStatus::Succeeded, None))
+        );
+        assert_eq!(
+            status_after_update(&failed).map(|(status, _)| status),
+            Some(TaskStatus::Failed)
+        );
+        assert_eq!(status_after_update("exit code 1"), None);
+    }
+
+    #[tokio::test]
+    async fn update_to_never_stops_periodic_task_after_current_run() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let release = CancellationToken::new();
+        let task: TaskRef = TaskFn::arc("periodic", {
+            let release = release.clone();
+            move |_ctx: CancellationToken| {
+                let release = release.clone();
+                async move {
+                    release.cancelled().await;
+                    Ok::<(), TaskError>(())
+                }
+            }
+        });
+        let policy = TaskPolicy::new(
+            "periodic".to_string(),
+            60_000,
+            RestartStrategy::always(),
+            backoff(),
+            AdmissionStrategy::DropIfRunning,
+        );
+        let id = api.submit_with_task(task, &policy).await.unwrap().task_id;
+        api.wait_for_status(&id, TaskStatus::Running, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        api.update_policy(&id, RestartStrategy::Never, backoff())
+            .await
+            .unwrap();
+        // The current run is not interrupted.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(api.get_task(&id).unwrap().status, TaskStatus::Running);
+
+        release.cancel();
+        let info = api
+            .wait_for_status(&id, TaskStatus::Succeeded, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(info.status, TaskStatus::Succeeded);
+        assert_eq!(info.attempt, 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let err = api
+            .update_policy(&id, RestartStrategy::always(), backoff())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CoreError::PolicyUpdate { .. }), "{err:?}");
+    }
+}
@@ -0,0 +1,131 @@
+//! Enforcement of [`RestartStrategy::max_attempts`], which taskvisor does not support natively.
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+use taskvisor::{Task, TaskError, TaskRef};
+use tno_model::RestartStrategy;
+use tokio_util::sync::CancellationToken;
+
+/// Marker included in the fatal error of a task that ran out of attempts.
+///
+/// Taskvisor reports such a task as `ActorDead`; the state subscriber uses the marker to mark it as exhausted instead.
+pub(crate) const RESTART_LIMIT_REASON: &str = "restart limit reached";
+
+/// Task wrapper that stops restarts after `max` consecutive failed attempts.
+///
+/// The failing attempt that hits the limit is turned into [`TaskError::Fatal`], which taskvisor never retries.
+/// An attempt counts as failed until it succeeds, so attempts cut short by a timeout count too;
+/// if the last allowed attempt timed out, the next one gives up without running the task.
+pub(crate) struct RestartLimited {
+    inner: TaskRef,
+    max: u32,
+    failures: Arc<AtomicU32>,
+}
+
+impl RestartLimited {
+    /// Wrap `task` if `restart` caps the number of attempts; otherwise return it unchanged.
+    pub(crate) fn wrap(task: TaskRef, restart: RestartStrategy) -> TaskRef {
+        match restart.max_attempts() {
+            Some(max) => Arc::new(Self {
+                inner: task,
+                max,
+                failures: Arc::new(AtomicU32::new(0)),
+            }),
+            None => task,
+        }
+    }
+
+    fn limit_error(max: u32, last: Option<&TaskError>) -> TaskError {
+        let reason = match last {
+            Some(e) => format!("{RESTART_LIMIT_REASON} after {max} failed attempts: {e}"),
+            None => format!("{RESTART_LIMIT_REASON} after {max} failed attempts"),
+        };
+        TaskError::Fatal { reason }
+    }
+}
+
+impl Task for RestartLimited {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn spawn(
+        &self,
+        ctx: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TaskError>> + Send + 'static>> {
+        let max = self.max;
+        let failures = Arc::clone(&self.failures);
+        let prior = failures.fetch_add(1, Ordering::AcqRel);
+        if prior >= max {
+            return Box::pin(async move { Err(Self::limit_error(max, None)) });
+        }
+
+        let attempt = self.inner.spawn(ctx);
+        Box::pin(async move {
+            match attempt.await {
+                Ok(()) => {
+                    failures.store(0, Ordering::Release);
+                    Ok(())
+                }
+                Err(TaskError::Canceled) => {
+                    failures.fetch_sub(1, Ordering::AcqRel);
+                    Err(TaskError::Canceled)
+                }
+                Err(e) if !e.is_fatal() && prior + 1 >= max => {
+                    Err(Self::limit_error(max, Some(&e)))
+                }
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::AtomicBool;
+
+    use taskvisor::TaskFn;
+
+    #[tokio::test]
+    async fn last_allowed_failure_becomes_fatal_and_success_resets_count() {
+        let succeed = Arc::new(AtomicBool::new(false));
+        let task: TaskRef = TaskFn::arc("flaky", {
+            let succeed = Arc::clone(&succeed);
+            move |_ctx: CancellationToken| {
+                let ok = succeed.load(Ordering::Acquire);
+                async move {
+                    if ok {
+                        Ok(())
+                    } else {
+                        Err(TaskError::Fail {
+                            reason: "boom".into(),
+                        })
+                    }
+                }
+            }
+        });
+        let task = RestartLimited::wrap(task, RestartStrategy::always().with_max_attempts(2));
+
+        let first = task.spawn(CancellationToken::new()).await;
+        assert!(matches!(first, Err(TaskError::Fail { .. })));
+
+        succeed.store(true, Ordering::Release);
+        task.spawn(CancellationToken::new()).await.unwrap();
+        succeed.store(false, Ordering::Release);
+
+        let again = task.spawn(CancellationToken::new()).await;
+        assert!(matches!(again, Err(TaskError::Fail { .. })));
+        match task.spawn(CancellationToken::new()).await {
+            Err(TaskError::Fatal { reason }) => assert!(reason.contains(RESTART_LIMIT_REASON)),
+            other => panic!("expected fatal error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Cron-style scheduling on top of taskvisor restarts.
+//!
+//! Taskvisor only knows fixed restart intervals. A scheduled task is therefore submitted with
+//! "always restart" semantics and wrapped in [`Scheduled`], which waits for the next fire time
+//! at the start of every attempt.
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use taskvisor::{Task, TaskError, TaskRef};
+use time::UtcOffset;
+use tno_model::{ScheduleStrategy, ScheduleTimeZone};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+/// Delay from `now` until the next fire time of `schedule` (`None` if it never fires again).
+pub(crate) fn next_delay(schedule: &ScheduleStrategy, now: SystemTime) -> Option<Duration> {
+    let ScheduleStrategy::Cron {
+        expression,
+        timezone,
+    } = schedule;
+
+    let now = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let offset = match timezone {
+        ScheduleTimeZone::Utc => 0,
+        // Re-read for every fire time, so DST transitions are picked up.
+        ScheduleTimeZone::Local => UtcOffset::current_local_offset()
+            .unwrap_or(UtcOffset::UTC)
+            .whole_seconds(),
+    };
+    let next = expression.next_after(now.as_secs() as i64, offset)?;
+    Some(Duration::from_secs(next as u64).saturating_sub(now))
+}
+
+/// Task wrapper that delays every attempt until the next fire time of its schedule.
+///
+/// The per-run timeout is enforced here rather than by taskvisor, so that waiting for the fire time does not count against it.
+pub(crate) struct Scheduled {
+    inner: TaskRef,
+    schedule: ScheduleStrategy,
+    timeout: Option<Duration>,
+}
+
+impl Scheduled {
+    pub(crate) fn wrap(
+        inner: TaskRef,
+        schedule: ScheduleStrategy,
+        timeout: Option<Duration>,
+    ) -> TaskRef {
+        Arc::new(Self {
+            inner,
+            schedule,
+            timeout: timeout.filter(|t| !t.is_zero()),
+        })
+    }
+}
+
+impl Task for Scheduled {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn spawn(
+        &self,
+        ctx: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<(), TaskError>> + Send + 'static>> {
+        let inner = Arc::clone(&self.inner);
+        let delay = next_delay(&self.schedule, SystemTime::now());
+        let timeout = self.timeout;
+
+        Box::pin(async move {
+            let Some(delay) = delay else {
+                return Err(TaskError::Fatal {
+                    reason: "schedule has no future fire time".into(),
+                });
+            };
+            debug!(
+                task = inner.name(),
+                delay_ms = delay.as_millis() as u64,
+                "waiting for next scheduled run"
+            );
+            tokio::select! {
+                _ = ctx.cancelled() => return Err(TaskError::Canceled),
+                _ = tokio::time::sleep(delay) => {}
+            }
+
+            let Some(timeout) = timeout else {
+                return inner.spawn(ctx).await;
+            };
+            let child = ctx.child_token();
+            match tokio::time::timeout(timeout, inner.spawn(child.clone())).await {
+                Ok(res) => res,
+                Err(_) => {
+                    child.cancel();
+                    Err(TaskError::Timeout { timeout })
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_delay_counts_down_to_fire_time() {
+        let schedule = ScheduleStrategy::cron("30 * * * *").unwrap();
+        // 1970-01-01 00:10:15 UTC -> 00:30:00.
+        let now = UNIX_EPOCH + Duration::from_secs(10 * 60 + 15);
+        assert_eq!(
+            next_delay(&schedule, now),
+            Some(Duration::from_secs(19 * 60 + 45))
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Draining all tasks on shutdown.
+use std::{
+    collections::HashSet,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use taskvisor::{Event, EventKind};
+use tno_model::{TaskId, TaskStatus};
+use tracing::{info, instrument, warn};
+
+use super::{STATUS_RECHECK_INTERVAL, SupervisorApi};
+use crate::metrics::TerminalReason;
+
+/// Error recorded for tasks that did not stop within the shutdown grace period.
+const FORCE_KILLED_REASON: &str = "did not stop within shutdown grace period";
+
+/// Outcome of [`SupervisorApi::shutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Tasks that were not terminal when the shutdown started.
+    pub total: usize,
+    /// Tasks that stopped within the grace period.
+    pub stopped_gracefully: usize,
+    /// Tasks still running when the grace period expired.
+    pub force_killed: usize,
+    /// Time spent draining.
+    pub duration: Duration,
+}
+
+impl ShutdownReport {
+    /// Whether every task stopped within the grace period (no task was force-killed).
+    pub fn all_stopped_within_grace(&self) -> bool {
+        self.force_killed == 0
+    }
+}
+
+impl SupervisorApi {
+    /// Cancel every non-terminal task and wait up to `grace` for all of them to stop.
+    ///
+    /// A task counts as stopped gracefully once a terminal event (`TaskStopped`, `TaskFailed`,
+    /// `ActorExhausted`, `ActorDead` or `TaskRemoved`) is observed for it within `grace`,
+    /// or once the supervisor no longer reports it alive.
+    /// Tasks still running afterwards are reported as force-killed; their cancellation continues
+    /// in the background.
+    ///
+    /// Tasks are cancelled in submission order. The supervisor stops them one at a time, so a task
+    /// that ignores cancellation also delays the tasks submitted after it.
+    ///
+    /// All drained tasks are marked as [`TaskStatus::Canceled`] in state.
+    /// From the start of the shutdown new submissions are refused with [`crate::CoreError::Supervisor`]
+    /// and [`SupervisorApi::is_ready`] reports `false`, so nothing escapes the drain.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn shutdown(&self, grace: Duration) -> ShutdownReport {
+        let started = Instant::now();
+        self.shutting_down.store(true, Ordering::Release);
+        let deadline = tokio::time::Instant::now() + grace;
+        // Subscribe before cancelling so that no terminal event is missed.
+        let mut events = self.subscribe_events(false);
+        // Queued tasks never start; they are drained below like tasks that were not started yet.
+        for id in self.queued.cancel_all() {
+            self.router.release(id.as_str());
+        }
+
+        let mut active: Vec<_> = self
+            .state
+            .list_all()
+            .into_iter()
+            .filter(|t| !t.status.is_terminal())
+            .collect();
+        // The supervisor removes tasks one at a time, waiting for each to stop: cancel in submission order.
+        active.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.as_str().cmp(b.id.as_str()))
+        });
+        let drained: Vec<TaskId> = active.into_iter().map(|t| t.id).collect();
+        info!(
+            total = drained.len(),
+            grace_ms = grace.as_millis() as u64,
+            "shutting down tasks"
+        );
+
+        // Requests are handled in publish order; tasks not started yet are covered by the alive re-check below.
+        let registered = self.sup.list_tasks().await;
+        for id in drained
+            .iter()
+            .filter(|id| registered.iter().any(|n| n == id.as_str()))
+        {
+            if let Err(e) = self.sup.remove_task(id.as_str()) {
+                warn!(task_id = %id, error = %e, "cancel failed during shutdown");
+            }
+        }
+
+        let mut pending: HashSet<TaskId> = drained.iter().cloned().collect();
+        while !pending.is_empty() {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            let recheck = deadline.min(now + STATUS_RECHECK_INTERVAL);
+            match tokio::time::timeout_at(recheck, events.recv()).await {
+                Ok(Some(event)) => {
+                    if let Some(task) = terminal_task(&event) {
+                        pending.remove(&task);
+                    }
+                }
+                Ok(None) => break,
+                // Tasks that never started (or ended before the subscription) emit no terminal event.
+                Err(_) => {
+                    for id in pending.clone() {
+                        if !self.sup.is_alive(id.as_str()).await {
+                            pending.remove(&id);
+                        }
+                    }
+                }
+            }
+        }
+
+        for id in &drained {
+            let error = pending
+                .contains(id)
+                .then(|| FORCE_KILLED_REASON.to_string());
+            self.router
+                .metrics()
+                .record_terminal(&self.state.runner(id), TerminalReason::Canceled);
+            self.state.update_status(id, TaskStatus::Canceled, error);
+        }
+
+        let report = ShutdownReport {
+            total: drained.len(),
+            stopped_gracefully: drained.len() - pending.len(),
+            force_killed: pending.len(),
+            duration: started.elapsed(),
+        };
+        if !report.all_stopped_within_grace() {
+            warn!(
+                ?report,
+                "some tasks did not stop within shutdown grace period"
+            );
+        } else {
+            info!(?report, "all tasks stopped within shutdown grace period");
+        }
+        report
+    }
+}
+
+/// Task ended by a terminal event, if any.
+fn terminal_task(event: &Event) -> Option<TaskId> {
+    match event.kind {
+        EventKind::TaskStopped
+        | EventKind::TaskFailed
+        | EventKind::ActorExhausted
+        | EventKind::ActorDead
+        | EventKind::TaskRemoved => event.task.as_deref().map(TaskId::from),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use taskvisor::{ControllerConfig, SupervisorConfig, TaskError, TaskFn, TaskRef};
+    use tno_model::{AdmissionStrategy, BackoffStrategy, JitterStrategy, RestartStrategy};
+    use tokio_util::sync::CancellationToken;
+
+    use crate::{policy::TaskPolicy, router::RunnerRouter};
+
+    fn policy(slot: &str) -> TaskPolicy {
+        TaskPolicy::new(
+            slot.to_string(),
+            60_000,
+            RestartStrategy::Never,
+            BackoffStrategy {
+                jitter: JitterStrategy::Equal,
+                first_ms: 1_000,
+                max_ms: 5_000,
+                factor: 2.0,
+            },
+            AdmissionStrategy::DropIfRunning,
+        )
+    }
+
+    #[tokio::test]
+    async fn shutdown_reports_graceful_and_force_killed_tasks() {
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            RunnerRouter::new(),
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let quick = |name: &'static str| -> TaskRef {
+            TaskFn::arc(name, |ctx: CancellationToken| async move {
+                ctx.cancelled().await;
+                Ok::<(), TaskError>(())
+            })
+        };
+        // Ignores cancellation.
+        let stubborn: TaskRef = TaskFn::arc("stubborn", |_ctx: CancellationToken| async move {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok::<(), TaskError>(())
+        });
+
+        let mut ids = Vec::new();
+        for (task, slot) in [
+            (quick("quick-1"), "quick-1"),
+            (quick("quick-2"), "quick-2"),
+            (stubborn, "stubborn"),
+        ] {
+            let id = api
+                .submit_with_task(task, &policy(slot))
+                .await
+                .unwrap()
+                .task_id;
+            api.wait_for_status(&id, TaskStatus::Running, Duration::from_secs(5))
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        let grace = Duration::from_millis(300);
+        let report = api.shutdown(grace).await;
+        assert_eq!(report.total, 3);
+        assert_eq!(report.stopped_gracefully, 2);
+        assert_eq!(report.force_killed, 1);
+        assert!(!report.all_stopped_within_grace());
+        assert!(report.duration >= grace);
+
+        assert!(!api.is_ready());
+        let late = api.submit_with_task(quick("late"), &policy("late")).await;
+        assert!(matches!(late, Err(crate::CoreError::Supervisor(_))));
+
+        for id in &ids {
+            assert_eq!(api.get_task(id).unwrap().status, TaskStatus::Canceled);
+        }
+        let stubborn = api.get_task(&ids[2]).unwrap();
+        assert_eq!(stubborn.error.as_deref(), Some(FORCE_KILLED_REASON));
+    }
+}
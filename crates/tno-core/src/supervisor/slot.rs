@@ -0,0 +1,172 @@
+//! Slot admission across run IDs.
+//!
+//! The controller keys its slots by task name, while every task built from a spec gets its own run ID,
+//! so submissions to the same [`tno_model::CreateSpec::slot`] never meet in a controller slot.
+//! Admission is therefore decided from the tasks that occupy the slot in task state:
+//! replaced tasks are cancelled before the new one is submitted, and queued tasks are held back
+//! until the tasks ahead of them have finished.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+};
+
+use taskvisor::{ControllerSpec, Supervisor};
+use tno_model::{TaskId, TaskStatus};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use super::{STATUS_RECHECK_INTERVAL, SupervisorApi};
+use crate::{events::EventStream, metrics::TerminalReason, state::TaskState};
+
+/// Error recorded for tasks cancelled in favor of a newer submission to their slot.
+const REPLACED_REASON: &str = "replaced by a newer submission to the slot";
+
+/// Tasks held back until their slot is free, keyed by task ID.
+#[derive(Clone, Default)]
+pub(crate) struct QueuedTasks {
+    inner: Arc<Mutex<HashMap<TaskId, CancellationToken>>>,
+}
+
+impl QueuedTasks {
+    fn insert(&self, id: TaskId) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.inner.lock().unwrap().insert(id, token.clone());
+        token
+    }
+
+    /// Take a task out of the queue to submit it; `false` if it was cancelled in the meantime.
+    fn take(&self, id: &TaskId) -> bool {
+        self.inner.lock().unwrap().remove(id).is_some()
+    }
+
+    /// Cancel a queued task. Returns `false` if the task is not (or no longer) queued.
+    pub(crate) fn cancel(&self, id: &TaskId) -> bool {
+        let token = self.inner.lock().unwrap().remove(id);
+        token.map(|token| token.cancel()).is_some()
+    }
+
+    /// Cancel all queued tasks and return their IDs.
+    pub(crate) fn cancel_all(&self) -> Vec<TaskId> {
+        let drained: Vec<_> = self.inner.lock().unwrap().drain().collect();
+        drained
+            .into_iter()
+            .map(|(id, token)| {
+                token.cancel();
+                id
+            })
+            .collect()
+    }
+}
+
+/// Tasks of `slot` that occupy it, in submission order.
+///
+/// A task occupies its slot while pending or running, and between restart attempts as long as its actor is alive.
+async fn occupants(sup: &Supervisor, state: &TaskState, slot: &str) -> Vec<TaskId> {
+    let mut occupants = Vec::new();
+    for (id, status) in state.unfinished_in_slot(slot) {
+        if !status.is_terminal() || sup.is_alive(id.as_str()).await {
+            occupants.push(id);
+        }
+    }
+    occupants
+}
+
+/// Wait until none of `ahead` occupies `slot`.
+///
+/// Returns `false` if the supervisor is gone or its event stream closed before.
+async fn wait_for_slot(
+    sup: &Weak<Supervisor>,
+    state: &TaskState,
+    mut events: EventStream,
+    slot: &str,
+    ahead: &[TaskId],
+) -> bool {
+    loop {
+        let Some(sup) = sup.upgrade() else {
+            return false;
+        };
+        let occupied = occupants(&sup, state, slot).await;
+        if !occupied.iter().any(|id| ahead.contains(id)) {
+            return true;
+        }
+        drop(sup);
+        if let Ok(None) = tokio::time::timeout(STATUS_RECHECK_INTERVAL, events.recv()).await {
+            return false;
+        }
+    }
+}
+
+impl SupervisorApi {
+    /// Tasks occupying `slot`, in submission order.
+    pub(super) async fn slot_occupants(&self, slot: &str) -> Vec<TaskId> {
+        occupants(&self.sup, &self.state, slot).await
+    }
+
+    /// Cancel the tasks occupying a slot in favor of a new submission.
+    ///
+    /// Running tasks are cancelled as in [`SupervisorApi::cancel_task`]; queued ones never start.
+    /// They are marked as [`TaskStatus::Canceled`] and recorded as [`TerminalReason::Replaced`].
+    pub(super) async fn replace_occupants(&self, occupants: &[TaskId]) {
+        for id in occupants {
+            let replaced = if self.queued.cancel(id) {
+                self.router.release(id.as_str());
+                true
+            } else {
+                self.sup.cancel(id.as_str()).await.unwrap_or_else(|e| {
+                    warn!(task_id = %id, error = %e, "failed to cancel replaced task");
+                    false
+                })
+            };
+            if !replaced {
+                debug!(task_id = %id, "replaced task finished before it was cancelled");
+                continue;
+            }
+            self.router
+                .metrics()
+                .record_terminal(&self.state.runner(id), TerminalReason::Replaced);
+            self.state
+                .update_status(id, TaskStatus::Canceled, Some(REPLACED_REASON.to_string()));
+        }
+    }
+
+    /// Submit an already registered task to the controller once none of `ahead` occupies `slot`.
+    ///
+    /// The task stays [`TaskStatus::Pending`] while queued, so later submissions to the slot queue behind it.
+    /// Cancelling it (see [`QueuedTasks::cancel`]) drops it without ever submitting it.
+    pub(super) fn enqueue(
+        &self,
+        task_id: TaskId,
+        slot: String,
+        spec: ControllerSpec,
+        ahead: Vec<TaskId>,
+    ) {
+        let cancelled = self.queued.insert(task_id.clone());
+        let queued = self.queued.clone();
+        let sup = Arc::downgrade(&self.sup);
+        let state = self.state.clone();
+        let leases = self.router.leases().clone();
+        // Subscribe before the first check so that no finished task is missed in between.
+        let events = self.subscribe_events(false);
+        tokio::spawn(async move {
+            let free = tokio::select! {
+                _ = cancelled.cancelled() => return,
+                free = wait_for_slot(&sup, &state, events, &slot, &ahead) => free,
+            };
+            if !queued.take(&task_id) {
+                return;
+            }
+            let submitted = match sup.upgrade() {
+                Some(sup) if free => {
+                    debug!(task_id = %task_id, "slot is free; submitting queued task");
+                    sup.submit(spec).await.map_err(|e| e.to_string())
+                }
+                _ => Err("supervisor stopped while the task was queued".to_string()),
+            };
+            if let Err(reason) = submitted {
+                warn!(task_id = %task_id, error = %reason, "failed to submit queued task");
+                state.update_status(&task_id, TaskStatus::Failed, Some(reason));
+                leases.release(task_id.as_str());
+            }
+        });
+    }
+}
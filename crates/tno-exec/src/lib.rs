@@ -5,8 +5,8 @@ mod utils;
 pub use utils::*;
 
 mod metrics;
-pub use metrics::task_error_to_outcome;
 pub use metrics::{RUNNER_TYPE_CONTAINER, RUNNER_TYPE_SUBPROCESS, RUNNER_TYPE_WASM};
+pub use metrics::{classify_attempt_error, classify_task_error, task_error_to_outcome};
 
 #[cfg(feature = "subprocess")]
 pub mod subprocess;
@@ -1,5 +1,7 @@
 //! Metrics for exec runner.
 
+use std::time::Duration;
+
 use taskvisor::TaskError;
 use tno_core::TaskOutcome;
 
@@ -14,13 +16,35 @@ pub const RUNNER_TYPE_CONTAINER: &str = "container";
 
 /// Convert TaskError to TaskOutcome for metrics.
 pub fn task_error_to_outcome(error: &TaskError) -> TaskOutcome {
+    classify_task_error(error, false)
+}
+
+/// Convert TaskError to TaskOutcome, honoring `CreateSpec::cancel_is_success`.
+pub fn classify_task_error(error: &TaskError, cancel_is_success: bool) -> TaskOutcome {
     match error {
         TaskError::Timeout { .. } => TaskOutcome::Timeout,
+        TaskError::Canceled if cancel_is_success => TaskOutcome::Success,
         TaskError::Canceled => TaskOutcome::Canceled,
         _ => TaskOutcome::Failure,
     }
 }
 
+/// Convert the error of an attempt that ran for `elapsed` to TaskOutcome, honoring `CreateSpec::cancel_is_success`.
+///
+/// The supervisor enforces `timeout` by cancelling the task, so a runner sees a timeout as [`TaskError::Canceled`];
+/// a cancellation once `timeout` has elapsed counts as [`TaskOutcome::Timeout`], never as a success.
+pub fn classify_attempt_error(
+    error: &TaskError,
+    cancel_is_success: bool,
+    elapsed: Duration,
+    timeout: Duration,
+) -> TaskOutcome {
+    match error {
+        TaskError::Canceled if elapsed >= timeout => TaskOutcome::Timeout,
+        _ => classify_task_error(error, cancel_is_success),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,6 +55,16 @@ mod tests {
         assert_eq!(task_error_to_outcome(&err), TaskOutcome::Canceled);
     }
 
+    #[test]
+    fn canceled_maps_to_success_when_requested() {
+        let err = TaskError::Canceled;
+        assert_eq!(classify_task_error(&err, true), TaskOutcome::Success);
+        let err = TaskError::Fail {
+            reason: "test".into(),
+        };
+        assert_eq!(classify_task_error(&err, true), TaskOutcome::Failure);
+    }
+
     #[test]
     fn fail_maps_to_failure() {
         let err = TaskError::Fail {
@@ -46,4 +80,48 @@ mod tests {
         };
         assert_eq!(task_error_to_outcome(&err), TaskOutcome::Failure);
     }
+
+    #[test]
+    fn timeout_maps_to_timeout() {
+        let err = TaskError::Timeout {
+            timeout: Duration::from_millis(200),
+        };
+        assert_eq!(task_error_to_outcome(&err), TaskOutcome::Timeout);
+        assert_eq!(classify_task_error(&err, true), TaskOutcome::Timeout);
+    }
+
+    #[test]
+    fn cancel_after_timeout_elapsed_maps_to_timeout() {
+        let timeout = Duration::from_millis(200);
+        let classify = |err: &TaskError, cancel_is_success, elapsed_ms| {
+            classify_attempt_error(
+                err,
+                cancel_is_success,
+                Duration::from_millis(elapsed_ms),
+                timeout,
+            )
+        };
+
+        assert_eq!(
+            classify(&TaskError::Canceled, false, 200),
+            TaskOutcome::Timeout
+        );
+        assert_eq!(
+            classify(&TaskError::Canceled, true, 250),
+            TaskOutcome::Timeout
+        );
+        assert_eq!(
+            classify(&TaskError::Canceled, false, 50),
+            TaskOutcome::Canceled
+        );
+        assert_eq!(
+            classify(&TaskError::Canceled, true, 50),
+            TaskOutcome::Success
+        );
+
+        let fail = TaskError::Fail {
+            reason: "exit code 1".into(),
+        };
+        assert_eq!(classify(&fail, false, 250), TaskOutcome::Failure);
+    }
 }
@@ -1,25 +1,107 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tno_core::TaskOutcome;
 use tokio::process::Command;
 use tracing::trace;
 
+use crate::ExecError;
 use crate::ExecError::InvalidRunnerConfig;
+use crate::subprocess::budget::OutputBudget;
+use crate::subprocess::capture::MAX_OUTPUT_CAPTURE_BYTES;
+use crate::subprocess::command::CommandPolicy;
+use crate::subprocess::env::EnvPolicy;
 use crate::subprocess::logger::LogConfig;
-use crate::utils::{CgroupLimits, RlimitConfig, SecurityConfig};
-use crate::utils::{attach_cgroup, attach_rlimits, attach_security};
+use crate::subprocess::redact::ArgRedaction;
+use crate::subprocess::secrets::{SecretFile, is_valid_secret_name};
+use crate::subprocess::task::SubprocessTaskConfig;
+use crate::utils::{
+    CgroupLimits, DEFAULT_CGROUP_ROOT, MAX_CGROUP_ATTACH_RETRIES, RlimitConfig, SecurityConfig,
+};
+use crate::utils::{attach_cgroup, attach_rlimits, attach_security, warm_up_security};
+
+/// Custom setup invoked in the parent right before a subprocess is spawned.
+///
+/// Receives the resolved task configuration; returning an error aborts the task (fatal) without spawning.
+pub type PreSpawnHook = Arc<dyn Fn(&SubprocessTaskConfig) -> Result<(), ExecError> + Send + Sync>;
+
+/// Custom cleanup/notification invoked after a spawned subprocess completes.
+///
+/// Receives the task configuration and the classified outcome (success, failure, cancel).
+pub type PostRunHook = Arc<dyn Fn(&SubprocessTaskConfig, &TaskOutcome) + Send + Sync>;
 
 /// Low-level OS/kernel configuration for subprocess execution.
 ///
 /// Controls resource limits, security policies, and isolation mechanisms.
 /// All fields are optional - if not specified, the subprocess inherits parent process settings.
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct SubprocessBackendConfig {
     /// POSIX rlimit-based resource limits.
     rlimits: Option<RlimitConfig>,
     /// Linux cgroup v2 resource limits.
     cgroups: Option<CgroupLimits>,
+    /// cgroup v2 hierarchy root (`None` = [`DEFAULT_CGROUP_ROOT`]).
+    cgroup_root: Option<PathBuf>,
     /// Security hardening.
     security: Option<SecurityConfig>,
+    /// Check at registration that `security` is actually enforced.
+    security_warm_up: bool,
     /// Subprocess output logging configuration.
     logger: LogConfig,
+    /// Byte budget for buffered subprocess output (`None` = unbounded).
+    output_budget: Option<OutputBudget>,
+    /// Per-stream byte limit for output captured into task state (`None` = no capture).
+    output_capture: Option<usize>,
+    /// Store captured output gzip-compressed in task state.
+    compress_captured_output: bool,
+    /// Masking rules for subprocess arguments in logs.
+    arg_redaction: ArgRedaction,
+    /// Grace period between `SIGTERM` and `SIGKILL` on cancellation.
+    ///
+    /// `None` kills the subprocess immediately (`SIGKILL`).
+    graceful_shutdown: Option<Duration>,
+    /// Custom setup run before each spawn.
+    pre_spawn: Option<PreSpawnHook>,
+    /// Custom cleanup/notification run after each completed subprocess.
+    post_run: Option<PostRunHook>,
+    /// Skip the working directory check at build time.
+    skip_cwd_check: bool,
+    /// Restrictions on executable commands.
+    command_policy: CommandPolicy,
+    /// Which inherited environment variables reach the subprocess.
+    env_policy: EnvPolicy,
+    /// Secrets passed to the subprocess as files.
+    secret_files: Vec<SecretFile>,
+    /// Base directory for secret files (`None` = `/dev/shm` if present, else the temp dir).
+    secret_dir: Option<PathBuf>,
+}
+
+impl fmt::Debug for SubprocessBackendConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubprocessBackendConfig")
+            .field("rlimits", &self.rlimits)
+            .field("cgroups", &self.cgroups)
+            .field("cgroup_root", &self.cgroup_root)
+            .field("security", &self.security)
+            .field("security_warm_up", &self.security_warm_up)
+            .field("logger", &self.logger)
+            .field("output_budget", &self.output_budget)
+            .field("output_capture", &self.output_capture)
+            .field("compress_captured_output", &self.compress_captured_output)
+            .field("arg_redaction", &self.arg_redaction)
+            .field("graceful_shutdown", &self.graceful_shutdown)
+            .field("pre_spawn", &self.pre_spawn.is_some())
+            .field("post_run", &self.post_run.is_some())
+            .field("skip_cwd_check", &self.skip_cwd_check)
+            .field("command_policy", &self.command_policy)
+            .field("env_policy", &self.env_policy)
+            .field("secret_files", &self.secret_files)
+            .field("secret_dir", &self.secret_dir)
+            .finish()
+    }
 }
 
 impl SubprocessBackendConfig {
@@ -40,23 +122,242 @@ impl SubprocessBackendConfig {
         self
     }
 
+    /// Set the cgroup v2 hierarchy root (default: `/sys/fs/cgroup`).
+    ///
+    /// Useful for rootless setups with a delegated subtree, or for tests against a fake hierarchy.
+    pub fn with_cgroup_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.cgroup_root = Some(root.into());
+        self
+    }
+
     /// Set security hardening.
     pub fn with_security(mut self, security: SecurityConfig) -> Self {
         self.security = Some(security);
         self
     }
 
+    /// Check at registration that the security config is actually enforced.
+    ///
+    /// Registration spawns a probe process under the security config and fails if the state it reports
+    /// (capabilities, `no_new_privs`, uid/gid) differs from the config; see [`crate::warm_up_security`].
+    pub fn with_security_warm_up(mut self) -> Self {
+        self.security_warm_up = true;
+        self
+    }
+
     /// Set logger configuration.
     pub fn with_logger(mut self, config: LogConfig) -> Self {
         self.logger = config;
         self
     }
 
+    /// Bound the memory used by subprocess output that has been read but not yet logged.
+    ///
+    /// Pass clones of the same [`OutputBudget`] to several runners to share one budget between them.
+    pub fn with_output_budget(mut self, budget: OutputBudget) -> Self {
+        self.output_budget = Some(budget);
+        self
+    }
+
+    /// Keep up to `max_bytes` of each output stream (stdout, stderr) in memory and expose it through `TaskInfo`.
+    ///
+    /// Output is still logged as usual. Bytes past the limit are dropped and replaced by a truncation marker,
+    /// while the pipe keeps being drained. The limit cannot exceed [`MAX_OUTPUT_CAPTURE_BYTES`].
+    pub fn with_output_capture(mut self, max_bytes: usize) -> Self {
+        self.output_capture = Some(max_bytes);
+        self
+    }
+
+    /// Store captured output gzip-compressed in task state (see [`Self::with_output_capture`]).
+    ///
+    /// Saves memory for tasks with large, repetitive output; it is decompressed when read through the API.
+    pub fn with_compressed_output_capture(mut self) -> Self {
+        self.compress_captured_output = true;
+        self
+    }
+
+    /// Set argument redaction rules for logs.
+    ///
+    /// The subprocess still receives the original arguments.
+    pub fn with_arg_redaction(mut self, redaction: ArgRedaction) -> Self {
+        self.arg_redaction = redaction;
+        self
+    }
+
+    /// Enable graceful shutdown on cancellation.
+    ///
+    /// Cancellation first sends `SIGTERM`, waits up to `grace` for the subprocess to exit and only then sends `SIGKILL`.
+    /// Keep `grace` below the supervisor grace period, otherwise the supervisor may give up on the task first.
+    /// On non-Unix platforms the subprocess is always killed immediately.
+    pub fn with_graceful_shutdown(mut self, grace: Duration) -> Self {
+        self.graceful_shutdown = Some(grace);
+        self
+    }
+
+    /// Run custom setup before every spawn (e.g. fetch a secret, prepare a mount).
+    ///
+    /// The hook runs in the parent process on the async runtime, after the command is fully configured
+    /// and immediately before `spawn`; keep it short. An error fails the task as fatal and the subprocess is never started.
+    /// Unlike `pre_exec`, it runs before `fork`, so no async-signal-safety restrictions apply.
+    pub fn with_pre_spawn<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SubprocessTaskConfig) -> Result<(), ExecError> + Send + Sync + 'static,
+    {
+        self.pre_spawn = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run custom cleanup or notification logic after every subprocess completes.
+    ///
+    /// The hook fires for success, failure and cancellation alike, after the task cgroup has been released.
+    /// It runs on the blocking thread pool and is not awaited, so a slow hook never delays the runner;
+    /// a panicking hook is logged and otherwise ignored.
+    pub fn with_post_run<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SubprocessTaskConfig, &TaskOutcome) + Send + Sync + 'static,
+    {
+        self.post_run = Some(Arc::new(hook));
+        self
+    }
+
+    /// Control which inherited environment variables reach the subprocess (default: [`EnvPolicy::Inherit`]).
+    ///
+    /// Use [`EnvPolicy::Clear`] or [`EnvPolicy::allow`] for untrusted tasks, so agent secrets are not leaked into them.
+    pub fn with_env_policy(mut self, policy: EnvPolicy) -> Self {
+        self.env_policy = policy;
+        self
+    }
+
+    /// Pass a secret to every subprocess as a file instead of an environment variable.
+    ///
+    /// Before each spawn `contents` is written to a private file (mode `0600`) whose path is exposed as `<NAME>_FILE`;
+    /// the file is removed when the attempt ends. `name` must match `[A-Z0-9_]+` and not start with a digit.
+    /// Files live under `/dev/shm` (memory-backed) when available, see [`SubprocessBackendConfig::with_secret_dir`].
+    pub fn with_secret_file(
+        mut self,
+        name: impl Into<String>,
+        contents: impl Into<Vec<u8>>,
+    ) -> Self {
+        let contents: Vec<u8> = contents.into();
+        self.secret_files.push(SecretFile {
+            name: name.into(),
+            contents: contents.into(),
+        });
+        self
+    }
+
+    /// Set the base directory for secret files (default: `/dev/shm` if present, else the temp dir).
+    ///
+    /// Prefer a memory-backed (tmpfs) directory so secrets never reach a disk.
+    pub fn with_secret_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.secret_dir = Some(dir.into());
+        self
+    }
+
+    /// Get secrets passed as files.
+    pub(crate) fn secret_files(&self) -> &[SecretFile] {
+        &self.secret_files
+    }
+
+    /// Get base directory for secret files (if configured).
+    pub(crate) fn secret_dir(&self) -> Option<&Path> {
+        self.secret_dir.as_deref()
+    }
+
+    /// Get environment inheritance policy.
+    pub(crate) fn env_policy(&self) -> &EnvPolicy {
+        &self.env_policy
+    }
+
+    /// Accept only absolute command paths, so commands are never resolved through the inherited `PATH`.
+    ///
+    /// Tasks with a relative command fail at build time.
+    pub fn with_require_absolute_command(mut self, require: bool) -> Self {
+        self.command_policy.require_absolute = require;
+        self
+    }
+
+    /// Allow only the listed commands (matched exactly against the task command).
+    ///
+    /// Tasks with any other command fail at build time and are never spawned.
+    /// Combine with [`SubprocessBackendConfig::with_require_absolute_command`] to pin commands to exact binaries.
+    pub fn with_command_allowlist<I, S>(mut self, commands: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let commands: BTreeSet<String> = commands.into_iter().map(Into::into).collect();
+        self.command_policy.allowlist = Some(Arc::new(commands));
+        self
+    }
+
+    /// Get command restrictions.
+    pub(crate) fn command_policy(&self) -> &CommandPolicy {
+        &self.command_policy
+    }
+
+    /// Do not check the task working directory when building a task.
+    ///
+    /// By default a `cwd` that does not exist, is not a directory or cannot be entered fails the build.
+    /// Disable the check when working directories live on slow (e.g. network) mounts or appear only
+    /// right before the spawn (e.g. created by a pre-spawn hook); a bad `cwd` then fails at spawn time.
+    pub fn without_cwd_check(mut self) -> Self {
+        self.skip_cwd_check = true;
+        self
+    }
+
+    /// Whether the task working directory is checked at build time.
+    pub(crate) fn checks_cwd(&self) -> bool {
+        !self.skip_cwd_check
+    }
+
+    /// Get post-run hook (if configured).
+    pub(crate) fn post_run(&self) -> Option<&PostRunHook> {
+        self.post_run.as_ref()
+    }
+
+    /// Get pre-spawn hook (if configured).
+    pub(crate) fn pre_spawn(&self) -> Option<&PreSpawnHook> {
+        self.pre_spawn.as_ref()
+    }
+
     // Get log configuration.
     pub(crate) fn log_config(&self) -> &LogConfig {
         &self.logger
     }
 
+    /// Get output byte budget (if configured).
+    pub(crate) fn output_budget(&self) -> Option<&OutputBudget> {
+        self.output_budget.as_ref()
+    }
+
+    /// Get per-stream output capture limit (if configured).
+    pub(crate) fn output_capture(&self) -> Option<usize> {
+        self.output_capture
+    }
+
+    /// Whether captured output is stored compressed.
+    pub(crate) fn compress_captured_output(&self) -> bool {
+        self.compress_captured_output
+    }
+
+    /// Get argument redaction rules.
+    pub(crate) fn arg_redaction(&self) -> &ArgRedaction {
+        &self.arg_redaction
+    }
+
+    /// Get graceful shutdown period (if configured).
+    pub(crate) fn graceful_shutdown(&self) -> Option<Duration> {
+        self.graceful_shutdown
+    }
+
+    /// Get cgroup v2 hierarchy root.
+    pub(crate) fn cgroup_root(&self) -> &Path {
+        self.cgroup_root
+            .as_deref()
+            .unwrap_or(Path::new(DEFAULT_CGROUP_ROOT))
+    }
+
     /// Check if any backend features are configured.
     pub(crate) fn is_empty(&self) -> bool {
         self.rlimits.is_none() && self.cgroups.is_none() && self.security.is_none()
@@ -75,6 +376,18 @@ impl SubprocessBackendConfig {
             {
                 return Err(InvalidRunnerConfig("cgroups.pids cannot be zero".into()));
             }
+            if cgroups.attach_retries() > MAX_CGROUP_ATTACH_RETRIES {
+                return Err(InvalidRunnerConfig(format!(
+                    "cgroups.attach_retries cannot exceed {MAX_CGROUP_ATTACH_RETRIES}"
+                )));
+            }
+            if let Some(weight) = cgroups.cpu_weight
+                && !(1..=10_000).contains(&weight)
+            {
+                return Err(InvalidRunnerConfig(
+                    "cgroups.cpu_weight must be in range 1..=10000".into(),
+                ));
+            }
         }
         if let Some(rlimits) = &self.rlimits
             && let Some(fsize) = rlimits.max_file_size_bytes
@@ -84,14 +397,164 @@ impl SubprocessBackendConfig {
                 "rlimits.max_file_size_bytes cannot be zero".into(),
             ));
         }
+        if let Some(rlimits) = &self.rlimits
+            && rlimits.max_cpu_seconds == Some(0)
+        {
+            return Err(InvalidRunnerConfig(
+                "rlimits.max_cpu_seconds cannot be zero".into(),
+            ));
+        }
+        if let Some(rlimits) = &self.rlimits
+            && let Some(core) = &rlimits.core_dump
+        {
+            if rlimits.disable_core_dumps {
+                return Err(InvalidRunnerConfig(
+                    "rlimits.core_dump conflicts with rlimits.disable_core_dumps".into(),
+                ));
+            }
+            if !core.dir.is_absolute() {
+                return Err(InvalidRunnerConfig(
+                    "rlimits.core_dump.dir must be an absolute path".into(),
+                ));
+            }
+            if core.max_size_bytes == Some(0) {
+                return Err(InvalidRunnerConfig(
+                    "rlimits.core_dump.max_size_bytes cannot be zero".into(),
+                ));
+            }
+            #[cfg(target_os = "linux")]
+            core.check_host_core_pattern()
+                .map_err(|e| InvalidRunnerConfig(format!("rlimits.core_dump: {e}")))?;
+        }
+        if let Some(root) = &self.cgroup_root
+            && !root.is_absolute()
+        {
+            return Err(InvalidRunnerConfig(
+                "cgroup_root must be an absolute path".into(),
+            ));
+        }
+        if let Some(ns) = self.security.as_ref().and_then(|s| s.namespace.as_ref())
+            && let Some(root) = &ns.root_dir
+        {
+            if !ns.new_mount_ns {
+                return Err(InvalidRunnerConfig(
+                    "security.namespace.root_dir requires new_mount_ns".into(),
+                ));
+            }
+            if !root.is_absolute() {
+                return Err(InvalidRunnerConfig(
+                    "security.namespace.root_dir must be an absolute path".into(),
+                ));
+            }
+        }
+        if let Some(ruleset) = self.security.as_ref().and_then(|s| s.landlock.as_ref())
+            && ruleset
+                .read_only
+                .iter()
+                .chain(&ruleset.read_write)
+                .any(|p| !p.is_absolute())
+        {
+            return Err(InvalidRunnerConfig(
+                "security.landlock paths must be absolute".into(),
+            ));
+        }
+        if let Some(allowlist) = &self.command_policy.allowlist {
+            if allowlist.iter().any(|c| c.trim().is_empty()) {
+                return Err(InvalidRunnerConfig(
+                    "command allowlist cannot contain empty commands".into(),
+                ));
+            }
+            if self.command_policy.require_absolute
+                && allowlist.iter().any(|c| !Path::new(c).is_absolute())
+            {
+                return Err(InvalidRunnerConfig(
+                    "command allowlist must contain absolute paths when absolute commands are required"
+                        .into(),
+                ));
+            }
+        }
+        let mut secret_names = BTreeSet::new();
+        for secret in &self.secret_files {
+            if !is_valid_secret_name(&secret.name) {
+                return Err(InvalidRunnerConfig(format!(
+                    "secret file name {:?} must match [A-Z0-9_]+ and not start with a digit",
+                    secret.name
+                )));
+            }
+            if !secret_names.insert(secret.name.as_str()) {
+                return Err(InvalidRunnerConfig(format!(
+                    "duplicate secret file name {:?}",
+                    secret.name
+                )));
+            }
+        }
+        if let Some(dir) = &self.secret_dir
+            && !dir.is_absolute()
+        {
+            return Err(InvalidRunnerConfig(
+                "secret_dir must be an absolute path".into(),
+            ));
+        }
+        if self.graceful_shutdown == Some(Duration::ZERO) {
+            return Err(InvalidRunnerConfig(
+                "graceful_shutdown cannot be zero".into(),
+            ));
+        }
+        if self
+            .arg_redaction
+            .flags()
+            .iter()
+            .any(|f| f.trim().is_empty())
+        {
+            return Err(InvalidRunnerConfig(
+                "arg_redaction flags cannot be empty".into(),
+            ));
+        }
+        if self
+            .output_budget
+            .as_ref()
+            .is_some_and(|b| b.capacity() == 0)
+        {
+            return Err(InvalidRunnerConfig("output_budget cannot be zero".into()));
+        }
+        if let Some(limit) = self.output_capture {
+            if limit == 0 {
+                return Err(InvalidRunnerConfig("output_capture cannot be zero".into()));
+            }
+            if limit > MAX_OUTPUT_CAPTURE_BYTES {
+                return Err(InvalidRunnerConfig(format!(
+                    "output_capture cannot exceed {MAX_OUTPUT_CAPTURE_BYTES} bytes"
+                )));
+            }
+        }
         if self.logger.max_line_length == 0 {
             return Err(InvalidRunnerConfig(
                 "log_config.max_line_length cannot be zero".into(),
             ));
         }
+        if let Some(files) = &self.logger.task_files {
+            if !files.dir.is_absolute() {
+                return Err(InvalidRunnerConfig(
+                    "log_config.task_files.dir must be an absolute path".into(),
+                ));
+            }
+            if files.max_bytes == 0 {
+                return Err(InvalidRunnerConfig(
+                    "log_config.task_files.max_bytes cannot be zero".into(),
+                ));
+            }
+        }
         Ok(())
     }
 
+    /// Run the security warm-up, if enabled (see [`SubprocessBackendConfig::with_security_warm_up`]).
+    pub(crate) fn warm_up(&self) -> Result<(), crate::ExecError> {
+        match &self.security {
+            Some(security) if self.security_warm_up => warm_up_security(security),
+            _ => Ok(()),
+        }
+    }
+
     /// Check if cgroup limits are configured.
     pub(crate) fn has_cgroups(&self) -> bool {
         self.cgroups.is_some()
@@ -124,7 +587,7 @@ impl SubprocessBackendConfig {
                 "subprocess backend: attaching cgroup limits: {:?} (group={})",
                 cgroups, cgroup_name
             );
-            attach_cgroup(cmd, cgroup_name, cgroups)?;
+            attach_cgroup(cmd, self.cgroup_root(), cgroup_name, cgroups)?;
         }
         if let Some(security) = &self.security {
             trace!(
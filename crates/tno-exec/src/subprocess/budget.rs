@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Byte budget for subprocess output that has been read but not yet logged.
+///
+/// Clones share the same budget, so a single instance can bound output memory across all tasks
+/// of one or several runners.
+/// When the budget is exhausted, output streams stop reading from the subprocess pipes until logged lines release their bytes;
+/// once the pipe buffer is full, the subprocess blocks on write (backpressure).
+///
+/// Lines longer than the remaining budget are logged in pieces instead of being buffered whole.
+#[derive(Debug, Clone)]
+pub struct OutputBudget {
+    permits: Arc<Semaphore>,
+    capacity: usize,
+}
+
+impl OutputBudget {
+    /// Create a budget of `max_bytes` (clamped to the maximum supported by the underlying semaphore).
+    pub fn new(max_bytes: usize) -> Self {
+        let capacity = max_bytes.min(Semaphore::MAX_PERMITS);
+        Self {
+            permits: Arc::new(Semaphore::new(capacity)),
+            capacity,
+        }
+    }
+
+    /// Total budget in bytes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Bytes currently held by output streams.
+    pub fn in_flight(&self) -> usize {
+        self.capacity - self.permits.available_permits()
+    }
+
+    /// Largest amount a single acquisition may request.
+    pub(crate) fn max_chunk(&self) -> usize {
+        self.capacity.min(u32::MAX as usize)
+    }
+
+    /// Reserve `bytes` without waiting.
+    pub(crate) fn try_acquire(&self, bytes: usize) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.permits)
+            .try_acquire_many_owned(bytes as u32)
+            .ok()
+    }
+
+    /// Reserve `bytes`, waiting until enough budget is released.
+    pub(crate) async fn acquire(&self, bytes: usize) -> OwnedSemaphorePermit {
+        Arc::clone(&self.permits)
+            .acquire_many_owned(bytes as u32)
+            .await
+            .expect("output budget semaphore is never closed")
+    }
+}
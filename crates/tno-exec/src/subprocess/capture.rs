@@ -0,0 +1,68 @@
+/// Hard ceiling for captured output per stream, regardless of the configured limit.
+pub const MAX_OUTPUT_CAPTURE_BYTES: usize = 1024 * 1024;
+
+/// Bounded in-memory copy of one subprocess output stream.
+///
+/// Bytes past the limit are counted but not stored; the stream keeps being drained either way.
+#[derive(Debug)]
+pub(crate) struct OutputCapture {
+    buf: Vec<u8>,
+    limit: usize,
+    dropped: usize,
+}
+
+impl OutputCapture {
+    /// Create a capture holding at most `limit` bytes (clamped to [`MAX_OUTPUT_CAPTURE_BYTES`]).
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            buf: Vec::new(),
+            limit: limit.min(MAX_OUTPUT_CAPTURE_BYTES),
+            dropped: 0,
+        }
+    }
+
+    /// Append raw stream bytes, keeping only what still fits.
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        let room = self.limit - self.buf.len();
+        let keep = bytes.len().min(room);
+        self.buf.extend_from_slice(&bytes[..keep]);
+        self.dropped += bytes.len() - keep;
+    }
+
+    /// Captured output as text, with a truncation marker if anything was dropped.
+    pub(crate) fn finish(self) -> String {
+        let mut out = String::from_utf8_lossy(&self.buf).into_owned();
+        if self.dropped > 0 {
+            out.push_str(&format!("... (truncated {} bytes)", self.dropped));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_output_within_limit() {
+        let mut capture = OutputCapture::new(16);
+        capture.push(b"hello\n");
+        capture.push(b"world\n");
+        assert_eq!(capture.finish(), "hello\nworld\n");
+    }
+
+    #[test]
+    fn truncates_past_limit_with_marker() {
+        let mut capture = OutputCapture::new(8);
+        capture.push(b"0123456");
+        capture.push(b"789\n");
+        capture.push(b"more\n");
+        assert_eq!(capture.finish(), "01234567... (truncated 8 bytes)");
+    }
+
+    #[test]
+    fn limit_is_clamped_to_ceiling() {
+        let capture = OutputCapture::new(usize::MAX);
+        assert_eq!(capture.limit, MAX_OUTPUT_CAPTURE_BYTES);
+    }
+}
@@ -0,0 +1,33 @@
+use std::{collections::BTreeSet, path::Path, sync::Arc};
+
+use crate::ExecError;
+
+/// Restrictions on which commands a runner may execute.
+///
+/// The default policy allows any command, resolved through the inherited `PATH`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommandPolicy {
+    /// Only absolute command paths are accepted (no `PATH` lookup).
+    pub(crate) require_absolute: bool,
+    /// Commands that may be executed, matched exactly (`None` = any command).
+    pub(crate) allowlist: Option<Arc<BTreeSet<String>>>,
+}
+
+impl CommandPolicy {
+    /// Check `command` against the policy.
+    pub(crate) fn check(&self, command: &str) -> Result<(), ExecError> {
+        if self.require_absolute && !Path::new(command).is_absolute() {
+            return Err(ExecError::InvalidSpec(format!(
+                "command '{command}' must be an absolute path for this runner"
+            )));
+        }
+        if let Some(allowlist) = &self.allowlist
+            && !allowlist.contains(command)
+        {
+            return Err(ExecError::InvalidSpec(format!(
+                "command '{command}' is not in the runner's command allowlist"
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use std::{collections::BTreeSet, path::Path};
+
+use tno_model::TaskEnv;
+
+/// Which environment variables a subprocess inherits from the agent.
+///
+/// "Inherited" covers both the agent process environment and the runner-wide environment
+/// from the build context ([`tno_core::BuildContext::env`]). Variables set in the task spec itself are always passed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EnvPolicy {
+    /// Pass the whole inherited environment (default).
+    #[default]
+    Inherit,
+    /// Start from an empty environment; the subprocess sees only the task's own variables.
+    ///
+    /// Recommended for runners executing untrusted tasks.
+    Clear,
+    /// Start from an empty environment and pass through only the listed inherited keys.
+    Allow(BTreeSet<String>),
+}
+
+impl EnvPolicy {
+    /// Allowlist policy for the given keys.
+    pub fn allow<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        EnvPolicy::Allow(keys.into_iter().map(Into::into).collect())
+    }
+
+    /// Whether the subprocess must not inherit the agent process environment as a whole.
+    pub(crate) fn clears_process_env(&self) -> bool {
+        !matches!(self, EnvPolicy::Inherit)
+    }
+
+    /// Inherited variables the subprocess may see, given the runner-wide environment `base`.
+    ///
+    /// With [`EnvPolicy::Allow`], allowed keys are taken from the agent process environment first
+    /// and overridden by `base`.
+    pub(crate) fn inherited(&self, base: &TaskEnv) -> TaskEnv {
+        match self {
+            EnvPolicy::Inherit => base.clone(),
+            EnvPolicy::Clear => TaskEnv::new(),
+            EnvPolicy::Allow(keys) => {
+                let mut env = TaskEnv::new();
+                for key in keys {
+                    if let Ok(value) = std::env::var(key) {
+                        env.push(key.as_str(), value);
+                    }
+                }
+                for kv in base.iter().filter(|kv| keys.contains(kv.key())) {
+                    env.push(kv.key(), kv.value());
+                }
+                env
+            }
+        }
+    }
+}
+
+/// Read a dotenv-style file (see [`parse_env_file`]).
+pub(crate) fn read_env_file(path: &Path) -> Result<TaskEnv, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("env file {}: {e}", path.display()))?;
+    parse_env_file(&content).map_err(|e| format!("env file {}: {e}", path.display()))
+}
+
+/// Parse `KEY=VALUE` lines into an environment, keeping their order.
+///
+/// Blank lines and lines starting with `#` are skipped, and an `export ` prefix is allowed.
+/// Values may be wrapped in single quotes (taken literally) or double quotes (`\n`, `\"` and `\\` are unescaped);
+/// unquoted values are trimmed and end at a ` #` comment.
+/// Errors name the offending (1-based) line.
+pub(crate) fn parse_env_file(content: &str) -> Result<TaskEnv, String> {
+    let mut env = TaskEnv::new();
+    for (idx, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let err = |msg: &str| format!("line {}: {msg}", idx + 1);
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| err("expected KEY=VALUE"))?;
+        let key = key.trim();
+        let value = parse_value(rest.trim()).map_err(err)?;
+        TaskEnv::single(key, value.as_str())
+            .validate()
+            .map_err(|e| err(&e.to_string()))?;
+        env.push(key, value);
+    }
+    Ok(env)
+}
+
+fn parse_value(raw: &str) -> Result<String, &'static str> {
+    let (value, rest) = match raw.chars().next() {
+        Some('\'') => {
+            let end = raw[1..].find('\'').ok_or("unterminated single quote")?;
+            (raw[1..=end].to_string(), &raw[end + 2..])
+        }
+        Some('"') => {
+            let mut value = String::new();
+            let mut chars = raw[1..].char_indices();
+            let end = loop {
+                match chars.next().ok_or("unterminated double quote")? {
+                    (i, '"') => break i + 2,
+                    (_, '\\') => match chars.next().ok_or("unterminated double quote")?.1 {
+                        'n' => value.push('\n'),
+                        c @ ('"' | '\\') => value.push(c),
+                        c => {
+                            value.push('\\');
+                            value.push(c);
+                        }
+                    },
+                    (_, c) => value.push(c),
+                }
+            };
+            (value, &raw[end..])
+        }
+        _ => {
+            let value = raw.split_once(" #").map_or(raw, |(v, _)| v);
+            return Ok(value.trim_end().to_string());
+        }
+    };
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(value)
+    } else {
+        Err("unexpected characters after quoted value")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_file_supports_comments_quotes_and_export() {
+        let env = parse_env_file(
+            "# database\n\
+             DB_HOST=localhost # local only\n\
+             export DB_USER='admin # not a comment'\n\
+             \n\
+             DB_PASS=\"p\\\"w\\nd\"\n\
+             EMPTY=\n",
+        )
+        .unwrap();
+
+        let pairs: Vec<_> = env.iter().map(|kv| (kv.key(), kv.value())).collect();
+        assert_eq!(
+            pairs,
+            [
+                ("DB_HOST", "localhost"),
+                ("DB_USER", "admin # not a comment"),
+                ("DB_PASS", "p\"w\nd"),
+                ("EMPTY", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn env_file_errors_name_the_line() {
+        for (content, line) in [
+            ("A=1\nnot a pair\n", "line 2"),
+            ("A=1\n\n# c\nB=\"open\n", "line 4"),
+            ("=value\n", "line 1"),
+            ("A='x' trailing\n", "line 1"),
+        ] {
+            let err = parse_env_file(content).unwrap_err();
+            assert!(err.starts_with(line), "{content:?}: {err}");
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use tno_core::RunnerErrorKind;
+
+use crate::utils::CgroupUsage;
+
+/// Resource limit that most likely ended a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LimitCause {
+    /// Killed by the OOM killer under the cgroup memory limit.
+    Oom,
+    /// Forks were rejected by the cgroup process limit (`pids.max`).
+    Pids,
+    /// File size rlimit exceeded (`SIGXFSZ`).
+    FileSize,
+    /// CPU time rlimit exceeded (`SIGXCPU`).
+    Cpu,
+}
+
+impl LimitCause {
+    /// Value reported in `TaskInfo::limit_cause`.
+    pub(crate) fn as_label(&self) -> &'static str {
+        match self {
+            LimitCause::Oom => "oom",
+            LimitCause::Pids => "pids",
+            LimitCause::FileSize => "fsize",
+            LimitCause::Cpu => "cpu",
+        }
+    }
+
+    /// Runner error kind recorded in metrics.
+    pub(crate) fn error_kind(&self) -> RunnerErrorKind {
+        match self {
+            LimitCause::Oom => RunnerErrorKind::LimitOom,
+            LimitCause::Pids => RunnerErrorKind::LimitPids,
+            LimitCause::FileSize => RunnerErrorKind::LimitFileSize,
+            LimitCause::Cpu => RunnerErrorKind::LimitCpu,
+        }
+    }
+
+    /// Infer the cause from the termination signal and the task cgroup counters.
+    ///
+    /// Only meaningful for attempts that failed. Cgroup counters are per task cgroup, so any OOM kill
+    /// or rejected fork happened within this attempt.
+    pub(crate) fn infer(signal: Option<i32>, usage: &CgroupUsage) -> Option<Self> {
+        if usage.oom_kills.is_some_and(|n| n > 0) {
+            return Some(LimitCause::Oom);
+        }
+        #[cfg(unix)]
+        match signal {
+            Some(libc::SIGXFSZ) => return Some(LimitCause::FileSize),
+            Some(libc::SIGXCPU) => return Some(LimitCause::Cpu),
+            _ => {}
+        }
+        #[cfg(not(unix))]
+        let _ = signal;
+        if usage.pids_max_hits.is_some_and(|n| n > 0) {
+            return Some(LimitCause::Pids);
+        }
+        None
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_cause_from_signal_and_cgroup_events() {
+        let none = CgroupUsage::unavailable();
+        let oom = CgroupUsage {
+            oom_kills: Some(1),
+            ..CgroupUsage::unavailable()
+        };
+        let pids = CgroupUsage {
+            oom_kills: Some(0),
+            pids_max_hits: Some(3),
+            ..CgroupUsage::unavailable()
+        };
+
+        assert_eq!(
+            LimitCause::infer(Some(libc::SIGKILL), &oom),
+            Some(LimitCause::Oom)
+        );
+        assert_eq!(
+            LimitCause::infer(Some(libc::SIGXFSZ), &none),
+            Some(LimitCause::FileSize)
+        );
+        assert_eq!(
+            LimitCause::infer(Some(libc::SIGXCPU), &none),
+            Some(LimitCause::Cpu)
+        );
+        assert_eq!(LimitCause::infer(None, &pids), Some(LimitCause::Pids));
+        assert_eq!(LimitCause::infer(Some(libc::SIGKILL), &none), None);
+        assert_eq!(LimitCause::infer(None, &none), None);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::subprocess::task_log::TaskLogFiles;
+
 /// Configuration for subprocess output logging.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct LogConfig {
     /// Max line length before truncation.
     pub max_line_length: usize,
@@ -7,6 +9,16 @@ pub struct LogConfig {
     pub stdout_info: bool,
     /// Log stderr at WARN level (false = DEBUG).
     pub stderr_warn: bool,
+    /// Write each task's output to its own file instead of (or in addition to) the tracing logger.
+    ///
+    /// If a task log file cannot be opened, that task falls back to the tracing logger.
+    pub task_files: Option<TaskLogFiles>,
+    /// Parse JSON object lines and log them as structured events (off by default).
+    ///
+    /// `msg`/`message` becomes the event message and `level`/`lvl`/`severity` the event level;
+    /// the remaining keys are attached as one `fields` value (compact JSON), since tracing field names are fixed at compile time.
+    /// Lines that are not JSON objects are logged as plain text. Applies to the tracing logger only, not to task log files.
+    pub parse_structured: bool,
 }
 
 impl Default for LogConfig {
@@ -15,6 +27,8 @@ impl Default for LogConfig {
             max_line_length: 4096,
             stdout_info: true,
             stderr_warn: true,
+            task_files: None,
+            parse_structured: false,
         }
     }
 }
@@ -1,13 +1,36 @@
 //! Subprocess runner for `tno_model::TaskKind::Subprocess`.
 mod backend;
-pub use backend::SubprocessBackendConfig;
+pub use backend::{PostRunHook, PreSpawnHook, SubprocessBackendConfig};
 
 mod task;
 pub use task::SubprocessTaskConfig;
 
+mod command;
+
+mod env;
+pub use env::EnvPolicy;
+
 mod logger;
 pub use logger::LogConfig;
 
+mod task_log;
+pub use task_log::TaskLogFiles;
+
+mod budget;
+pub use budget::OutputBudget;
+
+mod capture;
+pub use capture::MAX_OUTPUT_CAPTURE_BYTES;
+
+mod redact;
+pub use redact::{ArgRedaction, REDACTED};
+
+mod secrets;
+
+mod limit_cause;
+
+mod structured;
+
 mod runner;
 pub use runner::SubprocessRunner;
 
@@ -30,12 +53,18 @@ pub fn register_subprocess_runner(
     }
 
     let mut labels = RunnerLabels::new();
-    labels.insert(LABEL_RUNNER_TAG, name);
-    router.register_with_labels(Arc::new(SubprocessRunner::new(name)), labels);
-    Ok(())
+    labels
+        .insert(LABEL_RUNNER_TAG, name)
+        .map_err(|e| ExecError::InvalidRunnerConfig(e.to_string()))?;
+    router
+        .register_with_labels(Arc::new(SubprocessRunner::new(name)), labels)
+        .map_err(|e| ExecError::InvalidRunnerConfig(e.to_string()))
 }
 
 /// Register a subprocess runner with explicit runner configuration.
+///
+/// Fails if the configuration is invalid, or if the security warm-up is enabled and does not pass
+/// (see [`SubprocessBackendConfig::with_security_warm_up`]).
 pub fn register_subprocess_runner_with_backend(
     router: &mut RunnerRouter,
     name: &'static str,
@@ -47,12 +76,16 @@ pub fn register_subprocess_runner_with_backend(
         });
     }
     backend.validate()?;
+    backend.warm_up()?;
 
     let mut labels = RunnerLabels::new();
-    labels.insert(LABEL_RUNNER_TAG, name);
-    router.register_with_labels(
-        Arc::new(SubprocessRunner::with_config(name, backend)),
-        labels,
-    );
-    Ok(())
+    labels
+        .insert(LABEL_RUNNER_TAG, name)
+        .map_err(|e| ExecError::InvalidRunnerConfig(e.to_string()))?;
+    router
+        .register_with_labels(
+            Arc::new(SubprocessRunner::with_config(name, backend)),
+            labels,
+        )
+        .map_err(|e| ExecError::InvalidRunnerConfig(e.to_string()))
 }
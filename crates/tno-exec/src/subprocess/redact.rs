@@ -0,0 +1,114 @@
+/// Placeholder that replaces redacted argument values in logs.
+pub const REDACTED: &str = "***";
+
+/// Masking rules for subprocess arguments in logs.
+///
+/// Only affects what the runner logs; the subprocess always receives the original arguments.
+///
+/// # Example
+/// ```rust,ignore
+/// let redaction = ArgRedaction::new()
+///     .with_flag("--token")  // `--token=abc` and `--token abc`
+///     .with_position(2);     // third argument, whatever it is
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ArgRedaction {
+    /// Flags whose values are masked.
+    flags: Vec<String>,
+    /// Zero-based argument indices that are masked entirely.
+    positions: Vec<usize>,
+}
+
+impl ArgRedaction {
+    /// Create an empty redaction config (nothing is masked).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mask the value of `flag`, given either as `flag=value` or as `flag value`.
+    pub fn with_flag(mut self, flag: impl Into<String>) -> Self {
+        self.flags.push(flag.into());
+        self
+    }
+
+    /// Mask the argument at zero-based `index`.
+    pub fn with_position(mut self, index: usize) -> Self {
+        self.positions.push(index);
+        self
+    }
+
+    /// Check if no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty() && self.positions.is_empty()
+    }
+
+    /// Get configured flags.
+    pub(crate) fn flags(&self) -> &[String] {
+        &self.flags
+    }
+
+    /// Return a copy of `args` with secret values replaced by [`REDACTED`].
+    pub(crate) fn redact(&self, args: &[String]) -> Vec<String> {
+        let mut out = Vec::with_capacity(args.len());
+        let mut mask_next = false;
+
+        for (i, arg) in args.iter().enumerate() {
+            if mask_next || self.positions.contains(&i) {
+                mask_next = false;
+                out.push(REDACTED.to_string());
+                continue;
+            }
+            if self.flags.iter().any(|f| f == arg) {
+                mask_next = true;
+                out.push(arg.clone());
+                continue;
+            }
+            let masked = self.flags.iter().find_map(|f| {
+                arg.strip_prefix(f.as_str())
+                    .filter(|rest| rest.starts_with('='))
+                    .map(|_| format!("{f}={REDACTED}"))
+            });
+            out.push(masked.unwrap_or_else(|| arg.clone()));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn masks_flag_with_equals_and_separate_value() {
+        let r = ArgRedaction::new().with_flag("--token");
+        assert_eq!(
+            r.redact(&args(&[
+                "--token=secret",
+                "--token",
+                "secret",
+                "--tokens=x"
+            ])),
+            args(&["--token=***", "--token", "***", "--tokens=x"])
+        );
+    }
+
+    #[test]
+    fn masks_positions() {
+        let r = ArgRedaction::new().with_position(1);
+        assert_eq!(
+            r.redact(&args(&["user", "hunter2", "host"])),
+            args(&["user", "***", "host"])
+        );
+    }
+
+    #[test]
+    fn empty_config_keeps_args() {
+        let r = ArgRedaction::new();
+        assert!(r.is_empty());
+        assert_eq!(r.redact(&args(&["-a", "b"])), args(&["-a", "b"]));
+    }
+}
@@ -1,23 +1,58 @@
 use std::{
-    process::Stdio,
+    path::Path,
+    process::{ExitStatus, Stdio},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
     time::{Duration as StdDuration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use taskvisor::{TaskError, TaskFn, TaskRef};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
-    process::Command,
+    process::{Child, Command},
+    sync::OwnedSemaphorePermit,
 };
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, info, trace, warn};
+use tracing::{Instrument, Level, Span, debug, debug_span, info, trace, warn};
 
-use tno_core::{BuildContext, Runner, RunnerError};
-use tno_model::{CreateSpec, TaskKind};
+use tno_core::{
+    BuildContext, CapturedOutput, MetricsBackend, MetricsHandle, Runner, RunnerError,
+    RunnerErrorKind, TaskReporter,
+};
+use tno_model::{
+    AdmissionStrategy, BackoffStrategy, CreateSpec, Flag, JitterStrategy, RestartStrategy,
+    RunnerLabels, TaskEnv, TaskKind,
+};
 
-use crate::metrics::{RUNNER_TYPE_SUBPROCESS, task_error_to_outcome};
+use crate::metrics::{RUNNER_TYPE_SUBPROCESS, classify_attempt_error};
 use crate::subprocess::{
-    backend::SubprocessBackendConfig, logger::LogConfig, task::SubprocessTaskConfig,
+    backend::{PostRunHook, SubprocessBackendConfig},
+    budget::OutputBudget,
+    capture::OutputCapture,
+    env::{EnvPolicy, read_env_file},
+    limit_cause::LimitCause,
+    logger::LogConfig,
+    secrets::SecretDir,
+    structured::{StructuredLine, parse_json_line},
+    task::SubprocessTaskConfig,
+    task_log::TaskLogFile,
 };
+use crate::utils::{
+    CgroupRemoval, CgroupUsage, DEFAULT_CGROUP_ROOT, cgroup_exists, read_cgroup_usage,
+    remove_cgroup,
+};
+
+/// How many times to retry removing a busy cgroup after the task exits.
+const CGROUP_REMOVE_ATTEMPTS: u32 = 5;
+/// Delay between cgroup removal retries.
+const CGROUP_REMOVE_RETRY_DELAY: StdDuration = StdDuration::from_millis(20);
+
+/// Per-process sequence making task cgroup names unique.
+///
+/// Run ids cannot be used for this: caller-supplied task ids carry no sequence of their own.
+static CGROUP_SEQ: AtomicU64 = AtomicU64::new(1);
 
 /// Runner that executes `TaskKind::Subprocess` as OS subprocesses.
 pub struct SubprocessRunner {
@@ -47,20 +82,43 @@ impl SubprocessRunner {
         spec: &CreateSpec,
         ctx: &BuildContext,
     ) -> Result<SubprocessTaskConfig, RunnerError> {
+        let env_policy = self
+            .config
+            .as_ref()
+            .map(|c| c.env_policy())
+            .unwrap_or(&EnvPolicy::Inherit);
         let cfg = match &spec.kind {
             TaskKind::Subprocess {
                 command,
                 args,
                 env,
+                env_file,
                 cwd,
                 fail_on_non_zero,
             } => SubprocessTaskConfig {
-                run_id: self.build_run_id(&spec.slot),
+                run_id: ctx
+                    .task_id()
+                    .map_or_else(|| self.build_run_id(&spec.slot), |id| id.to_string()),
                 command: command.clone(),
                 args: args.clone(),
-                env: ctx.env().merged(env),
+                env: match env_file {
+                    Some(path) => env_policy
+                        .inherited(ctx.env())
+                        .merged(&read_env_file(path).map_err(RunnerError::InvalidSpec)?)
+                        .merged(env),
+                    None => env_policy.inherited(ctx.env()).merged(env),
+                },
+                clear_env: env_policy.clears_process_env(),
                 cwd: cwd.clone(),
+                check_cwd: self.config.as_ref().is_none_or(|c| c.checks_cwd()),
+                command_policy: self
+                    .config
+                    .as_ref()
+                    .map(|c| c.command_policy().clone())
+                    .unwrap_or_default(),
                 fail_on_non_zero: *fail_on_non_zero,
+                cancel_is_success: spec.cancel_is_success,
+                timeout: StdDuration::from_millis(spec.timeout_ms),
             },
             other => {
                 return Err(RunnerError::UnsupportedKind {
@@ -84,10 +142,41 @@ impl Runner for SubprocessRunner {
         matches!(spec.kind, TaskKind::Subprocess { .. })
     }
 
+    /// Runs `true` under the configured backend (rlimits, cgroups, security).
+    fn self_test_spec(&self) -> Option<CreateSpec> {
+        Some(CreateSpec {
+            slot: "self-test".into(),
+            kind: TaskKind::Subprocess {
+                command: "true".into(),
+                args: Vec::new(),
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        })
+    }
+
     fn build_task(&self, spec: &CreateSpec, ctx: &BuildContext) -> Result<TaskRef, RunnerError> {
         let task_cfg = self.build_task_config(spec, ctx)?;
         let runner_cfg = self.config.clone();
         let metrics = ctx.metrics().clone();
+        let reporter = ctx.reporter().clone();
 
         trace!(
             slot = %spec.slot,
@@ -105,7 +194,7 @@ impl Runner for SubprocessRunner {
                 Some(crate::utils::build_cgroup_name(
                     self.name,
                     &spec.slot,
-                    extract_seq_from_run_id(&task_cfg.run_id),
+                    CGROUP_SEQ.fetch_add(1, Ordering::Relaxed),
                     timestamp,
                 ))
             } else {
@@ -115,22 +204,42 @@ impl Runner for SubprocessRunner {
             None
         };
 
+        let cgroup_root: Arc<Path> = runner_cfg
+            .as_ref()
+            .map(|c| c.cgroup_root())
+            .unwrap_or(Path::new(DEFAULT_CGROUP_ROOT))
+            .into();
+
+        // Whether the task cgroup is currently accounted in metrics (shared across attempts).
+        let cgroup_tracked = Arc::new(AtomicBool::new(false));
+
         let task: TaskRef = TaskFn::arc(
             task_cfg.run_id.clone(),
             move |cancel: CancellationToken| {
                 let task_cfg = task_cfg.clone();
                 let runner_cfg = runner_cfg.clone();
                 let cgroup_name = cgroup_name.clone();
+                let cgroup_root = Arc::clone(&cgroup_root);
+                let cgroup_tracked = Arc::clone(&cgroup_tracked);
                 let metrics = metrics.clone();
+                let reporter = reporter.clone();
+                let span = debug_span!(
+                    "subprocess_task",
+                    task = %task_cfg.run_id,
+                    cgroup = tracing::field::Empty,
+                );
 
                 async move {
-                    metrics.record_task_started(RUNNER_TYPE_SUBPROCESS);
                     let start = Instant::now();
 
+                    let logged_args = match &runner_cfg {
+                        Some(c) => c.arg_redaction().redact(&task_cfg.args),
+                        None => task_cfg.args.clone(),
+                    };
                     trace!(
                         task = %task_cfg.run_id,
                         command = %task_cfg.command,
-                        args = ?task_cfg.args,
+                        args = ?logged_args,
                         cwd = ?task_cfg.cwd,
                         "spawning subprocess",
                     );
@@ -140,6 +249,9 @@ impl Runner for SubprocessRunner {
                     if let Some(cwd) = &task_cfg.cwd {
                         cmd.current_dir(cwd);
                     }
+                    if task_cfg.clear_env {
+                        cmd.env_clear();
+                    }
                     for kv in task_cfg.env.iter() {
                         cmd.env(kv.key(), kv.value());
                     }
@@ -149,91 +261,438 @@ impl Runner for SubprocessRunner {
                     if let Some(backend_cfg) = &runner_cfg {
                         let cgroup_name_ref = cgroup_name.as_deref().unwrap_or(&task_cfg.run_id);
                         if let Err(e) = backend_cfg.apply_to_command(&mut cmd, cgroup_name_ref) {
-                            metrics.record_runner_error(
-                                RUNNER_TYPE_SUBPROCESS,
-                                "backend_config_failed",
+                            let reason = format!("failed to apply runner config: {e}");
+                            record_runner_error(
+                                &metrics,
+                                &reporter,
+                                RunnerErrorKind::BackendConfigFailed,
+                                &task_cfg.run_id,
+                                &reason,
                             );
-                            return Err(TaskError::Fatal {
-                                reason: format!("failed to apply runner config: {e}"),
-                            });
+                            return Err(TaskError::Fatal { reason });
                         }
                     }
+                    // Removes the secret files when the attempt ends, whichever way it ends.
+                    let _secret_dir = match runner_cfg
+                        .as_ref()
+                        .filter(|c| !c.secret_files().is_empty())
+                    {
+                        Some(c) => match SecretDir::create(
+                            c.secret_dir(),
+                            &task_cfg.run_id,
+                            c.secret_files(),
+                        ) {
+                            Ok((dir, env)) => {
+                                for (key, path) in env {
+                                    cmd.env(key, path);
+                                }
+                                Some(dir)
+                            }
+                            Err(e) => {
+                                let reason = format!("failed to write secret files: {e}");
+                                record_runner_error(
+                                    &metrics,
+                                    &reporter,
+                                    RunnerErrorKind::SecretFilesFailed,
+                                    &task_cfg.run_id,
+                                    &reason,
+                                );
+                                return Err(TaskError::Fatal { reason });
+                            }
+                        },
+                        None => None,
+                    };
+                    if let Some(hook) = runner_cfg.as_ref().and_then(|c| c.pre_spawn())
+                        && let Err(e) = hook(&task_cfg)
+                    {
+                        let reason = format!("pre-spawn hook failed: {e}");
+                        record_runner_error(
+                            &metrics,
+                            &reporter,
+                            RunnerErrorKind::PreSpawnFailed,
+                            &task_cfg.run_id,
+                            &reason,
+                        );
+                        return Err(TaskError::Fatal { reason });
+                    }
                     let mut child = match cmd.spawn() {
                         Ok(child) => child,
                         Err(e) => {
-                            metrics.record_runner_error(RUNNER_TYPE_SUBPROCESS, "spawn_failed");
-                            return Err(TaskError::Fatal {
-                                reason: format!("spawn failed: {e}"),
-                            });
+                            let reason = format!("spawn failed: {e}");
+                            record_runner_error(
+                                &metrics,
+                                &reporter,
+                                RunnerErrorKind::SpawnFailed,
+                                &task_cfg.run_id,
+                                &reason,
+                            );
+                            return Err(TaskError::Fatal { reason });
                         }
                     };
+                    // Setup failures above are runner errors only; from here on the attempt is a started task.
+                    metrics.record_task_started(RUNNER_TYPE_SUBPROCESS);
+                    // The cgroup is created inside `pre_exec`; it exists only if that succeeded.
+                    if let Some(name) = cgroup_name.as_deref()
+                        && cgroup_exists(&cgroup_root, name)
+                    {
+                        if !cgroup_tracked.swap(true, Ordering::AcqRel) {
+                            metrics.record_cgroup_created(RUNNER_TYPE_SUBPROCESS);
+                        }
+                        Span::current().record("cgroup", name);
+                        reporter.report_cgroup(&task_cfg.run_id, name);
+                    }
 
-                    let log_cfg = runner_cfg
+                    let log_cfg = Arc::new(
+                        runner_cfg
+                            .as_ref()
+                            .map(|c| c.log_config().clone())
+                            .unwrap_or_default(),
+                    );
+                    let log_file = open_task_log(&log_cfg, &task_cfg.run_id);
+                    let budget = runner_cfg.as_ref().and_then(|c| c.output_budget().cloned());
+                    let capture = runner_cfg.as_ref().and_then(|c| c.output_capture());
+                    let compress_capture = runner_cfg
                         .as_ref()
-                        .map(|c| *c.log_config())
-                        .unwrap_or_default();
+                        .is_some_and(|c| c.compress_captured_output());
 
-                    let stdout = child.stdout.take().ok_or_else(|| TaskError::Fatal {
-                        reason: "failed to capture stdout".into(),
-                    })?;
-                    let run_id_stdout = task_cfg.run_id.clone();
+                    let Some(stdout) = child.stdout.take() else {
+                        return Err(fail_started_attempt(
+                            &metrics,
+                            &reporter,
+                            RunnerErrorKind::OutputCaptureFailed,
+                            &task_cfg.run_id,
+                            "failed to capture stdout".into(),
+                            start,
+                        ));
+                    };
+                    let sink_stdout = LineSink {
+                        run_id: task_cfg.run_id.clone(),
+                        stream: "stdout",
+                        config: Arc::clone(&log_cfg),
+                        file: log_file.clone(),
+                    };
+                    let (budget_stdout, metrics_stdout) = (budget.clone(), metrics.clone());
                     let stdout_task = tokio::spawn(async move {
-                        log_stream(stdout, &run_id_stdout, "stdout", &log_cfg).await;
+                        log_stream(
+                            stdout,
+                            &sink_stdout,
+                            budget_stdout.as_ref(),
+                            capture.map(OutputCapture::new),
+                            metrics_stdout.as_ref(),
+                        )
+                        .await
                     });
 
-                    let stderr = child.stderr.take().ok_or_else(|| TaskError::Fatal {
-                        reason: "failed to capture stderr".into(),
-                    })?;
-                    let run_id_stderr = task_cfg.run_id.clone();
+                    let Some(stderr) = child.stderr.take() else {
+                        return Err(fail_started_attempt(
+                            &metrics,
+                            &reporter,
+                            RunnerErrorKind::OutputCaptureFailed,
+                            &task_cfg.run_id,
+                            "failed to capture stderr".into(),
+                            start,
+                        ));
+                    };
+                    let sink_stderr = LineSink {
+                        run_id: task_cfg.run_id.clone(),
+                        stream: "stderr",
+                        config: log_cfg,
+                        file: log_file,
+                    };
+                    let metrics_stderr = metrics.clone();
                     let stderr_task = tokio::spawn(async move {
-                        log_stream(stderr, &run_id_stderr, "stderr", &log_cfg).await;
+                        log_stream(
+                            stderr,
+                            &sink_stderr,
+                            budget.as_ref(),
+                            capture.map(OutputCapture::new),
+                            metrics_stderr.as_ref(),
+                        )
+                        .await
                     });
 
+                    let mut exit_signal = None;
                     let status_fut = child.wait();
                     let result = tokio::select! {
-                        res = status_fut => {
-                            let status = res.map_err(|e| TaskError::Fatal {
-                                reason: format!("wait failed: {e}"),
-                            })?;
-                            if !status.success() && task_cfg.fail_on_non_zero.is_enabled() {
-                                let reason = match status.code() {
-                                    Some(code) => format!("process exited with non-zero code: {code}"),
-                                    None => "process terminated by signal".into(),
-                                };
-                                Err(TaskError::Fail { reason })
-                            } else {
-                                debug!(task = %task_cfg.run_id, "subprocess exited successfully");
-                                Ok(())
+                        res = status_fut => match res {
+                            Err(e) => {
+                                let reason = format!("wait failed: {e}");
+                                record_runner_error(
+                                    &metrics,
+                                    &reporter,
+                                    RunnerErrorKind::WaitFailed,
+                                    &task_cfg.run_id,
+                                    &reason,
+                                );
+                                Err(TaskError::Fatal { reason })
                             }
-                        }
+                            Ok(status) => {
+                                report_exit_status(&reporter, &task_cfg.run_id, &status);
+                                exit_signal = exit_status_parts(&status).1;
+                                if !status.success() && task_cfg.fail_on_non_zero.is_enabled() {
+                                    let reason = match exit_status_parts(&status) {
+                                        (Some(code), _) => format!("process exited with non-zero code: {code}"),
+                                        (None, Some(signal)) => format!("process terminated by signal {signal}"),
+                                        (None, None) => "process terminated by signal".into(),
+                                    };
+                                    Err(TaskError::Fail { reason })
+                                } else {
+                                    debug!(task = %task_cfg.run_id, "subprocess exited successfully");
+                                    Ok(())
+                                }
+                            }
+                        },
                         _ = cancel.cancelled() => {
-                            debug!(task = %task_cfg.run_id, "cancellation requested; killing subprocess");
-                            if let Err(e) = child.kill().await {
+                            debug!(task = %task_cfg.run_id, "cancellation requested; stopping subprocess");
+                            let grace = runner_cfg.as_ref().and_then(|c| c.graceful_shutdown());
+                            if let Err(e) = terminate_child(&mut child, grace, &task_cfg.run_id).await {
                                 debug!(task = %task_cfg.run_id, "failed to kill subprocess: {e}");
                             }
+                            if let Ok(Some(status)) = child.try_wait() {
+                                report_exit_status(&reporter, &task_cfg.run_id, &status);
+                            }
                             Err(TaskError::Canceled)
                         }
                     };
 
-                    let duration_ms = start.elapsed().as_millis() as u64;
+                    let elapsed = start.elapsed();
+                    let duration_ms = elapsed.as_millis() as u64;
                     let outcome = match &result {
                         Ok(()) => tno_core::TaskOutcome::Success,
-                        Err(e) => task_error_to_outcome(e),
+                        Err(e) => classify_attempt_error(
+                            e,
+                            task_cfg.cancel_is_success,
+                            elapsed,
+                            task_cfg.timeout,
+                        ),
                     };
                     metrics.record_task_completed(RUNNER_TYPE_SUBPROCESS, outcome, duration_ms);
 
-                    let _ = tokio::join!(stdout_task, stderr_task);
-                    if let Some(cgroup_name) = cgroup_name {
-                        let _ = crate::utils::cleanup_cgroup(&cgroup_name);
+                    let (stdout_out, stderr_out) = tokio::join!(stdout_task, stderr_task);
+                    if capture.is_some() {
+                        let store = |out: String| {
+                            if compress_capture {
+                                CapturedOutput::gzip(out)
+                            } else {
+                                CapturedOutput::Plain(out)
+                            }
+                        };
+                        reporter.report_output(
+                            &task_cfg.run_id,
+                            stdout_out.ok().flatten().map(store),
+                            stderr_out.ok().flatten().map(store),
+                        );
+                    }
+                    let usage = match cgroup_name.as_deref() {
+                        Some(name) => report_cgroup_usage(
+                            &cgroup_root,
+                            name,
+                            &task_cfg.run_id,
+                            metrics.as_ref(),
+                        ),
+                        None => CgroupUsage::unavailable(),
+                    };
+                    let limit_cause = match &result {
+                        Err(TaskError::Canceled) | Ok(()) => None,
+                        Err(_) => LimitCause::infer(exit_signal, &usage),
+                    };
+                    if let Some(cause) = limit_cause {
+                        warn!(
+                            task = %task_cfg.run_id,
+                            cause = cause.as_label(),
+                            "subprocess was most likely stopped by a resource limit"
+                        );
+                        metrics.record_runner_error(RUNNER_TYPE_SUBPROCESS, cause.error_kind());
+                    }
+                    reporter.report_limit_cause(
+                        &task_cfg.run_id,
+                        limit_cause.as_ref().map(LimitCause::as_label),
+                    );
+                    if let Some(cgroup_name) = cgroup_name
+                        && release_cgroup(&cgroup_root, &cgroup_name).await
+                        && cgroup_tracked.swap(false, Ordering::AcqRel)
+                    {
+                        metrics.record_cgroup_removed(RUNNER_TYPE_SUBPROCESS);
+                    }
+                    if let Some(hook) = runner_cfg.as_ref().and_then(|c| c.post_run()) {
+                        spawn_post_run(Arc::clone(hook), task_cfg, outcome);
                     }
                     result
                 }
+                .instrument(span)
             },
         );
         Ok(task)
     }
 }
 
+/// Run the post-run hook on the blocking pool without waiting for it.
+fn spawn_post_run(
+    hook: PostRunHook,
+    task_cfg: SubprocessTaskConfig,
+    outcome: tno_core::TaskOutcome,
+) {
+    let handle = tokio::task::spawn_blocking(move || hook(&task_cfg, &outcome));
+    tokio::spawn(async move {
+        if let Err(e) = handle.await {
+            warn!("post-run hook failed: {e}");
+        }
+    });
+}
+
+/// Read back the usage counters of a finished task's cgroup, log them and feed peak memory into metrics.
+///
+/// Must run before the cgroup is released. Returns the usage for further inspection
+/// ([`CgroupUsage::unavailable`] if it cannot be read).
+fn report_cgroup_usage(
+    root: &Path,
+    cgroup_name: &str,
+    run_id: &str,
+    metrics: &dyn MetricsBackend,
+) -> CgroupUsage {
+    match read_cgroup_usage(root, cgroup_name) {
+        Ok(usage) if usage.is_available() => {
+            debug!(
+                task = %run_id,
+                cgroup = %cgroup_name,
+                memory_peak_bytes = ?usage.memory_peak_bytes,
+                cpu_usage_usec = ?usage.cpu_usage_usec,
+                cpu_user_usec = ?usage.cpu_user_usec,
+                cpu_system_usec = ?usage.cpu_system_usec,
+                oom_kills = ?usage.oom_kills,
+                pids_max_hits = ?usage.pids_max_hits,
+                "subprocess cgroup usage"
+            );
+            if let Some(bytes) = usage.memory_peak_bytes {
+                metrics.record_task_memory_peak(RUNNER_TYPE_SUBPROCESS, bytes);
+            }
+            usage
+        }
+        Ok(usage) => {
+            trace!(task = %run_id, cgroup = %cgroup_name, "cgroup usage unavailable");
+            usage
+        }
+        Err(e) => {
+            debug!(task = %run_id, cgroup = %cgroup_name, "failed to read cgroup usage: {e}");
+            CgroupUsage::unavailable()
+        }
+    }
+}
+
+/// Stop a running subprocess.
+///
+/// With a `grace` period (Unix only) the process receives `SIGTERM` first and is force-killed only if it is still alive once the grace period elapses.
+/// Without it the process is killed immediately.
+async fn terminate_child(
+    child: &mut Child,
+    grace: Option<StdDuration>,
+    run_id: &str,
+) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(grace) = grace
+        && let Some(pid) = child.id()
+    {
+        let rc = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+        if rc == 0 {
+            match tokio::time::timeout(grace, child.wait()).await {
+                Ok(res) => {
+                    debug!(task = %run_id, "subprocess exited after SIGTERM");
+                    return res.map(|_| ());
+                }
+                Err(_) => {
+                    debug!(
+                        task = %run_id,
+                        grace_ms = grace.as_millis() as u64,
+                        "subprocess did not exit within grace period; sending SIGKILL"
+                    );
+                }
+            }
+        } else {
+            debug!(
+                task = %run_id,
+                error = %std::io::Error::last_os_error(),
+                "failed to send SIGTERM; sending SIGKILL"
+            );
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (grace, run_id);
+
+    child.kill().await
+}
+
+/// Remove the task cgroup, retrying briefly while it is still busy.
+///
+/// Returns `true` if the cgroup no longer exists.
+async fn release_cgroup(root: &Path, name: &str) -> bool {
+    let mut attempt = 1;
+    loop {
+        match remove_cgroup(root, name) {
+            CgroupRemoval::Busy if attempt < CGROUP_REMOVE_ATTEMPTS => {
+                attempt += 1;
+                tokio::time::sleep(CGROUP_REMOVE_RETRY_DELAY).await;
+            }
+            result => return result.is_gone(),
+        }
+    }
+}
+
+/// Split process exit status into `(exit code, terminating signal)`.
+///
+/// Exactly one of them is set on Unix; `(None, None)` elsewhere if the process did not exit normally.
+fn exit_status_parts(status: &ExitStatus) -> (Option<i32>, Option<i32>) {
+    if let Some(code) = status.code() {
+        return (Some(code), None);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        (None, status.signal())
+    }
+    #[cfg(not(unix))]
+    (None, None)
+}
+
+/// Record a runner error into metrics and the supervisor's recent runner errors.
+fn record_runner_error(
+    metrics: &MetricsHandle,
+    reporter: &TaskReporter,
+    kind: RunnerErrorKind,
+    run_id: &str,
+    reason: &str,
+) {
+    metrics.record_runner_error(RUNNER_TYPE_SUBPROCESS, kind);
+    reporter.report_runner_error(RUNNER_TYPE_SUBPROCESS, kind, Some(run_id), reason);
+}
+
+/// Record a runner error for an attempt whose process was already spawned and close the attempt in metrics.
+fn fail_started_attempt(
+    metrics: &MetricsHandle,
+    reporter: &TaskReporter,
+    kind: RunnerErrorKind,
+    run_id: &str,
+    reason: String,
+    start: Instant,
+) -> TaskError {
+    record_runner_error(metrics, reporter, kind, run_id, &reason);
+    let duration_ms = start.elapsed().as_millis() as u64;
+    metrics.record_task_completed(
+        RUNNER_TYPE_SUBPROCESS,
+        tno_core::TaskOutcome::Failure,
+        duration_ms,
+    );
+    TaskError::Fatal { reason }
+}
+
+/// Report how an attempt ended into task state.
+fn report_exit_status(reporter: &TaskReporter, run_id: &str, status: &ExitStatus) {
+    match exit_status_parts(status) {
+        (Some(code), _) => reporter.report_exit_code(run_id, code),
+        (None, Some(signal)) => reporter.report_signal(run_id, signal),
+        (None, None) => {}
+    }
+}
+
 /// Truncate line by Unicode scalar count, safe for UTF-8.
 ///
 /// If `max_chars` is 0, the caller should not invoke this function.
@@ -250,20 +709,38 @@ fn truncate_line(line: &str, max_chars: usize) -> String {
 }
 
 /// Log subprocess output stream with truncation.
-async fn log_stream<R>(reader: R, run_id: &str, stream: &str, config: &LogConfig)
+///
+/// With an output `budget`, every buffered byte is reserved before it is read from the pipe and released once its line is logged.
+/// The stream never waits for budget while holding some: a pending partial line is logged first, so streams cannot deadlock each other.
+///
+/// With a `capture`, read bytes are also copied into it up to its limit; the stream is drained to the end regardless.
+/// Returns the captured output, if any.
+async fn log_stream<R>(
+    reader: R,
+    sink: &LineSink,
+    budget: Option<&OutputBudget>,
+    mut capture: Option<OutputCapture>,
+    metrics: &dyn MetricsBackend,
+) -> Option<String>
 where
     R: tokio::io::AsyncRead + Unpin,
 {
-    let mut lines = BufReader::new(reader).lines();
+    let mut reader = BufReader::new(reader);
+    let mut pending: Vec<u8> = Vec::new();
+    let mut held: Option<OwnedSemaphorePermit> = None;
     let mut line_count = 0u64;
 
-    while let Some(result) = lines.next_line().await.transpose() {
-        let raw_line = match result {
-            Ok(line) => line,
+    loop {
+        let (mut take, mut eol) = match reader.fill_buf().await {
+            Ok([]) => break,
+            Ok(buf) => match buf.iter().position(|b| *b == b'\n') {
+                Some(i) => (i + 1, true),
+                None => (buf.len(), false),
+            },
             Err(e) => {
                 warn!(
-                    task = %run_id,
-                    stream = %stream,
+                    task = %sink.run_id,
+                    stream = %sink.stream,
                     error = %e,
                     line_num = line_count,
                     "error while reading subprocess stream"
@@ -272,21 +749,124 @@ where
             }
         };
 
+        if let Some(budget) = budget {
+            if take > budget.max_chunk() {
+                take = budget.max_chunk();
+                eol = false;
+            }
+            match budget.try_acquire(take) {
+                Some(permit) => match &mut held {
+                    Some(held) => held.merge(permit),
+                    None => held = Some(permit),
+                },
+                None => {
+                    if !pending.is_empty() {
+                        line_count += 1;
+                        sink.log_line(&pending, line_count);
+                        pending.clear();
+                    }
+                    drop(held.take());
+                    held = Some(budget.acquire(take).await);
+                }
+            }
+            report_output_in_flight(budget, metrics);
+        }
+
+        // The buffer still holds the bytes inspected above; this does not read from the pipe.
+        match reader.fill_buf().await {
+            Ok(buf) => {
+                pending.extend_from_slice(&buf[..take]);
+                if let Some(capture) = &mut capture {
+                    capture.push(&buf[..take]);
+                }
+            }
+            Err(_) => break,
+        }
+        reader.consume(take);
+
+        if eol {
+            line_count += 1;
+            sink.log_line(&pending, line_count);
+            pending.clear();
+            if let Some(budget) = budget
+                && held.take().is_some()
+            {
+                report_output_in_flight(budget, metrics);
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        line_count += 1;
+        sink.log_line(&pending, line_count);
+    }
+    if let Some(budget) = budget
+        && held.take().is_some()
+    {
+        report_output_in_flight(budget, metrics);
+    }
+
+    debug!(
+        task = %sink.run_id,
+        stream = %sink.stream,
+        total_lines = line_count,
+        "stream closed"
+    );
+    capture.map(OutputCapture::finish)
+}
+
+fn report_output_in_flight(budget: &OutputBudget, metrics: &dyn MetricsBackend) {
+    metrics.record_output_bytes_in_flight(RUNNER_TYPE_SUBPROCESS, budget.in_flight() as u64);
+}
+
+/// Where one output stream of a task goes: the tracing logger and/or the task log file.
+struct LineSink {
+    run_id: String,
+    stream: &'static str,
+    config: Arc<LogConfig>,
+    /// Task log file shared with the other stream of the same task (if enabled and opened).
+    file: Option<Arc<Mutex<TaskLogFile>>>,
+}
+
+impl LineSink {
+    /// Log a single line of subprocess output (without its line terminator).
+    fn log_line(&self, raw: &[u8], line_num: u64) {
+        let (run_id, config) = (&self.run_id, &self.config);
+        let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+        let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        let raw_line = String::from_utf8_lossy(raw);
+        let structured = if config.parse_structured {
+            parse_json_line(&raw_line)
+        } else {
+            None
+        };
+
         let line = if config.max_line_length > 0 {
             truncate_line(&raw_line, config.max_line_length)
         } else {
-            raw_line
+            raw_line.into_owned()
         };
 
-        line_count += 1;
+        if let Some(file) = &self.file {
+            if let Err(e) = file.lock().unwrap().write_line(self.stream, &line) {
+                debug!(task = %run_id, "failed to write task log file: {e}");
+            }
+            if !config.task_files.as_ref().is_some_and(|f| f.also_trace) {
+                return;
+            }
+        }
+        if let Some(parsed) = structured {
+            self.log_structured(parsed, line_num);
+            return;
+        }
 
-        match stream {
+        match self.stream {
             "stdout" => {
                 if config.stdout_info {
                     info!(
                         task = %run_id,
                         stream = "stdout",
-                        line_num = line_count,
+                        line_num,
                         "{}",
                         line
                     );
@@ -294,7 +874,7 @@ where
                     debug!(
                         task = %run_id,
                         stream = "stdout",
-                        line_num = line_count,
+                        line_num,
                         "{}",
                         line
                     );
@@ -305,7 +885,7 @@ where
                     warn!(
                         task = %run_id,
                         stream = "stderr",
-                        line_num = line_count,
+                        line_num,
                         "{}",
                         line
                     );
@@ -313,7 +893,7 @@ where
                     debug!(
                         task = %run_id,
                         stream = "stderr",
-                        line_num = line_count,
+                        line_num,
                         "{}",
                         line
                     );
@@ -323,19 +903,1550 @@ where
         }
     }
 
-    debug!(
-        task = %run_id,
-        stream = %stream,
-        total_lines = line_count,
-        "stream closed"
-    );
+    /// Log a subprocess output line that parsed as JSON, at its own level if it names one.
+    fn log_structured(&self, line: StructuredLine, line_num: u64) {
+        let config = &self.config;
+        let level = line.level.unwrap_or(match self.stream {
+            "stdout" if config.stdout_info => Level::INFO,
+            "stderr" if config.stderr_warn => Level::WARN,
+            _ => Level::DEBUG,
+        });
+        let fields = if config.max_line_length > 0 {
+            truncate_line(&line.fields, config.max_line_length)
+        } else {
+            line.fields
+        };
+
+        macro_rules! emit {
+            ($level:expr) => {
+                tracing::event!(
+                    $level,
+                    task = %self.run_id,
+                    stream = self.stream,
+                    line_num,
+                    fields = %fields,
+                    "{}",
+                    line.message
+                )
+            };
+        }
+        match level {
+            Level::TRACE => emit!(Level::TRACE),
+            Level::DEBUG => emit!(Level::DEBUG),
+            Level::INFO => emit!(Level::INFO),
+            Level::WARN => emit!(Level::WARN),
+            _ => emit!(Level::ERROR),
+        }
+    }
 }
 
-/// Extract sequence number from run_id.
-fn extract_seq_from_run_id(run_id: &str) -> u64 {
-    run_id
-        .rsplit('-')
-        .next()
-        .and_then(|s| u64::from_str_radix(s, 16).ok())
-        .unwrap_or(0)
+/// Open the task log file if per-task files are enabled.
+///
+/// Failures are logged and the task falls back to the tracing logger.
+/// The file is closed once both output streams are done with it.
+fn open_task_log(config: &LogConfig, run_id: &str) -> Option<Arc<Mutex<TaskLogFile>>> {
+    let files = config.task_files.as_ref()?;
+    match TaskLogFile::open(files, run_id) {
+        Ok(file) => {
+            trace!(
+                task = %run_id,
+                path = %file.path().display(),
+                "writing output to task log file"
+            );
+            Some(Arc::new(Mutex::new(file)))
+        }
+        Err(e) => {
+            warn!(
+                task = %run_id,
+                dir = %files.dir.display(),
+                "failed to open task log file; logging output instead: {e}"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use std::os::unix::process::ExitStatusExt;
+
+    /// Spawn `sh -c script` and wait until it prints its first line (signal traps are installed).
+    async fn spawn_ready(script: &str) -> Child {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn sh");
+
+        let stdout = child.stdout.take().expect("stdout must be piped");
+        let mut lines = BufReader::new(stdout).lines();
+        let ready = lines.next_line().await.expect("failed to read stdout");
+        assert_eq!(ready.as_deref(), Some("ready"));
+        child
+    }
+
+    #[tokio::test]
+    async fn sigterm_trap_exits_cleanly_within_grace() {
+        let mut child =
+            spawn_ready("trap 'exit 0' TERM; echo ready; while true; do sleep 0.05; done").await;
+
+        let start = Instant::now();
+        terminate_child(&mut child, Some(StdDuration::from_secs(5)), "test")
+            .await
+            .expect("terminate must succeed");
+        assert!(start.elapsed() < StdDuration::from_secs(5));
+
+        let status = child.wait().await.unwrap();
+        assert_eq!(
+            status.code(),
+            Some(0),
+            "process should exit via its TERM trap"
+        );
+    }
+
+    #[tokio::test]
+    async fn ignored_sigterm_is_force_killed_after_grace() {
+        let mut child = spawn_ready("trap '' TERM; echo ready; sleep 30").await;
+
+        let grace = StdDuration::from_millis(200);
+        let start = Instant::now();
+        terminate_child(&mut child, Some(grace), "test")
+            .await
+            .expect("terminate must succeed");
+        assert!(start.elapsed() >= grace);
+
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+        assert_eq!(exit_status_parts(&status), (None, Some(libc::SIGKILL)));
+    }
+
+    #[tokio::test]
+    async fn exit_code_reports_plain_exit_status() {
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg("exit 7")
+            .status()
+            .await
+            .unwrap();
+        assert_eq!(exit_status_parts(&status), (Some(7), None));
+    }
+
+    #[tokio::test]
+    async fn signal_terminated_process_reports_signal_only() {
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg("kill -TERM $$")
+            .status()
+            .await
+            .unwrap();
+        assert_eq!(exit_status_parts(&status), (None, Some(libc::SIGTERM)));
+    }
+
+    #[tokio::test]
+    async fn without_grace_kills_immediately() {
+        let mut child = spawn_ready("trap 'exit 0' TERM; echo ready; sleep 30").await;
+
+        terminate_child(&mut child, None, "test")
+            .await
+            .expect("kill must succeed");
+
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[derive(Default)]
+    struct CgroupGauge {
+        active: std::sync::atomic::AtomicI64,
+        created: std::sync::atomic::AtomicI64,
+    }
+
+    #[cfg(target_os = "linux")]
+    impl tno_core::MetricsBackend for CgroupGauge {
+        fn record_task_started(&self, _: &str) {}
+        fn record_task_completed(&self, _: &str, _: tno_core::TaskOutcome, _: u64) {}
+        fn record_runner_error(&self, _: &str, _: RunnerErrorKind) {}
+
+        fn record_cgroup_created(&self, _: &str) {
+            self.active.fetch_add(1, Ordering::SeqCst);
+            self.created.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn record_cgroup_removed(&self, _: &str) {
+            self.active.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn self_test_detects_broken_backend() {
+        let mut router = tno_core::RunnerRouter::new();
+        router
+            .register(Arc::new(SubprocessRunner::new("plain")))
+            .unwrap();
+        // No file descriptors left for the dynamic loader: `true` cannot start.
+        let broken = SubprocessBackendConfig::new().with_rlimits(crate::utils::RlimitConfig {
+            max_open_files: Some(0),
+            ..Default::default()
+        });
+        router
+            .register(Arc::new(SubprocessRunner::with_config("broken", broken)))
+            .unwrap();
+
+        let results = router.self_test().await;
+        assert_eq!(results[0].0, "plain");
+        assert!(results[0].1.is_ok(), "{:?}", results[0].1);
+        assert_eq!(results[1].0, "broken");
+        assert!(results[1].1.is_err());
+    }
+
+    #[derive(Default)]
+    struct OutcomeRecorder(std::sync::Mutex<Vec<tno_core::TaskOutcome>>);
+
+    impl tno_core::MetricsBackend for OutcomeRecorder {
+        fn record_task_started(&self, _: &str) {}
+        fn record_task_completed(&self, _: &str, outcome: tno_core::TaskOutcome, _: u64) {
+            self.0.lock().unwrap().push(outcome);
+        }
+        fn record_runner_error(&self, _: &str, _: RunnerErrorKind) {}
+    }
+
+    #[tokio::test]
+    async fn cancel_is_success_reports_success_outcome() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let recorder = Arc::new(OutcomeRecorder::default());
+        let ctx = BuildContext::new(TaskEnv::default(), recorder.clone());
+        let runner = SubprocessRunner::new("cancel-test");
+
+        for cancel_is_success in [true, false] {
+            let spec = CreateSpec {
+                slot: "cancel-slot".into(),
+                kind: TaskKind::Subprocess {
+                    command: "sleep".into(),
+                    args: vec!["5".into()],
+                    env: TaskEnv::default(),
+                    env_file: None,
+                    cwd: None,
+                    fail_on_non_zero: Flag::enabled(),
+                },
+                timeout_ms: 10_000,
+                restart: RestartStrategy::Never,
+                backoff: BackoffStrategy {
+                    jitter: JitterStrategy::None,
+                    first_ms: 0,
+                    max_ms: 0,
+                    factor: 1.0,
+                },
+                admission: AdmissionStrategy::DropIfRunning,
+                labels: RunnerLabels::default(),
+                runner_selector: RunnerLabels::default(),
+                cancel_is_success,
+                idempotency_key: None,
+                batch_id: None,
+                schedule: None,
+            };
+            let task = runner.build_task(&spec, &ctx).expect("build failed");
+            let token = CancellationToken::new();
+            let handle = tokio::spawn({
+                let token = token.clone();
+                async move { task.spawn(token).await }
+            });
+            tokio::time::sleep(StdDuration::from_millis(100)).await;
+            token.cancel();
+
+            let res = handle.await.unwrap();
+            assert!(matches!(res, Err(TaskError::Canceled)), "{res:?}");
+        }
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                tno_core::TaskOutcome::Success,
+                tno_core::TaskOutcome::Canceled
+            ]
+        );
+    }
+
+    #[derive(Default)]
+    struct CallRecorder(std::sync::Mutex<Vec<String>>);
+
+    impl tno_core::MetricsBackend for CallRecorder {
+        fn record_task_started(&self, _: &str) {
+            self.0.lock().unwrap().push("started".into());
+        }
+        fn record_task_completed(&self, _: &str, outcome: tno_core::TaskOutcome, _: u64) {
+            self.0.lock().unwrap().push(outcome.as_label().into());
+        }
+        fn record_runner_error(&self, _: &str, error_kind: RunnerErrorKind) {
+            self.0.lock().unwrap().push(error_kind.as_label().into());
+        }
+    }
+
+    #[tokio::test]
+    async fn spawn_failure_is_recorded_as_runner_error() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let recorder = Arc::new(CallRecorder::default());
+        let ctx = BuildContext::new(TaskEnv::default(), recorder.clone());
+        let runner = SubprocessRunner::new("metrics-test");
+
+        let spec = |command: &str| CreateSpec {
+            slot: "metrics-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: command.into(),
+                args: Vec::new(),
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 10_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+
+        for command in ["/nonexistent/tno-missing-binary", "true"] {
+            let task = runner
+                .build_task(&spec(command), &ctx)
+                .expect("build failed");
+            let _ = task.spawn(CancellationToken::new()).await;
+        }
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["spawn_failed", "started", "success"]
+        );
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn redacted_args_are_masked_in_logs_but_passed_to_child() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let out = std::env::temp_dir().join(format!("tno-redact-{}", std::process::id()));
+        let backend = SubprocessBackendConfig::new()
+            .with_arg_redaction(crate::subprocess::ArgRedaction::new().with_flag("--token"));
+        let runner = SubprocessRunner::with_config("redact-test", backend);
+        let spec = CreateSpec {
+            slot: "redact-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sh".into(),
+                args: vec![
+                    "-c".into(),
+                    "printf %s \"$1\" > \"$2\"".into(),
+                    "sh".into(),
+                    "--token=secret".into(),
+                    out.display().to_string(),
+                ],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let ctx = BuildContext::new(TaskEnv::default(), tno_core::noop_metrics());
+        let task = runner.build_task(&spec, &ctx).expect("build failed");
+        task.spawn(CancellationToken::new())
+            .await
+            .expect("task failed");
+
+        let received = tokio::fs::read_to_string(&out).await.unwrap();
+        let _ = tokio::fs::remove_file(&out).await;
+        assert_eq!(received, "--token=secret");
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let spawn_line = logs
+            .lines()
+            .find(|l| l.contains("spawning subprocess"))
+            .expect("spawn trace not logged");
+        assert!(spawn_line.contains("--token=***"), "{spawn_line}");
+        assert!(!logs.contains("secret"), "{logs}");
+    }
+
+    #[tokio::test]
+    async fn active_cgroups_gauge_returns_to_zero() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        if !std::path::Path::new("/sys/fs/cgroup/cgroup.controllers").is_file() {
+            eprintln!("cgroup v2 is not available; skipping");
+            return;
+        }
+
+        let gauge = Arc::new(CgroupGauge::default());
+        let ctx = BuildContext::new(TaskEnv::default(), gauge.clone());
+        let backend = SubprocessBackendConfig::new().with_cgroups(crate::utils::CgroupLimits {
+            pids: Some(64),
+            ..Default::default()
+        });
+        let runner = SubprocessRunner::with_config("cg-test", backend);
+
+        for _ in 0..3 {
+            let spec = CreateSpec {
+                slot: "cg-slot".into(),
+                kind: TaskKind::Subprocess {
+                    command: "true".into(),
+                    args: Vec::new(),
+                    env: TaskEnv::default(),
+                    env_file: None,
+                    cwd: None,
+                    fail_on_non_zero: Flag::enabled(),
+                },
+                timeout_ms: 5_000,
+                restart: RestartStrategy::Never,
+                backoff: BackoffStrategy {
+                    jitter: JitterStrategy::None,
+                    first_ms: 0,
+                    max_ms: 0,
+                    factor: 1.0,
+                },
+                admission: AdmissionStrategy::DropIfRunning,
+                labels: RunnerLabels::default(),
+                runner_selector: RunnerLabels::default(),
+                cancel_is_success: false,
+                idempotency_key: None,
+                batch_id: None,
+                schedule: None,
+            };
+            let task = runner.build_task(&spec, &ctx).expect("build failed");
+            task.spawn(CancellationToken::new())
+                .await
+                .expect("task failed");
+        }
+
+        assert_eq!(gauge.active.load(Ordering::SeqCst), 0);
+        if gauge.created.load(Ordering::SeqCst) == 0 {
+            eprintln!("cgroups could not be created (insufficient privileges?)");
+        }
+    }
+
+    #[derive(Default)]
+    struct InFlightRecorder {
+        max: std::sync::atomic::AtomicU64,
+        last: std::sync::atomic::AtomicU64,
+    }
+
+    impl tno_core::MetricsBackend for InFlightRecorder {
+        fn record_task_started(&self, _: &str) {}
+        fn record_task_completed(&self, _: &str, _: tno_core::TaskOutcome, _: u64) {}
+        fn record_runner_error(&self, _: &str, _: RunnerErrorKind) {}
+        fn record_output_bytes_in_flight(&self, _: &str, bytes: u64) {
+            self.max.fetch_max(bytes, Ordering::SeqCst);
+            self.last.store(bytes, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn output_budget_bounds_in_flight_bytes() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        const TASKS: usize = 4;
+        const LINES: usize = 2_000;
+        const LINE_LEN: usize = 99;
+        const BUDGET: usize = 256;
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let recorder = Arc::new(InFlightRecorder::default());
+        let ctx = BuildContext::new(TaskEnv::default(), recorder.clone());
+        let budget = crate::subprocess::OutputBudget::new(BUDGET);
+        let backend = SubprocessBackendConfig::new()
+            .with_logger(LogConfig {
+                max_line_length: 0,
+                ..Default::default()
+            })
+            .with_output_budget(budget.clone());
+        let runner = SubprocessRunner::with_config("budget-test", backend);
+
+        let mut handles = Vec::new();
+        for _ in 0..TASKS {
+            let spec = CreateSpec {
+                slot: "budget-slot".into(),
+                kind: TaskKind::Subprocess {
+                    command: "sh".into(),
+                    args: vec![
+                        "-c".into(),
+                        format!("yes {} | head -n {LINES}", "z".repeat(LINE_LEN)),
+                    ],
+                    env: TaskEnv::default(),
+                    env_file: None,
+                    cwd: None,
+                    fail_on_non_zero: Flag::enabled(),
+                },
+                timeout_ms: 30_000,
+                restart: RestartStrategy::Never,
+                backoff: BackoffStrategy {
+                    jitter: JitterStrategy::None,
+                    first_ms: 0,
+                    max_ms: 0,
+                    factor: 1.0,
+                },
+                admission: AdmissionStrategy::DropIfRunning,
+                labels: RunnerLabels::default(),
+                runner_selector: RunnerLabels::default(),
+                cancel_is_success: false,
+                idempotency_key: None,
+                batch_id: None,
+                schedule: None,
+            };
+            let task = runner.build_task(&spec, &ctx).expect("build failed");
+            handles.push(tokio::spawn(async move {
+                task.spawn(CancellationToken::new()).await
+            }));
+        }
+        for handle in handles {
+            tokio::time::timeout(StdDuration::from_secs(30), handle)
+                .await
+                .expect("output did not complete")
+                .unwrap()
+                .expect("task failed");
+        }
+
+        let max = recorder.max.load(Ordering::SeqCst);
+        assert!(max > 0, "budget was never used");
+        assert!(
+            max <= BUDGET as u64,
+            "in-flight bytes exceeded budget: {max}"
+        );
+        assert_eq!(recorder.last.load(Ordering::SeqCst), 0);
+        assert_eq!(budget.in_flight(), 0);
+
+        let logs = logs.0.lock().unwrap();
+        let logged = logs.iter().filter(|b| **b == b'z').count();
+        assert_eq!(logged, TASKS * LINES * LINE_LEN);
+    }
+
+    #[tokio::test]
+    async fn failing_pre_spawn_hook_prevents_spawn() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let marker = std::env::temp_dir().join(format!("tno-prespawn-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let seen = Arc::new(std::sync::Mutex::new(None));
+        let backend = SubprocessBackendConfig::new().with_pre_spawn({
+            let seen = Arc::clone(&seen);
+            move |cfg: &SubprocessTaskConfig| {
+                *seen.lock().unwrap() = Some(cfg.command().to_string());
+                Err(crate::ExecError::Internal("secret unavailable".into()))
+            }
+        });
+        let runner = SubprocessRunner::with_config("pre-spawn-test", backend);
+        let ctx = BuildContext::new(TaskEnv::default(), Arc::new(tno_core::NoOpMetrics));
+
+        let spec = CreateSpec {
+            slot: "pre-spawn-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "touch".into(),
+                args: vec![marker.display().to_string()],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task = runner.build_task(&spec, &ctx).expect("build failed");
+        let res = task.spawn(CancellationToken::new()).await;
+
+        match res {
+            Err(TaskError::Fatal { reason }) => assert!(reason.contains("secret unavailable")),
+            other => panic!("expected fatal error, got {other:?}"),
+        }
+        assert_eq!(seen.lock().unwrap().as_deref(), Some("touch"));
+        assert!(!marker.exists(), "subprocess must not be spawned");
+    }
+
+    #[tokio::test]
+    async fn spawn_failure_is_listed_in_recent_runner_errors() {
+        use taskvisor::{ControllerConfig, SupervisorConfig};
+        use tno_core::{RunnerRouter, SupervisorApi};
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let mut router = RunnerRouter::new();
+        router
+            .register(Arc::new(SubprocessRunner::new("spawn-error-test")))
+            .unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "spawn-error-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "/nonexistent/tno-test-binary".into(),
+                args: Vec::new(),
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task_id = api.submit(&spec).await.expect("submit failed").task_id;
+
+        let error = tokio::time::timeout(StdDuration::from_secs(5), async {
+            loop {
+                if let Some(error) = api.recent_runner_errors().into_iter().next() {
+                    return error;
+                }
+                tokio::time::sleep(StdDuration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("spawn failure was not recorded");
+
+        assert_eq!(error.runner, RUNNER_TYPE_SUBPROCESS);
+        assert_eq!(error.kind, "spawn_failed");
+        assert_eq!(error.task_id, Some(task_id));
+        assert!(error.message.starts_with("spawn failed:"));
+    }
+
+    #[tokio::test]
+    async fn sub_second_timeout_kills_process_and_reports_timeout() {
+        use taskvisor::{ControllerConfig, SupervisorConfig};
+        use tno_core::{RunnerRouter, SupervisorApi};
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv, TaskStatus,
+        };
+
+        let mut router = RunnerRouter::new();
+        router
+            .register(Arc::new(SubprocessRunner::new("timeout-test")))
+            .unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "timeout-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".into(),
+                args: vec!["1".into()],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 200,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let mut events = api.subscribe_events(false);
+        let started = Instant::now();
+        let task_id = api.submit(&spec).await.expect("submit failed").task_id;
+
+        let info = api
+            .wait_for_status(&task_id, TaskStatus::Timeout, StdDuration::from_secs(5))
+            .await
+            .expect("task did not finish");
+        assert_eq!(info.status, TaskStatus::Timeout);
+        assert!(
+            started.elapsed() >= StdDuration::from_millis(200),
+            "timeout fired after {:?}",
+            started.elapsed()
+        );
+
+        // Exhausting the `Never` restart policy afterwards keeps the timeout distinct from a failure.
+        // The supervisor removes the task only after its actor reported `ActorExhausted`.
+        tokio::time::timeout(StdDuration::from_secs(5), async {
+            while let Some(event) = events.recv().await {
+                if event.kind == taskvisor::EventKind::TaskRemoved
+                    && event.task.as_deref() == Some(task_id.as_str())
+                {
+                    return;
+                }
+            }
+        })
+        .await
+        .expect("task was not removed from the supervisor");
+        assert_eq!(api.get_task(&task_id).unwrap().status, TaskStatus::Timeout);
+    }
+
+    #[test]
+    fn missing_cwd_fails_at_build_time() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let spec_with_cwd = |cwd: &str| CreateSpec {
+            slot: "cwd-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "true".into(),
+                args: Vec::new(),
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: Some(cwd.into()),
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let ctx = BuildContext::default();
+        let runner = SubprocessRunner::new("cwd-test");
+
+        let missing = spec_with_cwd("/nonexistent/tno-test-dir");
+        let err = runner
+            .build_task(&missing, &ctx)
+            .err()
+            .expect("missing cwd accepted");
+        assert!(
+            err.to_string().contains("/nonexistent/tno-test-dir"),
+            "{err}"
+        );
+
+        let file = spec_with_cwd("/proc/self/status");
+        let err = runner
+            .build_task(&file, &ctx)
+            .err()
+            .expect("file cwd accepted");
+        assert!(err.to_string().contains("is not a directory"), "{err}");
+
+        assert!(runner.build_task(&spec_with_cwd("/"), &ctx).is_ok());
+
+        let unchecked = SubprocessRunner::with_config(
+            "cwd-unchecked",
+            SubprocessBackendConfig::new().without_cwd_check(),
+        );
+        assert!(unchecked.build_task(&missing, &ctx).is_ok());
+    }
+
+    #[test]
+    fn command_policy_is_enforced_at_build_time() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let spec_with_command = |command: &str| CreateSpec {
+            slot: "cmd-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: command.into(),
+                args: Vec::new(),
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let ctx = BuildContext::default();
+
+        let absolute = SubprocessRunner::with_config(
+            "abs-only",
+            SubprocessBackendConfig::new().with_require_absolute_command(true),
+        );
+        assert!(
+            absolute
+                .build_task(&spec_with_command("/bin/true"), &ctx)
+                .is_ok()
+        );
+        let err = absolute
+            .build_task(&spec_with_command("true"), &ctx)
+            .err()
+            .expect("relative command accepted");
+        assert!(
+            err.to_string().contains("must be an absolute path"),
+            "{err}"
+        );
+
+        let backend = SubprocessBackendConfig::new()
+            .with_require_absolute_command(true)
+            .with_command_allowlist(["/bin/true", "/bin/echo"]);
+        backend.validate().unwrap();
+        let allowlisted = SubprocessRunner::with_config("allowlisted", backend);
+        assert!(
+            allowlisted
+                .build_task(&spec_with_command("/bin/echo"), &ctx)
+                .is_ok()
+        );
+        let err = allowlisted
+            .build_task(&spec_with_command("/usr/bin/curl"), &ctx)
+            .err()
+            .expect("command outside the allowlist accepted");
+        assert!(
+            err.to_string()
+                .contains("'/usr/bin/curl' is not in the runner's command allowlist"),
+            "{err}"
+        );
+
+        let inconsistent = SubprocessBackendConfig::new()
+            .with_require_absolute_command(true)
+            .with_command_allowlist(["true"]);
+        assert!(inconsistent.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn env_policy_keeps_denied_keys_from_the_child() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let spec_with_script = |script: &str| CreateSpec {
+            slot: "env-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "/bin/sh".into(),
+                args: vec!["-c".into(), script.into()],
+                env: TaskEnv::single("TASK_VAR", "task"),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let mut agent_env = TaskEnv::single("AWS_SECRET_ACCESS_KEY", "secret");
+        agent_env.push("LANG", "C.UTF-8");
+        let ctx = BuildContext::new(agent_env, tno_core::noop_metrics());
+        let run = |policy: EnvPolicy, script: &str| {
+            let runner = SubprocessRunner::with_config(
+                "env-test",
+                SubprocessBackendConfig::new().with_env_policy(policy),
+            );
+            let task = runner
+                .build_task(&spec_with_script(script), &ctx)
+                .expect("build failed");
+            task.spawn(CancellationToken::new())
+        };
+
+        // Without a policy the agent environment leaks into the task.
+        run(
+            EnvPolicy::Inherit,
+            r#"test "$AWS_SECRET_ACCESS_KEY" = secret"#,
+        )
+        .await
+        .expect("inherited env missing");
+        run(
+            EnvPolicy::allow(["LANG"]),
+            r#"test -z "$AWS_SECRET_ACCESS_KEY" && test "$LANG" = C.UTF-8 && test "$TASK_VAR" = task"#,
+        )
+        .await
+        .expect("allowlist not applied");
+        run(
+            EnvPolicy::Clear,
+            // `HOME` comes from the agent process environment.
+            r#"test -z "$AWS_SECRET_ACCESS_KEY" && test -z "$LANG" && test -z "$HOME" && test "$TASK_VAR" = task"#,
+        )
+        .await
+        .expect("inherited env not cleared");
+    }
+
+    #[test]
+    fn env_file_is_merged_under_inline_env() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let dir = std::env::temp_dir().join(format!("tno-env-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("task.env");
+        std::fs::write(&path, "# secrets\nDB_USER=admin\nDB_PASS=\"from file\"\n").unwrap();
+
+        let spec = |env_file: &Path| CreateSpec {
+            slot: "env-file".into(),
+            kind: TaskKind::Subprocess {
+                command: "true".into(),
+                args: Vec::new(),
+                env: TaskEnv::single("DB_PASS", "inline"),
+                env_file: Some(env_file.to_path_buf()),
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 1_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let runner = SubprocessRunner::new("env-file-test");
+        let ctx = BuildContext::default();
+
+        let cfg = runner.build_task_config(&spec(&path), &ctx).unwrap();
+        assert_eq!(cfg.env.get("DB_USER"), Some("admin"));
+        assert_eq!(cfg.env.get("DB_PASS"), Some("inline"));
+
+        std::fs::write(&path, "DB_USER=admin\nDB_PASS='unterminated\n").unwrap();
+        let err = runner.build_task_config(&spec(&path), &ctx).unwrap_err();
+        assert!(
+            matches!(err, RunnerError::InvalidSpec(ref msg) if msg.contains("line 2")),
+            "{err:?}"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn cgroup_name_is_reported_in_task_info() {
+        use taskvisor::{ControllerConfig, SupervisorConfig};
+        use tno_core::{RunnerRouter, SupervisorApi};
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        // A plain directory that looks like a cgroup v2 root is enough for the cgroup to be "created".
+        let root = std::env::temp_dir().join(format!("tno-cgroup-field-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("cgroup.controllers"), "pids\n").unwrap();
+
+        let backend = SubprocessBackendConfig::new()
+            .with_cgroups(crate::utils::CgroupLimits {
+                pids: Some(64),
+                ..Default::default()
+            })
+            .with_cgroup_root(&root);
+        let mut router = RunnerRouter::new();
+        router
+            .register(Arc::new(SubprocessRunner::with_config("cg-field", backend)))
+            .unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "cg-field-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".into(),
+                args: vec!["5".into()],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 10_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task_id = api.submit(&spec).await.expect("submit failed").task_id;
+
+        let cgroup = tokio::time::timeout(StdDuration::from_secs(5), async {
+            loop {
+                if let Some(cgroup) = api.get_task(&task_id).and_then(|t| t.cgroup) {
+                    return cgroup;
+                }
+                tokio::time::sleep(StdDuration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("cgroup was not reported");
+        api.cancel_task(&task_id).await.expect("cancel failed");
+
+        assert!(cgroup.starts_with("cg-field-cg-field-slot-"), "{cgroup}");
+        assert!(root.join(&cgroup).is_dir());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn explicit_ids_get_distinct_cgroups() {
+        use taskvisor::{ControllerConfig, SupervisorConfig};
+        use tno_core::{RunnerRouter, SupervisorApi};
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv, TaskId,
+        };
+
+        let root = std::env::temp_dir().join(format!("tno-cgroup-ids-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("cgroup.controllers"), "pids\n").unwrap();
+
+        let backend = SubprocessBackendConfig::new()
+            .with_cgroups(crate::utils::CgroupLimits {
+                pids: Some(64),
+                ..Default::default()
+            })
+            .with_cgroup_root(&root);
+        let mut router = RunnerRouter::new();
+        router
+            .register(Arc::new(SubprocessRunner::with_config("cg-ids", backend)))
+            .unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        let spec = CreateSpec {
+            slot: "cg-ids-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sleep".into(),
+                args: vec!["5".into()],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 10_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+
+        // Neither id ends in a hex sequence, and both end in the same `-1` segment.
+        let mut cgroups = Vec::new();
+        for id in ["order-1", "invoice-1"] {
+            let task_id = api
+                .submit_with_id(TaskId::from(id), &spec)
+                .await
+                .expect("submit failed")
+                .task_id;
+            let cgroup = tokio::time::timeout(StdDuration::from_secs(5), async {
+                loop {
+                    if let Some(cgroup) = api.get_task(&task_id).and_then(|t| t.cgroup) {
+                        return cgroup;
+                    }
+                    tokio::time::sleep(StdDuration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("cgroup was not reported");
+            api.cancel_task(&task_id).await.expect("cancel failed");
+            cgroups.push(cgroup);
+        }
+
+        assert_ne!(cgroups[0], cgroups[1]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn memory_oom_is_reported_as_limit_cause() {
+        use taskvisor::{ControllerConfig, SupervisorConfig};
+        use tno_core::{RunnerRouter, SupervisorApi};
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv, TaskStatus,
+        };
+
+        if !std::path::Path::new("/sys/fs/cgroup/cgroup.controllers").is_file() {
+            eprintln!("cgroup v2 is not available; skipping");
+            return;
+        }
+
+        let backend = SubprocessBackendConfig::new().with_cgroups(crate::utils::CgroupLimits {
+            memory: Some(16 * 1024 * 1024),
+            ..Default::default()
+        });
+        let mut router = RunnerRouter::new();
+        router
+            .register(Arc::new(SubprocessRunner::with_config("oom-test", backend)))
+            .unwrap();
+        let api = SupervisorApi::new(
+            SupervisorConfig::default(),
+            ControllerConfig::default(),
+            Vec::new(),
+            router,
+        )
+        .await
+        .expect("failed to create SupervisorApi");
+
+        // `tail` buffers the whole newline-free input, far beyond the memory cap.
+        let spec = CreateSpec {
+            slot: "oom-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sh".into(),
+                args: vec![
+                    "-c".into(),
+                    "head -c 268435456 /dev/zero | tail -n 1 > /dev/null".into(),
+                ],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 30_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task_id = api.submit(&spec).await.expect("submit failed").task_id;
+
+        let info = api
+            .wait_for_status(&task_id, TaskStatus::Failed, StdDuration::from_secs(30))
+            .await
+            .expect("task did not finish in time");
+        if info.cgroup.is_none() {
+            eprintln!("cgroups could not be created (insufficient privileges?); skipping");
+            return;
+        }
+        if info.status == TaskStatus::Succeeded {
+            eprintln!("memory limit was not enforced (swap?); skipping");
+            return;
+        }
+        // The cause is reported right after the attempt ends, slightly after the status may change.
+        let cause = tokio::time::timeout(StdDuration::from_secs(5), async {
+            loop {
+                if let Some(cause) = api.get_task(&task_id).and_then(|t| t.limit_cause) {
+                    return cause;
+                }
+                tokio::time::sleep(StdDuration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("limit cause was not reported");
+        assert_eq!(cause, "oom");
+    }
+
+    #[tokio::test]
+    async fn post_run_hook_receives_outcome() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let backend = SubprocessBackendConfig::new().with_post_run(
+            move |cfg: &SubprocessTaskConfig, outcome: &tno_core::TaskOutcome| {
+                let _ = tx.send((cfg.command().to_string(), *outcome));
+            },
+        );
+        let runner = SubprocessRunner::with_config("post-run-test", backend);
+        let ctx = BuildContext::new(TaskEnv::default(), Arc::new(tno_core::NoOpMetrics));
+
+        for (command, expected) in [
+            ("true", tno_core::TaskOutcome::Success),
+            ("false", tno_core::TaskOutcome::Failure),
+        ] {
+            let spec = CreateSpec {
+                slot: "post-run-slot".into(),
+                kind: TaskKind::Subprocess {
+                    command: command.into(),
+                    args: Vec::new(),
+                    env: TaskEnv::default(),
+                    env_file: None,
+                    cwd: None,
+                    fail_on_non_zero: Flag::enabled(),
+                },
+                timeout_ms: 5_000,
+                restart: RestartStrategy::Never,
+                backoff: BackoffStrategy {
+                    jitter: JitterStrategy::None,
+                    first_ms: 0,
+                    max_ms: 0,
+                    factor: 1.0,
+                },
+                admission: AdmissionStrategy::DropIfRunning,
+                labels: RunnerLabels::default(),
+                runner_selector: RunnerLabels::default(),
+                cancel_is_success: false,
+                idempotency_key: None,
+                batch_id: None,
+                schedule: None,
+            };
+            let task = runner.build_task(&spec, &ctx).expect("build failed");
+            let _ = task.spawn(CancellationToken::new()).await;
+
+            let fired = tokio::time::timeout(StdDuration::from_secs(5), rx.recv())
+                .await
+                .expect("post-run hook did not fire")
+                .unwrap();
+            assert_eq!(fired, (command.to_string(), expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn captured_output_is_bounded_and_stream_fully_drained() {
+        let output = "line\n".repeat(1_000);
+        let budget = crate::subprocess::OutputBudget::new(64);
+
+        let sink = LineSink {
+            run_id: "capture-test".into(),
+            stream: "stdout",
+            config: Arc::new(LogConfig::default()),
+            file: None,
+        };
+
+        let captured = log_stream(
+            output.as_bytes(),
+            &sink,
+            Some(&budget),
+            Some(OutputCapture::new(12)),
+            &tno_core::NoOpMetrics,
+        )
+        .await;
+
+        let dropped = output.len() - 12;
+        assert_eq!(
+            captured.as_deref(),
+            Some(format!("line\nline\nli... (truncated {dropped} bytes)").as_str())
+        );
+        assert_eq!(budget.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn task_files_receive_both_streams() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let dir = std::env::temp_dir().join(format!("tno-task-files-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let backend = SubprocessBackendConfig::new().with_logger(LogConfig {
+            task_files: Some(crate::subprocess::TaskLogFiles::new(&dir)),
+            ..Default::default()
+        });
+        let runner = SubprocessRunner::with_config("files-test", backend);
+        let ctx = BuildContext::default();
+
+        let spec = CreateSpec {
+            slot: "files-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sh".into(),
+                args: vec!["-c".into(), "echo to-out; echo to-err >&2".into()],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task = runner.build_task(&spec, &ctx).expect("build failed");
+        let run_id = task.name().to_string();
+        task.spawn(CancellationToken::new())
+            .await
+            .expect("task failed");
+
+        let contents = std::fs::read_to_string(dir.join(format!("{run_id}.log"))).unwrap();
+        assert!(contents.contains("stdout: to-out\n"), "{contents}");
+        assert!(contents.contains("stderr: to-err\n"), "{contents}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn secret_files_are_private_during_task_and_removed_after() {
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, Flag, JitterStrategy, RestartStrategy,
+            RunnerLabels, TaskEnv,
+        };
+
+        let out = std::env::temp_dir().join(format!("tno-secret-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&out);
+        std::fs::create_dir_all(&out).unwrap();
+
+        let backend = SubprocessBackendConfig::new().with_secret_file("API_TOKEN", "hunter2");
+        backend.validate().unwrap();
+        let runner = SubprocessRunner::with_config("secret-test", backend);
+        let script = format!(
+            "echo \"$API_TOKEN_FILE\" > {dir}/path; stat -c %a \"$API_TOKEN_FILE\" > {dir}/mode; \
+             cat \"$API_TOKEN_FILE\" > {dir}/contents",
+            dir = out.display()
+        );
+        let spec = CreateSpec {
+            slot: "secret-slot".into(),
+            kind: TaskKind::Subprocess {
+                command: "sh".into(),
+                args: vec!["-c".into(), script],
+                env: TaskEnv::default(),
+                env_file: None,
+                cwd: None,
+                fail_on_non_zero: Flag::enabled(),
+            },
+            timeout_ms: 5_000,
+            restart: RestartStrategy::Never,
+            backoff: BackoffStrategy {
+                jitter: JitterStrategy::None,
+                first_ms: 0,
+                max_ms: 0,
+                factor: 1.0,
+            },
+            admission: AdmissionStrategy::DropIfRunning,
+            labels: RunnerLabels::default(),
+            runner_selector: RunnerLabels::default(),
+            cancel_is_success: false,
+            idempotency_key: None,
+            batch_id: None,
+            schedule: None,
+        };
+        let task = runner
+            .build_task(&spec, &BuildContext::default())
+            .expect("build failed");
+        task.spawn(CancellationToken::new())
+            .await
+            .expect("task failed");
+
+        let read = |name: &str| std::fs::read_to_string(out.join(name)).unwrap();
+        let path = read("path");
+        assert_eq!(read("mode").trim(), "600");
+        assert_eq!(read("contents"), "hunter2");
+        assert!(
+            !Path::new(path.trim()).exists(),
+            "secret file was not removed"
+        );
+        let _ = std::fs::remove_dir_all(&out);
+    }
+
+    #[tokio::test]
+    async fn structured_lines_are_logged_with_fields() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let sink = LineSink {
+            run_id: "structured-test".into(),
+            stream: "stdout",
+            config: Arc::new(LogConfig {
+                parse_structured: true,
+                ..Default::default()
+            }),
+            file: None,
+        };
+        let output = "{\"level\":\"warn\",\"msg\":\"disk low\",\"free_mb\":12}\nplain line\n";
+        log_stream(output.as_bytes(), &sink, None, None, &tno_core::NoOpMetrics).await;
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let structured = logs.lines().find(|l| l.contains("disk low")).unwrap();
+        assert!(structured.contains("WARN"), "{structured}");
+        assert!(
+            structured.contains(r#"fields={"free_mb":12}"#),
+            "{structured}"
+        );
+        assert!(
+            logs.lines()
+                .any(|l| l.contains("plain line") && l.contains("INFO"))
+        );
+    }
 }
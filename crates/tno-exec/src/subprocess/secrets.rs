@@ -0,0 +1,180 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use tracing::debug;
+
+/// Preferred base directory for secret files (memory-backed on most Linux systems).
+const SHM_DIR: &str = "/dev/shm";
+
+/// Distinguishes secret directories of attempts that share a run id.
+static SECRET_DIR_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Secret passed to a subprocess as a file instead of an environment variable.
+///
+/// Before each spawn the contents are written to a private file (mode `0600`) and only its path is exposed,
+/// as `<NAME>_FILE`; the file is removed once the attempt ends.
+/// Unlike an environment variable, the value does not show up in `/proc/<pid>/environ`.
+#[derive(Clone)]
+pub(crate) struct SecretFile {
+    pub(crate) name: String,
+    pub(crate) contents: Arc<[u8]>,
+}
+
+impl SecretFile {
+    /// Name of the environment variable holding the file path.
+    pub(crate) fn env_key(&self) -> String {
+        format!("{}_FILE", self.name)
+    }
+}
+
+impl fmt::Debug for SecretFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretFile")
+            .field("name", &self.name)
+            .field("contents", &super::REDACTED)
+            .finish()
+    }
+}
+
+/// Whether `name` is a valid secret name (`[A-Z0-9_]+`, not starting with a digit).
+pub(crate) fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Private directory holding the secret files of one attempt; removed on drop.
+#[derive(Debug)]
+pub(crate) struct SecretDir {
+    path: PathBuf,
+}
+
+impl SecretDir {
+    /// Create a private directory under `base` (default: `/dev/shm` if present, else the temp dir)
+    /// and write every secret into it.
+    ///
+    /// Returns the directory guard and the `(<NAME>_FILE, path)` pairs to add to the environment.
+    pub(crate) fn create(
+        base: Option<&Path>,
+        run_id: &str,
+        secrets: &[SecretFile],
+    ) -> io::Result<(Self, Vec<(String, PathBuf)>)> {
+        let base = match base {
+            Some(base) => base.to_path_buf(),
+            None if Path::new(SHM_DIR).is_dir() => PathBuf::from(SHM_DIR),
+            None => std::env::temp_dir(),
+        };
+        let seq = SECRET_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+        let stem: String = run_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let path = base.join(format!("tno-secrets-{stem}-{}-{seq}", std::process::id()));
+
+        create_private_dir(&path)?;
+        let dir = Self { path };
+
+        let mut env = Vec::with_capacity(secrets.len());
+        for secret in secrets {
+            let file = dir.path.join(&secret.name);
+            write_private_file(&file, &secret.contents)?;
+            env.push((secret.env_key(), file));
+        }
+        Ok((dir, env))
+    }
+
+    /// Directory path.
+    #[cfg(test)]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SecretDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            debug!(path = %self.path.display(), "failed to remove secret directory: {e}");
+        }
+    }
+}
+
+#[cfg(unix)]
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    fs::DirBuilder::new().mode(0o700).create(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    fs::create_dir(path)
+}
+
+#[cfg(unix)]
+fn write_private_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::{io::Write, os::unix::fs::OpenOptionsExt};
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_names_are_env_safe() {
+        assert!(is_valid_secret_name("DB_PASSWORD"));
+        assert!(is_valid_secret_name("TOKEN2"));
+        for bad in ["", "db_password", "2FA", "API-KEY", "A B"] {
+            assert!(!is_valid_secret_name(bad), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn debug_does_not_print_contents() {
+        let secret = SecretFile {
+            name: "TOKEN".into(),
+            contents: Arc::from(&b"hunter2"[..]),
+        };
+        assert!(!format!("{secret:?}").contains("hunter2"));
+    }
+
+    #[test]
+    fn dir_is_removed_on_drop() {
+        let secret = SecretFile {
+            name: "TOKEN".into(),
+            contents: Arc::from(&b"hunter2"[..]),
+        };
+        let (dir, env) = SecretDir::create(None, "task-1", &[secret]).unwrap();
+        assert_eq!(env[0].0, "TOKEN_FILE");
+        assert_eq!(fs::read(&env[0].1).unwrap(), b"hunter2");
+
+        let path = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,84 @@
+use serde_json::{Map, Value};
+use tracing::Level;
+
+/// Keys recognized as the line's message.
+const MESSAGE_KEYS: [&str; 2] = ["msg", "message"];
+/// Keys recognized as the line's severity.
+const LEVEL_KEYS: [&str; 3] = ["level", "lvl", "severity"];
+
+/// Subprocess output line that parsed as a JSON object.
+#[derive(Debug)]
+pub(crate) struct StructuredLine {
+    /// Severity taken from the line (if recognized).
+    pub(crate) level: Option<Level>,
+    /// Message taken from the line (empty if absent).
+    pub(crate) message: String,
+    /// Remaining keys as compact JSON (`{}` if none).
+    pub(crate) fields: String,
+}
+
+/// Parse `line` as a JSON object; anything else (including JSON arrays or scalars) yields `None`.
+pub(crate) fn parse_json_line(line: &str) -> Option<StructuredLine> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    let mut object: Map<String, Value> = serde_json::from_str(line).ok()?;
+
+    let message = take_first(&mut object, &MESSAGE_KEYS)
+        .map(|v| match v {
+            Value::String(s) => s,
+            other => other.to_string(),
+        })
+        .unwrap_or_default();
+    let level = take_first(&mut object, &LEVEL_KEYS).and_then(|v| v.as_str().and_then(parse_level));
+
+    Some(StructuredLine {
+        level,
+        message,
+        fields: Value::Object(object).to_string(),
+    })
+}
+
+/// Remove and return the value of the first present key.
+fn take_first(object: &mut Map<String, Value>, keys: &[&str]) -> Option<Value> {
+    keys.iter().find_map(|k| object.remove(*k))
+}
+
+fn parse_level(s: &str) -> Option<Level> {
+    match s.to_ascii_lowercase().as_str() {
+        "trace" => Some(Level::TRACE),
+        "debug" => Some(Level::DEBUG),
+        "info" => Some(Level::INFO),
+        "warn" | "warning" => Some(Level::WARN),
+        "error" | "fatal" | "critical" => Some(Level::ERROR),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_object_with_message_and_level() {
+        let line = parse_json_line(r#"{"level":"WARN","msg":"disk low","free_mb":12}"#).unwrap();
+        assert_eq!(line.level, Some(Level::WARN));
+        assert_eq!(line.message, "disk low");
+        assert_eq!(line.fields, r#"{"free_mb":12}"#);
+    }
+
+    #[test]
+    fn unknown_level_is_kept_out_of_fields() {
+        let line = parse_json_line(r#"{"severity":"notice","message":"hi"}"#).unwrap();
+        assert_eq!(line.level, None);
+        assert_eq!(line.fields, "{}");
+    }
+
+    #[test]
+    fn non_objects_fall_back() {
+        for input in ["plain text", "[1,2]", "42", r#"{"broken": "#, ""] {
+            assert!(parse_json_line(input).is_none(), "{input:?}");
+        }
+    }
+}
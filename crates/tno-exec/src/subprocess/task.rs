@@ -1,8 +1,12 @@
-use std::{fmt, path::PathBuf};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use tno_model::{Flag, TaskEnv};
 
-use crate::ExecError;
+use crate::{ExecError, subprocess::command::CommandPolicy};
 
 /// Task configuration for a subprocess.
 ///
@@ -17,27 +21,104 @@ pub struct SubprocessTaskConfig {
     pub(crate) args: Vec<String>,
     /// Environment for the subprocess.
     pub(crate) env: TaskEnv,
+    /// Whether the subprocess starts from an empty environment instead of the agent process environment.
+    pub(crate) clear_env: bool,
     /// Working directory for the subprocess.
     ///
     /// If `None`, the subprocess inherits the parent process working directory.
     pub(crate) cwd: Option<PathBuf>,
+    /// Whether [`SubprocessTaskConfig::validate`] checks `cwd` on the filesystem.
+    pub(crate) check_cwd: bool,
+    /// Commands the runner may execute.
+    pub(crate) command_policy: CommandPolicy,
     /// Whether non-zero exit codes should be treated as task failures.
     pub(crate) fail_on_non_zero: Flag,
+    /// Whether a cancellation is reported as a successful outcome.
+    pub(crate) cancel_is_success: bool,
+    /// Per-attempt timeout enforced by the supervisor (`CreateSpec::timeout_ms`).
+    pub(crate) timeout: Duration,
 }
 
 impl SubprocessTaskConfig {
+    /// End-to-End log identifier of this run.
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Command to execute.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Command-line arguments.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Resolved environment (runner defaults allowed by the runner's [`crate::subprocess::EnvPolicy`] merged with the task env).
+    pub fn env(&self) -> &TaskEnv {
+        &self.env
+    }
+
+    /// Working directory (`None` = inherited from the parent).
+    pub fn cwd(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
     /// Validate the configuration before spawning a subprocess.
     ///
     /// Rules:
-    /// - `command` is not empty or whitespace-only.
+    /// - `command` is not empty or whitespace-only;
+    /// - `command` is absolute and/or on the allowlist, if the runner requires it
+    ///   (see [`crate::subprocess::SubprocessBackendConfig::with_require_absolute_command`]
+    ///   and [`crate::subprocess::SubprocessBackendConfig::with_command_allowlist`]);
+    /// - `cwd` (if set) is an existing directory the runner may enter.
+    ///   Skipped if the runner was configured with [`crate::subprocess::SubprocessBackendConfig::without_cwd_check`].
     pub fn validate(&self) -> Result<(), ExecError> {
         if self.command.trim().is_empty() {
             return Err(ExecError::InvalidSpec("Subprocess command is empty".into()));
         }
+        self.command_policy.check(&self.command)?;
+        if self.check_cwd
+            && let Some(cwd) = &self.cwd
+        {
+            check_cwd(cwd)?;
+        }
         Ok(())
     }
 }
 
+/// Check that `cwd` exists, is a directory and can be entered.
+fn check_cwd(cwd: &Path) -> Result<(), ExecError> {
+    let meta = std::fs::metadata(cwd).map_err(|e| {
+        ExecError::InvalidRunnerConfig(format!(
+            "working directory {} is not accessible: {e}",
+            cwd.display()
+        ))
+    })?;
+    if !meta.is_dir() {
+        return Err(ExecError::InvalidRunnerConfig(format!(
+            "working directory {} is not a directory",
+            cwd.display()
+        )));
+    }
+    #[cfg(unix)]
+    {
+        use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+        if let Ok(path) = CString::new(cwd.as_os_str().as_bytes())
+            && unsafe { libc::access(path.as_ptr(), libc::X_OK) } != 0
+        {
+            return Err(ExecError::InvalidRunnerConfig(format!(
+                "working directory {} is not accessible: {}",
+                cwd.display(),
+                std::io::Error::last_os_error()
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl fmt::Display for SubprocessTaskConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -0,0 +1,148 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Per-task output files (see [`crate::subprocess::LogConfig::task_files`]).
+///
+/// Each task writes its stdout and stderr to `{dir}/{run_id}.log`, one line per output line prefixed with the stream name.
+/// Once a file grows past `max_bytes` it is rotated to `{run_id}.log.1` (replacing an older rotation),
+/// so every task uses at most twice `max_bytes` on disk.
+#[derive(Debug, Clone)]
+pub struct TaskLogFiles {
+    /// Directory holding the task log files (created if missing).
+    pub dir: PathBuf,
+    /// Size in bytes after which a task log file is rotated.
+    pub max_bytes: u64,
+    /// Keep logging output through `tracing` as well (`false` = files only).
+    pub also_trace: bool,
+}
+
+impl TaskLogFiles {
+    /// Write task output to files under `dir`, rotating them after 10 MiB, instead of the tracing logger.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: 10 * 1024 * 1024,
+            also_trace: false,
+        }
+    }
+}
+
+/// Open output file of one task, shared by its stdout and stderr streams.
+#[derive(Debug)]
+pub(crate) struct TaskLogFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl TaskLogFile {
+    /// Open (append to) the log file of `run_id`, creating the directory if needed.
+    pub(crate) fn open(cfg: &TaskLogFiles, run_id: &str) -> io::Result<Self> {
+        fs::create_dir_all(&cfg.dir)?;
+        let path = cfg.dir.join(format!("{}.log", file_stem(run_id)));
+        let file = open_append(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            written,
+            max_bytes: cfg.max_bytes,
+        })
+    }
+
+    /// Path of the current log file.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one output line, rotating the file first if it is full.
+    pub(crate) fn write_line(&mut self, stream: &str, line: &str) -> io::Result<()> {
+        if self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.writer, "{stream}: {line}")?;
+        self.written += (stream.len() + line.len() + 3) as u64;
+        Ok(())
+    }
+
+    /// Move the current file to `<path>.1` and start a new one.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        fs::rename(&self.path, rotated)?;
+        self.writer = BufWriter::new(open_append(&self.path)?);
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Make a run id usable as a file name (slots may contain path separators).
+fn file_stem(run_id: &str) -> String {
+    run_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tno-task-log-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn writes_prefixed_lines_to_run_id_file() {
+        let dir = temp_dir("write");
+        let cfg = TaskLogFiles::new(&dir);
+
+        let mut file = TaskLogFile::open(&cfg, "runner-slot/a-1").unwrap();
+        file.write_line("stdout", "hello").unwrap();
+        file.write_line("stderr", "oops").unwrap();
+        let path = file.path().to_path_buf();
+        drop(file);
+
+        assert_eq!(path, dir.join("runner-slot_a-1.log"));
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "stdout: hello\nstderr: oops\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotates_when_full() {
+        let dir = temp_dir("rotate");
+        let cfg = TaskLogFiles {
+            max_bytes: 16,
+            ..TaskLogFiles::new(&dir)
+        };
+
+        let mut file = TaskLogFile::open(&cfg, "task").unwrap();
+        file.write_line("stdout", "first line").unwrap();
+        file.write_line("stdout", "second").unwrap();
+        drop(file);
+
+        let current = fs::read_to_string(dir.join("task.log")).unwrap();
+        let rotated = fs::read_to_string(dir.join("task.log.1")).unwrap();
+        assert_eq!(rotated, "stdout: first line\n");
+        assert_eq!(current, "stdout: second\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -52,6 +52,18 @@ pub enum LinuxCapability {
     AuditControl,
     /// `CAP_SETFCAP`: Set file capabilities
     SetFCap,
+    /// `CAP_IPC_LOCK`: Lock memory (mlock(), mlockall(), mmap() with MAP_LOCKED)
+    IpcLock,
+    /// `CAP_SYS_MODULE`: Load and unload kernel modules
+    SysModule,
+    /// `CAP_SYSLOG`: Perform privileged syslog() operations; view kernel addresses
+    Syslog,
+    /// `CAP_WAKE_ALARM`: Trigger something that will wake up the system
+    WakeAlarm,
+    /// `CAP_PERFMON`: Use perf_event_open() and other performance monitoring operations
+    Perfmon,
+    /// `CAP_BPF`: Use privileged BPF operations
+    Bpf,
 }
 
 impl LinuxCapability {
@@ -81,6 +93,12 @@ impl LinuxCapability {
             Self::AuditWrite => "AUDIT_WRITE",
             Self::AuditControl => "AUDIT_CONTROL",
             Self::SetFCap => "SETFCAP",
+            Self::IpcLock => "IPC_LOCK",
+            Self::SysModule => "SYS_MODULE",
+            Self::Syslog => "SYSLOG",
+            Self::WakeAlarm => "WAKE_ALARM",
+            Self::Perfmon => "PERFMON",
+            Self::Bpf => "BPF",
         }
     }
 
@@ -110,6 +128,33 @@ impl LinuxCapability {
             Self::AuditWrite => 29,     // CAP_AUDIT_WRITE
             Self::AuditControl => 30,   // CAP_AUDIT_CONTROL
             Self::SetFCap => 31,        // CAP_SETFCAP
+            Self::IpcLock => 14,        // CAP_IPC_LOCK
+            Self::SysModule => 16,      // CAP_SYS_MODULE
+            Self::Syslog => 34,         // CAP_SYSLOG
+            Self::WakeAlarm => 35,      // CAP_WAKE_ALARM
+            Self::Perfmon => 38,        // CAP_PERFMON
+            Self::Bpf => 39,            // CAP_BPF
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_capabilities_match_kernel_header() {
+        let cases = [
+            (LinuxCapability::IpcLock, 14, "IPC_LOCK"),
+            (LinuxCapability::SysModule, 16, "SYS_MODULE"),
+            (LinuxCapability::Syslog, 34, "SYSLOG"),
+            (LinuxCapability::WakeAlarm, 35, "WAKE_ALARM"),
+            (LinuxCapability::Perfmon, 38, "PERFMON"),
+            (LinuxCapability::Bpf, 39, "BPF"),
+        ];
+        for (cap, value, name) in cases {
+            assert_eq!(cap.to_cap_value(), value, "{name}");
+            assert_eq!(cap.name(), name);
         }
     }
 }
@@ -5,6 +5,8 @@
 //! This module exposes structured API for applying cgroup v2 limits to child processes created via `tokio::process::Command`.
 //! - On **Linux with cgroup v2**, limits are applied by creating a cgroup and placing the child PID via `pre_exec` hook.
 //! - On **non-Linux platforms**, limits are ignored: a warning is emitted and the call returns `Ok(())`.
+use std::path::{Path, PathBuf};
+
 use tokio::process::Command;
 
 use crate::ExecError;
@@ -28,6 +30,76 @@ impl Default for CpuMax {
     }
 }
 
+/// Block device addressed by an `io.max` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoDevice {
+    /// Device number (`major:minor`).
+    Number {
+        /// Major device number.
+        major: u32,
+        /// Minor device number.
+        minor: u32,
+    },
+    /// Block device node (e.g. `/dev/sda`).
+    ///
+    /// Resolved to `major:minor` when the child is spawned.
+    /// If resolution fails, the rule is logged and skipped.
+    Path(PathBuf),
+}
+
+/// IO throttling rule (`io.max`) for a single block device.
+///
+/// Each limit is optional; `None` leaves that dimension unlimited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoDeviceLimit {
+    /// Target block device.
+    pub device: IoDevice,
+    /// Read bytes per second.
+    pub rbps: Option<u64>,
+    /// Write bytes per second.
+    pub wbps: Option<u64>,
+    /// Read IO operations per second.
+    pub riops: Option<u64>,
+    /// Write IO operations per second.
+    pub wiops: Option<u64>,
+}
+
+impl IoDeviceLimit {
+    /// Create a rule for `device` without limits.
+    pub fn new(device: IoDevice) -> Self {
+        Self {
+            device,
+            rbps: None,
+            wbps: None,
+            riops: None,
+            wiops: None,
+        }
+    }
+
+    /// Returns `true` if no limit is set for this device.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rbps.is_none() && self.wbps.is_none() && self.riops.is_none() && self.wiops.is_none()
+    }
+}
+
+/// Block IO limits (`io.max`) for cgroup v2.
+///
+/// Requires the `io` controller to be enabled in the parent cgroup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoMax {
+    /// Per-device throttling rules.
+    pub devices: Vec<IoDeviceLimit>,
+}
+
+impl IoMax {
+    /// Returns `true` if there are no device rules.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+}
+
 /// Declarative cgroup limits for a child process.
 ///
 /// All fields are optional. `None` means "no limit".
@@ -35,23 +107,52 @@ impl Default for CpuMax {
 pub struct CgroupLimits {
     /// CPU limit.
     pub cpu: Option<CpuMax>,
+    /// Proportional CPU share (`cpu.weight`, `1..=10000`, kernel default `100`).
+    ///
+    /// Unlike `cpu` quota, the weight only matters under contention: an idle box lets the task use spare CPU.
+    /// Both can be set at the same time.
+    pub cpu_weight: Option<u16>,
     /// Memory limit in bytes.
     pub memory: Option<u64>,
     /// Max number of processes (pids).
     pub pids: Option<u64>,
+    /// Block IO throttling.
+    pub io: Option<IoMax>,
+    /// Immediate re-attempts when placing the child PID into the cgroup fails with `EINVAL`.
+    ///
+    /// `None` uses [`DEFAULT_CGROUP_ATTACH_RETRIES`]; at most [`MAX_CGROUP_ATTACH_RETRIES`] is accepted.
+    pub attach_retries: Option<u8>,
 }
 
+/// Default number of cgroup PID attach re-attempts.
+pub const DEFAULT_CGROUP_ATTACH_RETRIES: u8 = 2;
+/// Upper bound for [`CgroupLimits::attach_retries`].
+pub const MAX_CGROUP_ATTACH_RETRIES: u8 = 5;
+
 impl CgroupLimits {
+    /// Effective number of PID attach re-attempts.
+    #[inline]
+    pub fn attach_retries(&self) -> u8 {
+        self.attach_retries.unwrap_or(DEFAULT_CGROUP_ATTACH_RETRIES)
+    }
+
     /// Returns `true` if all limits are `None`.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.cpu.is_none() && self.memory.is_none() && self.pids.is_none()
+        self.cpu.is_none()
+            && self.cpu_weight.is_none()
+            && self.memory.is_none()
+            && self.pids.is_none()
+            && self.io.is_none()
     }
 }
 
+/// Default mount point of the cgroup v2 hierarchy.
+pub const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
 /// Attach cgroup v2 limits to a `tokio::process::Command`.
 ///
-/// Creates a cgroup at `/sys/fs/cgroup/{cgroup_name}/` and places the child process into it.
+/// Creates a cgroup at `{root}/{cgroup_name}/` and places the child process into it.
 ///
 /// # Cgroup lifecycle
 /// - Kernel auto-removes empty cgroups when all processes exit
@@ -59,10 +160,12 @@ impl CgroupLimits {
 ///
 /// # Arguments
 /// - `cmd`: Command to attach cgroup to
+/// - `root`: cgroup v2 hierarchy root (usually [`DEFAULT_CGROUP_ROOT`])
 /// - `cgroup_name`: Unique cgroup name (`{runner}-{slot}-{seq}-{timestamp}`)
 /// - `limits`: Resource limits to apply
 pub fn attach_cgroup(
     cmd: &mut Command,
+    root: &Path,
     cgroup_name: &str,
     limits: &CgroupLimits,
 ) -> Result<(), ExecError> {
@@ -72,10 +175,11 @@ pub fn attach_cgroup(
 
     #[cfg(target_os = "linux")]
     {
-        linux_impl::attach(cmd, cgroup_name, limits);
+        linux_impl::attach(cmd, root, cgroup_name, limits);
     }
     #[cfg(not(target_os = "linux"))]
     {
+        let _ = root;
         tracing::warn!(
             "cgroup v2 limits requested for '{}', but OS={} does not support them; limits will be ignored",
             cgroup_name,
@@ -85,42 +189,182 @@ pub fn attach_cgroup(
     Ok(())
 }
 
-/// Attempt to remove a cgroup directory.
+/// Result of a cgroup removal attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupRemoval {
+    /// The cgroup directory was removed.
+    Removed,
+    /// The cgroup did not exist (already removed or never created).
+    NotFound,
+    /// The cgroup still has live processes (`EBUSY`).
+    Busy,
+    /// Removal failed for another reason (e.g. permission denied).
+    Failed,
+}
+
+impl CgroupRemoval {
+    /// Returns `true` if the cgroup no longer exists.
+    #[inline]
+    pub fn is_gone(&self) -> bool {
+        matches!(self, CgroupRemoval::Removed | CgroupRemoval::NotFound)
+    }
+}
+
+/// Returns `true` if a cgroup with the given name currently exists.
 #[cfg(target_os = "linux")]
-pub fn cleanup_cgroup(cgroup_name: &str) -> Result<(), ExecError> {
-    use std::path::Path;
+pub fn cgroup_exists(root: &Path, cgroup_name: &str) -> bool {
+    root.join(cgroup_name).is_dir()
+}
 
-    let full_path = Path::new("/sys/fs/cgroup").join(cgroup_name);
+#[cfg(not(target_os = "linux"))]
+pub fn cgroup_exists(_root: &Path, _cgroup_name: &str) -> bool {
+    false
+}
+
+/// Attempt to remove a cgroup directory, reporting what happened.
+#[cfg(target_os = "linux")]
+pub fn remove_cgroup(root: &Path, cgroup_name: &str) -> CgroupRemoval {
+    let full_path = root.join(cgroup_name);
 
     match std::fs::remove_dir(&full_path) {
         Ok(()) => {
             tracing::debug!("removed cgroup: {}", cgroup_name);
-            Ok(())
+            CgroupRemoval::Removed
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             tracing::trace!("cgroup '{}' not found (already removed)", cgroup_name);
-            Ok(())
+            CgroupRemoval::NotFound
         }
         Err(e) if e.raw_os_error() == Some(libc::EBUSY) => {
             tracing::debug!("cgroup '{}' is busy, skipping cleanup", cgroup_name);
-            Ok(())
+            CgroupRemoval::Busy
         }
         Err(e) if e.raw_os_error() == Some(libc::EACCES) => {
             tracing::debug!("cgroup '{}' cleanup: permission denied", cgroup_name);
-            Ok(())
+            CgroupRemoval::Failed
         }
         Err(e) => {
             tracing::warn!("failed to remove cgroup '{}': {}", cgroup_name, e);
-            Ok(())
+            CgroupRemoval::Failed
         }
     }
 }
 
 #[cfg(not(target_os = "linux"))]
-pub fn cleanup_cgroup(_cgroup_name: &str) -> Result<(), ExecError> {
+pub fn remove_cgroup(_root: &Path, _cgroup_name: &str) -> CgroupRemoval {
+    CgroupRemoval::NotFound
+}
+
+/// Attempt to remove a cgroup directory.
+///
+/// Best-effort: all failures are logged and `Ok(())` is returned.
+/// Use [`remove_cgroup`] to find out whether the cgroup is actually gone.
+pub fn cleanup_cgroup(root: &Path, cgroup_name: &str) -> Result<(), ExecError> {
+    let _ = remove_cgroup(root, cgroup_name);
     Ok(())
 }
 
+/// Resource usage read back from a cgroup.
+///
+/// All fields are `None` when the cgroup (or a specific stat file) is not available,
+/// e.g. the cgroup was already removed, cgroup v2 is not mounted, or the kernel is too old to expose `memory.peak`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CgroupUsage {
+    /// Peak memory usage in bytes (`memory.peak`).
+    pub memory_peak_bytes: Option<u64>,
+    /// Total CPU time in microseconds (`cpu.stat: usage_usec`).
+    pub cpu_usage_usec: Option<u64>,
+    /// User CPU time in microseconds (`cpu.stat: user_usec`).
+    pub cpu_user_usec: Option<u64>,
+    /// System CPU time in microseconds (`cpu.stat: system_usec`).
+    pub cpu_system_usec: Option<u64>,
+    /// Processes killed by the OOM killer because of the memory limit (`memory.events: oom_kill`).
+    pub oom_kills: Option<u64>,
+    /// Forks rejected because of the process limit (`pids.events: max`).
+    pub pids_max_hits: Option<u64>,
+}
+
+impl CgroupUsage {
+    /// Usage result for a cgroup that cannot be read.
+    pub fn unavailable() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if at least one value was read.
+    pub fn is_available(&self) -> bool {
+        *self != Self::unavailable()
+    }
+}
+
+/// Read resource usage of a cgroup.
+///
+/// Call this before [`cleanup_cgroup`]: once the cgroup is removed its counters are gone.
+/// A missing cgroup or stat file yields [`CgroupUsage::unavailable`] (or `None` for that field) rather than an error.
+#[cfg(target_os = "linux")]
+pub fn read_cgroup_usage(root: &Path, cgroup_name: &str) -> Result<CgroupUsage, ExecError> {
+    let dir = root.join(cgroup_name);
+    let read = |file: &str| -> Result<Option<String>, ExecError> {
+        match std::fs::read_to_string(dir.join(file)) {
+            Ok(s) => Ok(Some(s)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) if e.raw_os_error() == Some(libc::ENODEV) => Ok(None),
+            Err(e) => Err(ExecError::Io(e)),
+        }
+    };
+
+    let mut usage = CgroupUsage::unavailable();
+    if let Some(peak) = read("memory.peak")? {
+        usage.memory_peak_bytes = peak.trim().parse().ok();
+    }
+    if let Some(stat) = read("cpu.stat")? {
+        let (total, user, system) = parse_cpu_stat(&stat);
+        usage.cpu_usage_usec = total;
+        usage.cpu_user_usec = user;
+        usage.cpu_system_usec = system;
+    }
+    if let Some(events) = read("memory.events")? {
+        usage.oom_kills = parse_keyed(&events, "oom_kill");
+    }
+    if let Some(events) = read("pids.events")? {
+        usage.pids_max_hits = parse_keyed(&events, "max");
+    }
+    Ok(usage)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_cgroup_usage(_root: &Path, _cgroup_name: &str) -> Result<CgroupUsage, ExecError> {
+    Ok(CgroupUsage::unavailable())
+}
+
+/// Parse `usage_usec`, `user_usec` and `system_usec` from `cpu.stat` content.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_stat(content: &str) -> (Option<u64>, Option<u64>, Option<u64>) {
+    let (mut total, mut user, mut system) = (None, None, None);
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(val)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let val = val.parse().ok();
+        match key {
+            "usage_usec" => total = val,
+            "user_usec" => user = val,
+            "system_usec" => system = val,
+            _ => {}
+        }
+    }
+    (total, user, system)
+}
+
+/// Parse the value of `key` from flat-keyed cgroup file content (`key value` per line).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_keyed(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (k, v) = line.split_once(' ')?;
+        (k == key).then(|| v.trim().parse().ok()).flatten()
+    })
+}
+
 /// Build a unique cgroup name from components.
 ///
 /// Format: `{runner_tag}-{slot}-{seq:x}-{timestamp:x}`
@@ -130,34 +374,37 @@ pub fn build_cgroup_name(runner_tag: &str, slot: &str, seq: u64, timestamp: u64)
 
 #[cfg(target_os = "linux")]
 mod linux_impl {
-    use super::{CgroupLimits, CpuMax};
+    use super::{CgroupLimits, CpuMax, IoDevice, IoDeviceLimit, IoMax};
     use crate::utils::log::{pre_exec_log, pre_exec_log_errno};
 
     use std::{
         fs,
         io::{self, Write},
+        os::unix::fs::{FileTypeExt, MetadataExt},
         path::{Path, PathBuf},
     };
 
     use tokio::process::Command;
 
     const CONTROLLERS_FILE: &str = "cgroup.controllers";
-    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
 
-    pub fn attach(cmd: &mut Command, cgroup_name: &str, limits: &CgroupLimits) {
-        let cgroup_name = cgroup_name.to_string();
+    pub fn attach(cmd: &mut Command, root: &Path, cgroup_name: &str, limits: &CgroupLimits) {
+        let root = root.to_path_buf();
+        let cg_dir = root.join(cgroup_name);
         let limits = limits.clone();
+        let retries = limits
+            .attach_retries()
+            .min(super::MAX_CGROUP_ATTACH_RETRIES);
 
         unsafe {
             cmd.pre_exec(move || {
-                if !is_cgroup_v2(Path::new(CGROUP_ROOT)) {
+                if !is_cgroup_v2(&root) {
                     pre_exec_log(
-                        b"tno-exec: cgroup v2 not detected at /sys/fs/cgroup; limits will be ignored\n",
+                        b"tno-exec: cgroup v2 not detected at cgroup root; limits will be ignored\n",
                     );
                     return Ok(());
                 }
 
-                let cg_dir = Path::new(CGROUP_ROOT).join(&cgroup_name);
                 if let Err(e) = fs::create_dir_all(&cg_dir) {
                     pre_exec_log(b"tno-exec: failed to create cgroup directory; limits will be ignored\n");
                     if let Some(code) = e.raw_os_error() {
@@ -178,8 +425,13 @@ mod linux_impl {
                 // - EINVAL (22): Process state changed (e.g., already exec'd or exited)
                 // - EACCES (13): Permission denied (should have been caught at mkdir)
                 // - ESRCH  ( 3): Process doesn't exist (already terminated)
-                if let Err(_e) = add_self_to_cgroup(&cg_dir) {
+                //
+                // Only `EINVAL` is transient, so only it is retried (a few immediate re-attempts, no sleeping).
+                if let Err(e) = add_self_to_cgroup_with_retries(&cg_dir, retries) {
                     pre_exec_log(b"tno-exec: failed to attach PID to cgroup; limits will be ignored\n");
+                    if let Some(code) = e.raw_os_error() {
+                        pre_exec_log_errno(code);
+                    }
                     return Ok(());
                 }
                 Ok(())
@@ -195,15 +447,72 @@ mod linux_impl {
         if let Some(cpu) = limits.cpu {
             write_cpu_max(dir.join("cpu.max"), cpu)?;
         }
+        if let Some(weight) = limits.cpu_weight {
+            write_limit(dir.join("cpu.weight"), u64::from(weight))?;
+        }
         if let Some(mem) = limits.memory {
             write_limit(dir.join("memory.max"), mem)?;
         }
         if let Some(pids) = limits.pids {
             write_limit(dir.join("pids.max"), pids)?;
         }
+        if let Some(io) = &limits.io {
+            write_io_max(dir.join("io.max"), io)?;
+        }
+        Ok(())
+    }
+
+    /// Write one `io.max` line per device; devices that cannot be resolved are skipped.
+    fn write_io_max(path: PathBuf, io: &IoMax) -> io::Result<()> {
+        for rule in &io.devices {
+            let Some((major, minor)) = resolve_device(&rule.device) else {
+                pre_exec_log(b"tno-exec: failed to resolve io.max device; rule skipped\n");
+                continue;
+            };
+            if let Some(line) = io_max_line(major, minor, rule) {
+                fs::write(&path, line)?;
+            }
+        }
         Ok(())
     }
 
+    /// Resolve an [`IoDevice`] into `(major, minor)`.
+    pub(super) fn resolve_device(device: &IoDevice) -> Option<(u32, u32)> {
+        match device {
+            IoDevice::Number { major, minor } => Some((*major, *minor)),
+            IoDevice::Path(path) => {
+                let meta = fs::metadata(path).ok()?;
+                if !meta.file_type().is_block_device() {
+                    return None;
+                }
+                let rdev = meta.rdev() as libc::dev_t;
+                Some((libc::major(rdev), libc::minor(rdev)))
+            }
+        }
+    }
+
+    /// Format an `io.max` line (e.g. `8:0 rbps=1048576 wiops=100`).
+    ///
+    /// Returns `None` if the rule carries no limits.
+    pub(super) fn io_max_line(major: u32, minor: u32, rule: &IoDeviceLimit) -> Option<String> {
+        if rule.is_empty() {
+            return None;
+        }
+        let mut line = format!("{major}:{minor}");
+        for (key, val) in [
+            ("rbps", rule.rbps),
+            ("wbps", rule.wbps),
+            ("riops", rule.riops),
+            ("wiops", rule.wiops),
+        ] {
+            if let Some(v) = val {
+                line.push_str(&format!(" {key}={v}"));
+            }
+        }
+        line.push('\n');
+        Some(line)
+    }
+
     fn write_cpu_max(path: PathBuf, limit: CpuMax) -> io::Result<()> {
         let content = match limit.quota {
             None => format!("max {}\n", limit.period),
@@ -216,6 +525,18 @@ mod linux_impl {
         fs::write(path, format!("{val}\n"))
     }
 
+    fn add_self_to_cgroup_with_retries(dir: &Path, retries: u8) -> io::Result<()> {
+        let mut attempt = 0;
+        loop {
+            match add_self_to_cgroup(dir) {
+                Err(e) if e.raw_os_error() == Some(libc::EINVAL) && attempt < retries => {
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
     fn add_self_to_cgroup(dir: &Path) -> io::Result<()> {
         let procs = dir.join("cgroup.procs");
         let mut f = fs::OpenOptions::new().write(true).open(&procs)?;
@@ -235,7 +556,12 @@ mod tests {
         assert!(limits.is_empty());
 
         let mut cmd = Command::new("sh");
-        let r = attach_cgroup(&mut cmd, "test-cgroup", &limits);
+        let r = attach_cgroup(
+            &mut cmd,
+            Path::new(DEFAULT_CGROUP_ROOT),
+            "test-cgroup",
+            &limits,
+        );
         assert!(r.is_ok());
     }
 
@@ -278,12 +604,15 @@ mod tests {
     fn attach_with_limits_does_not_error() {
         let limits = CgroupLimits {
             cpu: Some(CpuMax::default()),
+            cpu_weight: Some(200),
             memory: Some(128 * 1024 * 1024),
             pids: Some(32),
+            io: None,
+            attach_retries: None,
         };
         let name = build_cgroup_name("test", "slot", 1, 1733045913);
         let mut cmd = Command::new("true");
-        let r = attach_cgroup(&mut cmd, &name, &limits);
+        let r = attach_cgroup(&mut cmd, Path::new(DEFAULT_CGROUP_ROOT), &name, &limits);
         assert!(r.is_ok());
     }
 
@@ -292,8 +621,11 @@ mod tests {
     fn non_linux_platforms_ignore_limits() {
         let limits = CgroupLimits {
             cpu: Some(CpuMax::default()),
+            cpu_weight: Some(200),
             memory: Some(1),
             pids: Some(1),
+            io: None,
+            attach_retries: None,
         };
         let mut cmd = Command::new("true");
         let r = attach_cgroup(&mut cmd, "test-cgroup", &limits);
@@ -303,11 +635,123 @@ mod tests {
         );
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn io_max_line_includes_only_set_limits() {
+        let mut rule = IoDeviceLimit::new(IoDevice::Number { major: 8, minor: 0 });
+        assert_eq!(linux_impl::io_max_line(8, 0, &rule), None);
+
+        rule.rbps = Some(1_048_576);
+        rule.wiops = Some(100);
+        assert_eq!(
+            linux_impl::io_max_line(8, 0, &rule).as_deref(),
+            Some("8:0 rbps=1048576 wiops=100\n")
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn io_device_resolution() {
+        let num = IoDevice::Number {
+            major: 259,
+            minor: 1,
+        };
+        assert_eq!(linux_impl::resolve_device(&num), Some((259, 1)));
+
+        // Character devices and missing paths are not resolvable.
+        let chr = IoDevice::Path("/dev/null".into());
+        assert_eq!(linux_impl::resolve_device(&chr), None);
+        let missing = IoDevice::Path("/dev/does-not-exist".into());
+        assert_eq!(linux_impl::resolve_device(&missing), None);
+    }
+
+    #[test]
+    fn parse_cpu_stat_extracts_usage() {
+        let stat = "usage_usec 1500\nuser_usec 1000\nsystem_usec 500\nnr_periods 0\n";
+        assert_eq!(parse_cpu_stat(stat), (Some(1500), Some(1000), Some(500)));
+        assert_eq!(parse_cpu_stat(""), (None, None, None));
+    }
+
+    #[test]
+    fn parse_keyed_finds_exact_key() {
+        let events = "low 0\nhigh 0\nmax 12\noom 2\noom_kill 1\noom_group_kill 0\n";
+        assert_eq!(parse_keyed(events, "oom_kill"), Some(1));
+        assert_eq!(parse_keyed(events, "max"), Some(12));
+        assert_eq!(parse_keyed(events, "missing"), None);
+    }
+
+    #[test]
+    fn usage_of_missing_cgroup_is_unavailable() {
+        let name = build_cgroup_name("test", "gone", 7, 1733045913);
+        let usage = read_cgroup_usage(Path::new(DEFAULT_CGROUP_ROOT), &name)
+            .expect("missing cgroup must not be an error");
+        assert!(!usage.is_available());
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn cleanup_nonexistent_cgroup_succeeds() {
         let name = build_cgroup_name("test", "nonexistent", 999, 1733045913);
-        let r = cleanup_cgroup(&name);
+        let root = Path::new(DEFAULT_CGROUP_ROOT);
+        let r = cleanup_cgroup(root, &name);
         assert!(r.is_ok(), "cleanup of nonexistent cgroup should succeed");
+        assert!(!cgroup_exists(root, &name));
+        assert_eq!(remove_cgroup(root, &name), CgroupRemoval::NotFound);
+        assert!(remove_cgroup(root, &name).is_gone());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn pid_attach_writes_child_pid() {
+        // Fake hierarchy with a pre-created `cgroup.procs`, so the attach path runs without root.
+        let root = std::env::temp_dir().join(format!("tno-cgattach-{}", std::process::id()));
+        let name = build_cgroup_name("test", "attach", 1, 1733045913);
+        std::fs::create_dir_all(root.join(&name)).unwrap();
+        std::fs::write(root.join("cgroup.controllers"), "pids\n").unwrap();
+        std::fs::write(root.join(&name).join("cgroup.procs"), "").unwrap();
+
+        let limits = CgroupLimits {
+            pids: Some(8),
+            attach_retries: Some(MAX_CGROUP_ATTACH_RETRIES),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("true");
+        attach_cgroup(&mut cmd, &root, &name, &limits).unwrap();
+        let mut child = cmd.spawn().unwrap();
+        let pid = child.id().unwrap();
+        assert!(child.wait().await.unwrap().success());
+
+        let procs = std::fs::read_to_string(root.join(&name).join("cgroup.procs")).unwrap();
+        assert_eq!(procs.trim(), pid.to_string());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn custom_root_is_used_for_cgroup_files() {
+        // A plain directory with `cgroup.controllers` passes the v2 check, so no root/mount is needed.
+        let root = std::env::temp_dir().join(format!("tno-cgroot-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("cgroup.controllers"), "cpu memory pids\n").unwrap();
+
+        let limits = CgroupLimits {
+            memory: Some(1024 * 1024),
+            pids: Some(8),
+            ..Default::default()
+        };
+        let name = build_cgroup_name("test", "root", 1, 1733045913);
+        let mut cmd = Command::new("true");
+        attach_cgroup(&mut cmd, &root, &name, &limits).unwrap();
+        let status = cmd.spawn().unwrap().wait().await.unwrap();
+        assert!(status.success());
+
+        assert!(cgroup_exists(&root, &name));
+        let memory_max = std::fs::read_to_string(root.join(&name).join("memory.max")).unwrap();
+        assert_eq!(memory_max, "1048576\n");
+        let usage = read_cgroup_usage(&root, &name).unwrap();
+        assert!(!usage.is_available());
+
+        std::fs::remove_dir_all(&root).unwrap();
     }
 }
@@ -0,0 +1,215 @@
+//! Landlock filesystem sandboxing for subprocess-based runners.
+//!
+//! ## Overview
+//!
+//! - On **Linux**, the ruleset is built and enforced inside the `pre_exec` hook, so it applies to the child right before `execve`.
+//! - Kernel support is probed once in the parent via the Landlock ABI version query.
+//!   On kernels without Landlock the ruleset is skipped with a warning; on supported kernels any failure is fatal.
+//! - On **non-Linux platforms**, the ruleset is ignored (see [`crate::attach_security`]).
+//!
+//! Unlike namespaces, Landlock does not require root: any process can restrict itself once `no_new_privs` is set.
+use std::path::PathBuf;
+
+/// Filesystem allowlist enforced with Landlock.
+///
+/// Everything outside the listed paths becomes inaccessible to the subprocess
+/// (reads, writes, directory listing and execution alike).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LandlockRuleset {
+    /// Paths (and everything beneath them) the subprocess may read and execute.
+    pub read_only: Vec<PathBuf>,
+    /// Paths (and everything beneath them) the subprocess may read, execute, create, modify and remove.
+    pub read_write: Vec<PathBuf>,
+}
+
+impl LandlockRuleset {
+    /// Returns `true` if no path is allowed at all.
+    ///
+    /// An empty ruleset is still enforced: it denies all filesystem access.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.read_only.is_empty() && self.read_write.is_empty()
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux_impl::{abi_version, prepare, restrict_self};
+
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use super::LandlockRuleset;
+
+    use std::{ffi::CString, io, mem, os::unix::ffi::OsStrExt, path::Path, ptr};
+
+    // Access rights (linux/landlock.h).
+    const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+    const ACCESS_FS_REFER: u64 = 1 << 13;
+    const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+    /// All rights known to ABI v1 (`EXECUTE` ..= `MAKE_SYM`).
+    const ACCESS_FS_ABI_V1: u64 = (1 << 13) - 1;
+    /// Rights that make sense on a regular file; the kernel rejects the others for non-directories.
+    const ACCESS_FILE: u64 =
+        ACCESS_FS_EXECUTE | ACCESS_FS_WRITE_FILE | ACCESS_FS_READ_FILE | ACCESS_FS_TRUNCATE;
+    const ACCESS_READ_ONLY: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+
+    const CREATE_RULESET_VERSION: u32 = 1 << 0;
+    const RULE_PATH_BENEATH: libc::c_int = 1;
+    const PR_SET_NO_NEW_PRIVS: libc::c_int = 38;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    /// Ruleset with paths converted to C strings (no allocation is allowed after `fork`).
+    #[derive(Debug, Clone)]
+    pub(crate) struct PreparedRuleset {
+        handled: u64,
+        rules: Vec<(CString, u64)>,
+    }
+
+    /// Query the Landlock ABI version supported by the running kernel.
+    ///
+    /// Returns `None` if Landlock is unavailable (not compiled in, or disabled at boot).
+    pub(crate) fn abi_version() -> Option<u32> {
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                ptr::null::<RulesetAttr>(),
+                0usize,
+                CREATE_RULESET_VERSION,
+            )
+        };
+        (rc > 0).then_some(rc as u32)
+    }
+
+    /// Prepare a [`LandlockRuleset`] for use inside `pre_exec`, restricted to the rights known to `abi`.
+    pub(crate) fn prepare(ruleset: &LandlockRuleset, abi: u32) -> io::Result<PreparedRuleset> {
+        let mut handled = ACCESS_FS_ABI_V1;
+        if abi >= 2 {
+            handled |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            handled |= ACCESS_FS_TRUNCATE;
+        }
+
+        let ro = ruleset.read_only.iter().map(|p| (p, ACCESS_READ_ONLY));
+        let rw = ruleset.read_write.iter().map(|p| (p, handled));
+        let rules = ro
+            .chain(rw)
+            .map(|(path, access)| Ok((c_path(path)?, access & handled)))
+            .collect::<io::Result<_>>()?;
+        Ok(PreparedRuleset { handled, rules })
+    }
+
+    fn c_path(path: &Path) -> io::Result<CString> {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    /// Build the ruleset and enforce it on the calling thread.
+    ///
+    /// Sets `no_new_privs` first (required to restrict an unprivileged process).
+    /// Only async-signal-safe calls are made, so this is safe to use inside `pre_exec`.
+    pub(crate) fn restrict_self(ruleset: &PreparedRuleset) -> io::Result<()> {
+        let attr = RulesetAttr {
+            handled_access_fs: ruleset.handled,
+        };
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                mem::size_of::<RulesetAttr>(),
+                0u32,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = fd as libc::c_int;
+
+        let res = add_rules(fd, ruleset).and_then(|()| {
+            check(unsafe { libc::prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } as libc::c_long)?;
+            check(unsafe { libc::syscall(libc::SYS_landlock_restrict_self, fd, 0u32) })
+        });
+        unsafe { libc::close(fd) };
+        res
+    }
+
+    fn add_rules(ruleset_fd: libc::c_int, ruleset: &PreparedRuleset) -> io::Result<()> {
+        for (path, access) in &ruleset.rules {
+            let fd = unsafe { libc::open(path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let res = add_path_rule(ruleset_fd, fd, *access);
+            unsafe { libc::close(fd) };
+            res?;
+        }
+        Ok(())
+    }
+
+    fn add_path_rule(ruleset_fd: libc::c_int, fd: libc::c_int, access: u64) -> io::Result<()> {
+        let mut stat: libc::stat = unsafe { mem::zeroed() };
+        if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let allowed_access = if stat.st_mode & libc::S_IFMT == libc::S_IFDIR {
+            access
+        } else {
+            access & ACCESS_FILE
+        };
+        let attr = PathBeneathAttr {
+            allowed_access,
+            parent_fd: fd,
+        };
+        check(unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset_fd,
+                RULE_PATH_BENEATH,
+                &attr as *const PathBeneathAttr,
+                0u32,
+            )
+        })
+    }
+
+    fn check(rc: libc::c_long) -> io::Result<()> {
+        if rc != 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn read_only_paths_never_get_write_access() {
+            let ruleset = LandlockRuleset {
+                read_only: vec!["/usr".into()],
+                read_write: vec!["/tmp".into()],
+            };
+            let prepared = prepare(&ruleset, 3).unwrap();
+            assert_eq!(prepared.rules[0].1 & ACCESS_FS_WRITE_FILE, 0);
+            assert_eq!(prepared.rules[1].1, prepared.handled);
+            assert_ne!(prepared.handled & ACCESS_FS_TRUNCATE, 0);
+
+            // ABI v1 kernels reject unknown rights.
+            let prepared = prepare(&ruleset, 1).unwrap();
+            assert_eq!(prepared.handled, ACCESS_FS_ABI_V1);
+        }
+    }
+}
@@ -5,6 +5,8 @@
 //! This module provides API for configuring classic POSIX process limits (`rlimit`) to child processes created via `tokio::process::Command`.
 //! - On **Unix platforms** limits are applied inside a `pre_exec` hook, executed in the child process after `fork()` and immediately before `execve()`.
 //! - On **non-Unix platforms**, rlimits are ignored: a warning is emitted and the call returns `Ok(())`.
+use std::path::{Path, PathBuf};
+
 use tokio::process::Command;
 
 #[cfg(not(unix))]
@@ -25,11 +27,38 @@ pub struct RlimitConfig {
     /// When the process attempts to grow a file beyond this limit, the kernel typically delivers `SIGXFSZ` and the process terminates.
     /// `None` leaves the OS / parent limits unchanged.
     pub max_file_size_bytes: Option<u64>,
+    /// Maximum size of the process virtual address space in bytes (`RLIMIT_AS`).
+    ///
+    /// Allocations (`mmap`/`brk`) beyond this limit fail with `ENOMEM`.
+    /// Note that runtimes reserving large virtual regions up front (JVM, Go, sanitizers) may fail to start with tight values.
+    /// `None` leaves the OS / parent limits unchanged.
+    pub max_address_space_bytes: Option<u64>,
+    /// Maximum number of processes for the task user (`RLIMIT_NPROC`).
+    ///
+    /// Guards against fork bombs when cgroup `pids.max` is unavailable.
+    /// The limit is accounted per real user ID, not per process tree, so processes of the same user outside the task count too.
+    /// `None` leaves the OS / parent limits unchanged.
+    pub max_processes: Option<u64>,
+    /// CPU time budget in seconds (`RLIMIT_CPU`).
+    ///
+    /// This is consumed CPU time, not wall-clock time: a process sleeping or blocked on IO does not use it up,
+    /// while a multi-threaded process may burn through it faster than real time.
+    /// It is orthogonal to the supervisor `timeout_ms`, which bounds wall-clock time of an attempt.
+    ///
+    /// When the soft limit is reached the kernel sends `SIGXCPU` (terminating the process by default);
+    /// processes that handle `SIGXCPU` are killed with `SIGKILL` once the hard limit is reached.
+    /// `None` leaves the OS / parent limits unchanged.
+    pub max_cpu_seconds: Option<u64>,
     /// Disable core dumps (`RLIMIT_CORE = 0`) when set to `true`.
     ///
     /// This prevents large core files from being written for failing tasks.
     /// When `false`, the OS default / inherited core limit is preserved.
     pub disable_core_dumps: bool,
+    /// Enable core dumps and collect them in a dedicated directory.
+    ///
+    /// Mutually exclusive with `disable_core_dumps`.
+    /// `None` leaves the OS / parent core dump behavior unchanged.
+    pub core_dump: Option<CoreDumpConfig>,
 }
 
 impl RlimitConfig {
@@ -38,10 +67,95 @@ impl RlimitConfig {
     pub fn is_empty(&self) -> bool {
         self.max_open_files.is_none()
             && self.max_file_size_bytes.is_none()
+            && self.max_address_space_bytes.is_none()
+            && self.max_processes.is_none()
+            && self.max_cpu_seconds.is_none()
             && !self.disable_core_dumps
+            && self.core_dump.is_none()
+    }
+}
+
+/// Core dump collection settings for a child process.
+///
+/// When configured, the child gets a non-zero `RLIMIT_CORE` and is marked dumpable via `prctl(PR_SET_DUMPABLE, 1)` right before `execve()`.
+///
+/// ## Where cores are written
+/// The kernel names core files after the host-wide `kernel.core_pattern`, which this setting does not change;
+/// tasks also keep their own working directory. Cores therefore land in `dir` only if the pattern is an absolute
+/// path inside it (e.g. `/var/lib/tno/cores/core.%e.%p`). On Linux, backend validation rejects a relative pattern
+/// (the default `core`, resolved against the task's working directory), a piped one (e.g. `systemd-coredump`)
+/// and one pointing outside `dir` (see [`CoreDumpConfig::check_core_pattern`]).
+///
+/// ## Privileges
+/// - Raising the soft limit up to the current hard limit and `PR_SET_DUMPABLE` require no privileges.
+/// - Raising the hard `RLIMIT_CORE` limit above its inherited value requires `CAP_SYS_RESOURCE`.
+/// - The agent must be able to create `dir`, and the task user must be able to write into it.
+/// - Set-user-ID binaries are governed by `fs.suid_dumpable` regardless of this setting.
+#[derive(Debug, Clone)]
+pub struct CoreDumpConfig {
+    /// Absolute directory that receives core files.
+    ///
+    /// Created (best-effort) before the child is spawned.
+    pub dir: PathBuf,
+    /// Maximum core file size in bytes (`RLIMIT_CORE`).
+    ///
+    /// `None` raises the soft limit to the inherited hard limit.
+    pub max_size_bytes: Option<u64>,
+}
+
+impl CoreDumpConfig {
+    /// Create a core dump config writing into `dir` without a size cap.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size_bytes: None,
+        }
+    }
+
+    /// Cap the core file size in bytes.
+    pub fn with_max_size_bytes(mut self, bytes: u64) -> Self {
+        self.max_size_bytes = Some(bytes);
+        self
+    }
+
+    /// Check that the host core pattern writes core files into `dir`.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn check_host_core_pattern(&self) -> Result<(), String> {
+        let pattern = std::fs::read_to_string(CORE_PATTERN_PATH)
+            .map_err(|e| format!("cannot read {CORE_PATTERN_PATH}: {e}"))?;
+        self.check_core_pattern(pattern.trim_end())
+    }
+
+    /// Check that a `kernel.core_pattern` value writes core files into `dir`.
+    ///
+    /// Fails for piped patterns (cores go to a handler), relative patterns
+    /// (cores go to the task's working directory) and absolute patterns outside `dir`.
+    pub fn check_core_pattern(&self, pattern: &str) -> Result<(), String> {
+        let dir = self.dir.display();
+        if pattern.starts_with('|') {
+            return Err(format!(
+                "kernel.core_pattern '{pattern}' pipes core files to a handler instead of {dir}"
+            ));
+        }
+        let path = Path::new(pattern);
+        if !path.is_absolute() {
+            return Err(format!(
+                "kernel.core_pattern '{pattern}' is relative to the task's working directory, not {dir}"
+            ));
+        }
+        if !path.starts_with(&self.dir) {
+            return Err(format!(
+                "kernel.core_pattern '{pattern}' writes core files outside {dir}"
+            ));
+        }
+        Ok(())
     }
 }
 
+/// Host-wide core file name pattern.
+#[cfg(target_os = "linux")]
+const CORE_PATTERN_PATH: &str = "/proc/sys/kernel/core_pattern";
+
 /// Attach `rlimit`-based process limits to a `tokio::process::Command`.
 pub fn attach_rlimits(cmd: &mut Command, config: &RlimitConfig) {
     if config.is_empty() {
@@ -50,6 +164,9 @@ pub fn attach_rlimits(cmd: &mut Command, config: &RlimitConfig) {
 
     #[cfg(unix)]
     {
+        if let Some(core) = &config.core_dump {
+            create_core_dump_dir(core);
+        }
         unix_impl::attach_rlimits(cmd, config);
     }
     #[cfg(not(unix))]
@@ -61,6 +178,18 @@ pub fn attach_rlimits(cmd: &mut Command, config: &RlimitConfig) {
     }
 }
 
+/// Create the core dump directory (best-effort).
+#[cfg(unix)]
+fn create_core_dump_dir(core: &CoreDumpConfig) {
+    if let Err(e) = std::fs::create_dir_all(&core.dir) {
+        tracing::warn!(
+            dir = %core.dir.display(),
+            error = %e,
+            "failed to create core dump directory; core files may not be written"
+        );
+    }
+}
+
 #[cfg(unix)]
 mod unix_impl {
     use super::RlimitConfig;
@@ -77,7 +206,11 @@ mod unix_impl {
 
         let max_file_size_bytes = config.max_file_size_bytes;
         let max_open_files = config.max_open_files;
+        let max_address_space_bytes = config.max_address_space_bytes;
+        let max_processes = config.max_processes;
+        let max_cpu_seconds = config.max_cpu_seconds;
         let disable_core_dumps = config.disable_core_dumps;
+        let core_dump_limit = config.core_dump.as_ref().map(|c| c.max_size_bytes);
 
         unsafe {
             cmd.pre_exec(move || {
@@ -99,6 +232,33 @@ mod unix_impl {
                     }
                     return Err(e);
                 }
+                if let Some(as_bytes) = max_address_space_bytes
+                    && let Err(e) = apply_rlimit(rlimit_as(), as_bytes)
+                {
+                    pre_exec_log(b"tno-exec: failed to set RLIMIT_AS: ");
+                    if let Some(code) = e.raw_os_error() {
+                        pre_exec_log_errno(code);
+                    }
+                    return Err(e);
+                }
+                if let Some(nproc) = max_processes
+                    && let Err(e) = apply_rlimit(rlimit_nproc(), nproc)
+                {
+                    pre_exec_log(b"tno-exec: failed to set RLIMIT_NPROC: ");
+                    if let Some(code) = e.raw_os_error() {
+                        pre_exec_log_errno(code);
+                    }
+                    return Err(e);
+                }
+                if let Some(cpu) = max_cpu_seconds
+                    && let Err(e) = apply_rlimit(rlimit_cpu(), cpu)
+                {
+                    pre_exec_log(b"tno-exec: failed to set RLIMIT_CPU: ");
+                    if let Some(code) = e.raw_os_error() {
+                        pre_exec_log_errno(code);
+                    }
+                    return Err(e);
+                }
                 if disable_core_dumps && let Err(e) = apply_rlimit(rlimit_core(), 0) {
                     pre_exec_log(b"tno-exec: failed to set RLIMIT_CORE: ");
                     if let Some(code) = e.raw_os_error() {
@@ -106,11 +266,69 @@ mod unix_impl {
                     }
                     return Err(e);
                 }
+                if let Some(max_size) = core_dump_limit {
+                    if let Err(e) = apply_core_limit(max_size) {
+                        pre_exec_log(b"tno-exec: failed to set RLIMIT_CORE: ");
+                        if let Some(code) = e.raw_os_error() {
+                            pre_exec_log_errno(code);
+                        }
+                        return Err(e);
+                    }
+                    if let Err(e) = set_dumpable() {
+                        pre_exec_log(b"tno-exec: failed to set PR_SET_DUMPABLE: ");
+                        if let Some(code) = e.raw_os_error() {
+                            pre_exec_log_errno(code);
+                        }
+                        return Err(e);
+                    }
+                }
                 Ok(())
             });
         }
     }
 
+    /// Raise `RLIMIT_CORE` to `max_size` or, if `None`, up to the current hard limit.
+    fn apply_core_limit(max_size: Option<u64>) -> io::Result<()> {
+        if let Some(bytes) = max_size {
+            return apply_rlimit(rlimit_core(), bytes);
+        }
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if unsafe { getrlimit_compat(rlimit_core(), &mut current) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let rlim = libc::rlimit {
+            rlim_cur: current.rlim_max,
+            rlim_max: current.rlim_max,
+        };
+        if unsafe { setrlimit_compat(rlimit_core(), &rlim) } != 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Mark the process as dumpable (`prctl(PR_SET_DUMPABLE, 1)`).
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn set_dumpable() -> io::Result<()> {
+        const PR_SET_DUMPABLE: libc::c_int = 4;
+
+        let rc = unsafe { libc::prctl(PR_SET_DUMPABLE, 1, 0, 0, 0) };
+        if rc != 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Dumpable flag is Linux-specific; other Unix platforms rely on `RLIMIT_CORE` only.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn set_dumpable() -> io::Result<()> {
+        Ok(())
+    }
+
     #[inline]
     fn rlimit_nofile() -> libc::c_int {
         #[cfg(any(target_os = "linux", target_os = "android"))]
@@ -135,6 +353,42 @@ mod unix_impl {
         }
     }
 
+    #[inline]
+    fn rlimit_as() -> libc::c_int {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            libc::RLIMIT_AS as libc::c_int
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            libc::RLIMIT_AS
+        }
+    }
+
+    #[inline]
+    fn rlimit_nproc() -> libc::c_int {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            libc::RLIMIT_NPROC as libc::c_int
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            libc::RLIMIT_NPROC
+        }
+    }
+
+    #[inline]
+    fn rlimit_cpu() -> libc::c_int {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            libc::RLIMIT_CPU as libc::c_int
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            libc::RLIMIT_CPU
+        }
+    }
+
     #[inline]
     fn rlimit_core() -> libc::c_int {
         #[cfg(any(target_os = "linux", target_os = "android"))]
@@ -231,7 +485,11 @@ mod tests {
         let config = RlimitConfig {
             max_open_files: Some(1024),
             max_file_size_bytes: Some(10 * 1024 * 1024),
+            max_address_space_bytes: Some(1024 * 1024 * 1024),
+            max_processes: Some(256),
+            max_cpu_seconds: Some(60),
             disable_core_dumps: true,
+            core_dump: None,
         };
 
         let mut cmd = Command::new("sh");
@@ -244,7 +502,11 @@ mod tests {
         let config = RlimitConfig {
             max_open_files: Some(512),
             max_file_size_bytes: None,
+            max_address_space_bytes: Some(1024 * 1024 * 1024),
+            max_processes: None,
+            max_cpu_seconds: None,
             disable_core_dumps: true,
+            core_dump: None,
         };
 
         let mut cmd = Command::new("sh");
@@ -257,7 +519,11 @@ mod tests {
         let config = RlimitConfig {
             max_open_files: Some(512),
             max_file_size_bytes: Some(1024 * 1024),
+            max_address_space_bytes: None,
+            max_processes: None,
+            max_cpu_seconds: None,
             disable_core_dumps: true,
+            core_dump: None,
         };
 
         let mut cmd = Command::new("sh");
@@ -268,4 +534,108 @@ mod tests {
         assert!(result.is_ok(), "rlimits should be applied successfully");
         assert!(result.unwrap().success());
     }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn address_space_and_process_limits_are_applied() {
+        let config = RlimitConfig {
+            max_address_space_bytes: Some(2 * 1024 * 1024 * 1024),
+            max_processes: Some(4096),
+            ..Default::default()
+        };
+        assert!(!config.is_empty());
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("cat /proc/self/limits");
+        cmd.stdout(std::process::Stdio::piped());
+        attach_rlimits(&mut cmd, &config);
+
+        let output = cmd.output().await.expect("spawn should succeed");
+        assert!(output.status.success());
+
+        let limits = String::from_utf8_lossy(&output.stdout);
+        let soft_limit = |name: &str| {
+            limits
+                .lines()
+                .find(|l| l.starts_with(name))
+                .and_then(|l| l[name.len()..].split_whitespace().next())
+                .map(str::to_owned)
+        };
+        assert_eq!(
+            soft_limit("Max address space").as_deref(),
+            Some("2147483648")
+        );
+        assert_eq!(soft_limit("Max processes").as_deref(), Some("4096"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn cpu_limit_kills_busy_loop() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let config = RlimitConfig {
+            max_cpu_seconds: Some(1),
+            ..Default::default()
+        };
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("while :; do :; done");
+        attach_rlimits(&mut cmd, &config);
+
+        let status = tokio::time::timeout(std::time::Duration::from_secs(30), cmd.status())
+            .await
+            .expect("busy loop must be stopped by RLIMIT_CPU")
+            .expect("spawn should succeed");
+        assert!(
+            matches!(status.signal(), Some(libc::SIGXCPU) | Some(libc::SIGKILL)),
+            "unexpected exit status: {status:?}"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn core_dumps_can_be_enabled() {
+        let dir = std::env::temp_dir().join(format!("tno-exec-cores-{}", std::process::id()));
+        let config = RlimitConfig {
+            core_dump: Some(CoreDumpConfig::new(&dir).with_max_size_bytes(64 * 1024 * 1024)),
+            ..Default::default()
+        };
+        assert!(!config.is_empty());
+
+        // The task keeps its own working directory.
+        let cwd = std::env::temp_dir();
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("test \"$(ulimit -c)\" != 0 && test \"$(pwd -P)\" = \"$1\"");
+        cmd.arg("sh").arg(cwd.canonicalize().unwrap());
+        cmd.current_dir(&cwd);
+        attach_rlimits(&mut cmd, &config);
+
+        let result = cmd.status().await;
+        assert!(dir.is_dir(), "core dump directory must be created");
+        let _ = std::fs::remove_dir(&dir);
+        assert!(
+            result.is_ok(),
+            "spawn with core dumps enabled should succeed"
+        );
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn core_pattern_must_write_into_core_dump_dir() {
+        let core = CoreDumpConfig::new("/var/lib/tno/cores");
+
+        assert!(
+            core.check_core_pattern("/var/lib/tno/cores/core.%e.%p")
+                .is_ok()
+        );
+        for pattern in [
+            "core",
+            "|/usr/lib/systemd/systemd-coredump %P %u %g %s %t %c %h",
+            "/tmp/core.%p",
+        ] {
+            let err = core.check_core_pattern(pattern).unwrap_err();
+            assert!(err.contains(pattern), "{err}");
+        }
+    }
 }
@@ -1,15 +1,33 @@
 mod cgroups;
-pub use cgroups::{CgroupLimits, CpuMax};
-pub use cgroups::{attach_cgroup, build_cgroup_name, cleanup_cgroup};
+pub use cgroups::{CgroupLimits, CpuMax, IoDevice, IoDeviceLimit, IoMax};
+pub use cgroups::{
+    CgroupRemoval, CgroupUsage, DEFAULT_CGROUP_ATTACH_RETRIES, DEFAULT_CGROUP_ROOT,
+    MAX_CGROUP_ATTACH_RETRIES, attach_cgroup, build_cgroup_name, cgroup_exists, cleanup_cgroup,
+    read_cgroup_usage, remove_cgroup,
+};
 
 mod limits;
-pub use limits::RlimitConfig;
 pub use limits::attach_rlimits;
+pub use limits::{CoreDumpConfig, RlimitConfig};
 
 mod security;
 pub use security::SecurityConfig;
 pub use security::attach_security;
 
+mod security_probe;
+#[cfg(target_os = "linux")]
+pub use security_probe::probe_security;
+pub use security_probe::{SECURITY_PROBE_COMMAND, SecurityProbeReport, warm_up_security};
+
+mod namespace;
+pub use namespace::NamespaceConfig;
+
+mod landlock;
+pub use landlock::LandlockRuleset;
+
+mod seccomp;
+pub use seccomp::SeccompProfile;
+
 mod capability;
 pub use capability::LinuxCapability;
 
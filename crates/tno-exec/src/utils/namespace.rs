@@ -0,0 +1,109 @@
+//! Mount namespace isolation for subprocess-based runners.
+//!
+//! ## Overview
+//!
+//! - On **Linux**, the child enters a new mount namespace inside the `pre_exec` hook and can optionally `pivot_root` into a restricted root directory.
+//! - On **non-Linux platforms**, namespace settings are ignored (see [`crate::attach_security`]).
+//!
+//! ## Requirements
+//! - `CAP_SYS_ADMIN` in the agent (in practice: running as root, or inside a user namespace that grants it).
+//! - `root_dir` must contain everything the command needs (binary, shared libraries, `/proc` if used):
+//!   after `pivot_root` the host filesystem is no longer reachable.
+use std::path::PathBuf;
+
+/// Namespace isolation settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceConfig {
+    /// Run the subprocess in its own mount namespace (`unshare(CLONE_NEWNS)`).
+    ///
+    /// Mount propagation back to the host is disabled (`/` is remounted as private).
+    pub new_mount_ns: bool,
+    /// Make this directory the root of the subprocess filesystem (`pivot_root`).
+    ///
+    /// Requires `new_mount_ns`. Must be an absolute path.
+    /// The working directory of the subprocess is reset to `/` of the new root and the command is resolved inside it.
+    pub root_dir: Option<PathBuf>,
+}
+
+impl NamespaceConfig {
+    /// Returns `true` if no namespace isolation is requested.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.new_mount_ns && self.root_dir.is_none()
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux_impl::{enter, prepare};
+
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use super::NamespaceConfig;
+
+    use std::{ffi::CString, io, os::unix::ffi::OsStrExt, ptr};
+
+    /// Namespace settings with paths converted to C strings (no allocation is allowed after `fork`).
+    #[derive(Debug, Clone)]
+    pub(crate) struct PreparedNamespace {
+        root_dir: Option<CString>,
+    }
+
+    /// Prepare a [`NamespaceConfig`] for use inside `pre_exec`.
+    pub(crate) fn prepare(config: &NamespaceConfig) -> io::Result<PreparedNamespace> {
+        let root_dir = match &config.root_dir {
+            Some(dir) => Some(
+                CString::new(dir.as_os_str().as_bytes())
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            ),
+            None => None,
+        };
+        Ok(PreparedNamespace { root_dir })
+    }
+
+    /// Enter a new mount namespace and optionally pivot into the restricted root.
+    ///
+    /// Only async-signal-safe calls are made, so this is safe to use inside `pre_exec`.
+    pub(crate) fn enter(ns: &PreparedNamespace) -> io::Result<()> {
+        check(unsafe { libc::unshare(libc::CLONE_NEWNS) })?;
+
+        // Keep our mounts (and the pivot) from propagating back to the host.
+        check(unsafe {
+            libc::mount(
+                ptr::null(),
+                c"/".as_ptr(),
+                ptr::null(),
+                libc::MS_REC | libc::MS_PRIVATE,
+                ptr::null(),
+            )
+        })?;
+
+        let Some(root) = &ns.root_dir else {
+            return Ok(());
+        };
+
+        // `pivot_root` requires the new root to be a mount point.
+        check(unsafe {
+            libc::mount(
+                root.as_ptr(),
+                root.as_ptr(),
+                ptr::null(),
+                libc::MS_BIND | libc::MS_REC,
+                ptr::null(),
+            )
+        })?;
+        check(unsafe { libc::chdir(root.as_ptr()) })?;
+        // `pivot_root(".", ".")` stacks the old root on top of the new one; detaching it leaves only the new root.
+        check(unsafe { libc::syscall(libc::SYS_pivot_root, c".".as_ptr(), c".".as_ptr()) } as i32)?;
+        check(unsafe { libc::umount2(c".".as_ptr(), libc::MNT_DETACH) })?;
+        check(unsafe { libc::chdir(c"/".as_ptr()) })?;
+        Ok(())
+    }
+
+    fn check(rc: libc::c_int) -> io::Result<()> {
+        if rc != 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! seccomp-bpf syscall filtering for subprocess-based runners.
+//!
+//! The filter is compiled in the parent process and installed inside the `pre_exec` hook,
+//! so it applies to the child right before `execve`.
+//! Denied syscalls fail with `EPERM`; syscalls from a foreign architecture kill the process.
+
+/// Syscall filter profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeccompProfile {
+    /// Allow everything except a built-in set of dangerous syscalls
+    /// (module loading, `kexec`, `reboot`, `mount`, `ptrace`, `bpf`, namespace manipulation, ...).
+    DefaultDenyDangerous,
+    /// Allow only the listed syscall numbers (e.g. `libc::SYS_read`).
+    ///
+    /// `execve` is always allowed, otherwise the subprocess could not be started at all.
+    Allow(Vec<i64>),
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux_impl::{compile, install};
+
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use super::SeccompProfile;
+
+    use std::io;
+
+    // Classic BPF opcodes (linux/filter.h).
+    const BPF_LD_W_ABS: u16 = 0x20; // BPF_LD | BPF_W | BPF_ABS
+    const BPF_JMP_JEQ_K: u16 = 0x15; // BPF_JMP | BPF_JEQ | BPF_K
+    const BPF_JMP_JGE_K: u16 = 0x35; // BPF_JMP | BPF_JGE | BPF_K
+    const BPF_RET_K: u16 = 0x06; // BPF_RET | BPF_K
+
+    // seccomp return actions (linux/seccomp.h).
+    const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    const SECCOMP_SET_MODE_FILTER: libc::c_uint = 1;
+    const PR_SET_NO_NEW_PRIVS: libc::c_int = 38;
+
+    // Offsets in `struct seccomp_data`.
+    const DATA_NR_OFFSET: u32 = 0;
+    const DATA_ARCH_OFFSET: u32 = 4;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xC000_003E);
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: Option<u32> = Some(0xC000_00B7);
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    const AUDIT_ARCH: Option<u32> = None;
+
+    /// x32 syscalls on x86_64 have this bit set; they are always denied.
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    pub(super) const DANGEROUS_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_acct,
+        libc::SYS_add_key,
+        libc::SYS_adjtimex,
+        libc::SYS_bpf,
+        libc::SYS_clock_settime,
+        libc::SYS_delete_module,
+        libc::SYS_finit_module,
+        libc::SYS_init_module,
+        libc::SYS_kexec_file_load,
+        libc::SYS_kexec_load,
+        libc::SYS_keyctl,
+        libc::SYS_mount,
+        libc::SYS_open_by_handle_at,
+        libc::SYS_perf_event_open,
+        libc::SYS_pivot_root,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_ptrace,
+        libc::SYS_reboot,
+        libc::SYS_request_key,
+        libc::SYS_setns,
+        libc::SYS_settimeofday,
+        libc::SYS_swapoff,
+        libc::SYS_swapon,
+        libc::SYS_umount2,
+        libc::SYS_unshare,
+        libc::SYS_userfaultfd,
+    ];
+
+    /// Compiled BPF program, ready to be installed in `pre_exec`.
+    #[derive(Debug, Clone)]
+    pub(crate) struct SeccompFilter(Vec<libc::sock_filter>);
+
+    impl SeccompFilter {
+        #[cfg(test)]
+        pub(crate) fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    fn stmt(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    /// Compile a profile into a BPF program.
+    ///
+    /// Returns `None` if seccomp filtering is not supported on this architecture.
+    pub(crate) fn compile(profile: &SeccompProfile) -> Option<SeccompFilter> {
+        let arch = AUDIT_ARCH?;
+        let deny = SECCOMP_RET_ERRNO | (libc::EPERM as u32);
+
+        let (listed, on_match, otherwise): (Vec<libc::c_long>, u32, u32) = match profile {
+            SeccompProfile::DefaultDenyDangerous => {
+                (DANGEROUS_SYSCALLS.to_vec(), deny, SECCOMP_RET_ALLOW)
+            }
+            SeccompProfile::Allow(allowed) => {
+                let mut list: Vec<libc::c_long> = allowed.iter().map(|nr| *nr as _).collect();
+                list.push(libc::SYS_execve);
+                list.sort_unstable();
+                list.dedup();
+                (list, SECCOMP_RET_ALLOW, deny)
+            }
+        };
+
+        let mut prog = vec![
+            stmt(BPF_LD_W_ABS, DATA_ARCH_OFFSET),
+            jump(BPF_JMP_JEQ_K, arch, 1, 0),
+            stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+            stmt(BPF_LD_W_ABS, DATA_NR_OFFSET),
+        ];
+        if cfg!(target_arch = "x86_64") {
+            prog.push(jump(BPF_JMP_JGE_K, X32_SYSCALL_BIT, 0, 1));
+            prog.push(stmt(BPF_RET_K, deny));
+        }
+        // One `JEQ` + `RET` pair per syscall keeps every jump offset at 0/1 regardless of list length.
+        for nr in listed {
+            prog.push(jump(BPF_JMP_JEQ_K, nr as u32, 0, 1));
+            prog.push(stmt(BPF_RET_K, on_match));
+        }
+        prog.push(stmt(BPF_RET_K, otherwise));
+        Some(SeccompFilter(prog))
+    }
+
+    /// Install a compiled filter for the calling thread.
+    ///
+    /// Sets `no_new_privs` first (required to install filters without `CAP_SYS_ADMIN`).
+    /// Only async-signal-safe calls are made, so this is safe to use inside `pre_exec`.
+    pub(crate) fn install(filter: &SeccompFilter) -> io::Result<()> {
+        let rc = unsafe { libc::prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let prog = libc::sock_fprog {
+            len: filter.0.len() as libc::c_ushort,
+            filter: filter.0.as_ptr() as *mut libc::sock_filter,
+        };
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_seccomp,
+                SECCOMP_SET_MODE_FILTER,
+                0,
+                &prog as *const libc::sock_fprog,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profile_has_one_check_per_syscall() {
+        let filter = compile(&SeccompProfile::DefaultDenyDangerous).expect("unsupported arch");
+        let header = if cfg!(target_arch = "x86_64") { 6 } else { 4 };
+        assert_eq!(
+            filter.len(),
+            header + 2 * linux_impl::DANGEROUS_SYSCALLS.len() + 1
+        );
+    }
+
+    #[test]
+    fn allow_profile_always_includes_execve() {
+        let a = compile(&SeccompProfile::Allow(vec![])).expect("unsupported arch");
+        let b = compile(&SeccompProfile::Allow(vec![libc::SYS_execve])).unwrap();
+        assert_eq!(a.len(), b.len());
+    }
+}
@@ -7,7 +7,7 @@
 //! - On **non-Linux platforms**, limits are ignored: a warning is emitted and the call returns `Ok(())`.
 use tokio::process::Command;
 
-use crate::utils::LinuxCapability;
+use crate::utils::{LandlockRuleset, LinuxCapability, NamespaceConfig, SeccompProfile};
 
 #[cfg(not(target_os = "linux"))]
 use tracing::warn;
@@ -29,13 +29,50 @@ pub struct SecurityConfig {
     /// This flag works without root privileges.
     /// Failures to set this flag are fatal (spawn will fail).
     pub no_new_privs: bool,
+    /// Switch to this uid before exec.
+    ///
+    /// Requires `CAP_SETUID` in the agent (e.g. running as root).
+    /// Capabilities are cleared by the kernel when leaving uid 0, so `keep_caps` has no effect in combination with a non-root uid.
+    /// Failures are fatal (spawn will fail).
+    pub run_as_uid: Option<u32>,
+    /// Switch to this gid before exec.
+    ///
+    /// Requires `CAP_SETGID` in the agent.
+    /// Setting either `run_as_uid` or `run_as_gid` also clears supplementary groups.
+    /// Failures are fatal (spawn will fail).
+    pub run_as_gid: Option<u32>,
+    /// Optional namespace isolation (mount namespace, restricted root).
+    ///
+    /// Requires `CAP_SYS_ADMIN` in the agent; see [`NamespaceConfig`].
+    /// Failures are fatal (spawn will fail).
+    pub namespace: Option<NamespaceConfig>,
+    /// Optional Landlock filesystem allowlist.
+    ///
+    /// Works without root and implies `no_new_privs`.
+    /// Skipped with a warning on kernels without Landlock support; other failures are fatal (spawn will fail).
+    pub landlock: Option<LandlockRuleset>,
+    /// Optional seccomp syscall filter.
+    ///
+    /// Installing a filter implies `no_new_privs`.
+    /// Failures to install the filter are fatal (spawn will fail): a partially applied filter is worse than none.
+    pub seccomp: Option<SeccompProfile>,
 }
 
 impl SecurityConfig {
     /// Returns `true` if no security knobs are configured.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        !self.drop_all_caps && self.keep_caps.is_empty() && !self.no_new_privs
+        !self.drop_all_caps
+            && self.keep_caps.is_empty()
+            && !self.no_new_privs
+            && self.run_as_uid.is_none()
+            && self.run_as_gid.is_none()
+            && self
+                .namespace
+                .as_ref()
+                .is_none_or(NamespaceConfig::is_empty)
+            && self.landlock.is_none()
+            && self.seccomp.is_none()
     }
 }
 
@@ -59,12 +96,16 @@ pub fn attach_security(cmd: &mut Command, config: &SecurityConfig) {
     }
 }
 
+#[cfg(target_os = "linux")]
+pub(super) use linux_impl::hook as pre_exec_hook;
+
 #[cfg(target_os = "linux")]
 mod linux_impl {
     use super::SecurityConfig;
     use crate::utils::{
-        LinuxCapability,
+        LinuxCapability, landlock,
         log::{pre_exec_log, pre_exec_log_errno},
+        namespace, seccomp,
     };
 
     use std::io;
@@ -82,23 +123,103 @@ mod linux_impl {
         if config.is_empty() {
             return;
         }
+        unsafe {
+            cmd.pre_exec(hook(config));
+        }
+    }
 
+    /// Build the `pre_exec` hook applying `config` in the child.
+    pub fn hook(config: &SecurityConfig) -> impl FnMut() -> io::Result<()> + Send + Sync + 'static {
         let cfg = config.clone();
-        unsafe {
-            cmd.pre_exec(move || {
-                if cfg.drop_all_caps
-                    && let Err(e) = drop_capabilities(&cfg.keep_caps)
-                {
-                    pre_exec_log(b"tno-exec: failed to drop capabilities (continuing): ");
-                    if let Some(code) = e.raw_os_error() {
-                        pre_exec_log_errno(code);
+        // Compile outside the hook: allocating after `fork` is not async-signal-safe.
+        let filter = cfg.seccomp.as_ref().map(seccomp::compile);
+        let ns = cfg
+            .namespace
+            .as_ref()
+            .filter(|ns| !ns.is_empty())
+            .map(|ns| namespace::prepare(ns).map_err(|e| e.kind()));
+        let ruleset = cfg.landlock.as_ref().and_then(|ruleset| {
+            let Some(abi) = landlock::abi_version() else {
+                tracing::warn!(
+                    "landlock is not supported by this kernel; filesystem allowlist will be ignored"
+                );
+                return None;
+            };
+            Some(landlock::prepare(ruleset, abi).map_err(|e| e.kind()))
+        });
+        move || {
+            // Namespaces first: they need CAP_SYS_ADMIN and syscalls denied by the seccomp profiles.
+            match &ns {
+                None => {}
+                Some(Ok(ns)) => {
+                    if let Err(e) = namespace::enter(ns) {
+                        pre_exec_log(b"tno-exec: failed to set up mount namespace: ");
+                        if let Some(code) = e.raw_os_error() {
+                            pre_exec_log_errno(code);
+                        }
+                        return Err(e);
+                    }
+                }
+                Some(Err(kind)) => {
+                    pre_exec_log(b"tno-exec: invalid namespace root_dir\n");
+                    return Err(io::Error::from(*kind));
+                }
+            }
+            // Identity first: dropping capabilities would remove CAP_SETUID/CAP_SETGID.
+            if (cfg.run_as_uid.is_some() || cfg.run_as_gid.is_some())
+                && let Err(e) = switch_identity(cfg.run_as_uid, cfg.run_as_gid)
+            {
+                pre_exec_log(b"tno-exec: failed to switch uid/gid: ");
+                if let Some(code) = e.raw_os_error() {
+                    pre_exec_log_errno(code);
+                }
+                return Err(e);
+            }
+            if cfg.drop_all_caps
+                && let Err(e) = drop_capabilities(&cfg.keep_caps)
+            {
+                pre_exec_log(b"tno-exec: failed to drop capabilities (continuing): ");
+                if let Some(code) = e.raw_os_error() {
+                    pre_exec_log_errno(code);
+                }
+            }
+            if cfg.no_new_privs {
+                apply_no_new_privs()?;
+            }
+            match &ruleset {
+                None => {}
+                Some(Ok(ruleset)) => {
+                    if let Err(e) = landlock::restrict_self(ruleset) {
+                        pre_exec_log(b"tno-exec: failed to apply landlock ruleset: ");
+                        if let Some(code) = e.raw_os_error() {
+                            pre_exec_log_errno(code);
+                        }
+                        return Err(e);
+                    }
+                }
+                Some(Err(kind)) => {
+                    pre_exec_log(b"tno-exec: invalid landlock path\n");
+                    return Err(io::Error::from(*kind));
+                }
+            }
+            // Must stay last: the filter may deny syscalls used by the steps above.
+            match &filter {
+                None => {}
+                Some(Some(filter)) => {
+                    if let Err(e) = seccomp::install(filter) {
+                        pre_exec_log(b"tno-exec: failed to install seccomp filter: ");
+                        if let Some(code) = e.raw_os_error() {
+                            pre_exec_log_errno(code);
+                        }
+                        return Err(e);
                     }
                 }
-                if cfg.no_new_privs {
-                    apply_no_new_privs()?;
+                Some(None) => {
+                    pre_exec_log(b"tno-exec: seccomp is not supported on this architecture\n");
+                    return Err(io::Error::from(io::ErrorKind::Unsupported));
                 }
-                Ok(())
-            });
+            }
+            Ok(())
         }
     }
 
@@ -223,6 +344,24 @@ mod linux_impl {
         Ok(())
     }
 
+    /// Clear supplementary groups, then switch gid and uid (in this order: after `setuid` the process can no longer change its gid).
+    fn switch_identity(uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        if unsafe { libc::setgroups(0, std::ptr::null()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if let Some(gid) = gid
+            && unsafe { libc::setgid(gid) } != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        if let Some(uid) = uid
+            && unsafe { libc::setuid(uid) } != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
     fn apply_no_new_privs() -> io::Result<()> {
         let rc = unsafe { libc::prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
         if rc != 0 {
@@ -308,6 +447,11 @@ mod tests {
             drop_all_caps: true,
             keep_caps: vec![LinuxCapability::NetAdmin, LinuxCapability::NetBindService],
             no_new_privs: true,
+            run_as_uid: None,
+            run_as_gid: None,
+            namespace: None,
+            landlock: None,
+            seccomp: None,
         };
 
         assert!(!cfg.is_empty());
@@ -323,6 +467,11 @@ mod tests {
             drop_all_caps: true,
             keep_caps: vec![LinuxCapability::NetAdmin],
             no_new_privs: true,
+            run_as_uid: None,
+            run_as_gid: None,
+            namespace: None,
+            landlock: None,
+            seccomp: None,
         };
 
         assert!(!cfg.is_empty());
@@ -345,6 +494,11 @@ mod tests {
             drop_all_caps: false,
             keep_caps: vec![],
             no_new_privs: true,
+            run_as_uid: None,
+            run_as_gid: None,
+            namespace: None,
+            landlock: None,
+            seccomp: None,
         };
         let mut cmd = Command::new("true");
         attach_security(&mut cmd, &cfg);
@@ -353,4 +507,156 @@ mod tests {
         assert!(result.is_ok(), "no_new_privs should work without root");
         assert!(result.unwrap().success());
     }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn seccomp_filter_is_installed_in_child() {
+        let cfg = SecurityConfig {
+            seccomp: Some(SeccompProfile::DefaultDenyDangerous),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("grep");
+        cmd.args(["^Seccomp:", "/proc/self/status"]);
+        attach_security(&mut cmd, &cfg);
+
+        let out = cmd
+            .output()
+            .await
+            .expect("spawn with seccomp filter failed");
+        assert!(out.status.success());
+        let line = String::from_utf8_lossy(&out.stdout);
+        assert_eq!(line.split_whitespace().nth(1), Some("2"), "{line}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn run_as_switches_uid_and_gid() {
+        let cfg = SecurityConfig {
+            run_as_uid: Some(65534),
+            run_as_gid: Some(65534),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("id");
+        attach_security(&mut cmd, &cfg);
+
+        let is_root = unsafe { libc::geteuid() } == 0;
+        match cmd.output().await {
+            Ok(out) if is_root => {
+                let id = String::from_utf8_lossy(&out.stdout);
+                assert!(id.contains("uid=65534"), "{id}");
+                assert!(id.contains("gid=65534"), "{id}");
+                assert!(
+                    !id.contains("groups=0"),
+                    "supplementary groups not cleared: {id}"
+                );
+            }
+            Ok(out) => panic!("unprivileged switch must fail, got {:?}", out.status),
+            Err(e) => assert!(!is_root, "switch failed as root: {e}"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn landlock_confines_writes_to_allowlist() {
+        if crate::utils::landlock::abi_version().is_none() {
+            eprintln!("landlock unsupported; skipping");
+            return;
+        }
+        let base = std::env::temp_dir().join(format!("tno-landlock-{}", std::process::id()));
+        let (allowed, denied) = (base.join("rw"), base.join("ro"));
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&denied).unwrap();
+
+        let cfg = SecurityConfig {
+            landlock: Some(LandlockRuleset {
+                read_only: vec!["/".into()],
+                read_write: vec![allowed.clone()],
+            }),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(format!(
+            "echo ok > {}/f && echo no > {}/f",
+            allowed.display(),
+            denied.display()
+        ));
+        attach_security(&mut cmd, &cfg);
+        let status = cmd.status().await.expect("spawn with landlock failed");
+        let (wrote_allowed, wrote_denied) = (allowed.join("f").exists(), denied.join("f").exists());
+        std::fs::remove_dir_all(&base).unwrap();
+
+        assert!(!status.success());
+        assert!(wrote_allowed);
+        assert!(!wrote_denied);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn can_unshare_mount_ns() -> bool {
+        if unsafe { libc::geteuid() } != 0 {
+            eprintln!("not running as root; skipping");
+            return false;
+        }
+        true
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn new_mount_ns_isolates_child() {
+        if !can_unshare_mount_ns() {
+            return;
+        }
+        let cfg = SecurityConfig {
+            namespace: Some(NamespaceConfig {
+                new_mount_ns: true,
+                root_dir: None,
+            }),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("readlink");
+        cmd.arg("/proc/self/ns/mnt");
+        attach_security(&mut cmd, &cfg);
+
+        let out = match cmd.output().await {
+            Ok(out) => out,
+            Err(e) => {
+                eprintln!("mount namespaces unavailable ({e}); skipping");
+                return;
+            }
+        };
+        assert!(out.status.success());
+        let host = std::fs::read_link("/proc/self/ns/mnt").unwrap();
+        let child = String::from_utf8_lossy(&out.stdout);
+        assert_ne!(child.trim(), host.to_string_lossy());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn root_dir_hides_host_filesystem() {
+        if !can_unshare_mount_ns() {
+            return;
+        }
+        // An empty root: the host `true` binary must not be reachable after `pivot_root`.
+        let root = std::env::temp_dir().join(format!("tno-nsroot-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let cfg = SecurityConfig {
+            namespace: Some(NamespaceConfig {
+                new_mount_ns: true,
+                root_dir: Some(root.clone()),
+            }),
+            ..Default::default()
+        };
+        let mut cmd = Command::new("/bin/true");
+        attach_security(&mut cmd, &cfg);
+        let res = cmd.status().await;
+        std::fs::remove_dir_all(&root).unwrap();
+
+        match res {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) if e.raw_os_error() == Some(libc::EPERM) => {
+                eprintln!("mount namespaces unavailable ({e}); skipping");
+            }
+            other => panic!("expected exec to fail inside empty root, got {other:?}"),
+        }
+    }
 }
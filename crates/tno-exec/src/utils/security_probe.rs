@@ -0,0 +1,209 @@
+//! Warm-up check that a [`SecurityConfig`] is actually enforced.
+//!
+//! Most security steps run in a `pre_exec` hook and only log on failure (e.g. capabilities that cannot be kept).
+//! The probe spawns a trivial process under the full config and compares the state it reports
+//! (`/proc/self/status`) with what the config asks for.
+use crate::ExecError;
+use crate::utils::SecurityConfig;
+
+#[cfg(not(target_os = "linux"))]
+use tracing::warn;
+
+/// Command of the probe process; it prints its own `/proc/self/status`.
+pub const SECURITY_PROBE_COMMAND: &str = "/bin/cat";
+
+/// Security state of the probe process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityProbeReport {
+    /// Effective capability set (`CapEff`).
+    pub effective_caps: u64,
+    /// Permitted capability set (`CapPrm`).
+    pub permitted_caps: u64,
+    /// Whether `no_new_privs` is set (`NoNewPrivs`).
+    pub no_new_privs: bool,
+    /// Effective uid.
+    pub uid: u32,
+    /// Effective gid.
+    pub gid: u32,
+}
+
+impl SecurityProbeReport {
+    /// Parse the content of `/proc/<pid>/status`.
+    ///
+    /// Returns `None` if a required field is missing.
+    pub(crate) fn parse(status: &str) -> Option<Self> {
+        let field = |name: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(':').map(str::trim))
+        };
+        // `Uid:` / `Gid:` list real, effective, saved and filesystem ids.
+        let id = |name: &str| field(name)?.split_whitespace().nth(1)?.parse().ok();
+        let caps = |name: &str| u64::from_str_radix(field(name)?, 16).ok();
+        Some(Self {
+            effective_caps: caps("CapEff")?,
+            permitted_caps: caps("CapPrm")?,
+            no_new_privs: field("NoNewPrivs")? == "1",
+            uid: id("Uid")?,
+            gid: id("Gid")?,
+        })
+    }
+
+    /// Check that the reported state matches `config`.
+    ///
+    /// Returns a description of the first mismatch.
+    pub fn verify(&self, config: &SecurityConfig) -> Result<(), String> {
+        // Installing a seccomp filter sets `no_new_privs` as well.
+        if (config.no_new_privs || config.seccomp.is_some()) && !self.no_new_privs {
+            return Err("no_new_privs is not set".into());
+        }
+        if let Some(uid) = config.run_as_uid
+            && self.uid != uid
+        {
+            return Err(format!("running as uid {} instead of {uid}", self.uid));
+        }
+        if let Some(gid) = config.run_as_gid
+            && self.gid != gid
+        {
+            return Err(format!("running as gid {} instead of {gid}", self.gid));
+        }
+        if config.drop_all_caps {
+            let keep = config
+                .keep_caps
+                .iter()
+                .fold(0u64, |mask, cap| mask | 1 << cap.to_cap_value());
+            let extra = (self.effective_caps | self.permitted_caps) & !keep;
+            if extra != 0 {
+                return Err(format!("capabilities were not dropped: {extra:#x}"));
+            }
+            if let Some(cap) = config
+                .keep_caps
+                .iter()
+                .find(|cap| self.effective_caps & (1 << cap.to_cap_value()) == 0)
+            {
+                return Err(format!("capability CAP_{} could not be kept", cap.name()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Spawn the probe process under `config` and report its security state.
+///
+/// The probe is [`SECURITY_PROBE_COMMAND`]; it must be reachable under the config (e.g. inside `namespace.root_dir`).
+/// Blocks until the probe exits.
+#[cfg(target_os = "linux")]
+pub fn probe_security(config: &SecurityConfig) -> Result<SecurityProbeReport, ExecError> {
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    let mut cmd = Command::new(SECURITY_PROBE_COMMAND);
+    cmd.arg("/proc/self/status")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    unsafe {
+        cmd.pre_exec(super::security::pre_exec_hook(config));
+    }
+    let out = cmd
+        .output()
+        .map_err(|e| ExecError::InvalidRunnerConfig(format!("security probe failed: {e}")))?;
+    if !out.status.success() {
+        return Err(ExecError::InvalidRunnerConfig(format!(
+            "security probe exited with {}",
+            out.status
+        )));
+    }
+    SecurityProbeReport::parse(&String::from_utf8_lossy(&out.stdout))
+        .ok_or_else(|| ExecError::InvalidRunnerConfig("security probe output is incomplete".into()))
+}
+
+/// Check that `config` is enforced, by running [`probe_security`] and [`SecurityProbeReport::verify`].
+///
+/// Security settings are not enforced outside Linux, so there is nothing to check: a warning is emitted and the call returns `Ok(())`.
+pub fn warm_up_security(config: &SecurityConfig) -> Result<(), ExecError> {
+    if config.is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        probe_security(config)?.verify(config).map_err(|reason| {
+            ExecError::InvalidRunnerConfig(format!("security config is not enforced: {reason}"))
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        warn!(
+            "security warm-up is only supported on Linux; current OS={}",
+            std::env::consts::OS
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::LinuxCapability;
+
+    const STATUS: &str = "Name:\tcat\nUid:\t0\t65534\t65534\t65534\nGid:\t0\t100\t100\t100\n\
+        CapPrm:\t0000000000000400\nCapEff:\t0000000000000400\nNoNewPrivs:\t1\n";
+
+    #[test]
+    fn report_is_parsed_and_verified() {
+        let report = SecurityProbeReport::parse(STATUS).unwrap();
+        assert_eq!(
+            report,
+            SecurityProbeReport {
+                effective_caps: 1 << 10,
+                permitted_caps: 1 << 10,
+                no_new_privs: true,
+                uid: 65534,
+                gid: 100,
+            }
+        );
+
+        let cfg = SecurityConfig {
+            drop_all_caps: true,
+            keep_caps: vec![LinuxCapability::NetBindService],
+            no_new_privs: true,
+            run_as_uid: Some(65534),
+            ..Default::default()
+        };
+        report.verify(&cfg).unwrap();
+
+        let cfg = SecurityConfig {
+            keep_caps: vec![LinuxCapability::NetAdmin],
+            ..cfg
+        };
+        let err = report.verify(&cfg).unwrap_err();
+        assert!(err.contains("not dropped"), "{err}");
+
+        assert!(SecurityProbeReport::parse("Name:\tcat\n").is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn warm_up_detects_capabilities_that_cannot_be_kept() {
+        if unsafe { libc::geteuid() } != 0 {
+            eprintln!("not running as root; skipping");
+            return;
+        }
+        let valid = SecurityConfig {
+            drop_all_caps: true,
+            keep_caps: vec![LinuxCapability::NetBindService],
+            no_new_privs: true,
+            ..Default::default()
+        };
+        warm_up_security(&valid).unwrap();
+
+        // Leaving uid 0 clears all capabilities, so the kept one is lost.
+        let impossible = SecurityConfig {
+            run_as_uid: Some(65534),
+            run_as_gid: Some(65534),
+            ..valid
+        };
+        let err = warm_up_security(&impossible).unwrap_err().to_string();
+        assert!(err.contains("CAP_NET_BIND_SERVICE"), "{err}");
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Slot, TaskInfo, TaskStatus};
+
+/// Number of recent terminal tasks included in a dashboard snapshot by default.
+pub const DEFAULT_DASHBOARD_RECENT: usize = 10;
+
+/// Coherent point-in-time overview of all known tasks.
+///
+/// Built from a single consistent read of task state, so all counts agree with each other.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSnapshot {
+    /// Number of known tasks.
+    pub total: usize,
+    /// Task counts by status (statuses without tasks are omitted).
+    pub by_status: BTreeMap<TaskStatus, usize>,
+    /// Active (pending or running) task counts by runner name.
+    pub active_by_runner: BTreeMap<String, usize>,
+    /// Occupancy of slots that have active tasks.
+    pub slots: BTreeMap<Slot, SlotOccupancy>,
+    /// Most recently finished tasks, newest first.
+    pub recent_terminal: Vec<TaskInfo>,
+}
+
+/// Active tasks within a single slot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotOccupancy {
+    /// Tasks currently executing.
+    pub running: usize,
+    /// Tasks waiting to start.
+    pub pending: usize,
+}
+
+impl DashboardSnapshot {
+    /// Aggregate a snapshot from `(task, runner name)` pairs in a single pass.
+    ///
+    /// At most `recent` terminal tasks (by last update) are kept in [`DashboardSnapshot::recent_terminal`].
+    pub fn collect<'a, I>(tasks: I, recent: usize) -> Self
+    where
+        I: IntoIterator<Item = (&'a TaskInfo, &'a str)>,
+    {
+        let mut snapshot = Self::default();
+        let mut terminal = Vec::new();
+
+        for (info, runner) in tasks {
+            snapshot.total += 1;
+            *snapshot.by_status.entry(info.status).or_default() += 1;
+
+            match info.status {
+                TaskStatus::Running | TaskStatus::Pending => {
+                    *snapshot
+                        .active_by_runner
+                        .entry(runner.to_string())
+                        .or_default() += 1;
+                    let slot = snapshot.slots.entry(info.slot.clone()).or_default();
+                    if info.status == TaskStatus::Running {
+                        slot.running += 1;
+                    } else {
+                        slot.pending += 1;
+                    }
+                }
+                _ => terminal.push(info),
+            }
+        }
+
+        terminal.sort_by_key(|t| std::cmp::Reverse(t.updated_at));
+        snapshot.recent_terminal = terminal.into_iter().take(recent).cloned().collect();
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::TaskId;
+
+    fn task(id: &str, slot: &str, status: TaskStatus, updated_secs: u64) -> TaskInfo {
+        TaskInfo {
+            id: TaskId::from(id),
+            slot: slot.to_string(),
+            status,
+            attempt: 1,
+            created_at: UNIX_EPOCH,
+            updated_at: UNIX_EPOCH + Duration::from_secs(updated_secs),
+            error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
+        }
+    }
+
+    #[test]
+    fn serializes_status_keys_as_names() {
+        let tasks = [task("a", "s", TaskStatus::Running, 1)];
+        let snapshot = DashboardSnapshot::collect(tasks.iter().map(|t| (t, "runner")), 1);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["byStatus"]["running"], 1);
+        assert_eq!(json["activeByRunner"]["runner"], 1);
+        assert_eq!(json["slots"]["s"]["running"], 1);
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Liveness of the supervisor together with the amount of work it holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthStatus {
+    /// Whether the supervisor run loop is alive, i.e. submitted tasks still run.
+    pub alive: bool,
+    /// Error the run loop exited with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Tasks currently executing.
+    pub running: usize,
+    /// Tasks waiting to start.
+    pub pending: usize,
+}
+
+impl HealthStatus {
+    /// Build a status from the run loop error (alive if `None`) and active task counts.
+    pub fn new(error: Option<String>, running: usize, pending: usize) -> Self {
+        Self {
+            alive: error.is_none(),
+            error,
+            running,
+            pending,
+        }
+    }
+}
@@ -8,13 +8,16 @@ mod flag;
 pub use flag::Flag;
 
 mod runner_labels;
-pub use runner_labels::RunnerLabels;
+pub use runner_labels::{
+    DEFAULT_MAX_LABEL_KEY_LEN, DEFAULT_MAX_LABEL_VALUE_LEN, DEFAULT_MAX_LABELS, LabelLimits,
+    RunnerLabels,
+};
 
 mod constants;
 pub use constants::LABEL_RUNNER_TAG;
 
 mod task_id;
-pub use task_id::TaskId;
+pub use task_id::{MAX_TASK_ID_LEN, TaskId};
 
 mod task_info;
 pub use task_info::TaskInfo;
@@ -22,6 +25,30 @@ pub use task_info::TaskInfo;
 mod task_status;
 pub use task_status::TaskStatus;
 
+mod runner_error;
+pub use runner_error::RunnerErrorInfo;
+
+mod submission;
+pub use submission::Submission;
+
+mod dashboard;
+pub use dashboard::{DEFAULT_DASHBOARD_RECENT, DashboardSnapshot, SlotOccupancy};
+
+mod health;
+pub use health::HealthStatus;
+
+mod task_counts;
+pub use task_counts::TaskCounts;
+
+mod task_filter;
+pub use task_filter::TaskFilter;
+
+mod task_query;
+pub use task_query::{SortDirection, TaskQuery, TaskSortKey};
+
+mod task_page;
+pub use task_page::{DEFAULT_TASK_PAGE_SIZE, MAX_TASK_PAGE_SIZE, TaskPage};
+
 /// Logical identifier for a controller slot.
 ///
 /// A slot groups tasks that must not run concurrently.
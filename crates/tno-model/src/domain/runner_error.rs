@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+use super::task_info::time_serde;
+use crate::TaskId;
+
+/// Runner-level error that is not reflected in a task's own status (spawn or runner config failures).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerErrorInfo {
+    /// Name of the runner that reported the error.
+    pub runner: String,
+    /// Error category (same value as the `error_kind` metric label, e.g. `"spawn_failed"`).
+    pub kind: String,
+    /// Human-readable error message.
+    pub message: String,
+    /// Task the runner was working on (if any).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<TaskId>,
+    /// When the error was reported.
+    #[serde(with = "time_serde")]
+    pub at: SystemTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_in_camel_case_without_empty_task() {
+        let info = RunnerErrorInfo {
+            runner: "subprocess".to_string(),
+            kind: "spawn_failed".to_string(),
+            message: "No such file or directory".to_string(),
+            task_id: None,
+            at: SystemTime::UNIX_EPOCH,
+        };
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["kind"], "spawn_failed");
+        assert_eq!(json["at"], 0);
+        assert!(json.get("taskId").is_none());
+    }
+}
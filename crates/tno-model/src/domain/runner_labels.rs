@@ -2,6 +2,70 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{ModelError, ModelResult};
+
+/// Default maximum number of labels per set.
+pub const DEFAULT_MAX_LABELS: usize = 64;
+/// Default maximum label key length in bytes.
+pub const DEFAULT_MAX_LABEL_KEY_LEN: usize = 128;
+/// Default maximum label value length in bytes.
+pub const DEFAULT_MAX_LABEL_VALUE_LEN: usize = 512;
+
+/// Size limits applied to [`RunnerLabels`].
+///
+/// Labels arrive from API clients and are used for routing, so their size must be bounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LabelLimits {
+    /// Maximum number of labels.
+    pub max_labels: usize,
+    /// Maximum key length in bytes.
+    pub max_key_len: usize,
+    /// Maximum value length in bytes.
+    pub max_value_len: usize,
+}
+
+impl Default for LabelLimits {
+    fn default() -> Self {
+        Self {
+            max_labels: DEFAULT_MAX_LABELS,
+            max_key_len: DEFAULT_MAX_LABEL_KEY_LEN,
+            max_value_len: DEFAULT_MAX_LABEL_VALUE_LEN,
+        }
+    }
+}
+
+impl LabelLimits {
+    /// Check a single key/value pair against length limits.
+    pub fn check_entry(&self, key: &str, val: &str) -> ModelResult<()> {
+        if key.len() > self.max_key_len {
+            return Err(ModelError::LabelLimitExceeded(format!(
+                "key length {} exceeds {} bytes",
+                key.len(),
+                self.max_key_len
+            )));
+        }
+        if val.len() > self.max_value_len {
+            return Err(ModelError::LabelLimitExceeded(format!(
+                "value length {} for key '{key}' exceeds {} bytes",
+                val.len(),
+                self.max_value_len
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a label count against the count limit.
+    pub fn check_count(&self, count: usize) -> ModelResult<()> {
+        if count > self.max_labels {
+            return Err(ModelError::LabelLimitExceeded(format!(
+                "{count} labels exceed the maximum of {}",
+                self.max_labels
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Structured key–value metadata based on [`BTreeMap`].
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -18,16 +82,48 @@ impl RunnerLabels {
         self.0.is_empty()
     }
 
-    /// Insert or overwrite a label.
+    /// Number of labels.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Insert or overwrite a label, enforcing [`LabelLimits::default`].
     ///
     /// Returns `self` for chaining.
-    pub fn insert<K, V>(&mut self, key: K, val: V) -> &mut Self
+    pub fn insert<K, V>(&mut self, key: K, val: V) -> ModelResult<&mut Self>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.insert_with_limits(key, val, &LabelLimits::default())
+    }
+
+    /// Insert or overwrite a label, enforcing the given limits.
+    ///
+    /// Overwriting an existing key never fails on the count limit.
+    pub fn insert_with_limits<K, V>(
+        &mut self,
+        key: K,
+        val: V,
+        limits: &LabelLimits,
+    ) -> ModelResult<&mut Self>
     where
         K: Into<String>,
         V: Into<String>,
     {
-        self.0.insert(key.into(), val.into());
-        self
+        let (key, val) = (key.into(), val.into());
+        limits.check_entry(&key, &val)?;
+        if !self.0.contains_key(&key) {
+            limits.check_count(self.0.len() + 1)?;
+        }
+        self.0.insert(key, val);
+        Ok(self)
+    }
+
+    /// Validate the whole label set against the given limits.
+    pub fn validate(&self, limits: &LabelLimits) -> ModelResult<()> {
+        limits.check_count(self.0.len())?;
+        self.iter().try_for_each(|(k, v)| limits.check_entry(k, v))
     }
 
     /// Get the value for a key, if present.
@@ -39,4 +135,98 @@ impl RunnerLabels {
     pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
         self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
     }
+
+    /// Returns `true` if every `selector` pair is present in `self` with an equal value.
+    ///
+    /// An empty selector matches any label set.
+    pub fn matches(&self, selector: &RunnerLabels) -> bool {
+        selector.iter().all(|(k, v)| self.get(k) == Some(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_limits() -> LabelLimits {
+        LabelLimits {
+            max_labels: 2,
+            max_key_len: 8,
+            max_value_len: 16,
+        }
+    }
+
+    #[test]
+    fn insert_rejects_too_many_labels() {
+        let limits = small_limits();
+        let mut labels = RunnerLabels::new();
+        labels.insert_with_limits("a", "1", &limits).unwrap();
+        labels.insert_with_limits("b", "2", &limits).unwrap();
+
+        let err = labels.insert_with_limits("c", "3", &limits).unwrap_err();
+        assert!(matches!(err, ModelError::LabelLimitExceeded(_)));
+        assert_eq!(labels.len(), 2);
+
+        // Overwriting an existing key is still allowed at the limit.
+        labels.insert_with_limits("a", "updated", &limits).unwrap();
+        assert_eq!(labels.get("a"), Some("updated"));
+    }
+
+    #[test]
+    fn insert_rejects_oversized_key_and_value() {
+        let limits = small_limits();
+        let mut labels = RunnerLabels::new();
+
+        let err = labels
+            .insert_with_limits("very-long-key", "v", &limits)
+            .unwrap_err();
+        assert!(matches!(err, ModelError::LabelLimitExceeded(_)));
+
+        let err = labels
+            .insert_with_limits("k", "x".repeat(17), &limits)
+            .unwrap_err();
+        assert!(matches!(err, ModelError::LabelLimitExceeded(_)));
+        assert!(labels.is_empty());
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> RunnerLabels {
+        RunnerLabels(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn matches_subset_selector() {
+        let runner = labels(&[("runner-tag", "a"), ("zone", "eu"), ("tier", "gold")]);
+        assert!(runner.matches(&labels(&[("runner-tag", "a")])));
+        assert!(runner.matches(&labels(&[("zone", "eu"), ("tier", "gold")])));
+    }
+
+    #[test]
+    fn matches_rejects_mismatch_and_missing_keys() {
+        let runner = labels(&[("runner-tag", "a"), ("zone", "eu")]);
+        assert!(!runner.matches(&labels(&[("runner-tag", "b")])));
+        assert!(!runner.matches(&labels(&[("runner-tag", "a"), ("tier", "gold")])));
+        assert!(!RunnerLabels::new().matches(&labels(&[("zone", "eu")])));
+    }
+
+    #[test]
+    fn empty_selector_matches_everything() {
+        let selector = RunnerLabels::new();
+        assert!(RunnerLabels::new().matches(&selector));
+        assert!(labels(&[("zone", "eu")]).matches(&selector));
+    }
+
+    #[test]
+    fn validate_checks_whole_set() {
+        let mut labels = RunnerLabels::new();
+        for i in 0..3 {
+            labels.0.insert(format!("k{i}"), "v".into());
+        }
+        assert!(labels.validate(&small_limits()).is_err());
+        assert!(labels.validate(&LabelLimits::default()).is_ok());
+    }
 }
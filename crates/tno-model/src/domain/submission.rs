@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{AdmissionOutcome, TaskId};
+
+/// Result of a successful task submission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Submission {
+    /// ID of the submitted task.
+    ///
+    /// For [`AdmissionOutcome::Dropped`] and idempotent resubmits this is the task that already holds the slot or key.
+    pub task_id: TaskId,
+    /// What the controller does with the task.
+    pub admission: AdmissionOutcome,
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Slot, TaskInfo, TaskStatus};
+
+/// Number of tracked tasks by status and by slot.
+///
+/// Statuses and slots without tasks are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskCounts {
+    /// Number of tracked tasks.
+    pub total: usize,
+    /// Task counts by status.
+    pub by_status: BTreeMap<TaskStatus, usize>,
+    /// Task counts by slot.
+    pub by_slot: BTreeMap<Slot, usize>,
+}
+
+impl TaskCounts {
+    /// Tally `tasks` in a single pass.
+    pub fn collect<'a, I>(tasks: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TaskInfo>,
+    {
+        let mut counts = Self::default();
+        for info in tasks {
+            counts.total += 1;
+            *counts.by_status.entry(info.status).or_default() += 1;
+            *counts.by_slot.entry(info.slot.clone()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Number of tasks in `status`.
+    pub fn status(&self, status: TaskStatus) -> usize {
+        self.by_status.get(&status).copied().unwrap_or(0)
+    }
+
+    /// Number of tasks in `slot`.
+    pub fn slot(&self, slot: &str) -> usize {
+        self.by_slot.get(slot).copied().unwrap_or(0)
+    }
+
+    /// Number of pending or running tasks.
+    pub fn active(&self) -> usize {
+        self.status(TaskStatus::Pending) + self.status(TaskStatus::Running)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::UNIX_EPOCH;
+
+    use crate::TaskId;
+
+    fn task(id: &str, slot: &str, status: TaskStatus) -> TaskInfo {
+        TaskInfo {
+            id: TaskId::from(id),
+            slot: slot.to_string(),
+            status,
+            attempt: 1,
+            created_at: UNIX_EPOCH,
+            updated_at: UNIX_EPOCH,
+            error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
+        }
+    }
+
+    #[test]
+    fn counts_by_status_and_slot() {
+        let tasks = [
+            task("a", "backup", TaskStatus::Running),
+            task("b", "backup", TaskStatus::Failed),
+            task("c", "sync", TaskStatus::Pending),
+            task("d", "sync", TaskStatus::Failed),
+        ];
+        let counts = TaskCounts::collect(&tasks);
+
+        assert_eq!(counts.total, 4);
+        assert_eq!(counts.status(TaskStatus::Failed), 2);
+        assert_eq!(counts.status(TaskStatus::Succeeded), 0);
+        assert_eq!(counts.active(), 2);
+        assert_eq!(counts.slot("backup"), 2);
+        assert_eq!(counts.slot("missing"), 0);
+
+        let json = serde_json::to_value(&counts).unwrap();
+        assert_eq!(json["byStatus"]["failed"], 2);
+        assert_eq!(json["bySlot"]["sync"], 2);
+    }
+}
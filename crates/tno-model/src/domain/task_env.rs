@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::KeyValue;
+use crate::error::{ModelError, ModelResult};
 
 /// List of environment variables passed to the task.
 ///
@@ -61,6 +62,33 @@ impl TaskEnv {
         self.0.push(KeyValue::new(key, value));
     }
 
+    /// Check that every entry can be put into a process environment block.
+    ///
+    /// Keys must be non-empty and contain neither `=` nor control characters (NUL included);
+    /// values must not contain NUL. Either would silently corrupt the environment of a spawned process.
+    pub fn validate(&self) -> ModelResult<()> {
+        for kv in self.iter() {
+            let key = kv.key();
+            if key.is_empty() {
+                return Err(ModelError::InvalidEnv("key cannot be empty".into()));
+            }
+            if key.contains('=') {
+                return Err(ModelError::InvalidEnv(format!("key {key:?} contains '='")));
+            }
+            if key.chars().any(char::is_control) {
+                return Err(ModelError::InvalidEnv(format!(
+                    "key {key:?} contains a control character"
+                )));
+            }
+            if kv.value().contains('\0') {
+                return Err(ModelError::InvalidEnv(format!(
+                    "value of {key:?} contains a NUL byte"
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Merge two environments, where entries from `other` override earlier ones.
     ///
     /// The environments are combined by simple concatenation, allowing [`RunnerEnv::get`] to resolve overrides naturally by scanning from the end.
@@ -133,6 +161,27 @@ mod tests {
         assert_eq!(merged.get("BAZ"), Some("baz"));
     }
 
+    #[test]
+    fn validate_rejects_keys_that_break_the_environment_block() {
+        let mut env = TaskEnv::new();
+        env.push("PATH", "/usr/bin");
+        env.push("lower_case.dotted", "ok");
+        env.validate().unwrap();
+
+        for (key, value) in [
+            ("FOO=BAR", "x"),
+            ("FOO\0BAR", "x"),
+            ("", "x"),
+            ("FOO", "a\0b"),
+        ] {
+            let err = TaskEnv::single(key, value).validate().unwrap_err();
+            assert!(
+                err.to_string().contains("invalid environment variable"),
+                "{err}"
+            );
+        }
+    }
+
     #[test]
     fn serde_transparent_roundtrip_json() {
         let mut env = TaskEnv::new();
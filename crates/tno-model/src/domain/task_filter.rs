@@ -0,0 +1,115 @@
+use crate::{RunnerLabels, Slot, TaskInfo, TaskStatus};
+
+/// Combined task filter: a task matches if it satisfies every criterion that is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskFilter {
+    /// Only tasks in this slot.
+    pub slot: Option<Slot>,
+    /// Only tasks in one of these statuses; empty matches any status.
+    pub statuses: Vec<TaskStatus>,
+    /// Only tasks submitted in this batch.
+    pub batch_id: Option<String>,
+    /// Only tasks carrying all of these labels (see [`RunnerLabels::matches`]); empty matches any labels.
+    pub labels: RunnerLabels,
+}
+
+impl TaskFilter {
+    /// Restrict to tasks in `slot`.
+    pub fn with_slot(mut self, slot: impl Into<Slot>) -> Self {
+        self.slot = Some(slot.into());
+        self
+    }
+
+    /// Also accept tasks in `status`.
+    pub fn with_status(mut self, status: TaskStatus) -> Self {
+        if !self.statuses.contains(&status) {
+            self.statuses.push(status);
+        }
+        self
+    }
+
+    /// Restrict to tasks submitted in batch `batch_id`.
+    pub fn with_batch(mut self, batch_id: impl Into<String>) -> Self {
+        self.batch_id = Some(batch_id.into());
+        self
+    }
+
+    /// Restrict to tasks labeled `key=value` (in addition to labels already required).
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.0.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns `true` if `info` satisfies the filter.
+    pub fn matches(&self, info: &TaskInfo) -> bool {
+        self.slot.as_ref().is_none_or(|slot| *slot == info.slot)
+            && (self.statuses.is_empty() || self.statuses.contains(&info.status))
+            && self
+                .batch_id
+                .as_ref()
+                .is_none_or(|batch| info.batch_id.as_ref() == Some(batch))
+            && info.labels.matches(&self.labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::SystemTime;
+
+    use crate::TaskId;
+
+    fn task(slot: &str, status: TaskStatus) -> TaskInfo {
+        let now = SystemTime::now();
+        TaskInfo {
+            id: TaskId::from("task"),
+            slot: slot.into(),
+            status,
+            attempt: 0,
+            created_at: now,
+            updated_at: now,
+            error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
+        }
+    }
+
+    #[test]
+    fn matches_slot_and_any_of_the_statuses() {
+        let filter = TaskFilter::default()
+            .with_slot("x")
+            .with_status(TaskStatus::Running)
+            .with_status(TaskStatus::Pending);
+
+        assert!(filter.matches(&task("x", TaskStatus::Running)));
+        assert!(filter.matches(&task("x", TaskStatus::Pending)));
+        assert!(!filter.matches(&task("x", TaskStatus::Failed)));
+        assert!(!filter.matches(&task("y", TaskStatus::Running)));
+        assert!(TaskFilter::default().matches(&task("y", TaskStatus::Failed)));
+    }
+
+    #[test]
+    fn matches_batch_and_all_labels() {
+        let mut info = task("x", TaskStatus::Running);
+        info.batch_id = Some("nightly".into());
+        info.labels.insert("team", "infra").unwrap();
+        info.labels.insert("tier", "gold").unwrap();
+
+        let filter = TaskFilter::default()
+            .with_batch("nightly")
+            .with_label("team", "infra");
+        assert!(filter.matches(&info));
+        assert!(!filter.clone().with_label("tier", "silver").matches(&info));
+        assert!(!TaskFilter::default().with_batch("weekly").matches(&info));
+        assert!(!filter.matches(&task("x", TaskStatus::Running)));
+    }
+}
@@ -2,9 +2,14 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{ModelError, ModelResult};
+
+/// Maximum length of a task ID in bytes (see [`TaskId::validate`]).
+pub const MAX_TASK_ID_LEN: usize = 128;
+
 /// Unique identifier for a task instance.
 ///
-/// Generated by the runner during task creation.
+/// Generated by the runner during task creation, unless the caller supplies its own.
 /// Format: `{runner}-{slot}-{seq:x}` (e.g., `subprocess-demo-slot-2a`)
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -27,6 +32,31 @@ impl TaskId {
     pub fn into_inner(self) -> String {
         self.0
     }
+
+    /// Check that a caller-supplied ID is usable as a task name.
+    ///
+    /// The ID must be non-empty, at most [`MAX_TASK_ID_LEN`] bytes long and consist of
+    /// ASCII letters, digits, `-`, `_` and `.` only.
+    pub fn validate(&self) -> ModelResult<()> {
+        if self.0.is_empty() {
+            return Err(ModelError::Invalid("task id cannot be empty".into()));
+        }
+        if self.0.len() > MAX_TASK_ID_LEN {
+            return Err(ModelError::Invalid(format!(
+                "task id is longer than {MAX_TASK_ID_LEN} bytes"
+            )));
+        }
+        if let Some(c) = self
+            .0
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        {
+            return Err(ModelError::Invalid(format!(
+                "task id contains invalid character {c:?}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl From<String> for TaskId {
@@ -79,6 +109,20 @@ mod tests {
         assert_eq!(back, id);
     }
 
+    #[test]
+    fn task_id_validation() {
+        assert!(TaskId::from("order-42_retry.1").validate().is_ok());
+        assert!(TaskId::from("").validate().is_err());
+        assert!(TaskId::from("has space").validate().is_err());
+        assert!(TaskId::from("a/b").validate().is_err());
+        assert!(TaskId::new("x".repeat(MAX_TASK_ID_LEN)).validate().is_ok());
+        assert!(
+            TaskId::new("x".repeat(MAX_TASK_ID_LEN + 1))
+                .validate()
+                .is_err()
+        );
+    }
+
     #[test]
     fn task_id_hash_equality() {
         use std::collections::HashSet;
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
-use crate::{Slot, TaskId, TaskStatus};
+use crate::{RunnerLabels, Slot, TaskId, TaskStatus};
 
 /// Detailed information about a task instance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,9 +24,53 @@ pub struct TaskInfo {
     /// Last error message (if status is Failed/Timeout).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Exit code of the most recently finished attempt (if the runner reports one).
+    ///
+    /// Signal-terminated processes are reported as the negative signal number (e.g. `-9` for `SIGKILL`);
+    /// the signal itself is also in [`TaskInfo::signal`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Signal that terminated the most recently finished attempt (e.g. `9` for `SIGKILL`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    /// Short description of what the task executes (e.g. `"subprocess: ls"`).
+    ///
+    /// Set at submit time from [`crate::TaskKind::summary`]; `None` for code-defined tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind_summary: Option<String>,
+    /// Name of the runner that built the task (see `Runner::name`).
+    ///
+    /// `None` for code-defined tasks that were not built by a runner.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runner_name: Option<String>,
+    /// Name of the cgroup the task runs in (relative to the cgroup root, e.g. `/sys/fs/cgroup/<name>`).
+    ///
+    /// Set only while the runner actually places the task in a cgroup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cgroup: Option<String>,
+    /// Captured stdout of the most recently finished attempt.
+    ///
+    /// Set only if the runner captures output; bounded by the runner and ends with a truncation marker if cut.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout: Option<String>,
+    /// Captured stderr of the most recently finished attempt (see [`TaskInfo::stdout`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+    /// Resource limit that most likely terminated the most recently finished attempt.
+    ///
+    /// One of `"oom"`, `"pids"`, `"fsize"` or `"cpu"`; inferred by the runner from the termination signal
+    /// and cgroup events. `None` if the attempt was not stopped by a limit (or the runner does not tell).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_cause: Option<String>,
+    /// Batch the task was submitted in (see `CreateSpec::batch_id`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    /// Labels the task was submitted with (see `CreateSpec::labels`).
+    #[serde(default, skip_serializing_if = "RunnerLabels::is_empty")]
+    pub labels: RunnerLabels,
 }
 
-mod time_serde {
+pub(crate) mod time_serde {
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -63,6 +107,16 @@ mod tests {
             created_at: SystemTime::now(),
             updated_at: SystemTime::now(),
             error: Some("timeout".to_string()),
+            exit_code: Some(-9),
+            signal: Some(9),
+            kind_summary: Some("subprocess: ls".to_string()),
+            runner_name: Some("subprocess".to_string()),
+            cgroup: Some("subprocess-demo-slot-1-0".to_string()),
+            stdout: Some("{\"ok\":true}\n".to_string()),
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -73,6 +127,13 @@ mod tests {
         assert_eq!(back.status, info.status);
         assert_eq!(back.attempt, info.attempt);
         assert_eq!(back.error, info.error);
+        assert_eq!(back.exit_code, info.exit_code);
+        assert_eq!(back.signal, info.signal);
+        assert_eq!(back.kind_summary, info.kind_summary);
+        assert_eq!(back.runner_name, info.runner_name);
+        assert_eq!(back.cgroup, info.cgroup);
+        assert_eq!(back.stdout, info.stdout);
+        assert_eq!(back.stderr, info.stderr);
     }
 
     #[test]
@@ -85,9 +146,23 @@ mod tests {
             created_at: SystemTime::now(),
             updated_at: SystemTime::now(),
             error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
         };
 
         let json = serde_json::to_string(&info).unwrap();
         assert!(!json.contains("error"));
+        assert!(!json.contains("exitCode"));
+        assert!(!json.contains("signal"));
+        assert!(!json.contains("runnerName"));
+        assert!(!json.contains("stdout"));
     }
 }
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::TaskInfo;
+
+/// Number of tasks per page when a task listing does not request a page size.
+pub const DEFAULT_TASK_PAGE_SIZE: usize = 100;
+
+/// Largest page size a task listing accepts.
+pub const MAX_TASK_PAGE_SIZE: usize = 1_000;
+
+/// One page of the task listing.
+///
+/// Tasks are ordered by creation time (oldest first, ties broken by ID), so following
+/// `next_offset` visits every task once as long as no task is removed in between.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPage {
+    /// Tasks on this page.
+    pub tasks: Vec<TaskInfo>,
+    /// Number of tasks across all pages.
+    pub total: usize,
+    /// Offset of the next page; `None` on the last page.
+    pub next_offset: Option<usize>,
+}
+
+impl TaskPage {
+    /// Cut the page of up to `limit` tasks starting at `offset` out of `tasks` (in any order).
+    pub fn collect<'a>(
+        tasks: impl IntoIterator<Item = &'a TaskInfo>,
+        offset: usize,
+        limit: usize,
+    ) -> Self {
+        let mut tasks: Vec<&TaskInfo> = tasks.into_iter().collect();
+        tasks.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.as_str().cmp(b.id.as_str()))
+        });
+
+        let total = tasks.len();
+        let end = offset.saturating_add(limit);
+        Self {
+            tasks: tasks
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+            total,
+            next_offset: (end < total).then_some(end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, SystemTime};
+
+    use crate::{TaskId, TaskStatus};
+
+    fn task(id: &str, created_secs: u64) -> TaskInfo {
+        let created_at = SystemTime::UNIX_EPOCH + Duration::from_secs(created_secs);
+        TaskInfo {
+            id: TaskId::from(id),
+            slot: "slot".into(),
+            status: TaskStatus::Pending,
+            attempt: 0,
+            created_at,
+            updated_at: created_at,
+            error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
+        }
+    }
+
+    fn ids(page: &TaskPage) -> Vec<&str> {
+        page.tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn pages_follow_creation_order() {
+        let tasks = [task("c", 2), task("b", 1), task("a", 2), task("d", 3)];
+
+        let first = TaskPage::collect(&tasks, 0, 3);
+        assert_eq!(ids(&first), ["b", "a", "c"]);
+        assert_eq!(first.total, 4);
+        assert_eq!(first.next_offset, Some(3));
+
+        let last = TaskPage::collect(&tasks, 3, 3);
+        assert_eq!(ids(&last), ["d"]);
+        assert_eq!(last.next_offset, None);
+
+        let past_end = TaskPage::collect(&tasks, 10, 3);
+        assert!(past_end.tasks.is_empty());
+        assert_eq!(past_end.total, 4);
+        assert_eq!(past_end.next_offset, None);
+    }
+}
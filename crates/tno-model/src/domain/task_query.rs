@@ -0,0 +1,143 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{TaskFilter, TaskInfo};
+
+/// Field a task listing is ordered by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSortKey {
+    /// Creation time.
+    #[default]
+    CreatedAt,
+    /// Time of the last state change.
+    UpdatedAt,
+    /// Number of execution attempts.
+    Attempt,
+}
+
+/// Direction of a task listing order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    /// Smallest (oldest) first.
+    #[default]
+    Ascending,
+    /// Largest (newest) first.
+    Descending,
+}
+
+/// Task listing query: a [`TaskFilter`] and the order of the result.
+///
+/// Ties on the sort key are broken by task ID, so the order is stable across calls.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskQuery {
+    /// Tasks to include.
+    pub filter: TaskFilter,
+    /// Field to order by.
+    pub sort_by: TaskSortKey,
+    /// Order direction.
+    pub direction: SortDirection,
+}
+
+impl TaskQuery {
+    /// Query tasks matching `filter`, oldest first.
+    pub fn new(filter: TaskFilter) -> Self {
+        Self {
+            filter,
+            ..Self::default()
+        }
+    }
+
+    /// Order the result by `key` in `direction`.
+    pub fn sorted_by(mut self, key: TaskSortKey, direction: SortDirection) -> Self {
+        self.sort_by = key;
+        self.direction = direction;
+        self
+    }
+
+    /// Returns `true` if `info` satisfies the filter.
+    pub fn matches(&self, info: &TaskInfo) -> bool {
+        self.filter.matches(info)
+    }
+
+    /// Compare two tasks in query order.
+    pub fn compare(&self, a: &TaskInfo, b: &TaskInfo) -> Ordering {
+        let by_key = match self.sort_by {
+            TaskSortKey::CreatedAt => a.created_at.cmp(&b.created_at),
+            TaskSortKey::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+            TaskSortKey::Attempt => a.attempt.cmp(&b.attempt),
+        };
+        let ordering = by_key.then_with(|| a.id.as_str().cmp(b.id.as_str()));
+        match self.direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+
+    /// Keep the tasks matching the filter and put them in query order.
+    pub fn apply(&self, tasks: &mut Vec<TaskInfo>) {
+        tasks.retain(|t| self.matches(t));
+        tasks.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, SystemTime};
+
+    use crate::{TaskId, TaskStatus};
+
+    fn task(id: &str, status: TaskStatus, attempt: u32, updated_secs: u64) -> TaskInfo {
+        TaskInfo {
+            id: TaskId::from(id),
+            slot: "slot".into(),
+            status,
+            attempt,
+            created_at: SystemTime::UNIX_EPOCH,
+            updated_at: SystemTime::UNIX_EPOCH + Duration::from_secs(updated_secs),
+            error: None,
+            exit_code: None,
+            signal: None,
+            kind_summary: None,
+            runner_name: None,
+            cgroup: None,
+            stdout: None,
+            stderr: None,
+            limit_cause: None,
+            batch_id: None,
+            labels: Default::default(),
+        }
+    }
+
+    fn ids(tasks: &[TaskInfo]) -> Vec<&str> {
+        tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn filters_then_orders_by_key_and_direction() {
+        let mut tasks = vec![
+            task("a", TaskStatus::Failed, 3, 20),
+            task("b", TaskStatus::Running, 1, 30),
+            task("c", TaskStatus::Failed, 1, 10),
+            task("d", TaskStatus::Failed, 2, 30),
+        ];
+
+        let query = TaskQuery::new(TaskFilter::default().with_status(TaskStatus::Failed))
+            .sorted_by(TaskSortKey::UpdatedAt, SortDirection::Descending);
+        query.apply(&mut tasks);
+        assert_eq!(ids(&tasks), ["d", "a", "c"]);
+
+        TaskQuery::default()
+            .sorted_by(TaskSortKey::Attempt, SortDirection::Ascending)
+            .apply(&mut tasks);
+        assert_eq!(ids(&tasks), ["c", "d", "a"]);
+
+        // Equal creation times fall back to the ID.
+        TaskQuery::default().apply(&mut tasks);
+        assert_eq!(ids(&tasks), ["a", "c", "d"]);
+    }
+}
@@ -11,6 +11,9 @@ pub enum ModelError {
     #[error("unknown restart strategy: {0}")]
     UnknownRestart(String),
 
+    #[error("invalid schedule: {0}")]
+    InvalidSchedule(String),
+
     #[error("unknown jitter strategy: {0}")]
     UnknownJitter(String),
 
@@ -19,6 +22,15 @@ pub enum ModelError {
 
     #[error("invalid model: {0}")]
     Invalid(String),
+
+    #[error("label limit exceeded: {0}")]
+    LabelLimitExceeded(String),
+
+    #[error("invalid environment variable: {0}")]
+    InvalidEnv(String),
+
+    #[error("invalid container image: {0}")]
+    InvalidImage(String),
 }
 
 pub type ModelResult<T> = Result<T, ModelError>;
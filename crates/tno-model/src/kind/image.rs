@@ -0,0 +1,172 @@
+use std::fmt;
+
+use crate::error::{ModelError, ModelResult};
+
+/// Parsed container image reference: `[registry/]repository[:tag][@digest]`.
+///
+/// Names are kept as written: `redis` is not expanded to `docker.io/library/redis`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    /// Registry host (with optional port), if the reference names one (e.g. `ghcr.io`, `localhost:5000`).
+    pub registry: Option<String>,
+    /// Repository path (e.g. `library/redis`, `org/app`).
+    pub repository: String,
+    /// Tag (e.g. `7`, `latest`).
+    pub tag: Option<String>,
+    /// Content digest (e.g. `sha256:<64 hex digits>`).
+    pub digest: Option<String>,
+}
+
+impl ImageRef {
+    /// Parse an image reference.
+    pub fn parse(image: &str) -> ModelResult<Self> {
+        let invalid = |reason: &str| ModelError::InvalidImage(format!("{image:?}: {reason}"));
+
+        let (name, digest) = match image.split_once('@') {
+            Some((name, digest)) => {
+                check_digest(digest).map_err(|reason| invalid(&reason))?;
+                (name, Some(digest.to_string()))
+            }
+            None => (image, None),
+        };
+        // A ':' after the last '/' starts the tag; earlier ones belong to the registry port.
+        let (name, tag) = match name.rsplit_once(':') {
+            Some((repo, tag)) if !tag.contains('/') => {
+                check_tag(tag).map_err(|reason| invalid(&reason))?;
+                (repo, Some(tag.to_string()))
+            }
+            _ => (name, None),
+        };
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, rest)) if host.contains(['.', ':']) || host == "localhost" => {
+                (Some(host.to_string()), rest)
+            }
+            _ => (None, name),
+        };
+        check_repository(repository).map_err(|reason| invalid(&reason))?;
+
+        Ok(Self {
+            registry,
+            repository: repository.to_string(),
+            tag,
+            digest,
+        })
+    }
+
+    /// Whether the image is pinned by digest, so it always resolves to the same content.
+    pub fn is_pinned(&self) -> bool {
+        self.digest.is_some()
+    }
+}
+
+impl fmt::Display for ImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(registry) = &self.registry {
+            write!(f, "{registry}/")?;
+        }
+        f.write_str(&self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{tag}")?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{digest}")?;
+        }
+        Ok(())
+    }
+}
+
+fn check_digest(digest: &str) -> Result<(), String> {
+    let (algorithm, hex) = digest
+        .split_once(':')
+        .ok_or("digest must be <algorithm>:<hex>")?;
+    let len = match algorithm {
+        "sha256" => 64,
+        "sha512" => 128,
+        _ => return Err(format!("unsupported digest algorithm '{algorithm}'")),
+    };
+    if hex.len() != len || !hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return Err(format!(
+            "{algorithm} digest must be {len} lowercase hex digits"
+        ));
+    }
+    Ok(())
+}
+
+fn check_tag(tag: &str) -> Result<(), String> {
+    let valid = (1..=128).contains(&tag.len())
+        && !tag.starts_with(['.', '-'])
+        && tag
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid tag '{tag}'"))
+    }
+}
+
+fn check_repository(repository: &str) -> Result<(), String> {
+    let valid_component = |c: &str| {
+        c.starts_with(|ch: char| ch.is_ascii_alphanumeric())
+            && c.ends_with(|ch: char| ch.is_ascii_alphanumeric())
+            && c.bytes().all(|b| {
+                b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'.' | b'_' | b'-')
+            })
+    };
+    if repository.split('/').all(valid_component) {
+        Ok(())
+    } else {
+        Err(format!("invalid repository '{repository}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn parses_components() {
+        let image = ImageRef::parse("redis:7").unwrap();
+        assert_eq!(image.registry, None);
+        assert_eq!(image.repository, "redis");
+        assert_eq!(image.tag.as_deref(), Some("7"));
+        assert!(!image.is_pinned());
+
+        let raw = format!("localhost:5000/org/app:v1.2@{DIGEST}");
+        let image = ImageRef::parse(&raw).unwrap();
+        assert_eq!(image.registry.as_deref(), Some("localhost:5000"));
+        assert_eq!(image.repository, "org/app");
+        assert_eq!(image.tag.as_deref(), Some("v1.2"));
+        assert_eq!(image.digest.as_deref(), Some(DIGEST));
+        assert!(image.is_pinned());
+        assert_eq!(image.to_string(), raw);
+
+        let image = ImageRef::parse(&format!("ghcr.io/org/app@{DIGEST}")).unwrap();
+        assert_eq!(image.registry.as_deref(), Some("ghcr.io"));
+        assert_eq!(image.tag, None);
+
+        // Without a dot, port or `localhost`, the first component is part of the repository.
+        let image = ImageRef::parse("library/nginx").unwrap();
+        assert_eq!(image.registry, None);
+        assert_eq!(image.repository, "library/nginx");
+    }
+
+    #[test]
+    fn rejects_malformed_references() {
+        for image in [
+            "",
+            "Redis:7",
+            "redis:",
+            "redis:-x",
+            "org//app",
+            "redis@sha256:abc",
+            "redis@md5:0123456789abcdef0123456789abcdef",
+            "registry.io/",
+        ] {
+            let err = ImageRef::parse(image).unwrap_err();
+            assert!(matches!(err, ModelError::InvalidImage(_)), "{image}: {err}");
+        }
+    }
+}
@@ -1,2 +1,5 @@
+mod image;
+pub use image::ImageRef;
+
 mod task;
 pub use task::TaskKind;
@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{Flag, TaskEnv};
+use crate::{
+    Flag, ImageRef, TaskEnv,
+    error::{ModelError, ModelResult},
+};
 
 /// Execution configuration for a task.
 ///
@@ -20,6 +23,11 @@ pub enum TaskKind {
         /// Environment variables for the process.
         #[serde(default, skip_serializing_if = "TaskEnv::is_empty")]
         env: TaskEnv,
+        /// Dotenv-style file (`KEY=VALUE` lines) with additional environment variables.
+        ///
+        /// Read when the task is built; variables in `env` take precedence over the file.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        env_file: Option<PathBuf>,
         /// Working directory.
         ///
         /// If `None`, the process inherits the working directory of the parent (agent) process.
@@ -45,10 +53,13 @@ pub enum TaskKind {
     /// Run a task inside an OCI-compatible container.
     Container {
         /// Container image (e.g. `"nginx:latest"`, `"docker.io/library/redis:7"`).
+        ///
+        /// Pin it by digest (`"redis@sha256:..."`) for reproducible runs (see [`TaskKind::validate_image`]).
         image: String,
         /// Override container entrypoint.
         ///
         /// If `None`, the image's default entrypoint is used.
+        /// `Some(vec![])` is an explicit empty override and is kept distinct from `None`.
         #[serde(skip_serializing_if = "Option::is_none")]
         command: Option<Vec<String>>,
         /// Arguments passed to the container entrypoint.
@@ -58,6 +69,16 @@ pub enum TaskKind {
         #[serde(default, skip_serializing_if = "TaskEnv::is_empty")]
         env: TaskEnv,
     },
+    /// Run a named in-process function registered with the builtin runner.
+    ///
+    /// Unlike [`TaskKind::None`], the task is fully described by the spec and goes through the router.
+    Builtin {
+        /// Name the function is registered under.
+        name: String,
+        /// Arguments passed to the function.
+        #[serde(default, skip_serializing_if = "TaskEnv::is_empty")]
+        args: TaskEnv,
+    },
     /// Built-in task that does not require a runner.
     ///
     /// Used only with `SupervisorApi::submit_with_task()`.
@@ -73,12 +94,149 @@ impl TaskKind {
     /// - `"subprocess"`
     /// - `"wasm"`
     /// - `"container"`
+    /// - `"builtin"`
     pub fn kind(&self) -> &'static str {
         match self {
             TaskKind::None => "none",
+            TaskKind::Builtin { .. } => "builtin",
             TaskKind::Wasm { .. } => "wasm",
             TaskKind::Container { .. } => "container",
             TaskKind::Subprocess { .. } => "subprocess",
         }
     }
+
+    /// Returns a short human-readable description of what the task executes.
+    ///
+    /// Only the kind and the program/module/image/function name are included (e.g. `"subprocess: ls"`);
+    /// arguments and environment are omitted, as they may carry secrets.
+    /// Returns `None` for [`TaskKind::None`].
+    pub fn summary(&self) -> Option<String> {
+        match self {
+            TaskKind::None => None,
+            TaskKind::Subprocess { command, .. } => Some(format!("{}: {command}", self.kind())),
+            TaskKind::Wasm { module, .. } => Some(format!("{}: {}", self.kind(), module.display())),
+            TaskKind::Container { image, .. } => Some(format!("{}: {image}", self.kind())),
+            TaskKind::Builtin { name, .. } => Some(format!("{}: {name}", self.kind())),
+        }
+    }
+
+    /// Environment variables the task is started with.
+    ///
+    /// Returns `None` for kinds that are not started as a process ([`TaskKind::Builtin`], [`TaskKind::None`]).
+    pub fn env(&self) -> Option<&TaskEnv> {
+        match self {
+            TaskKind::Subprocess { env, .. }
+            | TaskKind::Wasm { env, .. }
+            | TaskKind::Container { env, .. } => Some(env),
+            TaskKind::Builtin { .. } | TaskKind::None => None,
+        }
+    }
+
+    /// Parsed image reference of a [`TaskKind::Container`] task.
+    ///
+    /// Returns `None` for other kinds.
+    pub fn container_image_ref(&self) -> Option<ModelResult<ImageRef>> {
+        match self {
+            TaskKind::Container { image, .. } => Some(ImageRef::parse(image)),
+            _ => None,
+        }
+    }
+
+    /// Check the image of a [`TaskKind::Container`] task; with `require_digest` it must be pinned by digest.
+    ///
+    /// Other kinds always pass.
+    pub fn validate_image(&self, require_digest: bool) -> ModelResult<()> {
+        let Some(image) = self.container_image_ref().transpose()? else {
+            return Ok(());
+        };
+        if require_digest && !image.is_pinned() {
+            return Err(ModelError::InvalidImage(format!(
+                "{image} is not pinned by digest"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_omits_args_and_env() {
+        let mut env = TaskEnv::new();
+        env.push("TOKEN", "secret");
+
+        let kind = TaskKind::Subprocess {
+            command: "ls".into(),
+            args: vec!["--password=hunter2".into()],
+            env,
+            env_file: None,
+            cwd: None,
+            fail_on_non_zero: Flag::enabled(),
+        };
+        assert_eq!(kind.summary().as_deref(), Some("subprocess: ls"));
+
+        let kind = TaskKind::Container {
+            image: "redis:7".into(),
+            command: None,
+            args: Vec::new(),
+            env: TaskEnv::default(),
+        };
+        assert_eq!(kind.summary().as_deref(), Some("container: redis:7"));
+
+        let mut args = TaskEnv::new();
+        args.push("DAYS", "7");
+        let kind = TaskKind::Builtin {
+            name: "prune-logs".into(),
+            args,
+        };
+        assert_eq!(kind.summary().as_deref(), Some("builtin: prune-logs"));
+        assert_eq!(TaskKind::None.summary(), None);
+    }
+
+    #[test]
+    fn container_empty_command_survives_serde() {
+        let kind = TaskKind::Container {
+            image: "redis:7".into(),
+            command: Some(Vec::new()),
+            args: Vec::new(),
+            env: TaskEnv::default(),
+        };
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(serde_json::from_str::<TaskKind>(&json).unwrap(), kind);
+
+        let json = r#"{"container":{"image":"redis:7"}}"#;
+        let parsed: TaskKind = serde_json::from_str(json).unwrap();
+        assert!(matches!(parsed, TaskKind::Container { command: None, .. }));
+    }
+
+    #[test]
+    fn validate_image_requires_digest_when_strict() {
+        let container = |image: &str| TaskKind::Container {
+            image: image.into(),
+            command: None,
+            args: Vec::new(),
+            env: TaskEnv::default(),
+        };
+        let pinned = container(
+            "redis@sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        );
+        let tagged = container("nginx:latest");
+
+        assert!(pinned.validate_image(true).is_ok());
+        assert!(tagged.validate_image(false).is_ok());
+        assert!(matches!(
+            tagged.validate_image(true),
+            Err(ModelError::InvalidImage(_))
+        ));
+        assert!(container("Nginx").validate_image(false).is_err());
+
+        let builtin = TaskKind::Builtin {
+            name: "prune-logs".into(),
+            args: TaskEnv::new(),
+        };
+        assert!(builtin.container_image_ref().is_none());
+        assert!(builtin.validate_image(true).is_ok());
+    }
 }
@@ -1,17 +1,27 @@
 mod domain;
 pub use domain::LABEL_RUNNER_TAG;
+pub use domain::{DEFAULT_DASHBOARD_RECENT, DashboardSnapshot, HealthStatus, SlotOccupancy};
 pub use domain::{
-    Flag, KeyValue, RunnerLabels, Slot, TaskEnv, TaskId, TaskInfo, TaskStatus, TimeoutMs,
+    DEFAULT_MAX_LABEL_KEY_LEN, DEFAULT_MAX_LABEL_VALUE_LEN, DEFAULT_MAX_LABELS, LabelLimits,
 };
+pub use domain::{DEFAULT_TASK_PAGE_SIZE, MAX_TASK_PAGE_SIZE, TaskFilter, TaskPage};
+pub use domain::{
+    Flag, KeyValue, MAX_TASK_ID_LEN, RunnerErrorInfo, RunnerLabels, Slot, Submission, TaskEnv,
+    TaskId, TaskInfo, TaskStatus, TimeoutMs,
+};
+pub use domain::{SortDirection, TaskCounts, TaskQuery, TaskSortKey};
 
 mod error;
 pub use error::ModelError;
 
 mod kind;
-pub use kind::TaskKind;
+pub use kind::{ImageRef, TaskKind};
 
 mod spec;
 pub use spec::CreateSpec;
 
 mod strategy;
-pub use strategy::{AdmissionStrategy, BackoffStrategy, JitterStrategy, RestartStrategy};
+pub use strategy::{
+    AdmissionOutcome, AdmissionStrategy, BackoffStrategy, JitterStrategy, RestartStrategy,
+};
+pub use strategy::{CronSchedule, ScheduleStrategy, ScheduleTimeZone};
@@ -4,7 +4,7 @@ use crate::{
     LABEL_RUNNER_TAG, RunnerLabels,
     domain::{Slot, TimeoutMs},
     kind::TaskKind,
-    strategy::{AdmissionStrategy, BackoffStrategy, RestartStrategy},
+    strategy::{AdmissionStrategy, BackoffStrategy, RestartStrategy, ScheduleStrategy},
 };
 
 /// Declarative specification used when creating a new task.
@@ -12,7 +12,7 @@ use crate::{
 /// `CreateSpec` describes *what* should be run and *how* it should be managed by the runtime.
 ///
 /// Fields cover:
-/// - logical grouping and concurrency control (`slot`, `admission`)
+/// - logical grouping and concurrency control (`slot`, `admission`, `batch_id`)
 /// - execution backend (`kind`)
 /// - lifecycle policies (`timeout_ms`, `restart`, `backoff`)
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -49,6 +49,35 @@ pub struct CreateSpec {
     /// Router uses key `runner-tag` (if present) to select a specific runner among those that support this `TaskKind`.
     #[serde(default, skip_serializing_if = "RunnerLabels::is_empty")]
     pub labels: RunnerLabels,
+    /// Labels a runner must carry to build this task (e.g. `region=eu`, `class=gpu`).
+    ///
+    /// Router keeps only runners whose labels include every selector pair, in addition to the `runner-tag` match.
+    /// An empty selector matches any runner.
+    #[serde(default, skip_serializing_if = "RunnerLabels::is_empty")]
+    pub runner_selector: RunnerLabels,
+    /// Treat a user-initiated cancellation as a successful outcome.
+    ///
+    /// For workflows where cancel means "we got what we needed": such tasks are reported with a success outcome instead of `canceled`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancel_is_success: bool,
+    /// Caller-supplied key that makes submission idempotent.
+    ///
+    /// While a non-terminal task submitted with the same key exists, submitting again returns its `TaskId`
+    /// instead of creating a new task (e.g. client retries after a network error).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Caller-supplied ID grouping related tasks (e.g. the steps of one workflow).
+    ///
+    /// Tasks of a batch can be listed and cancelled together. Unlike `slot`, a batch has no effect on admission:
+    /// tasks of the same batch may run concurrently in different slots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    /// Run the task on a schedule (e.g. a cron expression) instead of right away.
+    ///
+    /// A scheduled task waits for each fire time and is re-armed after every run regardless of its outcome,
+    /// so `restart` only contributes its `max_attempts` cap; `timeout_ms` applies to each run, not to the wait.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ScheduleStrategy>,
 }
 
 impl CreateSpec {
@@ -70,6 +99,7 @@ impl CreateSpec {
     ///         command: "ls".into(),
     ///         args: vec!["/tmp".into()],
     ///         env: TaskEnv::default(),
+    ///         env_file: None,
     ///         cwd: None,
     ///         fail_on_non_zero: Flag::enabled(),
     ///     },
@@ -83,11 +113,21 @@ impl CreateSpec {
     ///     },
     ///     admission: AdmissionStrategy::DropIfRunning,
     ///     labels: RunnerLabels::new(),
+    ///     runner_selector: RunnerLabels::new(),
+    ///     cancel_is_success: false,
+    ///     idempotency_key: None,
+    ///     batch_id: None,
+    ///     schedule: None,
     /// }
     /// .with_runner_tag("runner-a");
     /// ```
+    ///
+    /// The tag is always set, bypassing [`crate::LabelLimits`];
+    /// API layers validate the final label set as a whole.
     pub fn with_runner_tag(mut self, tag: impl Into<String>) -> Self {
-        self.labels.insert(LABEL_RUNNER_TAG, tag);
+        self.labels
+            .0
+            .insert(LABEL_RUNNER_TAG.to_string(), tag.into());
         self
     }
 
@@ -98,4 +138,16 @@ impl CreateSpec {
     pub fn runner_tag(&self) -> Option<&str> {
         self.labels.get(LABEL_RUNNER_TAG)
     }
+
+    /// Require runners to carry `key=value` (see [`CreateSpec::runner_selector`]).
+    ///
+    /// Like [`CreateSpec::with_runner_tag`], this bypasses [`crate::LabelLimits`].
+    pub fn with_runner_selector(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.runner_selector.0.insert(key.into(), value.into());
+        self
+    }
 }
@@ -39,3 +39,29 @@ impl FromStr for AdmissionStrategy {
         }
     }
 }
+
+/// What happened to a submitted task at admission time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdmissionOutcome {
+    /// The slot was free; the task runs right away.
+    Admitted,
+    /// The slot was busy and [`AdmissionStrategy::DropIfRunning`] applied; the task will not run.
+    Dropped,
+    /// The task replaces the one currently running in the slot ([`AdmissionStrategy::Replace`]).
+    Replaced,
+    /// The task waits until the slot becomes free ([`AdmissionStrategy::Queue`]).
+    Queued,
+}
+
+impl AdmissionOutcome {
+    /// Outcome of submitting with `strategy` into a slot that is (or is not) `busy`.
+    pub fn decide(strategy: AdmissionStrategy, busy: bool) -> Self {
+        match (busy, strategy) {
+            (false, _) => AdmissionOutcome::Admitted,
+            (true, AdmissionStrategy::DropIfRunning) => AdmissionOutcome::Dropped,
+            (true, AdmissionStrategy::Replace) => AdmissionOutcome::Replaced,
+            (true, AdmissionStrategy::Queue) => AdmissionOutcome::Queued,
+        }
+    }
+}
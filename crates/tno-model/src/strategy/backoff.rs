@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::JitterStrategy;
+use crate::error::{ModelError, ModelResult};
 
 /// Defines how backoff delays are calculated when retrying or restarting a task.
 ///
@@ -17,13 +21,18 @@ use serde::{Deserialize, Serialize};
 /// - `factor` — Multiplier for exponential growth.
 ///   For example:
 ///   - `factor = 2.0` → classic doubling (100 → 200 → 400 → ...)
-///   - `factor = 1.0` → linear growth
-///   - `factor < 1.0` → decaying backoff (rare, but allowed)
+///   - `factor = 1.0` → constant delay
+///
+/// ## Invariants
+/// Checked by [`BackoffStrategy::validate`]:
+/// - `first_ms <= max_ms`;
+/// - `factor` is finite and `>= 1.0`;
+/// - `first_ms` is non-zero unless `max_ms` is zero too (`first_ms = max_ms = 0` with `factor = 1.0` means "no backoff").
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BackoffStrategy {
     /// Jitter policy applied to each computed delay.
-    pub jitter: super::JitterStrategy,
+    pub jitter: JitterStrategy,
     /// Initial delay (ms) for exponential backoff.
     pub first_ms: u64,
     /// Maximum allowed delay (ms).
@@ -31,3 +40,244 @@ pub struct BackoffStrategy {
     /// Exponential growth multiplier.
     pub factor: f64,
 }
+
+impl BackoffStrategy {
+    /// Check the invariants listed on [`BackoffStrategy`].
+    pub fn validate(&self) -> ModelResult<()> {
+        if self.first_ms > self.max_ms {
+            return Err(ModelError::Invalid(format!(
+                "backoff first_ms ({}) exceeds max_ms ({})",
+                self.first_ms, self.max_ms
+            )));
+        }
+        if !self.factor.is_finite() || self.factor < 1.0 {
+            return Err(ModelError::Invalid(format!(
+                "backoff factor must be at least 1.0, got {}",
+                self.factor
+            )));
+        }
+        if self.first_ms == 0 && self.max_ms != 0 {
+            return Err(ModelError::Invalid(
+                "backoff first_ms cannot be zero when max_ms is set".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delays (without jitter) before each of the first `attempts` retries.
+    ///
+    /// Starts at `first_ms` and grows by `factor`, clamped to `max_ms`.
+    /// Pure computation: no task is run and no randomness is involved.
+    pub fn preview(&self, attempts: u32) -> Vec<Duration> {
+        let max = self.max_ms as f64;
+        let mut delay = (self.first_ms as f64).min(max);
+        (0..attempts)
+            .map(|_| {
+                let current = delay;
+                delay = (delay * self.factor).min(max);
+                Duration::from_millis(current as u64)
+            })
+            .collect()
+    }
+
+    /// Delay (ms) before retry number `attempt` (0-based), with jitter applied.
+    ///
+    /// `prev_delay_ms` is the delay returned for the previous retry (`0` before the first one);
+    /// only [`JitterStrategy::Decorrelated`] uses it. `rng` must return uniform samples from `[0, 1)`.
+    /// With `base = min(first_ms * factor^attempt, max_ms)`:
+    /// - `None`: `base`;
+    /// - `Full`: uniform in `[0, base]`;
+    /// - `Equal`: uniform in `[base / 2, base]`;
+    /// - `Decorrelated`: uniform in `[first_ms, 3 * max(prev_delay_ms, first_ms)]`, capped at `max_ms`.
+    ///
+    /// This is the reference computation for the delays taskvisor produces from the mapped policy.
+    pub fn next_delay(
+        &self,
+        attempt: u32,
+        prev_delay_ms: u64,
+        rng: &mut impl FnMut() -> f64,
+    ) -> u64 {
+        let mut uniform = |lo: u64, hi: u64| lo + ((hi - lo) as f64 * rng().clamp(0.0, 1.0)) as u64;
+        let base = self.base_delay_ms(attempt);
+        match self.jitter {
+            JitterStrategy::None => base,
+            JitterStrategy::Full => uniform(0, base),
+            JitterStrategy::Equal => uniform(base / 2, base),
+            JitterStrategy::Decorrelated => {
+                let first = self.first_ms.min(self.max_ms);
+                let hi = prev_delay_ms.max(first).saturating_mul(3).min(self.max_ms);
+                uniform(first, hi.max(first))
+            }
+        }
+    }
+
+    /// Delay (ms) before retry number `attempt` without jitter: `first_ms * factor^attempt`, clamped to `max_ms`.
+    fn base_delay_ms(&self, attempt: u32) -> u64 {
+        let exp = i32::try_from(attempt).unwrap_or(i32::MAX);
+        (self.first_ms as f64 * self.factor.powi(exp)).min(self.max_ms as f64) as u64
+    }
+
+    /// Range `(min, max)` each of the first `attempts` delays falls into once jitter is applied.
+    ///
+    /// See [`BackoffStrategy::preview`] for the base delays.
+    pub fn preview_jittered(&self, attempts: u32) -> Vec<(Duration, Duration)> {
+        let first = Duration::from_millis(self.first_ms);
+        let max = Duration::from_millis(self.max_ms);
+        self.preview(attempts)
+            .into_iter()
+            .map(|base| match self.jitter {
+                JitterStrategy::None => (base, base),
+                JitterStrategy::Full => (Duration::ZERO, base),
+                JitterStrategy::Equal => (base / 2, base),
+                JitterStrategy::Decorrelated => (first.min(max), base.saturating_mul(3).min(max)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backoff(first_ms: u64, max_ms: u64, factor: f64) -> BackoffStrategy {
+        BackoffStrategy {
+            jitter: JitterStrategy::None,
+            first_ms,
+            max_ms,
+            factor,
+        }
+    }
+
+    #[test]
+    fn validate_enforces_invariants() {
+        assert!(backoff(0, 0, 1.0).validate().is_ok());
+        assert!(backoff(100, 100, 1.0).validate().is_ok());
+        assert!(backoff(100, 5_000, 2.0).validate().is_ok());
+
+        assert!(backoff(5_000, 100, 2.0).validate().is_err());
+        assert!(backoff(100, 5_000, 0.5).validate().is_err());
+        assert!(backoff(100, 5_000, f64::NAN).validate().is_err());
+        assert!(backoff(0, 5_000, 2.0).validate().is_err());
+    }
+
+    #[test]
+    fn preview_grows_geometrically_up_to_max() {
+        let ms = |v: &[u64]| {
+            v.iter()
+                .map(|&m| Duration::from_millis(m))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            backoff(100, 1_000, 2.0).preview(6),
+            ms(&[100, 200, 400, 800, 1_000, 1_000])
+        );
+        assert_eq!(backoff(250, 250, 1.0).preview(3), ms(&[250, 250, 250]));
+        assert!(backoff(100, 1_000, 2.0).preview(0).is_empty());
+    }
+
+    #[test]
+    fn preview_jittered_bounds_follow_strategy() {
+        let mut strategy = backoff(100, 1_000, 2.0);
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            strategy.preview_jittered(2),
+            [(ms(100), ms(100)), (ms(200), ms(200))]
+        );
+
+        strategy.jitter = JitterStrategy::Full;
+        assert_eq!(strategy.preview_jittered(1), [(ms(0), ms(100))]);
+
+        strategy.jitter = JitterStrategy::Equal;
+        assert_eq!(strategy.preview_jittered(1), [(ms(50), ms(100))]);
+
+        strategy.jitter = JitterStrategy::Decorrelated;
+        assert_eq!(
+            strategy.preview_jittered(4),
+            [
+                (ms(100), ms(300)),
+                (ms(100), ms(600)),
+                (ms(100), ms(1_000)),
+                (ms(100), ms(1_000)),
+            ]
+        );
+    }
+
+    /// Deterministic xorshift generator of samples in `[0, 1)`.
+    fn rng(seed: u64) -> impl FnMut() -> f64 {
+        let mut state = seed | 1;
+        move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        }
+    }
+
+    #[test]
+    fn next_delay_without_jitter_matches_preview() {
+        let strategy = backoff(100, 1_000, 2.0);
+        let mut rng = rng(1);
+        let delays: Vec<_> = (0..6)
+            .map(|attempt| Duration::from_millis(strategy.next_delay(attempt, 0, &mut rng)))
+            .collect();
+        assert_eq!(delays, strategy.preview(6));
+    }
+
+    #[test]
+    fn full_and_equal_jitter_stay_within_base() {
+        for (seed, (first, max, factor)) in [(100, 1_000, 2.0), (1, 60_000, 1.5), (500, 500, 1.0)]
+            .into_iter()
+            .enumerate()
+        {
+            let mut full = backoff(first, max, factor);
+            full.jitter = JitterStrategy::Full;
+            let mut equal = full.clone();
+            equal.jitter = JitterStrategy::Equal;
+
+            let mut rng = rng(seed as u64 + 7);
+            for attempt in 0..64 {
+                let base = full.base_delay_ms(attempt);
+                for _ in 0..32 {
+                    let d = full.next_delay(attempt, 0, &mut rng);
+                    assert!(d <= base && d <= max, "full: {d} > {base}");
+                    let d = equal.next_delay(attempt, 0, &mut rng);
+                    assert!(
+                        (base / 2..=base).contains(&d),
+                        "equal: {d} not in [{}, {base}]",
+                        base / 2
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decorrelated_jitter_stays_within_three_times_previous_delay() {
+        let mut strategy = backoff(100, 10_000, 2.0);
+        strategy.jitter = JitterStrategy::Decorrelated;
+
+        for seed in 0..16 {
+            let mut rng = rng(seed);
+            let mut prev = 0;
+            let mut distinct = std::collections::BTreeSet::new();
+            for attempt in 0..200 {
+                let d = strategy.next_delay(attempt, prev, &mut rng);
+                let hi = (3 * prev.max(100)).min(10_000);
+                assert!(
+                    (100..=hi).contains(&d),
+                    "{d} not in [100, {hi}] (prev {prev})"
+                );
+                distinct.insert(d);
+                prev = d;
+            }
+            // The delay keeps moving instead of settling on the cap.
+            assert!(
+                distinct.len() > 100,
+                "seed {seed}: {} distinct delays",
+                distinct.len()
+            );
+        }
+    }
+}
@@ -14,7 +14,7 @@ use crate::error::{ModelError, ModelResult};
 /// - `Equal`: Equal jitter, picks a delay around `base/2 ± (base/2 * rand)`.
 /// - `Decorrelated`: Decorrelated jitter (a.k.a. "decorrelated exponential"), commonly used to avoid coordinated retries while still converging.
 ///
-/// The exact math is implemented in the backoff subsystem. This enum only specifies the policy.
+/// This enum only specifies the policy; [`crate::BackoffStrategy::next_delay`] is the reference for the resulting delays.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum JitterStrategy {
@@ -27,7 +27,7 @@ pub enum JitterStrategy {
     Full,
     /// Equal jitter: delay is sampled around the midpoint (`base / 2`), providing a balance between stability and randomness.
     Equal,
-    /// Decorrelated jitter: delay is sampled from `min(max, rand(base * 3))`.
+    /// Decorrelated jitter: delay is sampled from `min(max, rand(first, prev * 3))`, where `prev` is the previous delay.
     Decorrelated,
 }
 
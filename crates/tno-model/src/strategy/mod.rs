@@ -1,5 +1,5 @@
 mod admission;
-pub use admission::AdmissionStrategy;
+pub use admission::{AdmissionOutcome, AdmissionStrategy};
 
 mod backoff;
 pub use backoff::BackoffStrategy;
@@ -9,3 +9,6 @@ pub use jitter::JitterStrategy;
 
 mod restart;
 pub use restart::RestartStrategy;
+
+mod schedule;
+pub use schedule::{CronSchedule, ScheduleStrategy, ScheduleTimeZone};
@@ -15,17 +15,24 @@ use crate::error::{ModelError, ModelResult};
 ///   - `interval_ms: None` → restart immediately
 ///   - `interval_ms: Some(N)` → periodic task, wait N milliseconds between runs
 ///
+/// `OnFailure` and `Always` accept an optional `max_attempts` cap: after that many consecutive failed attempts
+/// the task is not restarted again and ends as `Exhausted`. Without a cap the task is restarted indefinitely.
+///
 /// Restart behavior is evaluated after each task execution cycle.
 /// If a task is canceled (via controller or shutdown), it is **not** considered a failure
 /// and will not be restarted unless explicitly treated as such by the runner.
-#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum RestartStrategy {
     /// Never restart the task.
     Never,
     /// Restart the task only if it failed (non-zero exit, error, panic, etc.).
-    #[default]
-    OnFailure,
+    #[serde(rename_all = "camelCase")]
+    OnFailure {
+        /// Give up after this many consecutive failed attempts (`None` = retry forever).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_attempts: Option<u32>,
+    },
     /// Always restart after completion.
     ///
     /// If `interval_ms` is provided, the task becomes periodic and waits
@@ -34,26 +41,92 @@ pub enum RestartStrategy {
     Always {
         #[serde(skip_serializing_if = "Option::is_none")]
         interval_ms: Option<u64>,
+        /// Give up after this many consecutive failed attempts (`None` = restart forever).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_attempts: Option<u32>,
     },
 }
 
+impl Default for RestartStrategy {
+    fn default() -> Self {
+        Self::on_failure()
+    }
+}
+
 impl RestartStrategy {
+    /// Create an OnFailure policy without attempt limit.
+    pub const fn on_failure() -> Self {
+        RestartStrategy::OnFailure { max_attempts: None }
+    }
+
     /// Create an Always policy without interval (immediate restart).
     pub const fn always() -> Self {
-        RestartStrategy::Always { interval_ms: None }
+        RestartStrategy::Always {
+            interval_ms: None,
+            max_attempts: None,
+        }
     }
 
     /// Create an Always policy with periodic interval.
     pub const fn periodic(interval_ms: u64) -> Self {
         RestartStrategy::Always {
             interval_ms: Some(interval_ms),
+            max_attempts: None,
         }
     }
+
+    /// Cap the number of consecutive failed attempts (no effect on `Never`).
+    pub const fn with_max_attempts(self, max: u32) -> Self {
+        match self {
+            RestartStrategy::Never => RestartStrategy::Never,
+            RestartStrategy::OnFailure { .. } => RestartStrategy::OnFailure {
+                max_attempts: Some(max),
+            },
+            RestartStrategy::Always { interval_ms, .. } => RestartStrategy::Always {
+                interval_ms,
+                max_attempts: Some(max),
+            },
+        }
+    }
+
+    /// Maximum number of consecutive failed attempts, if capped.
+    pub const fn max_attempts(&self) -> Option<u32> {
+        match self {
+            RestartStrategy::Never => None,
+            RestartStrategy::OnFailure { max_attempts }
+            | RestartStrategy::Always { max_attempts, .. } => *max_attempts,
+        }
+    }
+}
+
+/// Parse an optional `u64`/`u32` segment of a restart string (empty = `None`).
+fn parse_part<T: FromStr>(
+    part: Option<&str>,
+    what: &str,
+    original: &str,
+) -> ModelResult<Option<T>> {
+    let Some(part) = part.map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    part.parse::<T>()
+        .map(Some)
+        .map_err(|_| ModelError::UnknownRestart(format!("invalid {what} in '{original}'")))
+}
+
+/// Parse a `max_attempts` segment; zero attempts is rejected.
+fn parse_max_attempts(part: Option<&str>, original: &str) -> ModelResult<Option<u32>> {
+    match parse_part::<u32>(part, "max attempts: must be u32", original)? {
+        Some(0) => Err(ModelError::UnknownRestart(format!(
+            "invalid max attempts in '{original}': must be positive"
+        ))),
+        max => Ok(max),
+    }
 }
 
 impl FromStr for RestartStrategy {
     type Err = ModelError;
 
+    /// Accepted forms: `never`, `on-failure[:max_attempts]`, `always[:interval_ms[:max_attempts]]`.
     fn from_str(s: &str) -> ModelResult<Self> {
         let original = s.trim();
         if original.is_empty() {
@@ -61,31 +134,26 @@ impl FromStr for RestartStrategy {
         }
 
         let lower = original.to_ascii_lowercase();
-        let mut parts = lower.splitn(2, ':');
+        let mut parts = lower.splitn(3, ':');
         let head = parts.next().unwrap();
 
         match head {
             "never" => Ok(RestartStrategy::Never),
-            "on-failure" | "failure" => Ok(RestartStrategy::OnFailure),
+            "on-failure" | "failure" => {
+                let max_attempts = parse_max_attempts(parts.next(), original)?;
+                if parts.next().is_some() {
+                    return Err(ModelError::UnknownRestart(original.to_string()));
+                }
+                Ok(RestartStrategy::OnFailure { max_attempts })
+            }
             "always" => {
-                let interval_ms = match parts.next() {
-                    None => None,
-                    Some(rest) => {
-                        let rest = rest.trim();
-                        if rest.is_empty() {
-                            None
-                        } else {
-                            let v = rest.parse::<u64>().map_err(|_| {
-                                ModelError::UnknownRestart(format!(
-                                    "invalid interval in '{}': must be u64",
-                                    original
-                                ))
-                            })?;
-                            Some(v)
-                        }
-                    }
-                };
-                Ok(RestartStrategy::Always { interval_ms })
+                let interval_ms =
+                    parse_part::<u64>(parts.next(), "interval: must be u64", original)?;
+                let max_attempts = parse_max_attempts(parts.next(), original)?;
+                Ok(RestartStrategy::Always {
+                    interval_ms,
+                    max_attempts,
+                })
             }
             _ => Err(ModelError::UnknownRestart(original.to_string())),
         }
@@ -118,15 +186,15 @@ mod tests {
     fn parse_on_failure() {
         assert_eq!(
             RestartStrategy::from_str("on-failure").unwrap(),
-            RestartStrategy::OnFailure
+            RestartStrategy::on_failure()
         );
         assert_eq!(
             RestartStrategy::from_str("failure").unwrap(),
-            RestartStrategy::OnFailure
+            RestartStrategy::on_failure()
         );
         assert_eq!(
             RestartStrategy::from_str("  Failure ").unwrap(),
-            RestartStrategy::OnFailure
+            RestartStrategy::on_failure()
         );
     }
 
@@ -134,19 +202,19 @@ mod tests {
     fn parse_always_immediate() {
         assert_eq!(
             RestartStrategy::from_str("always").unwrap(),
-            RestartStrategy::Always { interval_ms: None }
+            RestartStrategy::always()
         );
         assert_eq!(
             RestartStrategy::from_str("  ALWAYS  ").unwrap(),
-            RestartStrategy::Always { interval_ms: None }
+            RestartStrategy::always()
         );
         assert_eq!(
             RestartStrategy::from_str("always:").unwrap(),
-            RestartStrategy::Always { interval_ms: None }
+            RestartStrategy::always()
         );
         assert_eq!(
             RestartStrategy::from_str("always:   ").unwrap(),
-            RestartStrategy::Always { interval_ms: None }
+            RestartStrategy::always()
         );
     }
 
@@ -154,15 +222,11 @@ mod tests {
     fn parse_always_with_interval() {
         assert_eq!(
             RestartStrategy::from_str("always:1000").unwrap(),
-            RestartStrategy::Always {
-                interval_ms: Some(1000)
-            }
+            RestartStrategy::periodic(1000)
         );
         assert_eq!(
             RestartStrategy::from_str(" Always:  60000 ").unwrap(),
-            RestartStrategy::Always {
-                interval_ms: Some(60000)
-            }
+            RestartStrategy::periodic(60000)
         );
     }
 
@@ -172,6 +236,37 @@ mod tests {
         assert!(matches!(err, ModelError::UnknownRestart(_)));
     }
 
+    #[test]
+    fn parse_max_attempts() {
+        assert_eq!(
+            RestartStrategy::from_str("on-failure:5").unwrap(),
+            RestartStrategy::OnFailure {
+                max_attempts: Some(5)
+            }
+        );
+        assert_eq!(
+            RestartStrategy::from_str("always:1000:3").unwrap(),
+            RestartStrategy::periodic(1000).with_max_attempts(3)
+        );
+        assert_eq!(
+            RestartStrategy::from_str("always::3").unwrap(),
+            RestartStrategy::always().with_max_attempts(3)
+        );
+        assert!(RestartStrategy::from_str("on-failure:0").is_err());
+        assert!(RestartStrategy::from_str("on-failure:many").is_err());
+    }
+
+    #[test]
+    fn serde_omits_absent_max_attempts() {
+        let json = serde_json::to_value(RestartStrategy::on_failure()).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "onFailure" }));
+
+        let capped: RestartStrategy =
+            serde_json::from_value(serde_json::json!({ "type": "onFailure", "maxAttempts": 4 }))
+                .unwrap();
+        assert_eq!(capped.max_attempts(), Some(4));
+    }
+
     #[test]
     fn parse_unknown_head_fails() {
         let err = RestartStrategy::from_str("random").unwrap_err();
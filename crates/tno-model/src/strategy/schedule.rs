@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::error::{ModelError, ModelResult};
+
+/// Determines *when* a task runs, as opposed to [`crate::RestartStrategy`] which only decides *whether* it runs again.
+///
+/// Strategies:
+/// - `Cron`: run at the times matched by a cron expression (e.g. `0 2 * * *` = every day at 02:00).
+///
+/// A scheduled task waits for the next fire time before each run and is re-armed after every run,
+/// whether it succeeded or failed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScheduleStrategy {
+    /// Run at the times matched by a cron expression.
+    #[serde(rename_all = "camelCase")]
+    Cron {
+        /// Five-field cron expression (`minute hour day-of-month month day-of-week`).
+        expression: CronSchedule,
+        /// Time zone the expression is evaluated in.
+        #[serde(default)]
+        timezone: ScheduleTimeZone,
+    },
+}
+
+impl ScheduleStrategy {
+    /// Create a cron schedule evaluated in UTC.
+    pub fn cron(expression: &str) -> ModelResult<Self> {
+        Ok(ScheduleStrategy::Cron {
+            expression: expression.parse()?,
+            timezone: ScheduleTimeZone::Utc,
+        })
+    }
+}
+
+impl FromStr for ScheduleStrategy {
+    type Err = ModelError;
+
+    /// Parse a cron expression evaluated in UTC; prefix it with `local:` to use the local time zone.
+    fn from_str(s: &str) -> ModelResult<Self> {
+        let s = s.trim();
+        let (timezone, expr) = match s.strip_prefix("local:") {
+            Some(expr) => (ScheduleTimeZone::Local, expr),
+            None => (ScheduleTimeZone::Utc, s.strip_prefix("utc:").unwrap_or(s)),
+        };
+        Ok(ScheduleStrategy::Cron {
+            expression: expr.parse()?,
+            timezone,
+        })
+    }
+}
+
+/// Time zone a schedule is evaluated in.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduleTimeZone {
+    /// Coordinated Universal Time.
+    #[default]
+    Utc,
+    /// Local system time zone (offset is re-read for every fire time, so DST changes are followed).
+    Local,
+}
+
+/// Parsed five-field cron expression.
+///
+/// Supported syntax per field: `*`, single values, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `0-30/10`).
+/// Day of week is `0-7` with both `0` and `7` meaning Sunday. The `@hourly`, `@daily`, `@weekly`,
+/// `@monthly` and `@yearly` shortcuts are accepted as well.
+///
+/// As in classic cron, if both day of month and day of week are restricted, a day matching either one fires.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expr: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// Upper bound on the search for the next fire time (e.g. `0 0 30 2 *` never fires).
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+impl CronSchedule {
+    /// Original expression.
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    /// Next fire time strictly after `after`, both in seconds since the Unix epoch.
+    ///
+    /// `utc_offset_secs` is the offset of the time zone the expression is evaluated in (`0` for UTC).
+    /// Returns `None` if the expression never fires (e.g. February 30th).
+    pub fn next_after(&self, after: i64, utc_offset_secs: i32) -> Option<i64> {
+        let offset = i64::from(utc_offset_secs);
+        // Local wall-clock minute right after `after`.
+        let mut minute = (after + offset).div_euclid(60) + 1;
+        let limit = minute.div_euclid(1440) + MAX_SEARCH_DAYS;
+
+        while minute.div_euclid(1440) <= limit {
+            let day = minute.div_euclid(1440);
+            let (year, month, dom) = civil_from_days(day);
+            if !bit(self.months, month) {
+                let (y, m) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                minute = days_from_civil(y, m, 1) * 1440;
+                continue;
+            }
+            if !self.matches_day(dom, weekday(day)) {
+                minute = (day + 1) * 1440;
+                continue;
+            }
+            let of_day = minute.rem_euclid(1440);
+            let (hour, min) = (of_day / 60, of_day % 60);
+            if !bit(self.hours, hour as u32) {
+                minute = day * 1440 + (hour + 1) * 60;
+                continue;
+            }
+            if !bit(self.minutes, min as u32) {
+                minute += 1;
+                continue;
+            }
+            return Some(minute * 60 - offset);
+        }
+        None
+    }
+
+    fn matches_day(&self, dom: u32, dow: u32) -> bool {
+        let day = bit(self.days, dom);
+        let weekday = bit(self.weekdays, dow);
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> ModelResult<Self> {
+        let original = s.trim();
+        let expanded = match original.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ => original,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            return Err(ModelError::InvalidSchedule(format!(
+                "'{original}': expected 5 fields (minute hour day-of-month month day-of-week)"
+            )));
+        };
+
+        let field = |text: &str, min: u32, max: u32, name: &str| {
+            parse_field(text, min, max).map_err(|reason| {
+                ModelError::InvalidSchedule(format!("'{original}': {name} {reason}"))
+            })
+        };
+        let mut weekdays = field(dow, 0, 7, "day-of-week")?;
+        if bit(weekdays, 7) {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(CronSchedule {
+            expr: original.to_string(),
+            minutes: field(minute, 0, 59, "minute")?,
+            hours: field(hour, 0, 23, "hour")?,
+            days: field(dom, 1, 31, "day-of-month")?,
+            months: field(month, 1, 12, "month")?,
+            weekdays,
+            any_day: dom == "*",
+            any_weekday: dow == "*",
+        })
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+impl fmt::Debug for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CronSchedule").field(&self.expr).finish()
+    }
+}
+
+impl Serialize for CronSchedule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.expr)
+    }
+}
+
+impl<'de> Deserialize<'de> for CronSchedule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parse one cron field into a bitmask of allowed values.
+fn parse_field(text: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("has invalid step in '{part}'"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((lo, hi)) => (value(lo, min, max)?, value(hi, min, max)?),
+                None => {
+                    let v = value(range, min, max)?;
+                    // `5/10` means "from 5 to the end, every 10".
+                    (v, if step > 1 { max } else { v })
+                }
+            },
+        };
+        if lo > hi {
+            return Err(format!("has an empty range '{range}'"));
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+fn value(text: &str, min: u32, max: u32) -> Result<u32, String> {
+    text.parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| format!("value '{text}' is outside {min}-{max}"))
+}
+
+#[inline]
+fn bit(mask: u64, v: u32) -> bool {
+    mask & (1 << v) != 0
+}
+
+/// Day of week (`0` = Sunday) of a day counted from 1970-01-01 (a Thursday).
+fn weekday(days: i64) -> u32 {
+    (days + 4).rem_euclid(7) as u32
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = i64::from((m + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Proleptic Gregorian date `(year, month, day)` for days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seconds since the epoch for a UTC date and time.
+    fn at(y: i64, m: u32, d: u32, hour: i64, min: i64) -> i64 {
+        days_from_civil(y, m, d) * 86_400 + hour * 3600 + min * 60
+    }
+
+    #[test]
+    fn daily_fires_at_next_occurrence() {
+        let cron: CronSchedule = "0 2 * * *".parse().unwrap();
+        let now = at(2024, 3, 10, 13, 5);
+        assert_eq!(cron.next_after(now, 0), Some(at(2024, 3, 11, 2, 0)));
+        // Exactly at a fire time: the next one is strictly later.
+        assert_eq!(
+            cron.next_after(at(2024, 3, 11, 2, 0), 0),
+            Some(at(2024, 3, 12, 2, 0))
+        );
+    }
+
+    #[test]
+    fn steps_weekdays_and_month_rollover() {
+        let cron: CronSchedule = "*/15 9-17 * * 1-5".parse().unwrap();
+        // Saturday 2024-03-16 -> Monday 09:00.
+        assert_eq!(
+            cron.next_after(at(2024, 3, 16, 12, 0), 0),
+            Some(at(2024, 3, 18, 9, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2024, 3, 18, 9, 0), 0),
+            Some(at(2024, 3, 18, 9, 15))
+        );
+
+        let yearly: CronSchedule = "@yearly".parse().unwrap();
+        assert_eq!(
+            yearly.next_after(at(2024, 12, 31, 23, 59), 0),
+            Some(at(2025, 1, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn evaluates_in_given_offset() {
+        let cron: CronSchedule = "0 2 * * *".parse().unwrap();
+        // 02:00 at UTC+3 is 23:00 UTC the day before.
+        assert_eq!(
+            cron.next_after(at(2024, 3, 10, 12, 0), 3 * 3600),
+            Some(at(2024, 3, 10, 23, 0))
+        );
+    }
+
+    #[test]
+    fn impossible_date_never_fires() {
+        let cron: CronSchedule = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(cron.next_after(0, 0), None);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            let err = expr.parse::<CronSchedule>().unwrap_err();
+            assert!(matches!(err, ModelError::InvalidSchedule(_)), "{expr}");
+        }
+    }
+
+    #[test]
+    fn schedule_serde_roundtrip() {
+        let schedule: ScheduleStrategy = "local:30 4 1,15 * *".parse().unwrap();
+        let json = serde_json::to_value(&schedule).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "cron", "expression": "30 4 1,15 * *", "timezone": "local" })
+        );
+
+        let back: ScheduleStrategy = serde_json::from_value(json).unwrap();
+        assert_eq!(back, schedule);
+        assert!(
+            serde_json::from_value::<ScheduleStrategy>(
+                serde_json::json!({ "type": "cron", "expression": "bogus" })
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::io::IsTerminal;
 
-use crate::logger::object::{LoggerFormat, LoggerLevel, LoggerTimeZone};
+use crate::logger::object::{
+    LoggerFileConfig, LoggerFormat, LoggerLevel, LoggerTimeZone, OtlpConfig,
+};
 
 /// Logger configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +19,10 @@ pub struct LoggerConfig {
     pub with_targets: bool,
     /// Whether to use colored output.
     pub use_color: bool,
+    /// File output settings (used with [`LoggerFormat::File`]).
+    pub file: LoggerFileConfig,
+    /// OpenTelemetry OTLP span export (`None` = disabled; requires the `otlp` feature).
+    pub otlp: Option<OtlpConfig>,
 }
 
 impl Default for LoggerConfig {
@@ -27,6 +33,8 @@ impl Default for LoggerConfig {
             tz: LoggerTimeZone::default(),
             with_targets: true,
             use_color: true,
+            file: LoggerFileConfig::default(),
+            otlp: None,
         }
     }
 }
@@ -36,7 +44,8 @@ impl LoggerConfig {
     ///
     /// Color is enabled only if:
     /// 1. `use_color` config is `true` (user hasn't explicitly disabled it), AND
-    /// 2. stdout is a terminal (not redirected to a file/pipe)
+    /// 2. stdout is a terminal (not redirected to a file/pipe), AND
+    /// 3. the format is not [`LoggerFormat::File`] (files never get escape codes)
     ///
     /// This method should be called during logger initialization, not during
     /// config parsing, to ensure accurate terminal detection.
@@ -50,7 +59,7 @@ impl LoggerConfig {
     /// // Returns true only if stdout is currently a terminal
     /// ```
     pub fn should_use_color(&self) -> bool {
-        self.use_color && std::io::stdout().is_terminal()
+        self.use_color && self.format != LoggerFormat::File && std::io::stdout().is_terminal()
     }
 }
 
@@ -77,6 +86,8 @@ mod tests {
             level: "debug".parse().unwrap(),
             with_targets: false,
             use_color: false,
+            file: LoggerFileConfig::default(),
+            otlp: Some(OtlpConfig::default()),
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -87,6 +98,8 @@ mod tests {
         assert_eq!(config.use_color, parsed.use_color);
         assert_eq!(config.format, parsed.format);
         assert_eq!(config.tz, parsed.tz);
+        assert_eq!(config.file, parsed.file);
+        assert_eq!(config.otlp, parsed.otlp);
     }
 
     #[test]
@@ -99,6 +112,7 @@ mod tests {
         assert_eq!(config.tz, LoggerTimeZone::default());
         assert_eq!(config.with_targets, true);
         assert_eq!(config.use_color, true);
+        assert!(config.otlp.is_none());
     }
 
     #[test]
@@ -111,4 +125,15 @@ mod tests {
         assert_eq!(config.with_targets, true);
         assert_eq!(config.use_color, true);
     }
+
+    #[test]
+    fn color_is_forced_off_for_file_output() {
+        let config = LoggerConfig {
+            format: LoggerFormat::File,
+            use_color: true,
+            ..Default::default()
+        };
+
+        assert!(!config.should_use_color());
+    }
 }
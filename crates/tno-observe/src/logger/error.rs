@@ -2,7 +2,7 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum LoggerError {
-    #[error("Invalid log format: {0} (expected: text|json|journald)")]
+    #[error("Invalid log format: {0} (expected: text|json|journald|file)")]
     InvalidFormat(String),
 
     #[error("Journald is not supported on this platform")]
@@ -11,6 +11,9 @@ pub enum LoggerError {
     #[error("Failed to initialize journald: {0}")]
     JournaldInitFailed(String),
 
+    #[error("Failed to open log file: {0}")]
+    FileOpenFailed(String),
+
     #[error("Logger already initialized")]
     AlreadyInitialized,
 
@@ -22,6 +25,15 @@ pub enum LoggerError {
 
     #[error("Invalid log level: {0}")]
     InvalidLevel(String),
+
+    #[error("OTLP export requires the `otlp` feature")]
+    OtlpNotSupported,
+
+    #[error("Invalid OTLP config: {0}")]
+    InvalidOtlpConfig(String),
+
+    #[error("Failed to initialize OTLP export: {0}")]
+    OtlpInitFailed(String),
 }
 
 pub type LoggerResult<T> = Result<T, LoggerError>;
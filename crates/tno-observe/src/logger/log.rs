@@ -5,6 +5,7 @@ use crate::logger::{
     config::LoggerConfig,
     error::{LoggerError, LoggerResult},
     object::LoggerRfc3339,
+    otlp::otlp_layer,
 };
 
 /// Initializes text logger.
@@ -15,7 +16,10 @@ pub fn logger_text(cfg: &LoggerConfig) -> LoggerResult<()> {
         .with_target(cfg.with_targets)
         .with_timer(LoggerRfc3339);
 
-    let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(otlp_layer(cfg.otlp.as_ref())?);
     init_subscriber(subscriber)
 }
 
@@ -28,7 +32,26 @@ pub fn logger_json(cfg: &LoggerConfig) -> LoggerResult<()> {
         .with_target(cfg.with_targets)
         .with_timer(LoggerRfc3339);
 
-    let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(otlp_layer(cfg.otlp.as_ref())?);
+    init_subscriber(subscriber)
+}
+
+/// Initializes rotating file logger (plain text, never colored).
+pub fn logger_file(cfg: &LoggerConfig) -> LoggerResult<()> {
+    let filter = cfg.level.to_env_filter();
+    let fmt_layer = fmt::layer()
+        .with_writer(cfg.file.appender()?)
+        .with_ansi(cfg.should_use_color())
+        .with_target(cfg.with_targets)
+        .with_timer(LoggerRfc3339);
+
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(otlp_layer(cfg.otlp.as_ref())?);
     init_subscriber(subscriber)
 }
 
@@ -39,7 +62,10 @@ pub fn logger_journald(cfg: &LoggerConfig) -> LoggerResult<()> {
     let journald =
         tracing_journald::layer().map_err(|e| LoggerError::JournaldInitFailed(e.to_string()))?;
 
-    let subscriber = tracing_subscriber::registry().with(filter).with(journald);
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(journald)
+        .with(otlp_layer(cfg.otlp.as_ref())?);
     init_subscriber(subscriber)
 }
 
@@ -73,6 +99,8 @@ mod tests {
             level: "info".parse().unwrap(),
             with_targets: true,
             use_color: false,
+            file: Default::default(),
+            otlp: None,
         };
 
         assert_eq!(config.format, LoggerFormat::Text);
@@ -87,6 +115,8 @@ mod tests {
             level: "debug".parse().unwrap(),
             with_targets: false,
             use_color: true,
+            file: Default::default(),
+            otlp: None,
         };
 
         assert_eq!(config.format, LoggerFormat::Json);
@@ -126,4 +156,32 @@ mod tests {
         let filter = config.level.to_env_filter();
         let _ = format!("{:?}", filter);
     }
+
+    #[test]
+    fn file_output_creates_log_file() {
+        let dir = std::env::temp_dir().join(format!("tno-observe-file-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = LoggerConfig {
+            format: LoggerFormat::File,
+            file: crate::LoggerFileConfig {
+                directory: dir.clone(),
+                prefix: "test.log".to_string(),
+                rotation: crate::LoggerRotation::Never,
+                max_files: None,
+            },
+            ..Default::default()
+        };
+
+        let fmt_layer = fmt::layer()
+            .with_writer(config.file.appender().unwrap())
+            .with_ansi(config.should_use_color());
+        let subscriber = tracing_subscriber::registry().with(fmt_layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("written to file");
+        });
+
+        let contents = std::fs::read_to_string(dir.join("test.log")).unwrap();
+        assert!(contents.contains("written to file"), "{contents}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -2,17 +2,23 @@ mod config;
 mod error;
 mod log;
 mod object;
+mod otlp;
 mod tasks;
 
 pub use config::LoggerConfig;
 pub use error::LoggerError;
 pub use object::LoggerFormat;
 pub use object::LoggerLevel;
+pub use object::OtlpConfig;
+pub use object::{LoggerFileConfig, LoggerRotation};
 pub use object::{LoggerTimeZone, init_local_offset};
 
 #[cfg(feature = "timezone-sync")]
 pub use tasks::timezone_sync;
 
+#[cfg(feature = "otlp")]
+pub use otlp::shutdown_otlp;
+
 /// Initializes the global tracing subscriber with the given configuration.
 ///
 /// This function configures and installs a tracing subscriber based on the provided [`LoggerConfig`].
@@ -21,6 +27,11 @@ pub use tasks::timezone_sync;
 /// # Important: Local Timezone
 /// For using `LoggerTimeZone::Local`, you **must** call [`object::timezone::init_local_offset`] in `main()` function before spawning any threads.
 ///
+/// # OTLP export
+/// If [`LoggerConfig::otlp`] is set, spans are additionally exported over OTLP/HTTP.
+/// This requires the `otlp` feature; without it initialization fails with [`LoggerError::OtlpNotSupported`].
+/// Call [`shutdown_otlp`] before exiting to flush pending spans.
+///
 /// # Examples
 /// ```rust
 /// use tno_observe::{LoggerConfig, init_logger};
@@ -36,5 +47,6 @@ pub fn init_logger(cfg: &LoggerConfig) -> Result<(), LoggerError> {
         LoggerFormat::Text => log::logger_text(cfg),
         LoggerFormat::Json => log::logger_json(cfg),
         LoggerFormat::Journald => log::logger_journald(cfg),
+        LoggerFormat::File => log::logger_file(cfg),
     }
 }
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+use crate::logger::error::{LoggerError, LoggerResult};
+
+/// How often the log file is rotated.
+///
+/// Rotation is time-based: a new file (`<prefix>.<date/time>`) is started at each period boundary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggerRotation {
+    /// New file every minute.
+    Minutely,
+    /// New file every hour.
+    Hourly,
+    /// New file every day (default).
+    #[default]
+    Daily,
+    /// Single file, never rotated.
+    Never,
+}
+
+impl From<LoggerRotation> for Rotation {
+    fn from(rotation: LoggerRotation) -> Self {
+        match rotation {
+            LoggerRotation::Minutely => Rotation::MINUTELY,
+            LoggerRotation::Hourly => Rotation::HOURLY,
+            LoggerRotation::Daily => Rotation::DAILY,
+            LoggerRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+/// File output settings, used with [`crate::LoggerFormat::File`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggerFileConfig {
+    /// Directory the log files are written to (created if missing).
+    pub directory: PathBuf,
+    /// File name prefix.
+    pub prefix: String,
+    /// Rotation period.
+    pub rotation: LoggerRotation,
+    /// Number of rotated files to keep (`None` = keep all).
+    pub max_files: Option<usize>,
+}
+
+impl Default for LoggerFileConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("logs"),
+            prefix: "tno.log".to_string(),
+            rotation: LoggerRotation::default(),
+            max_files: None,
+        }
+    }
+}
+
+impl LoggerFileConfig {
+    /// Opens the rolling file appender described by this config.
+    pub(crate) fn appender(&self) -> LoggerResult<RollingFileAppender> {
+        let mut builder = RollingFileAppender::builder()
+            .rotation(self.rotation.into())
+            .filename_prefix(&self.prefix);
+        if let Some(max) = self.max_files {
+            builder = builder.max_log_files(max);
+        }
+        builder
+            .build(&self.directory)
+            .map_err(|e| LoggerError::FileOpenFailed(format!("{}: {e}", self.directory.display())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_serde_uses_lowercase_names() {
+        let json = serde_json::to_string(&LoggerRotation::Hourly).unwrap();
+        assert_eq!(json, r#""hourly""#);
+
+        let parsed: LoggerRotation = serde_json::from_str(r#""never""#).unwrap();
+        assert_eq!(parsed, LoggerRotation::Never);
+    }
+
+    #[test]
+    fn serde_uses_defaults_for_missing_fields() {
+        let config: LoggerFileConfig = serde_json::from_str(r#"{"prefix": "agent.log"}"#).unwrap();
+
+        assert_eq!(config.prefix, "agent.log");
+        assert_eq!(config.directory, PathBuf::from("logs"));
+        assert_eq!(config.rotation, LoggerRotation::Daily);
+        assert_eq!(config.max_files, None);
+    }
+}
@@ -8,6 +8,7 @@ use crate::logger::LoggerError;
 /// - `Text`     — human-friendly, colored (when enabled) text logs.
 /// - `Json`     — structured JSON logs for machines / log collectors.
 /// - `Journald` — logs are sent to systemd-journald (Linux only).
+/// - `File`     — plain text logs written to rotating files (see [`crate::LoggerFileConfig`]).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum LoggerFormat {
@@ -17,6 +18,8 @@ pub enum LoggerFormat {
     Json,
     /// systemd-journald output (Linux only).
     Journald,
+    /// Rotating log files.
+    File,
 }
 
 impl Default for LoggerFormat {
@@ -32,6 +35,7 @@ impl FromStr for LoggerFormat {
         match norm.as_str() {
             "text" => Ok(Self::Text),
             "json" => Ok(Self::Json),
+            "file" => Ok(Self::File),
             "journald" | "journal" => {
                 #[cfg(target_os = "linux")]
                 {
@@ -53,6 +57,7 @@ impl fmt::Display for LoggerFormat {
             LoggerFormat::Text => "text",
             LoggerFormat::Json => "json",
             LoggerFormat::Journald => "journald",
+            LoggerFormat::File => "file",
         };
         f.write_str(s)
     }
@@ -93,6 +98,7 @@ mod tests {
         assert_eq!(LoggerFormat::from_str("TEXT").unwrap(), LoggerFormat::Text);
         assert_eq!(LoggerFormat::from_str("json").unwrap(), LoggerFormat::Json);
         assert_eq!(LoggerFormat::from_str("JsOn").unwrap(), LoggerFormat::Json);
+        assert_eq!(LoggerFormat::from_str("File").unwrap(), LoggerFormat::File);
     }
 
     #[test]
@@ -127,11 +133,12 @@ mod tests {
         assert_eq!(LoggerFormat::Text.to_string(), "text");
         assert_eq!(LoggerFormat::Json.to_string(), "json");
         assert_eq!(LoggerFormat::Journald.to_string(), "journald");
+        assert_eq!(LoggerFormat::File.to_string(), "file");
     }
 
     #[test]
     fn serde_roundtrip() {
-        for fmt in [LoggerFormat::Text, LoggerFormat::Json] {
+        for fmt in [LoggerFormat::Text, LoggerFormat::Json, LoggerFormat::File] {
             let json = serde_json::to_string(&fmt).unwrap();
             let parsed: LoggerFormat = serde_json::from_str(&json).unwrap();
             assert_eq!(fmt, parsed, "serde roundtrip failed for {fmt:?}");
@@ -9,3 +9,9 @@ pub use rfc3339::LoggerRfc3339;
 
 pub mod timezone;
 pub use timezone::{LoggerTimeZone, init_local_offset};
+
+pub mod otlp;
+pub use otlp::OtlpConfig;
+
+pub mod file;
+pub use file::{LoggerFileConfig, LoggerRotation};
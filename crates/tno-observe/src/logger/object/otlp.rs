@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// OpenTelemetry OTLP span export settings.
+///
+/// Takes effect only when the crate is built with the `otlp` feature;
+/// otherwise [`crate::init_logger`] rejects a config that sets it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpConfig {
+    /// OTLP/HTTP traces endpoint.
+    pub endpoint: String,
+    /// Reported `service.name` resource attribute.
+    pub service_name: String,
+    /// Fraction of traces to sample (`0.0..=1.0`).
+    pub sample_ratio: f64,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4318/v1/traces".to_string(),
+            service_name: "tno".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_uses_defaults_for_missing_fields() {
+        let config: OtlpConfig = serde_json::from_str(r#"{"service_name": "agent"}"#).unwrap();
+        assert_eq!(config.service_name, "agent");
+        assert_eq!(config.sample_ratio, 1.0);
+    }
+}
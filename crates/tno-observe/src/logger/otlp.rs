@@ -0,0 +1,137 @@
+//! OpenTelemetry OTLP span export (`otlp` feature).
+
+use crate::logger::{
+    error::{LoggerError, LoggerResult},
+    object::OtlpConfig,
+};
+
+#[cfg(feature = "otlp")]
+mod export {
+    use std::sync::OnceLock;
+
+    use opentelemetry::{global, trace::TracerProvider as _};
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{
+        Resource,
+        trace::{Sampler, SdkTracerProvider, Tracer},
+    };
+    use tracing::Subscriber;
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    use super::*;
+
+    /// Tracer provider installed by [`otlp_layer`], kept for [`shutdown_otlp`].
+    static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+    /// Builds the OTLP export layer (`None` if export is not configured).
+    pub(crate) fn otlp_layer<S>(
+        cfg: Option<&OtlpConfig>,
+    ) -> LoggerResult<Option<OpenTelemetryLayer<S, Tracer>>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let Some(cfg) = cfg else {
+            return Ok(None);
+        };
+        validate(cfg)?;
+
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(cfg.endpoint.clone())
+            .build()
+            .map_err(|e| LoggerError::OtlpInitFailed(e.to_string()))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                cfg.sample_ratio,
+            ))))
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(cfg.service_name.clone())
+                    .build(),
+            )
+            .build();
+        let tracer = provider.tracer("tno");
+
+        PROVIDER
+            .set(provider.clone())
+            .map_err(|_| LoggerError::AlreadyInitialized)?;
+        global::set_tracer_provider(provider);
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+    }
+
+    /// Flushes buffered spans and stops OTLP export.
+    ///
+    /// Call once before the process exits, otherwise spans still queued for export are lost.
+    /// Does nothing if OTLP export was never initialized.
+    pub fn shutdown_otlp() {
+        if let Some(provider) = PROVIDER.get()
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("failed to shut down OTLP export: {e}");
+        }
+    }
+}
+
+#[cfg(feature = "otlp")]
+pub(crate) use export::otlp_layer;
+#[cfg(feature = "otlp")]
+pub use export::shutdown_otlp;
+
+/// Rejects OTLP settings when the crate is built without the `otlp` feature.
+#[cfg(not(feature = "otlp"))]
+pub(crate) fn otlp_layer(
+    cfg: Option<&OtlpConfig>,
+) -> LoggerResult<Option<tracing_subscriber::layer::Identity>> {
+    match cfg {
+        Some(_) => Err(LoggerError::OtlpNotSupported),
+        None => Ok(None),
+    }
+}
+
+/// Validates OTLP settings.
+#[cfg_attr(not(feature = "otlp"), allow(dead_code))]
+fn validate(cfg: &OtlpConfig) -> LoggerResult<()> {
+    if cfg.endpoint.trim().is_empty() {
+        return Err(LoggerError::InvalidOtlpConfig(
+            "endpoint cannot be empty".into(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&cfg.sample_ratio) {
+        return Err(LoggerError::InvalidOtlpConfig(format!(
+            "sample_ratio must be in range 0.0..=1.0, got {}",
+            cfg.sample_ratio
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_out_of_range_sample_ratio() {
+        let cfg = OtlpConfig {
+            sample_ratio: 1.5,
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate(&cfg),
+            Err(LoggerError::InvalidOtlpConfig(_))
+        ));
+        assert!(validate(&OtlpConfig::default()).is_ok());
+    }
+
+    #[test]
+    #[cfg(not(feature = "otlp"))]
+    fn otlp_config_is_rejected_without_feature() {
+        let cfg = OtlpConfig::default();
+        assert!(matches!(
+            otlp_layer(Some(&cfg)),
+            Err(LoggerError::OtlpNotSupported)
+        ));
+        assert!(matches!(otlp_layer(None), Ok(None)));
+    }
+}
@@ -61,6 +61,11 @@ pub fn timezone_sync() -> (TaskRef, CreateSpec) {
         admission: AdmissionStrategy::Replace,
         kind: TaskKind::None,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
     (task, spec)
 }
@@ -5,27 +5,88 @@
 //! Maps Taskvisor events to structured tracing logs with appropriate severity levels.
 //! Processes events asynchronously via bounded queue to avoid blocking the event system.
 
-use std::borrow::Borrow;
+mod spans;
+pub use spans::TaskSpans;
+
+use std::{borrow::Borrow, sync::Arc};
 
 use async_trait::async_trait;
 use taskvisor::{Event, EventKind, Subscribe};
-use tracing::{debug, error, info, trace, warn};
+use tracing::{Level, debug, error, info, trace, warn};
 
 /// Subscriber that logs all Taskvisor events using the tracing framework.
 ///
 /// Events are processed asynchronously with structured fields (task, attempt, etc.).
 /// Queue overflow results in `SubscriberOverflow` events being emitted.
-#[derive(Default)]
-pub struct Subscriber;
+/// Log levels can be tuned per event with [`Subscriber::with_level_override`].
+pub struct Subscriber {
+    capacity: usize,
+    level_override: Option<LevelOverride>,
+}
+
+/// Function picking the log level for an event (`None` keeps the default level).
+pub type LevelOverride = Arc<dyn Fn(&Event) -> Option<Level> + Send + Sync>;
 
 /// Queue capacity sized for ~2K events/sec burst with sub-millisecond processing.
 /// On overflow, events are dropped and `SubscriberOverflow` event is emitted (non-blocking).
 const SUBSCRIBER_QUEUE_CAPACITY: usize = 2048;
 
+impl Subscriber {
+    /// Create a subscriber with a custom event queue capacity.
+    ///
+    /// Size it to the expected event burst; the default is 2048.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "subscriber queue capacity cannot be zero");
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    /// Override the log level of selected events.
+    ///
+    /// `f` is consulted for every event; returning `None` keeps the default level.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // Failed attempts that will be retried are not errors.
+    /// let sub = Subscriber::default().with_level_override(|e| {
+    ///     (e.kind == EventKind::TaskFailed && retry_expected(e)).then_some(Level::WARN)
+    /// });
+    /// ```
+    pub fn with_level_override<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Event) -> Option<Level> + Send + Sync + 'static,
+    {
+        self.level_override = Some(Arc::new(f));
+        self
+    }
+
+    fn level_for(&self, event: &Event) -> Level {
+        self.level_override
+            .as_ref()
+            .and_then(|f| f(event))
+            .unwrap_or_else(|| default_level(event.kind))
+    }
+}
+
+impl Default for Subscriber {
+    fn default() -> Self {
+        Self {
+            capacity: SUBSCRIBER_QUEUE_CAPACITY,
+            level_override: None,
+        }
+    }
+}
+
 #[async_trait]
 impl Subscribe for Subscriber {
     async fn on_event(&self, event: &Event) {
-        log_event(event);
+        log_event(event, self.level_for(event));
     }
 
     fn name(&self) -> &'static str {
@@ -33,55 +94,95 @@ impl Subscribe for Subscriber {
     }
 
     fn queue_capacity(&self) -> usize {
-        SUBSCRIBER_QUEUE_CAPACITY
+        self.capacity
     }
 }
 
-/// Logs an event with appropriate tracing level and structured fields.
-///
-/// This is public to allow custom subscribers to reuse the same logging logic.
-fn log_event<E: View>(e: E) {
+/// Emit a tracing event at a level chosen at runtime.
+macro_rules! log_at {
+    ($level:expr, $($arg:tt)+) => {
+        match $level {
+            Level::TRACE => trace!($($arg)+),
+            Level::DEBUG => debug!($($arg)+),
+            Level::INFO => info!($($arg)+),
+            Level::WARN => warn!($($arg)+),
+            _ => error!($($arg)+),
+        }
+    };
+}
+
+/// Default tracing level for each event kind.
+fn default_level(kind: EventKind) -> Level {
+    match kind {
+        // Routine management and lifecycle
+        EventKind::TaskRemoveRequested
+        | EventKind::TaskAddRequested
+        | EventKind::TaskRemoved
+        | EventKind::TaskStopped
+        | EventKind::ControllerSubmitted => Level::TRACE,
+        EventKind::TaskAdded
+        | EventKind::ActorExhausted
+        | EventKind::BackoffScheduled
+        | EventKind::ControllerSlotTransition => Level::DEBUG,
+
+        // Shutdown and attempt starts; grace overrun, timeouts and rejections warn
+        EventKind::ShutdownRequested
+        | EventKind::AllStoppedWithinGrace
+        | EventKind::TaskStarting => Level::INFO,
+        EventKind::GraceExceeded | EventKind::TimeoutHit | EventKind::ControllerRejected => {
+            Level::WARN
+        }
+
+        // Subscriber errors, dead actors and failed attempts
+        EventKind::SubscriberPanicked
+        | EventKind::SubscriberOverflow
+        | EventKind::ActorDead
+        | EventKind::TaskFailed => Level::ERROR,
+    }
+}
+
+/// Logs an event at `level` with structured fields.
+fn log_event<E: View>(e: E, level: Level) {
     let msg = message_for(e.kind());
 
     match e.kind() {
-        // Management - trace level for routine operations
-        EventKind::TaskRemoveRequested => trace!(task = e.as_task(), "{msg}"),
-        EventKind::TaskAddRequested => trace!(task = e.as_task(), "{msg}"),
-        EventKind::TaskRemoved => trace!(task = e.as_task(), "{msg}"),
-        EventKind::TaskAdded => debug!(task = e.as_task(), "{msg}"),
-
-        // Shutdown - info/warn for lifecycle events
-        EventKind::ShutdownRequested => info!("{msg}"),
-        EventKind::AllStoppedWithinGrace => info!("{msg}"),
-        EventKind::GraceExceeded => warn!("{msg}"),
-
-        // Subscriber errors - always error level
-        EventKind::SubscriberPanicked => {
-            error!(task = e.as_task(), reason = e.as_reason(), "{msg}")
-        }
-        EventKind::SubscriberOverflow => {
-            error!(task = e.as_task(), reason = e.as_reason(), "{msg}")
-        }
+        // Events without structured fields
+        EventKind::ShutdownRequested
+        | EventKind::AllStoppedWithinGrace
+        | EventKind::GraceExceeded => log_at!(level, "{msg}"),
 
-        // Terminal states - debug for exhausted, error for dead
-        EventKind::ActorExhausted => {
-            debug!(task = e.as_task(), reason = e.as_reason(), "{msg}")
-        }
-        EventKind::ActorDead => {
-            error!(task = e.as_task(), reason = e.as_reason(), "{msg}")
+        // Task-only events
+        EventKind::TaskRemoveRequested
+        | EventKind::TaskAddRequested
+        | EventKind::TaskRemoved
+        | EventKind::TaskAdded
+        | EventKind::TaskStopped => log_at!(level, task = e.as_task(), "{msg}"),
+
+        // Events with a reason
+        EventKind::SubscriberPanicked
+        | EventKind::SubscriberOverflow
+        | EventKind::ActorExhausted
+        | EventKind::ActorDead
+        | EventKind::ControllerRejected
+        | EventKind::ControllerSubmitted
+        | EventKind::ControllerSlotTransition => {
+            log_at!(level, task = e.as_task(), reason = e.as_reason(), "{msg}")
         }
 
         // Lifecycle events
         EventKind::TimeoutHit => {
-            warn!(task = e.as_task(), timeout_ms = e.timeout_ms(), "{msg}")
+            log_at!(
+                level,
+                task = e.as_task(),
+                timeout_ms = e.timeout_ms(),
+                "{msg}"
+            )
         }
         EventKind::TaskStarting => {
-            info!(task = e.as_task(), attempt = e.attempt(), "{msg}")
-        }
-        EventKind::TaskStopped => {
-            trace!(task = e.as_task(), "{msg}")
+            log_at!(level, task = e.as_task(), attempt = e.attempt(), "{msg}")
         }
-        EventKind::TaskFailed => error!(
+        EventKind::TaskFailed => log_at!(
+            level,
             task = e.as_task(),
             attempt = e.attempt(),
             reason = e.as_reason(),
@@ -91,7 +192,8 @@ fn log_event<E: View>(e: E) {
         // Backoff - differentiate retry vs scheduled next run
         EventKind::BackoffScheduled => {
             if e.has_reason() {
-                debug!(
+                log_at!(
+                    level,
                     task = e.as_task(),
                     attempt = e.attempt(),
                     delay_ms = e.delay_ms(),
@@ -99,7 +201,8 @@ fn log_event<E: View>(e: E) {
                     "retry scheduled after failure",
                 );
             } else {
-                debug!(
+                log_at!(
+                    level,
                     task = e.as_task(),
                     attempt = e.attempt(),
                     delay_ms = e.delay_ms(),
@@ -107,17 +210,6 @@ fn log_event<E: View>(e: E) {
                 );
             }
         }
-
-        // Controller events
-        EventKind::ControllerRejected => {
-            warn!(task = e.as_task(), reason = e.as_reason(), "{msg}")
-        }
-        EventKind::ControllerSubmitted => {
-            trace!(task = e.as_task(), reason = e.as_reason(), "{msg}")
-        }
-        EventKind::ControllerSlotTransition => {
-            debug!(task = e.as_task(), reason = e.as_reason(), "{msg}")
-        }
     }
 }
 
@@ -214,3 +306,43 @@ fn message_for(kind: EventKind) -> &'static str {
         EventKind::ControllerSlotTransition => "controller slot transition",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_capacity_defaults_and_overrides() {
+        assert_eq!(
+            Subscriber::default().queue_capacity(),
+            SUBSCRIBER_QUEUE_CAPACITY
+        );
+        assert_eq!(Subscriber::with_capacity(16_384).queue_capacity(), 16_384);
+    }
+
+    #[test]
+    fn level_override_falls_back_to_default() {
+        let sub = Subscriber::default().with_level_override(|e| {
+            (e.kind == EventKind::TaskFailed && e.attempt.is_some_and(|a| a < 3))
+                .then_some(Level::WARN)
+        });
+
+        let failed = |attempt| {
+            Event::new(EventKind::TaskFailed)
+                .with_task("task-1")
+                .with_attempt(attempt)
+        };
+        assert_eq!(sub.level_for(&failed(1)), Level::WARN);
+        assert_eq!(sub.level_for(&failed(3)), Level::ERROR);
+        assert_eq!(
+            sub.level_for(&Event::new(EventKind::TaskStarting)),
+            Level::INFO
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be zero")]
+    fn zero_capacity_is_rejected() {
+        Subscriber::with_capacity(0);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use taskvisor::{Event, EventKind, Subscribe};
+use tracing::{Span, field, info_span};
+
+use super::{SUBSCRIBER_QUEUE_CAPACITY, View};
+
+/// Subscriber that records every task attempt as a tracing span.
+///
+/// A `task` span opens at `TaskStarting` and closes at `TaskStopped` or `TaskFailed`,
+/// with the task (run id) and attempt as fields.
+/// Combined with OTLP export (`otlp` feature) every attempt becomes an exported span.
+#[derive(Default)]
+pub struct TaskSpans {
+    open: Mutex<HashMap<String, Span>>,
+}
+
+#[async_trait]
+impl Subscribe for TaskSpans {
+    async fn on_event(&self, event: &Event) {
+        self.apply(event);
+    }
+
+    fn name(&self) -> &'static str {
+        "task-spans"
+    }
+
+    fn queue_capacity(&self) -> usize {
+        SUBSCRIBER_QUEUE_CAPACITY
+    }
+}
+
+impl TaskSpans {
+    fn apply(&self, e: &Event) {
+        match e.kind() {
+            EventKind::TaskStarting => {
+                let span = info_span!(
+                    parent: None,
+                    "task",
+                    task = e.as_task(),
+                    attempt = e.attempt(),
+                    otel.status_code = field::Empty,
+                    reason = field::Empty,
+                );
+                self.open
+                    .lock()
+                    .unwrap()
+                    .insert(e.as_task().to_string(), span);
+            }
+            EventKind::TaskStopped | EventKind::TaskRemoved => {
+                self.open.lock().unwrap().remove(e.as_task());
+            }
+            EventKind::TaskFailed => {
+                if let Some(span) = self.open.lock().unwrap().remove(e.as_task()) {
+                    span.record("otel.status_code", "ERROR");
+                    span.record("reason", e.as_reason());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Number of attempts with an open span.
+    #[cfg(test)]
+    fn open_count(&self) -> usize {
+        self.open.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_lives_from_start_to_stop_or_failure() {
+        let spans = TaskSpans::default();
+
+        spans.apply(&Event::new(EventKind::TaskStarting).with_task("a"));
+        spans.apply(&Event::new(EventKind::TaskStarting).with_task("b"));
+        assert_eq!(spans.open_count(), 2);
+
+        spans.apply(&Event::new(EventKind::TaskStopped).with_task("a"));
+        spans.apply(&Event::new(EventKind::TaskFailed).with_task("b"));
+        assert_eq!(spans.open_count(), 0);
+    }
+}
@@ -1,41 +1,64 @@
 use std::sync::Arc;
 
-use prometheus::{CounterVec, HistogramVec, Opts, Registry, proto::MetricFamily};
+use prometheus::{
+    CounterVec, Encoder, HistogramVec, IntGaugeVec, Opts, Registry, TextEncoder, core::Collector,
+    proto::MetricFamily,
+};
 
-use tno_core::{MetricsBackend, TaskOutcome};
+use tno_core::{
+    MetricsBackend, MetricsHandle, RunnerErrorKind, TaskOutcome, TerminalReason, UNKNOWN_RUNNER,
+};
+
+use crate::error::PrometheusInitError;
+use crate::exposition::{ExpositionFormat, encode_families};
+use crate::snapshot::{MetricsSnapshot, label, samples};
 
 /// Prometheus metrics backend for tno.
 ///
 /// Implements [`MetricsBackend`] and exposes prometheus metrics that can be scraped via HTTP endpoint.
 ///
 /// ## Metrics
-/// - `tno_tasks_started_total{runner_type}` - Counter of spawned tasks
-/// - `tno_tasks_completed_total{runner_type, outcome}` - Counter of completed tasks
-/// - `tno_task_duration_seconds{runner_type}` - Histogram of task execution time
-/// - `tno_runner_errors_total{runner_type, error_kind}` - Counter of runner errors
+/// - `tno_tasks_started_total{runner_type, runner}` - Counter of spawned tasks
+/// - `tno_tasks_completed_total{runner_type, runner, outcome}` - Counter of completed tasks
+/// - `tno_task_duration_seconds{runner_type, runner}` - Histogram of task execution time
+/// - `tno_runner_errors_total{runner_type, runner, error_kind}` - Counter of runner errors
+/// - `tno_active_cgroups{runner_type, runner}` - Gauge of cgroups currently owned by runners
+/// - `tno_task_memory_peak_bytes{runner_type, runner}` - Histogram of per-task peak memory (cgroup v2)
+/// - `tno_output_bytes_in_flight{runner_type, runner}` - Gauge of buffered, not yet logged subprocess output
+/// - `tno_task_terminal_total{runner_type, runner, reason}` - Counter of tasks that ended without succeeding
 ///
 /// ## Label cardinality
 /// All labels are bounded (low cardinality):
 /// - `runner_type`: "subprocess", "wasm", "container"
+/// - `runner`: names of registered runners (see [`MetricsBackend::for_runner`]), "unknown" when recorded outside a runner
 /// - `outcome`: "success", "failure", "canceled", "timeout"
-/// - `error_kind`: "spawn_failed", "backend_config_failed", etc
+/// - `error_kind`: labels of [`RunnerErrorKind`] ("spawn_failed", "backend_config_failed", etc)
+/// - `reason`: "timeout", "failed", "canceled", "exhausted", "replaced"
 #[derive(Clone)]
 pub struct PrometheusMetrics {
     tasks_started: CounterVec,
     tasks_completed: CounterVec,
     tasks_duration: HistogramVec,
     runner_errors: CounterVec,
+    active_cgroups: IntGaugeVec,
+    task_memory_peak: HistogramVec,
+    output_bytes_in_flight: IntGaugeVec,
+    task_terminal: CounterVec,
     registry: Arc<Registry>,
+    /// Value of the `runner` label.
+    runner: &'static str,
 }
 
 impl PrometheusMetrics {
     /// Create a new prometheus metrics backend with custom registry.
-    pub fn new_with_registry(registry: Arc<Registry>) -> Result<Self, prometheus::Error> {
+    ///
+    /// Fails with [`PrometheusInitError::AlreadyRegistered`] if the registry already holds tno metrics.
+    pub fn new_with_registry(registry: Arc<Registry>) -> Result<Self, PrometheusInitError> {
         let tasks_started = CounterVec::new(
             Opts::new("tno_tasks_started_total", "Total number of tasks started").namespace("tno"),
-            &["runner_type"],
+            &["runner_type", "runner"],
         )?;
-        registry.register(Box::new(tasks_started.clone()))?;
+        register(&registry, &tasks_started)?;
 
         let tasks_completed = CounterVec::new(
             Opts::new(
@@ -43,9 +66,9 @@ impl PrometheusMetrics {
                 "Total number of tasks completed",
             )
             .namespace("tno"),
-            &["runner_type", "outcome"],
+            &["runner_type", "runner", "outcome"],
         )?;
-        registry.register(Box::new(tasks_completed.clone()))?;
+        register(&registry, &tasks_completed)?;
 
         let tasks_duration = HistogramVec::new(
             prometheus::HistogramOpts::new(
@@ -54,33 +77,79 @@ impl PrometheusMetrics {
             )
             .namespace("tno")
             .buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0]),
-            &["runner_type"],
+            &["runner_type", "runner"],
         )?;
-        registry.register(Box::new(tasks_duration.clone()))?;
+        register(&registry, &tasks_duration)?;
 
         let runner_errors = CounterVec::new(
             Opts::new("tno_runner_errors_total", "Total runner-level errors").namespace("tno"),
-            &["runner_type", "error_kind"],
+            &["runner_type", "runner", "error_kind"],
+        )?;
+        register(&registry, &runner_errors)?;
+
+        let active_cgroups = IntGaugeVec::new(
+            Opts::new(
+                "tno_active_cgroups",
+                "Number of cgroups currently owned by runners",
+            )
+            .namespace("tno"),
+            &["runner_type", "runner"],
+        )?;
+        register(&registry, &active_cgroups)?;
+
+        let task_memory_peak = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "tno_task_memory_peak_bytes",
+                "Peak memory usage of finished tasks in bytes",
+            )
+            .namespace("tno")
+            .buckets(prometheus::exponential_buckets(1024.0 * 1024.0, 4.0, 8)?),
+            &["runner_type", "runner"],
         )?;
-        registry.register(Box::new(runner_errors.clone()))?;
+        register(&registry, &task_memory_peak)?;
+
+        let output_bytes_in_flight = IntGaugeVec::new(
+            Opts::new(
+                "tno_output_bytes_in_flight",
+                "Subprocess output bytes buffered but not yet logged",
+            )
+            .namespace("tno"),
+            &["runner_type", "runner"],
+        )?;
+        register(&registry, &output_bytes_in_flight)?;
+
+        let task_terminal = CounterVec::new(
+            Opts::new(
+                "tno_task_terminal_total",
+                "Total number of tasks that ended without succeeding, by reason",
+            )
+            .namespace("tno"),
+            &["runner_type", "runner", "reason"],
+        )?;
+        register(&registry, &task_terminal)?;
 
         Ok(Self {
             tasks_started,
             tasks_completed,
             tasks_duration,
             runner_errors,
+            active_cgroups,
+            task_memory_peak,
+            output_bytes_in_flight,
+            task_terminal,
             registry,
+            runner: UNKNOWN_RUNNER,
         })
     }
 
     /// Create a new prometheus metrics backend with default registry.
-    pub fn new() -> Result<Self, prometheus::Error> {
+    pub fn new() -> Result<Self, PrometheusInitError> {
         Self::new_with_registry(Arc::new(Registry::new()))
     }
 
     /// Gather all metrics for exposition.
     ///
-    /// Use this to implement `/metrics` HTTP endpoint.
+    /// [`Self::encode`] covers the usual `/metrics` formats; use this to feed another encoder.
     ///
     /// # Example
     /// ```rust,ignore
@@ -93,6 +162,78 @@ impl PrometheusMetrics {
         self.registry.gather()
     }
 
+    /// Gather the registry and encode it in `format`.
+    ///
+    /// Returns the exposition and its `Content-Type`, ready to serve from a `/metrics` endpoint
+    /// (pick the format with [`ExpositionFormat::from_accept`]).
+    pub fn encode(&self, format: ExpositionFormat) -> prometheus::Result<(Vec<u8>, &'static str)> {
+        let body = encode_families(&self.gather(), format)?;
+        Ok((body, format.content_type()))
+    }
+
+    /// Take a typed snapshot of the tno counters and gauges.
+    ///
+    /// Custom metrics registered in the same registry are not included; use [`Self::gather`] for those.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut snapshot = MetricsSnapshot::default();
+        // Several runners may share a runner type: sum their series.
+        for m in samples(&self.tasks_started) {
+            let value = m.get_counter().value() as u64;
+            *snapshot
+                .tasks_started
+                .entry(label(&m, "runner_type"))
+                .or_default() += value;
+            *snapshot
+                .tasks_started_by_runner
+                .entry(label(&m, "runner"))
+                .or_default() += value;
+        }
+        for m in samples(&self.tasks_completed) {
+            let value = m.get_counter().value() as u64;
+            *snapshot
+                .tasks_completed
+                .entry(label(&m, "runner_type"))
+                .or_default()
+                .entry(label(&m, "outcome"))
+                .or_default() += value;
+            *snapshot
+                .tasks_completed_by_runner
+                .entry(label(&m, "runner"))
+                .or_default()
+                .entry(label(&m, "outcome"))
+                .or_default() += value;
+        }
+        for m in samples(&self.runner_errors) {
+            *snapshot
+                .runner_errors
+                .entry(label(&m, "runner_type"))
+                .or_default()
+                .entry(label(&m, "error_kind"))
+                .or_default() += m.get_counter().value() as u64;
+        }
+        for m in samples(&self.task_terminal) {
+            *snapshot
+                .task_terminal
+                .entry(label(&m, "runner_type"))
+                .or_default()
+                .entry(label(&m, "reason"))
+                .or_default() += m.get_counter().value() as u64;
+        }
+        for m in samples(&self.active_cgroups) {
+            *snapshot
+                .active_cgroups
+                .entry(label(&m, "runner_type"))
+                .or_default() += m.get_gauge().value() as i64;
+        }
+        for m in samples(&self.output_bytes_in_flight) {
+            *snapshot
+                .output_bytes_in_flight
+                .entry(label(&m, "runner_type"))
+                .or_default() += m.get_gauge().value() as i64;
+        }
+        snapshot
+    }
+
     /// Get reference to underlying prometheus registry.
     ///
     /// Useful for registering custom metrics alongside tno metrics.
@@ -102,27 +243,91 @@ impl PrometheusMetrics {
     }
 }
 
+/// Register a collector, reporting a name clash as [`PrometheusInitError::AlreadyRegistered`].
+fn register<C>(registry: &Registry, collector: &C) -> Result<(), PrometheusInitError>
+where
+    C: Collector + Clone + 'static,
+{
+    match registry.register(Box::new(collector.clone())) {
+        Ok(()) => Ok(()),
+        Err(prometheus::Error::AlreadyReg) => Err(PrometheusInitError::AlreadyRegistered {
+            metric: collector
+                .desc()
+                .first()
+                .map(|d| d.fq_name.clone())
+                .unwrap_or_default(),
+        }),
+        Err(e) => Err(e.into()),
+    }
+}
+
 impl MetricsBackend for PrometheusMetrics {
+    fn health_check(&self) -> Result<(), String> {
+        // Gathering fails on inconsistent collectors, which also breaks the `/metrics` exposition.
+        TextEncoder::new()
+            .encode(&self.gather(), &mut std::io::sink())
+            .map_err(|e| e.to_string())
+    }
+
     fn record_task_started(&self, runner_type: &str) {
-        self.tasks_started.with_label_values(&[runner_type]).inc();
+        self.tasks_started
+            .with_label_values(&[runner_type, self.runner])
+            .inc();
     }
 
     fn record_task_completed(&self, runner_type: &str, outcome: TaskOutcome, duration_ms: u64) {
         self.tasks_completed
-            .with_label_values(&[runner_type, outcome.as_label()])
+            .with_label_values(&[runner_type, self.runner, outcome.as_label()])
             .inc();
 
         let duration_seconds = duration_ms as f64 / 1000.0;
         self.tasks_duration
-            .with_label_values(&[runner_type])
+            .with_label_values(&[runner_type, self.runner])
             .observe(duration_seconds);
     }
 
-    fn record_runner_error(&self, runner_type: &str, error_kind: &str) {
+    fn record_runner_error(&self, runner_type: &str, error_kind: RunnerErrorKind) {
         self.runner_errors
-            .with_label_values(&[runner_type, error_kind])
+            .with_label_values(&[runner_type, self.runner, error_kind.as_label()])
             .inc();
     }
+
+    fn record_cgroup_created(&self, runner_type: &str) {
+        self.active_cgroups
+            .with_label_values(&[runner_type, self.runner])
+            .inc();
+    }
+
+    fn record_cgroup_removed(&self, runner_type: &str) {
+        self.active_cgroups
+            .with_label_values(&[runner_type, self.runner])
+            .dec();
+    }
+
+    fn record_task_memory_peak(&self, runner_type: &str, bytes: u64) {
+        self.task_memory_peak
+            .with_label_values(&[runner_type, self.runner])
+            .observe(bytes as f64);
+    }
+
+    fn record_output_bytes_in_flight(&self, runner_type: &str, bytes: u64) {
+        self.output_bytes_in_flight
+            .with_label_values(&[runner_type, self.runner])
+            .set(bytes.min(i64::MAX as u64) as i64);
+    }
+
+    fn record_terminal(&self, runner_type: &str, reason: TerminalReason) {
+        self.task_terminal
+            .with_label_values(&[runner_type, self.runner, reason.as_label()])
+            .inc();
+    }
+
+    fn for_runner(&self, runner: &'static str) -> Option<MetricsHandle> {
+        Some(Arc::new(Self {
+            runner,
+            ..self.clone()
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -134,6 +339,20 @@ mod tests {
         let _metrics = PrometheusMetrics::new().expect("failed to create metrics");
     }
 
+    #[test]
+    fn second_backend_on_same_registry_is_already_registered() {
+        let registry = Arc::new(Registry::new());
+        let _first = PrometheusMetrics::new_with_registry(Arc::clone(&registry)).unwrap();
+
+        match PrometheusMetrics::new_with_registry(registry) {
+            Err(PrometheusInitError::AlreadyRegistered { metric }) => {
+                assert_eq!(metric, "tno_tno_tasks_started_total");
+            }
+            Err(e) => panic!("expected AlreadyRegistered, got {e}"),
+            Ok(_) => panic!("expected AlreadyRegistered, got a backend"),
+        }
+    }
+
     #[test]
     fn record_task_started_increments_counter() {
         let metrics = PrometheusMetrics::new().unwrap();
@@ -177,9 +396,9 @@ mod tests {
     fn record_runner_error_increments_counter() {
         let metrics = PrometheusMetrics::new().unwrap();
 
-        metrics.record_runner_error("subprocess", "spawn_failed");
-        metrics.record_runner_error("subprocess", "spawn_failed");
-        metrics.record_runner_error("wasm", "module_load_failed");
+        metrics.record_runner_error("subprocess", RunnerErrorKind::SpawnFailed);
+        metrics.record_runner_error("subprocess", RunnerErrorKind::SpawnFailed);
+        metrics.record_runner_error("wasm", RunnerErrorKind::Other("module_load_failed"));
 
         let families = metrics.gather();
         let errors = families
@@ -190,6 +409,143 @@ mod tests {
         assert_eq!(errors.get_metric().len(), 2);
     }
 
+    #[test]
+    fn active_cgroups_gauge_tracks_create_and_remove() {
+        let metrics = PrometheusMetrics::new().unwrap();
+
+        metrics.record_cgroup_created("subprocess");
+        metrics.record_cgroup_created("subprocess");
+        metrics.record_cgroup_removed("subprocess");
+
+        let gauge = || {
+            metrics
+                .gather()
+                .into_iter()
+                .find(|f| f.name() == "tno_tno_active_cgroups")
+                .expect("active cgroups gauge not found")
+                .get_metric()[0]
+                .get_gauge()
+                .value()
+        };
+        assert_eq!(gauge(), 1.0);
+
+        metrics.record_cgroup_removed("subprocess");
+        assert_eq!(gauge(), 0.0);
+    }
+
+    #[test]
+    fn snapshot_groups_values_by_labels() {
+        let metrics = PrometheusMetrics::new().unwrap();
+
+        metrics.record_task_started("subprocess");
+        metrics.record_task_started("subprocess");
+        metrics.record_task_completed("subprocess", TaskOutcome::Success, 10);
+        metrics.record_task_completed("subprocess", TaskOutcome::Failure, 10);
+        metrics.record_runner_error("subprocess", RunnerErrorKind::SpawnFailed);
+        metrics.record_cgroup_created("subprocess");
+        metrics.record_terminal("subprocess", TerminalReason::Timeout);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.tasks_started["subprocess"], 2);
+        assert_eq!(snapshot.tasks_completed["subprocess"]["success"], 1);
+        assert_eq!(snapshot.tasks_completed["subprocess"]["failure"], 1);
+        assert_eq!(snapshot.tasks_completed_total(), 2);
+        assert_eq!(snapshot.runner_errors["subprocess"]["spawn_failed"], 1);
+        assert_eq!(snapshot.active_cgroups["subprocess"], 1);
+        assert_eq!(snapshot.task_terminal["subprocess"]["timeout"], 1);
+    }
+
+    #[test]
+    fn runner_scoped_metrics_are_split_by_runner_name() {
+        use taskvisor::{TaskError, TaskFn, TaskRef};
+        use tno_core::{BuildContext, Runner, RunnerError, RunnerRouter};
+        use tno_model::{
+            AdmissionStrategy, BackoffStrategy, CreateSpec, Flag, JitterStrategy, LABEL_RUNNER_TAG,
+            RestartStrategy, RunnerLabels, TaskEnv, TaskKind,
+        };
+        use tokio_util::sync::CancellationToken;
+
+        /// Runner that records a subprocess start for every built task, like the real subprocess runner.
+        struct CountingRunner(&'static str);
+
+        impl Runner for CountingRunner {
+            fn name(&self) -> &'static str {
+                self.0
+            }
+
+            fn supports(&self, _spec: &CreateSpec) -> bool {
+                true
+            }
+
+            fn build_task(
+                &self,
+                _spec: &CreateSpec,
+                ctx: &BuildContext,
+            ) -> Result<TaskRef, RunnerError> {
+                ctx.metrics().record_task_started("subprocess");
+                Ok(TaskFn::arc(self.0, |_ctx: CancellationToken| async move {
+                    Ok::<(), TaskError>(())
+                }))
+            }
+        }
+
+        let metrics = PrometheusMetrics::new().unwrap();
+        let mut router = RunnerRouter::new().with_context(BuildContext::new(
+            TaskEnv::default(),
+            Arc::new(metrics.clone()),
+        ));
+        for name in ["dev-runner", "untrusted-runner"] {
+            let mut labels = RunnerLabels::new();
+            labels.insert(LABEL_RUNNER_TAG, name).unwrap();
+            router
+                .register_with_labels(Arc::new(CountingRunner(name)), labels)
+                .unwrap();
+        }
+
+        let spec = |runner: &str| {
+            CreateSpec {
+                slot: "slot".to_string(),
+                kind: TaskKind::Subprocess {
+                    command: "true".into(),
+                    args: Vec::new(),
+                    env: TaskEnv::default(),
+                    env_file: None,
+                    cwd: None,
+                    fail_on_non_zero: Flag::enabled(),
+                },
+                timeout_ms: 1_000,
+                restart: RestartStrategy::Never,
+                backoff: BackoffStrategy {
+                    jitter: JitterStrategy::None,
+                    first_ms: 100,
+                    max_ms: 100,
+                    factor: 1.0,
+                },
+                admission: AdmissionStrategy::DropIfRunning,
+                labels: RunnerLabels::default(),
+                runner_selector: RunnerLabels::default(),
+                cancel_is_success: false,
+                idempotency_key: None,
+                batch_id: None,
+                schedule: None,
+            }
+            .with_runner_tag(runner)
+        };
+        router.build(&spec("dev-runner")).unwrap();
+        router.build(&spec("dev-runner")).unwrap();
+        router.build(&spec("untrusted-runner")).unwrap();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.tasks_started["subprocess"], 3);
+        assert_eq!(snapshot.tasks_started_by_runner["dev-runner"], 2);
+        assert_eq!(snapshot.tasks_started_by_runner["untrusted-runner"], 1);
+        assert!(
+            !snapshot
+                .tasks_started_by_runner
+                .contains_key(UNKNOWN_RUNNER)
+        );
+    }
+
     #[test]
     fn can_use_custom_registry() {
         let registry = Arc::new(Registry::new());
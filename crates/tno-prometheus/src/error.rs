@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+/// Errors returned when creating a [`crate::PrometheusMetrics`] backend.
+#[derive(Debug, Error)]
+pub enum PrometheusInitError {
+    /// A tno metric with the same name is already registered in the registry
+    /// (e.g. another backend was created on it before).
+    ///
+    /// Callers sharing a registry can treat this as "already initialized" and reuse the existing backend.
+    #[error("metric '{metric}' is already registered")]
+    AlreadyRegistered {
+        /// Fully qualified name of the conflicting metric.
+        metric: String,
+    },
+    /// Any other prometheus error (invalid metric definition, inconsistent labels, ...).
+    #[error(transparent)]
+    Prometheus(#[from] prometheus::Error),
+}
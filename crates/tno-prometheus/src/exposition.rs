@@ -0,0 +1,363 @@
+use std::fmt::Write;
+
+use prometheus::{
+    Encoder, PROTOBUF_FORMAT, ProtobufEncoder, TEXT_FORMAT, TextEncoder,
+    proto::{Metric, MetricFamily, MetricType},
+};
+
+/// Content type of the OpenMetrics text exposition.
+pub const OPENMETRICS_FORMAT: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Wire format of a `/metrics` exposition.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ExpositionFormat {
+    /// Prometheus text format 0.0.4.
+    #[default]
+    Text,
+    /// OpenMetrics 1.0 text format.
+    OpenMetrics,
+    /// Length-delimited `io.prometheus.client.MetricFamily` protobuf messages.
+    Protobuf,
+}
+
+impl ExpositionFormat {
+    /// `Content-Type` of an exposition in this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Text => TEXT_FORMAT,
+            Self::OpenMetrics => OPENMETRICS_FORMAT,
+            Self::Protobuf => PROTOBUF_FORMAT,
+        }
+    }
+
+    /// Pick the format preferred by an HTTP `Accept` header.
+    ///
+    /// Media ranges are ranked by their `q` parameter, earlier ones winning ties. Falls back to
+    /// [`Self::Text`] when nothing supported is accepted, as Prometheus itself does.
+    pub fn from_accept(accept: &str) -> Self {
+        let mut best: Option<(f32, Self)> = None;
+        for range in accept.split(',') {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default().to_ascii_lowercase();
+            let mut q = 1.0;
+            let mut proto = None;
+            for param in params {
+                match param.split_once('=') {
+                    Some(("q", value)) => q = value.trim().parse().unwrap_or(0.0),
+                    Some(("proto", value)) => proto = Some(value.trim()),
+                    _ => {}
+                }
+            }
+            let format = match media_type.as_str() {
+                "application/vnd.google.protobuf"
+                    if proto.is_none_or(|p| p == "io.prometheus.client.MetricFamily") =>
+                {
+                    Self::Protobuf
+                }
+                "application/openmetrics-text" => Self::OpenMetrics,
+                "text/plain" | "text/*" | "*/*" => Self::Text,
+                _ => continue,
+            };
+            if q > 0.0 && best.is_none_or(|(best_q, _)| q > best_q) {
+                best = Some((q, format));
+            }
+        }
+        best.map_or(Self::Text, |(_, format)| format)
+    }
+}
+
+/// Encode gathered metric families in `format`.
+pub(crate) fn encode_families(
+    families: &[MetricFamily],
+    format: ExpositionFormat,
+) -> prometheus::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    match format {
+        ExpositionFormat::Text => TextEncoder::new().encode(families, &mut buffer)?,
+        ExpositionFormat::Protobuf => ProtobufEncoder::new().encode(families, &mut buffer)?,
+        ExpositionFormat::OpenMetrics => buffer = encode_openmetrics(families).into_bytes(),
+    }
+    Ok(buffer)
+}
+
+/// OpenMetrics text exposition; the prometheus crate only ships text and protobuf encoders.
+fn encode_openmetrics(families: &[MetricFamily]) -> String {
+    let mut out = String::new();
+    for family in families {
+        let name = family.name();
+        let metric_type = family.get_field_type();
+        let type_name = match metric_type {
+            MetricType::COUNTER => "counter",
+            MetricType::GAUGE => "gauge",
+            MetricType::HISTOGRAM => "histogram",
+            MetricType::SUMMARY => "summary",
+            MetricType::UNTYPED => "unknown",
+        };
+        // OpenMetrics names counter families without the `_total` suffix their samples carry.
+        let base = match metric_type {
+            MetricType::COUNTER => name.strip_suffix("_total").unwrap_or(name),
+            _ => name,
+        };
+        if !family.help().is_empty() {
+            let _ = writeln!(out, "# HELP {base} {}", escape(family.help(), false));
+        }
+        let _ = writeln!(out, "# TYPE {base} {type_name}");
+
+        for m in family.get_metric() {
+            match metric_type {
+                MetricType::COUNTER => {
+                    sample(&mut out, base, "_total", m, None, m.get_counter().value());
+                }
+                MetricType::GAUGE => sample(&mut out, base, "", m, None, m.get_gauge().value()),
+                MetricType::UNTYPED => sample(&mut out, base, "", m, None, m.untyped.value()),
+                MetricType::HISTOGRAM => {
+                    let h = m.get_histogram();
+                    let mut inf_seen = false;
+                    for b in h.get_bucket() {
+                        inf_seen |= b.upper_bound() == f64::INFINITY;
+                        let le = number(b.upper_bound());
+                        let count = b.cumulative_count() as f64;
+                        sample(
+                            &mut out,
+                            base,
+                            "_bucket",
+                            m,
+                            Some(("le", le.as_str())),
+                            count,
+                        );
+                    }
+                    let count = h.get_sample_count() as f64;
+                    if !inf_seen {
+                        sample(&mut out, base, "_bucket", m, Some(("le", "+Inf")), count);
+                    }
+                    sample(&mut out, base, "_count", m, None, count);
+                    sample(&mut out, base, "_sum", m, None, h.get_sample_sum());
+                }
+                MetricType::SUMMARY => {
+                    let s = m.get_summary();
+                    for q in s.get_quantile() {
+                        let quantile = number(q.quantile());
+                        sample(
+                            &mut out,
+                            base,
+                            "",
+                            m,
+                            Some(("quantile", quantile.as_str())),
+                            q.value(),
+                        );
+                    }
+                    sample(&mut out, base, "_count", m, None, s.sample_count() as f64);
+                    sample(&mut out, base, "_sum", m, None, s.sample_sum());
+                }
+            }
+        }
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Write one sample line: `<name><suffix>{<labels>[,<extra>]} <value>`.
+fn sample(
+    out: &mut String,
+    name: &str,
+    suffix: &str,
+    metric: &Metric,
+    extra: Option<(&str, &str)>,
+    value: f64,
+) {
+    let labels = metric
+        .get_label()
+        .iter()
+        .map(|l| (l.name(), l.value()))
+        .chain(extra);
+    let _ = write!(out, "{name}{suffix}");
+    for (i, (label, label_value)) in labels.enumerate() {
+        let sep = if i == 0 { '{' } else { ',' };
+        let _ = write!(out, "{sep}{label}=\"{}\"", escape(label_value, true));
+    }
+    if !metric.get_label().is_empty() || extra.is_some() {
+        out.push('}');
+    }
+    let _ = writeln!(out, " {}", number(value));
+}
+
+/// Canonical OpenMetrics number: integral values keep a `.0`, infinities are `+Inf`/`-Inf`.
+fn number(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".to_owned()
+    } else if v.is_infinite() {
+        let sign = if v > 0.0 { '+' } else { '-' };
+        format!("{sign}Inf")
+    } else if v.fract() == 0.0 && v.abs() < 1e15 {
+        format!("{v:.1}")
+    } else {
+        v.to_string()
+    }
+}
+
+fn escape(s: &str, quote: bool) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if quote => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prometheus::proto::{
+        Bucket, Counter, Gauge, Histogram, LabelPair, Quantile, Summary, Untyped,
+    };
+    use tno_core::{MetricsBackend, TaskOutcome};
+
+    use crate::PrometheusMetrics;
+
+    fn family(name: &str, metric_type: MetricType, metric: Metric) -> MetricFamily {
+        let mut label = LabelPair::new();
+        label.set_name("job".into());
+        label.set_value("a\"b".into());
+        let mut metric = metric;
+        metric.set_label(vec![label]);
+
+        let mut family = MetricFamily::new();
+        family.set_name(name.into());
+        family.set_help(format!("{name} help"));
+        family.set_field_type(metric_type);
+        family.set_metric(vec![metric]);
+        family
+    }
+
+    #[test]
+    fn negotiates_format_from_accept_header() {
+        let prometheus = "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;\
+                          encoding=delimited;q=0.7,text/plain;version=0.0.4;q=0.3,*/*;q=0.1";
+        assert_eq!(
+            ExpositionFormat::from_accept(prometheus),
+            ExpositionFormat::Protobuf
+        );
+        assert_eq!(
+            ExpositionFormat::from_accept(
+                "application/openmetrics-text;version=1.0.0;q=0.5,text/plain;q=0.4"
+            ),
+            ExpositionFormat::OpenMetrics
+        );
+        assert_eq!(
+            ExpositionFormat::from_accept("application/json, text/html"),
+            ExpositionFormat::Text
+        );
+        assert_eq!(
+            ExpositionFormat::from_accept("application/openmetrics-text;q=0"),
+            ExpositionFormat::Text
+        );
+    }
+
+    #[test]
+    fn encodes_each_format_with_its_content_type() {
+        let metrics = PrometheusMetrics::new().unwrap();
+        metrics.record_task_started("subprocess");
+        metrics.record_task_completed("subprocess", TaskOutcome::Success, 20);
+
+        let (text, content_type) = metrics.encode(ExpositionFormat::Text).unwrap();
+        assert_eq!(content_type, TEXT_FORMAT);
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.contains("# TYPE tno_tno_tasks_started_total counter"));
+
+        let (open, content_type) = metrics.encode(ExpositionFormat::OpenMetrics).unwrap();
+        assert_eq!(content_type, OPENMETRICS_FORMAT);
+        let open = String::from_utf8(open).unwrap();
+        assert!(
+            open.contains("# TYPE tno_tno_tasks_started counter\n"),
+            "{open}"
+        );
+        assert!(
+            open.contains(
+                "tno_tno_tasks_started_total{runner=\"unknown\",runner_type=\"subprocess\"} 1.0\n"
+            ),
+            "{open}"
+        );
+        assert!(open.contains("le=\"+Inf\"} 1.0\n"), "{open}");
+        assert!(open.ends_with("# EOF\n"));
+
+        let (proto, content_type) = metrics.encode(ExpositionFormat::Protobuf).unwrap();
+        assert_eq!(content_type, PROTOBUF_FORMAT);
+        assert!(!proto.is_empty());
+    }
+
+    #[test]
+    fn encodes_every_metric_type_as_openmetrics() {
+        let mut counter = Counter::new();
+        counter.set_value(3.0);
+        let mut gauge = Gauge::new();
+        gauge.set_value(-1.5);
+        let mut untyped = Untyped::new();
+        untyped.set_value(7.0);
+        let mut bucket = Bucket::new();
+        bucket.set_upper_bound(0.5);
+        bucket.set_cumulative_count(1);
+        let mut histogram = Histogram::new();
+        histogram.set_bucket(vec![bucket]);
+        histogram.set_sample_count(2);
+        histogram.set_sample_sum(1.25);
+        let mut quantile = Quantile::new();
+        quantile.set_quantile(0.99);
+        quantile.set_value(4.0);
+        let mut summary = Summary::new();
+        summary.set_quantile(vec![quantile]);
+        summary.set_sample_count(5);
+        summary.set_sample_sum(10.0);
+
+        let mut families = Vec::new();
+        let mut m = Metric::new();
+        m.set_counter(counter);
+        families.push(family("jobs_total", MetricType::COUNTER, m));
+        let mut m = Metric::new();
+        m.set_gauge(gauge);
+        families.push(family("queue_depth", MetricType::GAUGE, m));
+        let mut m = Metric::new();
+        m.untyped = Some(untyped).into();
+        families.push(family("legacy", MetricType::UNTYPED, m));
+        let mut m = Metric::new();
+        m.set_histogram(histogram);
+        families.push(family("latency_seconds", MetricType::HISTOGRAM, m));
+        let mut m = Metric::new();
+        m.set_summary(summary);
+        families.push(family("size_bytes", MetricType::SUMMARY, m));
+
+        let open = encode_families(&families, ExpositionFormat::OpenMetrics).unwrap();
+        let expected = "\
+# HELP jobs jobs_total help
+# TYPE jobs counter
+jobs_total{job=\"a\\\"b\"} 3.0
+# HELP queue_depth queue_depth help
+# TYPE queue_depth gauge
+queue_depth{job=\"a\\\"b\"} -1.5
+# HELP legacy legacy help
+# TYPE legacy unknown
+legacy{job=\"a\\\"b\"} 7.0
+# HELP latency_seconds latency_seconds help
+# TYPE latency_seconds histogram
+latency_seconds_bucket{job=\"a\\\"b\",le=\"0.5\"} 1.0
+latency_seconds_bucket{job=\"a\\\"b\",le=\"+Inf\"} 2.0
+latency_seconds_count{job=\"a\\\"b\"} 2.0
+latency_seconds_sum{job=\"a\\\"b\"} 1.25
+# HELP size_bytes size_bytes help
+# TYPE size_bytes summary
+size_bytes{job=\"a\\\"b\",quantile=\"0.99\"} 4.0
+size_bytes_count{job=\"a\\\"b\"} 5.0
+size_bytes_sum{job=\"a\\\"b\"} 10.0
+# EOF
+";
+        assert_eq!(String::from_utf8(open).unwrap(), expected);
+
+        // The protobuf encoder handles every type as well.
+        let proto = encode_families(&families, ExpositionFormat::Protobuf).unwrap();
+        assert!(!proto.is_empty());
+    }
+}
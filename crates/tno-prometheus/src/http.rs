@@ -0,0 +1,224 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use prometheus::proto::MetricFamily;
+
+use crate::{ExpositionFormat, PrometheusMetrics, exposition::encode_families};
+
+/// Build an axum router exposing the metrics of `metrics`.
+///
+/// Routes:
+/// - `GET /metrics` - exposition in the format negotiated from `Accept` (Prometheus text by default,
+///   OpenMetrics or protobuf on request, see [`ExpositionFormat::from_accept`])
+/// - `GET /metrics.json` - [`crate::MetricsSnapshot`] as JSON
+///
+/// Merge it into the application router: `app.merge(metrics_router(metrics))`.
+pub fn metrics_router(metrics: PrometheusMetrics) -> Router {
+    router(MetricsState {
+        metrics,
+        cache: None,
+    })
+}
+
+/// Same as [`metrics_router`], but `/metrics` gathers the registry at most once per `refresh_interval`.
+///
+/// Scrapes in between are served the cached metrics, encoded in the format each scrape asks for.
+/// Useful when the shared registry holds many application metrics and gathering on every scrape
+/// is expensive. `/metrics.json` is not cached.
+pub fn metrics_router_cached(metrics: PrometheusMetrics, refresh_interval: Duration) -> Router {
+    router(MetricsState {
+        metrics,
+        cache: Some(Arc::new(ExpositionCache {
+            refresh_interval,
+            last: Mutex::new(None),
+        })),
+    })
+}
+
+impl PrometheusMetrics {
+    /// Router serving these metrics; same as [`metrics_router`].
+    pub fn into_router(self) -> Router {
+        metrics_router(self)
+    }
+}
+
+fn router(state: MetricsState) -> Router {
+    Router::new()
+        .route("/metrics", get(exposition))
+        .route("/metrics.json", get(snapshot_json))
+        .with_state(state)
+}
+
+#[derive(Clone)]
+struct MetricsState {
+    metrics: PrometheusMetrics,
+    cache: Option<Arc<ExpositionCache>>,
+}
+
+/// Last gathered metric families and when they were gathered.
+struct ExpositionCache {
+    refresh_interval: Duration,
+    last: Mutex<Option<(Instant, Arc<Vec<MetricFamily>>)>>,
+}
+
+impl ExpositionCache {
+    /// Return the cached families, gathering them again if they are older than the refresh interval.
+    ///
+    /// The lock is held while gathering, so concurrent scrapes trigger a single refresh.
+    fn get_or_refresh(&self, metrics: &PrometheusMetrics) -> Arc<Vec<MetricFamily>> {
+        let mut last = self.last.lock().unwrap();
+        if let Some((at, families)) = last.as_ref()
+            && at.elapsed() < self.refresh_interval
+        {
+            return Arc::clone(families);
+        }
+        let families = Arc::new(metrics.gather());
+        *last = Some((Instant::now(), Arc::clone(&families)));
+        families
+    }
+}
+
+async fn exposition(State(state): State<MetricsState>, headers: HeaderMap) -> Response {
+    let format = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map_or_else(ExpositionFormat::default, ExpositionFormat::from_accept);
+    let body = match &state.cache {
+        Some(cache) => encode_families(&cache.get_or_refresh(&state.metrics), format),
+        None => state.metrics.encode(format).map(|(body, _)| body),
+    };
+    match body {
+        Ok(body) => (
+            [(header::CONTENT_TYPE, format.content_type())],
+            Bytes::from(body),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn snapshot_json(State(state): State<MetricsState>) -> Response {
+    Json(state.metrics.snapshot()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use tno_core::MetricsBackend;
+    use tower::ServiceExt;
+
+    async fn get_body(router: Router, uri: &str) -> (String, String) {
+        let resp = router
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let content_type = resp.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn serves_prometheus_text_and_json_snapshot() {
+        let metrics = PrometheusMetrics::new().unwrap();
+        metrics.record_task_started("subprocess");
+        metrics.record_task_started("subprocess");
+        metrics.record_task_completed("subprocess", tno_core::TaskOutcome::Success, 5);
+        let router = metrics.into_router();
+
+        let (content_type, text) = get_body(router.clone(), "/metrics").await;
+        assert!(content_type.starts_with("text/plain"), "{content_type}");
+        assert!(
+            text.contains(
+                "tno_tno_tasks_started_total{runner=\"unknown\",runner_type=\"subprocess\"} 2"
+            ),
+            "{text}"
+        );
+
+        let (content_type, json) = get_body(router, "/metrics.json").await;
+        assert_eq!(content_type, "application/json");
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["tasks_started"]["subprocess"], 2);
+        assert_eq!(json["tasks_completed"]["subprocess"]["success"], 1);
+    }
+
+    #[tokio::test]
+    async fn negotiates_exposition_format_from_accept() {
+        let metrics = PrometheusMetrics::new().unwrap();
+        metrics.record_task_started("subprocess");
+        let router = metrics_router_cached(metrics, Duration::from_secs(60));
+
+        let scrape = |accept: &'static str| {
+            let router = router.clone();
+            async move {
+                let resp = router
+                    .oneshot(
+                        Request::get("/metrics")
+                            .header(header::ACCEPT, accept)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(resp.status(), StatusCode::OK);
+                let content_type = resp.headers()[header::CONTENT_TYPE].clone();
+                let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+                (content_type, body)
+            }
+        };
+
+        let (content_type, body) = scrape("application/openmetrics-text;version=1.0.0").await;
+        assert_eq!(content_type, crate::OPENMETRICS_FORMAT);
+        assert!(body.ends_with(b"# EOF\n"));
+
+        let (content_type, body) = scrape(
+            "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited",
+        )
+        .await;
+        assert_eq!(content_type, prometheus::PROTOBUF_FORMAT);
+        assert!(!body.is_empty());
+
+        let (content_type, _) = scrape("text/plain;version=0.0.4").await;
+        assert_eq!(content_type, prometheus::TEXT_FORMAT);
+    }
+
+    #[tokio::test]
+    async fn cached_router_refreshes_after_interval() {
+        let metrics = PrometheusMetrics::new().unwrap();
+        metrics.record_task_started("subprocess");
+        let router = metrics_router_cached(metrics.clone(), Duration::from_millis(200));
+
+        let (_, first) = get_body(router.clone(), "/metrics").await;
+        metrics.record_task_started("subprocess");
+        let (_, second) = get_body(router.clone(), "/metrics").await;
+        assert_eq!(first, second, "scrape within the interval must be cached");
+        assert!(first.contains(
+            "tno_tno_tasks_started_total{runner=\"unknown\",runner_type=\"subprocess\"} 1"
+        ));
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let (_, third) = get_body(router, "/metrics").await;
+        assert!(
+            third.contains(
+                "tno_tno_tasks_started_total{runner=\"unknown\",runner_type=\"subprocess\"} 2"
+            ),
+            "{third}"
+        );
+    }
+}
@@ -17,10 +17,8 @@
 //! // Inject into build context
 //! let ctx = BuildContext::new(TaskEnv::default(), metrics_handle);
 //!
-//! // Expose /metrics endpoint (example with custom HTTP server)
-//! // let metric_families = metrics.gather();
-//! // let encoder = prometheus::TextEncoder::new();
-//! // encoder.encode(&metric_families, &mut response_buffer)?;
+//! // Encode the metrics for a `/metrics` response (see "HTTP Server" below)
+//! let (body, content_type) = metrics.encode(tno_prometheus::ExpositionFormat::Text)?;
 //! # Ok(())
 //! # }
 //! ```
@@ -30,28 +28,40 @@
 //! - `tno_tasks_completed_total{runner_type, outcome}` - Counter
 //! - `tno_task_duration_seconds{runner_type}` - Histogram
 //! - `tno_runner_errors_total{runner_type, error_kind}` - Counter
+//! - `tno_active_cgroups{runner_type}` - Gauge
+//! - `tno_output_bytes_in_flight{runner_type}` - Gauge
+//! - `tno_task_terminal_total{runner_type, reason}` - Counter
 //!
 //! ## HTTP Server
-//! This crate does NOT provide HTTP server for `/metrics` endpoint.
-//! Use your application's existing HTTP framework (axum, warp, etc):
+//! The crate does not run a server itself. With the `axum` feature, `metrics_router` (or `PrometheusMetrics::into_router`)
+//! builds a router serving `/metrics` (Prometheus text, OpenMetrics or protobuf, negotiated from `Accept`) and
+//! `/metrics.json` ([`MetricsSnapshot`]);
+//! `metrics_router_cached` additionally limits `gather()` to once per refresh interval.
 //!
 //! ```rust,ignore
-//! // Example with axum
-//! async fn metrics_handler(
-//!     State(metrics): State<Arc<PrometheusMetrics>>
-//! ) -> Response {
-//!     let families = metrics.gather();
-//!     let encoder = prometheus::TextEncoder::new();
-//!     let mut buffer = vec![];
-//!     encoder.encode(&families, &mut buffer).unwrap();
-//!     Response::builder()
-//!         .header("Content-Type", encoder.format_type())
-//!         .body(buffer.into())
-//!         .unwrap()
-//! }
+//! let app = axum::Router::new()
+//!     .route("/", get(index))
+//!     .merge(metrics.into_router());
 //! ```
+//!
+//! Without the feature, call `encode` in your framework's handler as shown above, with the format picked by
+//! [`ExpositionFormat::from_accept`], and set the `Content-Type` header to the returned content type.
 
 mod backend;
 pub use backend::PrometheusMetrics;
 
+mod error;
+pub use error::PrometheusInitError;
+
+mod exposition;
+pub use exposition::{ExpositionFormat, OPENMETRICS_FORMAT};
+
+mod snapshot;
+pub use snapshot::MetricsSnapshot;
+
+#[cfg(feature = "axum")]
+mod http;
+#[cfg(feature = "axum")]
+pub use http::{metrics_router, metrics_router_cached};
+
 pub use prometheus::{Encoder, Registry, TextEncoder};
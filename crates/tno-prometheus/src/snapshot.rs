@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use prometheus::{core::Collector, proto::Metric};
+use serde::Serialize;
+
+/// Point-in-time view of the tno counters and gauges.
+///
+/// Unlike [`crate::PrometheusMetrics::gather`] this is a typed structure keyed by label values,
+/// convenient for JSON APIs and admin UIs. Histograms are not included.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    /// Started tasks by `runner_type`.
+    pub tasks_started: BTreeMap<String, u64>,
+    /// Started tasks by `runner` (runner name).
+    pub tasks_started_by_runner: BTreeMap<String, u64>,
+    /// Completed tasks by `runner_type`, then `outcome`.
+    pub tasks_completed: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Completed tasks by `runner` (runner name), then `outcome`.
+    pub tasks_completed_by_runner: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Runner errors by `runner_type`, then `error_kind`.
+    pub runner_errors: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Tasks that ended without succeeding by `runner_type`, then `reason`.
+    pub task_terminal: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Cgroups currently owned by runners, by `runner_type`.
+    pub active_cgroups: BTreeMap<String, i64>,
+    /// Buffered, not yet logged subprocess output in bytes, by `runner_type`.
+    pub output_bytes_in_flight: BTreeMap<String, i64>,
+}
+
+impl MetricsSnapshot {
+    /// Total number of started tasks across all runners.
+    pub fn tasks_started_total(&self) -> u64 {
+        self.tasks_started.values().sum()
+    }
+
+    /// Total number of completed tasks across all runners and outcomes.
+    pub fn tasks_completed_total(&self) -> u64 {
+        self.tasks_completed.values().flat_map(|m| m.values()).sum()
+    }
+}
+
+/// Collect all samples of a metric vector.
+pub(crate) fn samples(collector: &dyn Collector) -> Vec<Metric> {
+    collector
+        .collect()
+        .into_iter()
+        .flat_map(|family| family.get_metric().to_vec())
+        .collect()
+}
+
+/// Value of label `name` on a sample (empty if absent).
+pub(crate) fn label(metric: &Metric, name: &str) -> String {
+    metric
+        .get_label()
+        .iter()
+        .find(|l| l.name() == name)
+        .map(|l| l.value().to_owned())
+        .unwrap_or_default()
+}
@@ -5,10 +5,12 @@ use tracing::info;
 use taskvisor::{ControllerConfig, Subscribe, SupervisorConfig};
 use tno_core::{RunnerRouter, SupervisorApi, TaskPolicy};
 
-use tno_exec::subprocess::SubprocessBackendConfig;
 use tno_exec::subprocess::register_subprocess_runner_with_backend;
+use tno_exec::subprocess::{EnvPolicy, SubprocessBackendConfig};
 
-use tno_exec::{CgroupLimits, CpuMax, LinuxCapability, RlimitConfig, SecurityConfig};
+use tno_exec::{
+    CgroupLimits, CpuMax, LinuxCapability, RlimitConfig, SeccompProfile, SecurityConfig,
+};
 
 use tno_observe::{LoggerConfig, LoggerLevel, Subscriber, init_logger, timezone_sync};
 
@@ -28,7 +30,7 @@ async fn main() -> anyhow::Result<()> {
     info!("logger initialized");
 
     // 2) subscribers
-    let subscribers: Vec<Arc<dyn Subscribe>> = vec![Arc::new(Subscriber)];
+    let subscribers: Vec<Arc<dyn Subscribe>> = vec![Arc::new(Subscriber::default())];
 
     // 3) router + runners with DIFFERENT security profiles
     let mut router = RunnerRouter::new();
@@ -46,15 +48,22 @@ async fn main() -> anyhow::Result<()> {
         .with_rlimits(RlimitConfig {
             max_open_files: Some(1024),
             max_file_size_bytes: Some(100 * 1024 * 1024), // 100 MB
+            max_address_space_bytes: None,
+            max_processes: None,
+            max_cpu_seconds: None,
             disable_core_dumps: true,
+            core_dump: None,
         })
         .with_cgroups(CgroupLimits {
             cpu: Some(CpuMax {
                 quota: Some(50_000), // 50% CPU (50ms per 100ms)
                 period: 100_000,     // 100ms
             }),
+            cpu_weight: None,
             memory: Some(256 * 1024 * 1024), // 256 MB
             pids: Some(64),                  // max 64 processes
+            io: None,
+            attach_retries: None,
         });
     register_subprocess_runner_with_backend(&mut router, "prod-runner", prod_backend)?;
     info!("registered prod-runner (moderate restrictions)");
@@ -64,25 +73,47 @@ async fn main() -> anyhow::Result<()> {
         .with_rlimits(RlimitConfig {
             max_open_files: Some(128),
             max_file_size_bytes: Some(10 * 1024 * 1024), // 10 MB only
+            max_address_space_bytes: None,
+            max_processes: None,
+            max_cpu_seconds: None,
             disable_core_dumps: true,
+            core_dump: None,
         })
         .with_cgroups(CgroupLimits {
             cpu: Some(CpuMax {
                 quota: Some(25_000),
                 period: 100_000,
             }),
+            cpu_weight: None,
 
             memory: Some(64 * 1024 * 1024),
             pids: Some(16),
+            io: None,
+            attach_retries: None,
         })
         .with_security(SecurityConfig {
             drop_all_caps: true,
             keep_caps: vec![LinuxCapability::NetBindService],
             no_new_privs: true, // CRITICAL  untrusted code
-        });
+            run_as_uid: None,
+            run_as_gid: None,
+            namespace: None,
+            landlock: None,
+            seccomp: Some(SeccompProfile::DefaultDenyDangerous),
+        })
+        // Do not leak agent secrets (cloud credentials, tokens) into untrusted tasks.
+        .with_env_policy(EnvPolicy::Clear);
     register_subprocess_runner_with_backend(&mut router, "untrusted-runner", untrusted_backend)?;
     info!("registered untrusted-runner (MAXIMUM security)");
 
+    // 3d) Startup self-test: surface broken runner configs before accepting tasks
+    for (runner, res) in router.self_test().await {
+        match res {
+            Ok(()) => info!("self-test passed: {}", runner),
+            Err(e) => tracing::warn!("self-test failed: {}: {}", runner, e),
+        }
+    }
+
     // 4) SupervisorApi
     let api = SupervisorApi::new(
         SupervisorConfig::default(),
@@ -94,8 +125,8 @@ async fn main() -> anyhow::Result<()> {
 
     // 5) internal timezone-sync
     let (tz_task, tz_spec) = timezone_sync();
-    let tz_policy = TaskPolicy::from_spec(&tz_spec);
-    let tz_id = api.submit_with_task(tz_task, &tz_policy).await?;
+    let tz_policy = TaskPolicy::from_spec(&tz_spec)?;
+    let tz_id = api.submit_with_task(tz_task, &tz_policy).await?.task_id;
     info!("submitted timezone-sync task: {}", tz_id);
 
     // 6a) Dev runner
@@ -105,6 +136,7 @@ async fn main() -> anyhow::Result<()> {
             command: "ls".into(),
             args: vec!["-lah".into(), "/tmp".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -118,6 +150,11 @@ async fn main() -> anyhow::Result<()> {
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     }
     .with_runner_tag("dev-runner");
 
@@ -128,6 +165,7 @@ async fn main() -> anyhow::Result<()> {
             command: "date".into(),
             args: vec!["+%Y-%m-%d %H:%M:%S".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -141,6 +179,11 @@ async fn main() -> anyhow::Result<()> {
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     }
     .with_runner_tag("prod-runner");
 
@@ -151,6 +194,7 @@ async fn main() -> anyhow::Result<()> {
             command: "sleep".into(),
             args: vec!["2".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -164,6 +208,11 @@ async fn main() -> anyhow::Result<()> {
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     }
     .with_runner_tag("untrusted-runner");
 
@@ -177,6 +226,7 @@ async fn main() -> anyhow::Result<()> {
                 "for i in $(seq 1 100); do sleep 1 & done; wait".into(),
             ],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::disabled(),
         },
@@ -190,23 +240,28 @@ async fn main() -> anyhow::Result<()> {
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     }
     .with_runner_tag("untrusted-runner");
 
     // Submit tasks
     info!("submitting tasks...");
-    let task_id = api.submit(&ls_spec).await?;
+    let task_id = api.submit(&ls_spec).await?.task_id;
     tokio::time::sleep(Duration::from_millis(100)).await;
     if let Some(info) = api.get_task(&task_id) {
         info!("task {} status: {:?}", task_id, info.status);
     }
 
     info!("submitted task: {}", task_id);
-    let date_id = api.submit(&date_spec).await?;
+    let date_id = api.submit(&date_spec).await?.task_id;
     info!("submitted date: {}", date_id);
-    let sleep_id = api.submit(&sleep_spec).await?;
+    let sleep_id = api.submit(&sleep_spec).await?.task_id;
     info!("submitted sleep: {}", sleep_id);
-    let stress_id = api.submit(&stress_spec).await?;
+    let stress_id = api.submit(&stress_spec).await?.task_id;
     info!("submitted stress: {}", stress_id);
 
     info!("all tasks submitted, waiting for completion...");
@@ -29,7 +29,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("registered default subprocess runner");
 
     // 3) Create supervisor
-    let subscribers: Vec<Arc<dyn Subscribe>> = vec![Arc::new(Subscriber)];
+    let subscribers: Vec<Arc<dyn Subscribe>> = vec![Arc::new(Subscriber::default())];
     let supervisor = SupervisorApi::new(
         SupervisorConfig::default(),
         ControllerConfig::default(),
@@ -41,7 +41,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 4) Submit timezone sync task
     let (tz_task, tz_spec) = timezone_sync();
-    let tz_policy = tno_core::TaskPolicy::from_spec(&tz_spec);
+    let tz_policy = tno_core::TaskPolicy::from_spec(&tz_spec)?;
     supervisor.submit_with_task(tz_task, &tz_policy).await?;
     info!("timezone sync task submitted");
 
@@ -75,6 +75,7 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
             command: "date".into(),
             args: vec!["+%Y-%m-%d %H:%M:%S".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -88,6 +89,11 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
 
     // Task 2: Print uptime every 30 seconds
@@ -97,6 +103,7 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
             command: "uptime".into(),
             args: vec![],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -110,6 +117,11 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
 
     // Task 3: Echo message every 5 seconds
@@ -119,6 +131,7 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
             command: "echo".into(),
             args: vec!["Hello from tno periodic task!".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -132,15 +145,20 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
         },
         admission: AdmissionStrategy::Replace,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
 
-    let date_id = api.submit(&date_spec).await?;
+    let date_id = api.submit(&date_spec).await?.task_id;
     info!("submitted periodic date task: {}", date_id);
 
-    let uptime_id = api.submit(&uptime_spec).await?;
+    let uptime_id = api.submit(&uptime_spec).await?.task_id;
     info!("submitted periodic uptime task: {}", uptime_id);
 
-    let echo_id = api.submit(&echo_spec).await?;
+    let echo_id = api.submit(&echo_spec).await?.task_id;
     info!("submitted periodic echo task: {}", echo_id);
 
     Ok(())
@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use axum::routing::get;
 use tracing::info;
 
 use taskvisor::{ControllerConfig, Subscribe, SupervisorConfig};
@@ -12,7 +11,7 @@ use tno_model::{
     RunnerLabels, TaskEnv, TaskKind,
 };
 use tno_observe::{LoggerConfig, LoggerLevel, Subscriber, init_logger, timezone_sync};
-use tno_prometheus::PrometheusMetrics;
+use tno_prometheus::{PrometheusMetrics, metrics_router};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -36,7 +35,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("registered default subprocess runner");
 
     // 4) Create supervisor
-    let subscribers: Vec<Arc<dyn Subscribe>> = vec![Arc::new(Subscriber)];
+    let subscribers: Vec<Arc<dyn Subscribe>> = vec![Arc::new(Subscriber::default())];
     let supervisor = SupervisorApi::new(
         SupervisorConfig::default(),
         ControllerConfig::default(),
@@ -48,7 +47,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 5) Submit timezone sync task
     let (tz_task, tz_spec) = timezone_sync();
-    let tz_policy = tno_core::TaskPolicy::from_spec(&tz_spec);
+    let tz_policy = tno_core::TaskPolicy::from_spec(&tz_spec)?;
     supervisor.submit_with_task(tz_task, &tz_policy).await?;
     info!("timezone sync task submitted");
 
@@ -61,12 +60,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let http_api = HttpApi::new(handler);
     let app = http_api.router();
 
-    // 8) Add /metrics endpoint
-    let metrics_clone = metrics.clone();
-    let app = app.route(
-        "/metrics",
-        get(move || metrics_handler(metrics_clone.clone())),
-    );
+    // 8) Add /metrics and /metrics.json endpoints
+    let app = app.merge(metrics_router(metrics));
 
     // 9) Start HTTP server
     let addr = "0.0.0.0:8080";
@@ -74,24 +69,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("starting HTTP server on http://{}", addr);
     info!("API: http://{}/api/v1/tasks", addr);
     info!("Metrics: http://{}/metrics", addr);
+    info!("Metrics (JSON): http://{}/metrics.json", addr);
 
     axum::serve(listener, app).await?;
 
     Ok(())
 }
 
-/// Prometheus metrics handler
-async fn metrics_handler(metrics: PrometheusMetrics) -> String {
-    use tno_prometheus::{Encoder, TextEncoder};
-
-    let families = metrics.gather();
-    let encoder = TextEncoder::new();
-    let mut buffer = vec![];
-
-    encoder.encode(&families, &mut buffer).unwrap();
-    String::from_utf8(buffer).unwrap()
-}
-
 /// Submit demo periodic tasks that run continuously
 async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error::Error>> {
     // Task 1: Print date every 10 seconds
@@ -101,6 +85,7 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
             command: "date".into(),
             args: vec!["+%Y-%m-%d %H:%M:%S".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -114,6 +99,11 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
 
     // Task 2: Print uptime every 30 seconds
@@ -123,6 +113,7 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
             command: "uptime".into(),
             args: vec![],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -136,6 +127,11 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
         },
         admission: AdmissionStrategy::DropIfRunning,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
 
     // Task 3: Echo message every 5 seconds
@@ -145,6 +141,7 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
             command: "echo".into(),
             args: vec!["Hello from tno periodic task!".into()],
             env: TaskEnv::default(),
+            env_file: None,
             cwd: None,
             fail_on_non_zero: Flag::enabled(),
         },
@@ -158,15 +155,20 @@ async fn submit_demo_tasks(api: &SupervisorApi) -> Result<(), Box<dyn std::error
         },
         admission: AdmissionStrategy::Replace,
         labels: RunnerLabels::default(),
+        runner_selector: RunnerLabels::default(),
+        cancel_is_success: false,
+        idempotency_key: None,
+        batch_id: None,
+        schedule: None,
     };
 
-    let date_id = api.submit(&date_spec).await?;
+    let date_id = api.submit(&date_spec).await?.task_id;
     info!("submitted periodic date task: {}", date_id);
 
-    let uptime_id = api.submit(&uptime_spec).await?;
+    let uptime_id = api.submit(&uptime_spec).await?.task_id;
     info!("submitted periodic uptime task: {}", uptime_id);
 
-    let echo_id = api.submit(&echo_spec).await?;
+    let echo_id = api.submit(&echo_spec).await?.task_id;
     info!("submitted periodic echo task: {}", echo_id);
 
     Ok(())